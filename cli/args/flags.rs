@@ -118,6 +118,20 @@ pub struct EvalFlags {
   pub code: String,
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FfiSubcommand {
+  Bindgen {
+    header: String,
+    output: Option<PathBuf>,
+    lib: Option<String>,
+  },
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FfiFlags {
+  pub subcommand: FfiSubcommand,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct FmtFlags {
   pub check: bool,
@@ -220,6 +234,30 @@ pub struct WatchFlagsWithPaths {
   pub no_clear_screen: bool,
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StorageSubcommand {
+  List {
+    json: bool,
+  },
+  Inspect {
+    target: Option<String>,
+    json: bool,
+  },
+  Export {
+    target: Option<String>,
+    output: Option<PathBuf>,
+  },
+  Clear {
+    target: Option<String>,
+    all: bool,
+  },
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StorageFlags {
+  pub subcommand: StorageSubcommand,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct TaskFlags {
   pub cwd: Option<String>,
@@ -247,6 +285,7 @@ pub struct TestFlags {
   pub shuffle: Option<u64>,
   pub concurrent_jobs: Option<NonZeroUsize>,
   pub trace_ops: bool,
+  pub in_memory_fs: bool,
   pub watch: Option<WatchFlags>,
   pub reporter: TestReporterConfig,
   pub junit_path: Option<String>,
@@ -279,6 +318,7 @@ pub enum DenoSubcommand {
   Coverage(CoverageFlags),
   Doc(DocFlags),
   Eval(EvalFlags),
+  Ffi(FfiFlags),
   Fmt(FmtFlags),
   Init(InitFlags),
   Info(InfoFlags),
@@ -289,6 +329,7 @@ pub enum DenoSubcommand {
   Lint(LintFlags),
   Repl(ReplFlags),
   Run(RunFlags),
+  Storage(StorageFlags),
   Task(TaskFlags),
   Test(TestFlags),
   Types,
@@ -394,6 +435,8 @@ pub struct Flags {
   pub inspect_brk: Option<SocketAddr>,
   pub inspect_wait: Option<SocketAddr>,
   pub inspect: Option<SocketAddr>,
+  pub local_storage_dir: Option<PathBuf>,
+  pub local_storage_quota: Option<usize>,
   pub location: Option<Url>,
   pub lock_write: bool,
   pub lock: Option<PathBuf>,
@@ -402,6 +445,7 @@ pub struct Flags {
   pub no_lock: bool,
   pub no_npm: bool,
   pub no_prompt: bool,
+  pub otel_export: Option<String>,
   pub reload: bool,
   pub seed: Option<u64>,
   pub unstable: bool,
@@ -685,9 +729,9 @@ impl Flags {
       | Test(_) | Bench(_) | Repl(_) | Compile(_) => {
         std::env::current_dir().ok()
       }
-      Bundle(_) | Completions(_) | Doc(_) | Fmt(_) | Init(_) | Install(_)
-      | Uninstall(_) | Jupyter(_) | Lsp | Lint(_) | Types | Upgrade(_)
-      | Vendor(_) => None,
+      Bundle(_) | Completions(_) | Doc(_) | Ffi(_) | Fmt(_) | Init(_)
+      | Install(_) | Uninstall(_) | Jupyter(_) | Lsp | Lint(_) | Storage(_)
+      | Types | Upgrade(_) | Vendor(_) => None,
     }
   }
 
@@ -823,6 +867,7 @@ pub fn flags_from_vec(args: Vec<String>) -> clap::error::Result<Flags> {
       "coverage" => coverage_parse(&mut flags, &mut m),
       "doc" => doc_parse(&mut flags, &mut m),
       "eval" => eval_parse(&mut flags, &mut m),
+      "ffi" => ffi_parse(&mut flags, &mut m),
       "fmt" => fmt_parse(&mut flags, &mut m),
       "init" => init_parse(&mut flags, &mut m),
       "info" => info_parse(&mut flags, &mut m),
@@ -832,6 +877,7 @@ pub fn flags_from_vec(args: Vec<String>) -> clap::error::Result<Flags> {
       "lsp" => lsp_parse(&mut flags, &mut m),
       "repl" => repl_parse(&mut flags, &mut m),
       "run" => run_parse(&mut flags, &mut m, app)?,
+      "storage" => storage_parse(&mut flags, &mut m),
       "task" => task_parse(&mut flags, &mut m),
       "test" => test_parse(&mut flags, &mut m),
       "types" => types_parse(&mut flags, &mut m),
@@ -925,6 +971,7 @@ fn clap_root() -> Command {
         .subcommand(coverage_subcommand())
         .subcommand(doc_subcommand())
         .subcommand(eval_subcommand())
+        .subcommand(ffi_subcommand())
         .subcommand(fmt_subcommand())
         .subcommand(init_subcommand())
         .subcommand(info_subcommand())
@@ -934,6 +981,7 @@ fn clap_root() -> Command {
         .subcommand(lsp_subcommand())
         .subcommand(lint_subcommand())
         .subcommand(repl_subcommand())
+        .subcommand(storage_subcommand())
         .subcommand(task_subcommand())
         .subcommand(test_subcommand())
         .subcommand(types_subcommand())
@@ -1392,6 +1440,54 @@ This command has implicit access to all permissions (--allow-all).",
     })
 }
 
+fn ffi_subcommand() -> Command {
+  Command::new("ffi")
+    .about("Tools for working with foreign libraries")
+    .subcommand_required(true)
+    .defer(|cmd| {
+      cmd.subcommand(
+        Command::new("bindgen")
+          .about("Generate FFI bindings from a C header")
+          .long_about(
+            "Generate a TypeScript module with 'Deno.dlopen' bindings from the
+declarations of a C header file.
+
+  deno ffi bindgen mylib.h > mylib.ts
+  deno ffi bindgen mylib.h --lib ./libmylib.so --output mylib.ts
+
+Functions, structs, enums, typedefs and '#define' constants are translated.
+The header is not run through a preprocessor: '#include' and conditional
+directives are ignored, and declarations that cannot be represented are
+skipped with a warning. Struct layouts are computed for the current platform.",
+          )
+          .arg(
+            Arg::new("header")
+              .help("The C header file to read declarations from")
+              .required(true)
+              .value_hint(ValueHint::FilePath),
+          )
+          .arg(
+            Arg::new("output")
+              .long("output")
+              .short('o')
+              .value_name("FILE")
+              .help("Write to a file instead of stdout")
+              .value_parser(value_parser!(PathBuf))
+              .value_hint(ValueHint::FilePath),
+          )
+          .arg(
+            Arg::new("lib")
+              .long("lib")
+              .value_name("PATH")
+              .help(
+                "Default path of the library loaded by the generated module",
+              )
+              .value_hint(ValueHint::FilePath),
+          ),
+      )
+    })
+}
+
 fn fmt_subcommand() -> Command {
   Command::new("fmt")
     .about("Format source files")
@@ -1863,6 +1959,93 @@ Specifying the filename '-' to read the file from stdin.
     )
 }
 
+fn storage_subcommand() -> Command {
+  fn target_args(cmd: Command) -> Command {
+    cmd
+      .arg(
+        Arg::new("target")
+          .value_name("SCRIPT_OR_ID")
+          .help("A script, or the id of a storage origin as shown by 'deno storage list'")
+          .value_hint(ValueHint::FilePath),
+      )
+      .arg(location_arg().help(
+        "Select the storage origin of scripts run with '--location=<HREF>'",
+      ))
+      .arg(config_arg())
+      .arg(no_config_arg())
+      .arg(local_storage_dir_arg())
+  }
+
+  Command::new("storage")
+    .about("List, inspect, export and clear localStorage data")
+    .long_about(
+      "List, inspect, export and clear the data scripts persisted with the
+Web Storage API.
+
+  deno storage list
+  deno storage inspect main.ts
+  deno storage inspect --location https://example.com
+  deno storage export main.ts --output storage.json
+  deno storage clear 3c1f0a...
+  deno storage clear --all
+
+The storage origin of a script is resolved in the same way as 'deno run'
+does: from '--location' if given, otherwise from the configuration file,
+otherwise from the path or URL of the script.",
+    )
+    .subcommand_required(true)
+    .defer(|cmd| {
+      cmd
+        .subcommand(
+          Command::new("list")
+            .about("List stored origins")
+            .arg(
+              Arg::new("json")
+                .long("json")
+                .action(ArgAction::SetTrue)
+                .help("Output in JSON format"),
+            )
+            .arg(config_arg())
+            .arg(no_config_arg())
+            .arg(local_storage_dir_arg()),
+        )
+        .subcommand(
+          target_args(Command::new("inspect"))
+            .about("Print the keys and values stored for an origin")
+            .arg(
+              Arg::new("json")
+                .long("json")
+                .action(ArgAction::SetTrue)
+                .help("Output in JSON format"),
+            ),
+        )
+        .subcommand(
+          target_args(Command::new("export"))
+            .about("Export the data of an origin as a JSON object")
+            .arg(
+              Arg::new("output")
+                .long("output")
+                .short('o')
+                .value_name("FILE")
+                .help("Write to a file instead of stdout")
+                .value_parser(value_parser!(PathBuf))
+                .value_hint(ValueHint::FilePath),
+            ),
+        )
+        .subcommand(
+          target_args(Command::new("clear"))
+            .about("Delete the data of an origin")
+            .arg(
+              Arg::new("all")
+                .long("all")
+                .action(ArgAction::SetTrue)
+                .help("Delete the data of all origins")
+                .conflicts_with_all(["target", "location"]),
+            ),
+        )
+    })
+}
+
 fn task_subcommand() -> Command {
   Command::new("task")
     .about("Run a task defined in the configuration file")
@@ -1926,6 +2109,14 @@ Directory arguments are expanded to all contained files matching the glob
         .help("Enable tracing of async ops. Useful when debugging leaking ops in test, but impacts test execution time.")
         .action(ArgAction::SetTrue),
     )
+    .arg(
+      Arg::new("fs")
+        .long("fs")
+        .value_name("KIND")
+        .require_equals(true)
+        .value_parser(["real", "memory"])
+        .help("Select the file system used by the Deno file system APIs in tests. 'memory' runs tests against an empty, in-memory file system. Defaults to 'real'."),
+    )
     .arg(
       Arg::new("doc")
         .long("doc")
@@ -2538,8 +2729,11 @@ fn runtime_args(
   app
     .arg(cached_only_arg())
     .arg(location_arg())
+    .arg(local_storage_quota_arg())
+    .arg(local_storage_dir_arg())
     .arg(v8_flags_arg())
     .arg(seed_arg())
+    .arg(otel_export_arg())
     .arg(enable_testing_features_arg())
 }
 
@@ -2670,6 +2864,50 @@ fn location_arg() -> Arg {
     .value_hint(ValueHint::Url)
 }
 
+fn local_storage_quota_arg() -> Arg {
+  Arg::new("local-storage-quota")
+    .long("local-storage-quota")
+    .value_name("SIZE")
+    .value_parser(parse_storage_quota)
+    .help("Maximum size of 'localStorage' per origin in bytes (default 10m)")
+    .long_help(
+      "Maximum size of 'localStorage' and 'sessionStorage' per origin.
+Accepts a number of bytes, optionally followed by a 'k', 'm' or 'g' suffix:
+
+  --local-storage-quota=50m",
+    )
+}
+
+fn local_storage_dir_arg() -> Arg {
+  Arg::new("local-storage-dir")
+    .long("local-storage-dir")
+    .value_name("DIR")
+    .value_parser(value_parser!(PathBuf))
+    .help("Directory used to persist 'localStorage' data")
+    .value_hint(ValueHint::DirPath)
+}
+
+/// Parses a storage size like `1048576`, `512k`, `50m` or `1g`. Suffixes are
+/// binary multiples.
+pub fn parse_storage_quota(size: &str) -> Result<usize, String> {
+  let size = size.trim();
+  let (digits, multiplier) = match size.char_indices().last() {
+    Some((i, 'k' | 'K')) => (&size[..i], 1024),
+    Some((i, 'm' | 'M')) => (&size[..i], 1024 * 1024),
+    Some((i, 'g' | 'G')) => (&size[..i], 1024 * 1024 * 1024),
+    _ => (size, 1),
+  };
+  let value = digits
+    .parse::<usize>()
+    .ok()
+    .and_then(|value| value.checked_mul(multiplier))
+    .ok_or_else(|| format!("invalid size '{size}'"))?;
+  if value == 0 {
+    return Err("size must be greater than 0".to_string());
+  }
+  Ok(value)
+}
+
 fn enable_testing_features_arg() -> Arg {
   Arg::new("enable-testing-features-do-not-use")
     .long("enable-testing-features-do-not-use")
@@ -2697,6 +2935,21 @@ fn seed_arg() -> Arg {
     .value_parser(value_parser!(u64))
 }
 
+fn otel_export_arg() -> Arg {
+  Arg::new("otel-export")
+    .long("otel-export")
+    .value_name("FILE|ENDPOINT")
+    .require_equals(true)
+    .help("Export OpenTelemetry traces to a file or an OTLP/HTTP endpoint")
+    .long_help(
+      "Export OpenTelemetry traces of fetch, Deno.serve, Deno KV and subprocess
+spans. An http:// or https:// URL is used as an OTLP/HTTP endpoint, anything
+else as a file that OTLP JSON is appended to, one export request per line.
+W3C traceparent headers are propagated automatically.",
+    )
+    .value_hint(ValueHint::AnyPath)
+}
+
 fn watch_arg(takes_files: bool) -> Arg {
   let arg = Arg::new("watch")
     .long("watch")
@@ -3121,6 +3374,19 @@ fn eval_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   flags.subcommand = DenoSubcommand::Eval(EvalFlags { print, code });
 }
 
+fn ffi_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  let (subcommand, mut matches) = matches.remove_subcommand().unwrap();
+  let subcommand = match subcommand.as_str() {
+    "bindgen" => FfiSubcommand::Bindgen {
+      header: matches.remove_one::<String>("header").unwrap(),
+      output: matches.remove_one::<PathBuf>("output"),
+      lib: matches.remove_one::<String>("lib"),
+    },
+    _ => unreachable!(),
+  };
+  flags.subcommand = DenoSubcommand::Ffi(FfiFlags { subcommand });
+}
+
 fn fmt_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   config_args_parse(flags, matches);
   ext_arg_parse(flags, matches);
@@ -3310,6 +3576,34 @@ fn run_parse(
   Ok(())
 }
 
+fn storage_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  let (subcommand, mut matches) = matches.remove_subcommand().unwrap();
+  config_args_parse(flags, &mut matches);
+  local_storage_dir_arg_parse(flags, &mut matches);
+  if subcommand != "list" {
+    location_arg_parse(flags, &mut matches);
+  }
+  let subcommand = match subcommand.as_str() {
+    "list" => StorageSubcommand::List {
+      json: matches.get_flag("json"),
+    },
+    "inspect" => StorageSubcommand::Inspect {
+      target: matches.remove_one::<String>("target"),
+      json: matches.get_flag("json"),
+    },
+    "export" => StorageSubcommand::Export {
+      target: matches.remove_one::<String>("target"),
+      output: matches.remove_one::<PathBuf>("output"),
+    },
+    "clear" => StorageSubcommand::Clear {
+      target: matches.remove_one::<String>("target"),
+      all: matches.get_flag("all"),
+    },
+    _ => unreachable!(),
+  };
+  flags.subcommand = DenoSubcommand::Storage(StorageFlags { subcommand });
+}
+
 fn task_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   flags.config_flag = matches
     .remove_one::<String>("config")
@@ -3350,6 +3644,8 @@ fn test_parse(flags: &mut Flags, matches: &mut ArgMatches) {
 
   let no_run = matches.get_flag("no-run");
   let trace_ops = matches.get_flag("trace-ops");
+  let in_memory_fs =
+    matches.remove_one::<String>("fs").as_deref() == Some("memory");
   let doc = matches.get_flag("doc");
   let allow_none = matches.get_flag("allow-none");
   let filter = matches.remove_one::<String>("filter");
@@ -3438,6 +3734,7 @@ fn test_parse(flags: &mut Flags, matches: &mut ArgMatches) {
     allow_none,
     concurrent_jobs,
     trace_ops,
+    in_memory_fs,
     watch: watch_arg_parse(matches),
     reporter,
     junit_path,
@@ -3622,8 +3919,10 @@ fn runtime_args_parse(
     inspect_arg_parse(flags, matches);
   }
   location_arg_parse(flags, matches);
+  local_storage_args_parse(flags, matches);
   v8_flags_arg_parse(flags, matches);
   seed_arg_parse(flags, matches);
+  otel_export_arg_parse(flags, matches);
   enable_testing_features_arg_parse(flags, matches);
 }
 
@@ -3702,12 +4001,26 @@ fn location_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   flags.location = matches.remove_one::<Url>("location");
 }
 
+fn local_storage_args_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  flags.local_storage_quota =
+    matches.remove_one::<usize>("local-storage-quota");
+  local_storage_dir_arg_parse(flags, matches);
+}
+
+fn local_storage_dir_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  flags.local_storage_dir = matches.remove_one::<PathBuf>("local-storage-dir");
+}
+
 fn v8_flags_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   if let Some(v8_flags) = matches.remove_many::<String>("v8-flags") {
     flags.v8_flags = v8_flags.collect();
   }
 }
 
+fn otel_export_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  flags.otel_export = matches.remove_one::<String>("otel-export");
+}
+
 fn seed_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   if let Some(seed) = matches.remove_one::<u64>("seed") {
     flags.seed = Some(seed);
@@ -5936,6 +6249,30 @@ mod tests {
     );
   }
 
+  #[test]
+  fn run_otel_export() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--otel-export=http://localhost:4318",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+          watch: Default::default(),
+        }),
+        otel_export: Some("http://localhost:4318".to_string()),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "test", "--otel-export=spans.jsonl"]);
+    assert_eq!(r.unwrap().otel_export, Some("spans.jsonl".to_string()));
+  }
+
   #[test]
   fn run_seed_with_v8_flags() {
     let r = flags_from_vec(svec![
@@ -6677,6 +7014,7 @@ mod tests {
           shuffle: None,
           concurrent_jobs: None,
           trace_ops: true,
+          in_memory_fs: false,
           coverage_dir: Some("cov".to_string()),
           watch: Default::default(),
           reporter: Default::default(),
@@ -6758,6 +7096,7 @@ mod tests {
           },
           concurrent_jobs: Some(NonZeroUsize::new(4).unwrap()),
           trace_ops: false,
+          in_memory_fs: false,
           coverage_dir: None,
           watch: Default::default(),
           junit_path: None,
@@ -6791,6 +7130,7 @@ mod tests {
           },
           concurrent_jobs: None,
           trace_ops: false,
+          in_memory_fs: false,
           coverage_dir: None,
           watch: Default::default(),
           reporter: Default::default(),
@@ -6806,6 +7146,41 @@ mod tests {
     assert!(r.is_err());
   }
 
+  #[test]
+  fn test_with_in_memory_fs() {
+    let r = flags_from_vec(svec!["deno", "test", "--fs=memory"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          no_run: false,
+          doc: false,
+          fail_fast: None,
+          filter: None,
+          allow_none: false,
+          shuffle: None,
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+          },
+          concurrent_jobs: None,
+          trace_ops: false,
+          in_memory_fs: true,
+          coverage_dir: None,
+          watch: Default::default(),
+          reporter: Default::default(),
+          junit_path: None,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        no_prompt: true,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "test", "--fs=disk"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn test_with_enable_testing_features() {
     let r = flags_from_vec(svec![
@@ -6829,6 +7204,7 @@ mod tests {
           },
           concurrent_jobs: None,
           trace_ops: false,
+          in_memory_fs: false,
           coverage_dir: None,
           watch: Default::default(),
           reporter: Default::default(),
@@ -6946,6 +7322,7 @@ mod tests {
           },
           concurrent_jobs: None,
           trace_ops: false,
+          in_memory_fs: false,
           coverage_dir: None,
           watch: Default::default(),
           reporter: Default::default(),
@@ -6977,6 +7354,7 @@ mod tests {
           },
           concurrent_jobs: None,
           trace_ops: false,
+          in_memory_fs: false,
           coverage_dir: None,
           watch: Some(WatchFlags {
             no_clear_screen: false,
@@ -7009,6 +7387,7 @@ mod tests {
           },
           concurrent_jobs: None,
           trace_ops: false,
+          in_memory_fs: false,
           coverage_dir: None,
           watch: Some(WatchFlags {
             no_clear_screen: false,
@@ -7043,6 +7422,7 @@ mod tests {
           },
           concurrent_jobs: None,
           trace_ops: false,
+          in_memory_fs: false,
           coverage_dir: None,
           watch: Some(WatchFlags {
             no_clear_screen: true,
@@ -7398,6 +7778,180 @@ mod tests {
       .contains("Expected protocol \"http\" or \"https\""));
   }
 
+  #[test]
+  fn run_with_local_storage_flags() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--local-storage-quota=50m",
+      "--local-storage-dir",
+      "./storage",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+          watch: Default::default(),
+        }),
+        local_storage_quota: Some(50 * 1024 * 1024),
+        local_storage_dir: Some(PathBuf::from("./storage")),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--local-storage-quota=0",
+      "script.ts"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn storage_quota_sizes() {
+    assert_eq!(parse_storage_quota("1024"), Ok(1024));
+    assert_eq!(parse_storage_quota("512k"), Ok(512 * 1024));
+    assert_eq!(parse_storage_quota("2G"), Ok(2 * 1024 * 1024 * 1024));
+    assert!(parse_storage_quota("").is_err());
+    assert!(parse_storage_quota("m").is_err());
+    assert!(parse_storage_quota("10mb").is_err());
+    assert!(parse_storage_quota("0k").is_err());
+  }
+
+  #[test]
+  fn ffi_bindgen() {
+    let r = flags_from_vec(svec!["deno", "ffi", "bindgen", "mylib.h"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Ffi(FfiFlags {
+          subcommand: FfiSubcommand::Bindgen {
+            header: "mylib.h".to_string(),
+            output: None,
+            lib: None,
+          },
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "ffi",
+      "bindgen",
+      "mylib.h",
+      "--lib",
+      "./libmylib.so",
+      "-o",
+      "mylib.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Ffi(FfiFlags {
+          subcommand: FfiSubcommand::Bindgen {
+            header: "mylib.h".to_string(),
+            output: Some(PathBuf::from("mylib.ts")),
+            lib: Some("./libmylib.so".to_string()),
+          },
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "ffi", "bindgen"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn storage_subcommand() {
+    let r = flags_from_vec(svec!["deno", "storage", "list", "--json"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Storage(StorageFlags {
+          subcommand: StorageSubcommand::List { json: true },
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "storage",
+      "inspect",
+      "--location",
+      "https://example.com/app",
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Storage(StorageFlags {
+          subcommand: StorageSubcommand::Inspect {
+            target: None,
+            json: false,
+          },
+        }),
+        location: Some(Url::parse("https://example.com/app").unwrap()),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "storage",
+      "export",
+      "main.ts",
+      "--config",
+      "deno.json",
+      "-o",
+      "out.json"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Storage(StorageFlags {
+          subcommand: StorageSubcommand::Export {
+            target: Some("main.ts".to_string()),
+            output: Some(PathBuf::from("out.json")),
+          },
+        }),
+        config_flag: ConfigFlag::Path("deno.json".to_string()),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "storage",
+      "clear",
+      "--all",
+      "--local-storage-dir=/tmp/storage"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Storage(StorageFlags {
+          subcommand: StorageSubcommand::Clear {
+            target: None,
+            all: true,
+          },
+        }),
+        local_storage_dir: Some(PathBuf::from("/tmp/storage")),
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "storage", "clear", "--all", "main.ts"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "storage"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn test_config_path_args() {
     let flags = flags_from_vec(svec!["deno", "run", "foo.js"]).unwrap();
@@ -12,6 +12,8 @@ use ::import_map::ImportMap;
 use deno_core::resolve_url_or_path;
 use deno_npm::resolution::ValidSerializedNpmResolutionSnapshot;
 use deno_npm::NpmSystemInfo;
+use deno_runtime::deno_tls::Proxy;
+use deno_runtime::deno_tls::ProxyRule;
 use deno_runtime::deno_tls::RootCertStoreProvider;
 use deno_semver::npm::NpmPackageReqReference;
 use indexmap::IndexMap;
@@ -602,6 +604,8 @@ pub struct CliOptions {
   initial_cwd: PathBuf,
   maybe_node_modules_folder: Option<PathBuf>,
   maybe_vendor_folder: Option<PathBuf>,
+  local_storage_config: LocalStorageConfig,
+  proxy: Option<Proxy>,
   maybe_config_file: Option<ConfigFile>,
   maybe_package_json: Option<PackageJson>,
   maybe_lockfile: Option<Arc<Mutex<Lockfile>>>,
@@ -639,6 +643,15 @@ impl CliOptions {
     .with_context(|| "Resolving node_modules folder.")?;
     let maybe_vendor_folder =
       resolve_vendor_folder(&initial_cwd, &flags, maybe_config_file.as_ref());
+    let local_storage_config = resolve_local_storage_config(
+      &initial_cwd,
+      &flags,
+      maybe_config_file.as_ref(),
+    )?;
+    let proxy = match &maybe_config_file {
+      Some(config_file) => read_proxy_config(config_file)?,
+      None => None,
+    };
 
     Ok(Self {
       flags,
@@ -648,6 +661,8 @@ impl CliOptions {
       maybe_package_json,
       maybe_node_modules_folder,
       maybe_vendor_folder,
+      local_storage_config,
+      proxy,
       overrides: Default::default(),
     })
   }
@@ -1154,6 +1169,22 @@ impl CliOptions {
     &self.flags.location
   }
 
+  /// The folder `localStorage` data is persisted in, when it differs from
+  /// the origin data folder of the `DENO_DIR`.
+  pub fn local_storage_dir(&self) -> Option<PathBuf> {
+    self.local_storage_config.dir.clone()
+  }
+
+  pub fn local_storage_quota(&self) -> Option<usize> {
+    self.local_storage_config.quota
+  }
+
+  /// The proxy configured in the configuration file, which replaces the
+  /// `HTTP_PROXY` and `HTTPS_PROXY` environment variables.
+  pub fn proxy(&self) -> Option<&Proxy> {
+    self.proxy.as_ref()
+  }
+
   pub fn maybe_custom_root(&self) -> &Option<PathBuf> {
     &self.flags.cache_path
   }
@@ -1304,6 +1335,167 @@ fn resolve_vendor_folder(
   }
 }
 
+/// The `"localStorage"` settings of a configuration file, or their flag
+/// equivalents.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LocalStorageConfig {
+  /// The folder holding one sub folder per storage origin.
+  pub dir: Option<PathBuf>,
+  /// The maximum number of bytes an origin may store.
+  pub quota: Option<usize>,
+}
+
+fn parse_local_storage_config(
+  value: serde_json::Value,
+  config_dir: &Path,
+) -> Result<LocalStorageConfig, AnyError> {
+  let serde_json::Value::Object(mut map) = value else {
+    bail!("Expected \"localStorage\" to be an object.");
+  };
+  let dir = match map.remove("dir") {
+    None | Some(serde_json::Value::Null) => None,
+    Some(serde_json::Value::String(dir)) => Some(config_dir.join(dir)),
+    Some(_) => bail!("Expected \"localStorage.dir\" to be a string."),
+  };
+  let quota = match map.remove("quota") {
+    None | Some(serde_json::Value::Null) => None,
+    Some(serde_json::Value::Number(quota)) => Some(
+      quota
+        .as_u64()
+        .and_then(|quota| usize::try_from(quota).ok())
+        .filter(|quota| *quota > 0)
+        .ok_or_else(|| {
+          anyhow!("Expected \"localStorage.quota\" to be a positive integer.")
+        })?,
+    ),
+    Some(serde_json::Value::String(quota)) => Some(
+      parse_storage_quota(&quota)
+        .map_err(|err| anyhow!("Invalid \"localStorage.quota\": {err}"))?,
+    ),
+    Some(_) => {
+      bail!("Expected \"localStorage.quota\" to be a number or a size string.")
+    }
+  };
+  if let Some(key) = map.keys().next() {
+    bail!("Unknown key \"{key}\" in \"localStorage\".");
+  }
+  Ok(LocalStorageConfig { dir, quota })
+}
+
+/// Reads a key that is not part of the typed configuration from the original
+/// configuration file.
+fn read_config_file_key(
+  config_file: &ConfigFile,
+  key: &str,
+) -> Result<Option<serde_json::Value>, AnyError> {
+  let Ok(config_path) = config_file.specifier.to_file_path() else {
+    return Ok(None);
+  };
+  let text = std::fs::read_to_string(&config_path).with_context(|| {
+    format!("Failed reading config file: {}", config_path.display())
+  })?;
+  let value = jsonc_parser::parse_to_serde_value(&text, &Default::default())
+    .map_err(|err| anyhow!("Failed parsing config file: {err}"))?;
+  let Some(serde_json::Value::Object(mut map)) = value else {
+    return Ok(None);
+  };
+  Ok(map.remove(key))
+}
+
+fn read_local_storage_config(
+  config_file: &ConfigFile,
+) -> Result<Option<LocalStorageConfig>, AnyError> {
+  let Some(value) = read_config_file_key(config_file, "localStorage")? else {
+    return Ok(None);
+  };
+  let config_path = config_file.specifier.to_file_path().unwrap();
+  parse_local_storage_config(value, config_path.parent().unwrap())
+    .with_context(|| {
+      format!("Failed resolving config file: {}", config_file.specifier)
+    })
+    .map(Some)
+}
+
+/// Parses the `"proxy"` setting of a configuration file. The hosts listed in
+/// `no_proxy`, the value of the `NO_PROXY` environment variable, are
+/// connected to directly.
+fn parse_proxy_config(
+  value: serde_json::Value,
+  no_proxy: Option<&str>,
+) -> Result<Proxy, AnyError> {
+  let mut proxy: Proxy = serde_json::from_value(value)
+    .map_err(|err| anyhow!("Invalid \"proxy\": {err}"))?;
+  for (_, server) in proxy.servers() {
+    let url = Url::parse(server.url)
+      .map_err(|err| anyhow!("Invalid proxy URL \"{}\": {err}", server.url))?;
+    if !matches!(url.scheme(), "http" | "https" | "socks5" | "socks5h") {
+      bail!("Unsupported proxy scheme \"{}\".", url.scheme());
+    }
+  }
+
+  let mut hosts = vec![];
+  for host in no_proxy.unwrap_or_default().split(',') {
+    let host = host.trim();
+    if host == "*" {
+      hosts.push(host.to_string());
+      continue;
+    }
+    let domain = host.trim_start_matches('.');
+    if !domain.is_empty() {
+      // like with curl, a domain also matches its subdomains
+      hosts.push(domain.to_string());
+      hosts.push(format!("*.{domain}"));
+    }
+  }
+  if !hosts.is_empty() {
+    proxy.rules.insert(
+      0,
+      ProxyRule {
+        hosts,
+        ..Default::default()
+      },
+    );
+  }
+  Ok(proxy)
+}
+
+fn read_proxy_config(
+  config_file: &ConfigFile,
+) -> Result<Option<Proxy>, AnyError> {
+  let Some(value) = read_config_file_key(config_file, "proxy")? else {
+    return Ok(None);
+  };
+  let no_proxy = env::var("NO_PROXY").or_else(|_| env::var("no_proxy")).ok();
+  parse_proxy_config(value, no_proxy.as_deref())
+    .with_context(|| {
+      format!("Failed resolving config file: {}", config_file.specifier)
+    })
+    .map(Some)
+}
+
+/// Resolves the `localStorage` settings. Flags take precedence over the
+/// configuration file.
+fn resolve_local_storage_config(
+  cwd: &Path,
+  flags: &Flags,
+  maybe_config_file: Option<&ConfigFile>,
+) -> Result<LocalStorageConfig, AnyError> {
+  let config = match maybe_config_file {
+    Some(config_file) => {
+      read_local_storage_config(config_file)?.unwrap_or_default()
+    }
+    None => LocalStorageConfig::default(),
+  };
+  Ok(LocalStorageConfig {
+    dir: flags
+      .local_storage_dir
+      .as_ref()
+      .map(|dir| cwd.join(dir))
+      .or(config.dir),
+    quota: flags.local_storage_quota.or(config.quota),
+  })
+}
+
 fn resolve_import_map_specifier(
   maybe_import_map_path: Option<&str>,
   maybe_config_file: Option<&ConfigFile>,
@@ -1398,6 +1590,15 @@ impl StorageKeyResolver {
     Self(Some(None))
   }
 
+  /// Resolves the storage key when it does not depend on the main module,
+  /// which is the case when a location or a config file is used. Returns
+  /// `None` when the main module is needed.
+  pub fn resolve_storage_key_without_main_module(
+    &self,
+  ) -> Option<Option<String>> {
+    self.0.clone()
+  }
+
   /// Resolves the storage key to use based on the current flags, config, or main module.
   pub fn resolve_storage_key(
     &self,
@@ -1692,4 +1893,92 @@ mod test {
       ]
     )
   }
+
+  #[test]
+  fn local_storage_config() {
+    let config_dir = PathBuf::from("/deno");
+    let config = parse_local_storage_config(
+      serde_json::json!({ "quota": "50m", "dir": "./storage" }),
+      &config_dir,
+    )
+    .unwrap();
+    assert_eq!(
+      config,
+      LocalStorageConfig {
+        dir: Some(config_dir.join("./storage")),
+        quota: Some(50 * 1024 * 1024),
+      }
+    );
+
+    let config = parse_local_storage_config(
+      serde_json::json!({ "quota": 1024 }),
+      &config_dir,
+    )
+    .unwrap();
+    assert_eq!(config.quota, Some(1024));
+    assert_eq!(config.dir, None);
+
+    assert!(parse_local_storage_config(
+      serde_json::json!({ "quota": -1 }),
+      &config_dir
+    )
+    .is_err());
+    assert!(parse_local_storage_config(
+      serde_json::json!({ "size": 1 }),
+      &config_dir
+    )
+    .is_err());
+    assert!(
+      parse_local_storage_config(serde_json::json!(true), &config_dir).is_err()
+    );
+  }
+
+  #[test]
+  fn local_storage_flags_override_config() {
+    let cwd = PathBuf::from("/cwd");
+    let flags = Flags {
+      local_storage_dir: Some(PathBuf::from("data")),
+      local_storage_quota: Some(1),
+      ..Default::default()
+    };
+    assert_eq!(
+      resolve_local_storage_config(&cwd, &flags, None).unwrap(),
+      LocalStorageConfig {
+        dir: Some(cwd.join("data")),
+        quota: Some(1),
+      }
+    );
+  }
+
+  #[test]
+  fn proxy_config() {
+    let proxy = parse_proxy_config(
+      serde_json::json!({
+        "url": "socks5h://proxy.corp.example:1080",
+        "rules": [
+          { "hosts": ["*.internal.example"], "url": "http://10.0.0.1:3128", "bearerToken": "abc" },
+          { "hosts": ["direct.example"] },
+        ],
+      }),
+      Some("localhost, .svc.example"),
+    )
+    .unwrap();
+    assert_eq!(proxy.rules.len(), 3);
+    assert!(proxy.for_host("localhost").is_none());
+    assert!(proxy.for_host("api.svc.example").is_none());
+    assert!(proxy.for_host("direct.example").is_none());
+    let server = proxy.for_host("git.internal.example").unwrap();
+    assert_eq!(server.url, "http://10.0.0.1:3128");
+    assert_eq!(server.authorization().as_deref(), Some("Bearer abc"));
+    assert_eq!(
+      proxy.for_host("deno.land").unwrap().url,
+      "socks5h://proxy.corp.example:1080"
+    );
+
+    assert!(parse_proxy_config(
+      serde_json::json!({ "url": "ftp://proxy.example" }),
+      None
+    )
+    .is_err());
+  }
 }
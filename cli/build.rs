@@ -371,8 +371,9 @@ fn create_cli_snapshot(snapshot_path: PathBuf) -> CreateSnapshotOutput {
       "".to_owned(),
       None,
       None,
+      None,
     ),
-    deno_webstorage::deno_webstorage::init_ops(None),
+    deno_webstorage::deno_webstorage::init_ops(None, None),
     deno_crypto::deno_crypto::init_ops(None),
     deno_broadcast_channel::deno_broadcast_channel::init_ops(
       deno_broadcast_channel::InMemoryBroadcastChannel::default(),
@@ -258,10 +258,13 @@ impl CliFactory {
 
   pub fn http_client(&self) -> &Arc<HttpClient> {
     self.services.http_client.get_or_init(|| {
-      Arc::new(HttpClient::new(
-        Some(self.root_cert_store_provider().clone()),
-        self.options.unsafely_ignore_certificate_errors().clone(),
-      ))
+      Arc::new(
+        HttpClient::new(
+          Some(self.root_cert_store_provider().clone()),
+          self.options.unsafely_ignore_certificate_errors().clone(),
+        )
+        .with_proxy(self.options.proxy().cloned()),
+      )
     })
   }
 
@@ -282,6 +285,20 @@ impl CliFactory {
     self.services.fs.get_or_init(|| Arc::new(deno_fs::RealFs))
   }
 
+  /// The file system exposed to user code through the `Deno` APIs. This is
+  /// the real file system unless `deno test --fs=memory` was requested.
+  /// Modules, including npm packages, are always loaded from the real file
+  /// system.
+  fn worker_fs(&self) -> Result<Arc<dyn deno_fs::FileSystem>, AnyError> {
+    match self.options.sub_command() {
+      DenoSubcommand::Test(test_flags) if test_flags.in_memory_fs => {
+        let fs = deno_fs::InMemoryFs::with_cwd(self.options.initial_cwd())?;
+        Ok(Arc::new(fs))
+      }
+      _ => Ok(self.fs().clone()),
+    }
+  }
+
   pub fn maybe_lockfile(&self) -> &Option<Arc<Mutex<Lockfile>>> {
     self
       .services
@@ -599,7 +616,8 @@ impl CliFactory {
         ),
       )),
       self.root_cert_store_provider().clone(),
-      self.fs().clone(),
+      self.worker_fs()?,
+      fs.clone(),
       self.maybe_inspector_server().clone(),
       self.maybe_lockfile().clone(),
       self.create_cli_main_worker_options()?,
@@ -633,11 +651,14 @@ impl CliFactory {
         maybe_binary_command_name
       },
       origin_data_folder_path: Some(self.deno_dir()?.origin_data_folder_path()),
+      local_storage_folder_path: self.options.local_storage_dir(),
+      local_storage_quota: self.options.local_storage_quota(),
       seed: self.options.seed(),
       unsafely_ignore_certificate_errors: self
         .options
         .unsafely_ignore_certificate_errors()
         .clone(),
+      proxy: self.options.proxy().cloned(),
       unstable: self.options.unstable(),
       maybe_root_package_json_deps: self.options.maybe_package_json_deps(),
     })
@@ -16,6 +16,7 @@ use deno_runtime::deno_fetch::reqwest;
 use deno_runtime::deno_fetch::reqwest::header::LOCATION;
 use deno_runtime::deno_fetch::reqwest::Response;
 use deno_runtime::deno_fetch::CreateHttpClientOptions;
+use deno_runtime::deno_tls::Proxy;
 use deno_runtime::deno_tls::RootCertStoreProvider;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -248,6 +249,13 @@ impl HttpClient {
     }
   }
 
+  /// Sends the requests through `proxy` instead of the proxies configured
+  /// with the `HTTP_PROXY` and `HTTPS_PROXY` environment variables.
+  pub fn with_proxy(mut self, proxy: Option<Proxy>) -> Self {
+    self.options.proxy = proxy;
+    self
+  }
+
   #[cfg(test)]
   pub fn from_client(client: reqwest::Client) -> Self {
     let result = Self {
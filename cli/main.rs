@@ -38,6 +38,7 @@ use deno_core::error::JsError;
 use deno_core::futures::FutureExt;
 use deno_core::unsync::JoinHandle;
 use deno_runtime::colors;
+use deno_runtime::deno_telemetry::OtelExport;
 use deno_runtime::fmt_errors::format_js_error;
 use deno_runtime::tokio_util::create_and_run_current_thread_with_maybe_metrics;
 use factory::CliFactory;
@@ -123,6 +124,9 @@ async fn run_subcommand(flags: Flags) -> Result<i32, AnyError> {
     DenoSubcommand::Coverage(coverage_flags) => spawn_subcommand(async {
       tools::coverage::cover_files(flags, coverage_flags).await
     }),
+    DenoSubcommand::Ffi(ffi_flags) => {
+      spawn_subcommand(async { tools::ffi::ffi(ffi_flags).await })
+    }
     DenoSubcommand::Fmt(fmt_flags) => {
       spawn_subcommand(
         async move { tools::fmt::format(flags, fmt_flags).await },
@@ -165,6 +169,9 @@ async fn run_subcommand(flags: Flags) -> Result<i32, AnyError> {
         tools::run::run_script(flags, run_flags).await
       }
     }),
+    DenoSubcommand::Storage(storage_flags) => spawn_subcommand(async {
+      tools::storage::storage(flags, storage_flags).await
+    }),
     DenoSubcommand::Task(task_flags) => spawn_subcommand(async {
       tools::task::execute_script(flags, task_flags).await
     }),
@@ -251,6 +258,7 @@ fn unwrap_or_exit<T>(result: Result<T, AnyError>) -> T {
         colors::red_bold("error"),
         error_string.trim_start_matches("error: ")
       );
+      deno_runtime::deno_telemetry::flush();
       std::process::exit(error_code);
     }
   }
@@ -310,11 +318,16 @@ pub fn main() {
 
     util::logger::init(flags.log_level);
 
+    if let Some(export) = &flags.otel_export {
+      deno_runtime::deno_telemetry::init(OtelExport::parse(export))?;
+    }
+
     run_subcommand(flags).await
   };
 
   let exit_code =
     unwrap_or_exit(create_and_run_current_thread_with_maybe_metrics(future));
 
+  deno_runtime::deno_telemetry::flush();
   std::process::exit(exit_code);
 }
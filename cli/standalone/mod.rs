@@ -431,6 +431,7 @@ pub async fn run(
     Default::default(),
     Box::new(module_loader_factory),
     root_cert_store_provider,
+    fs.clone(),
     fs,
     None,
     None,
@@ -451,9 +452,12 @@ pub async fn run(
       .ok()
       .map(|req_ref| npm_pkg_req_ref_to_binary_command(&req_ref)),
       origin_data_folder_path: None,
+      local_storage_folder_path: None,
+      local_storage_quota: None,
       seed: metadata.seed,
       unsafely_ignore_certificate_errors: metadata
         .unsafely_ignore_certificate_errors,
+      proxy: None,
       unstable: metadata.unstable,
       maybe_root_package_json_deps: package_json_deps_provider.deps().cloned(),
     },
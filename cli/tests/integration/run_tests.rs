@@ -3989,6 +3989,37 @@ async fn test_resolve_dns() {
     assert_eq!(expected, out);
   }
 
+  // Pass: `Deno.DnsResolver`
+  {
+    let output = util::deno_cmd()
+      .current_dir(util::testdata_path())
+      .env("NO_COLOR", "1")
+      .arg("run")
+      .arg("--check")
+      .arg("--unstable")
+      .arg("--allow-net")
+      .arg("run/resolve_dns_resolver.ts")
+      .stdout(std::process::Stdio::piped())
+      .stderr(std::process::Stdio::piped())
+      .spawn()
+      .unwrap()
+      .wait_with_output()
+      .unwrap();
+    let err = String::from_utf8_lossy(&output.stderr);
+    let out = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() {
+      eprintln!("stderr: {err}");
+    }
+    assert!(output.status.success());
+    assert!(err.starts_with("Check file"));
+
+    let expected = std::fs::read_to_string(
+      util::testdata_path().join("run/resolve_dns_resolver.ts.out"),
+    )
+    .unwrap();
+    assert_eq!(expected, out);
+  }
+
   // Permission error: `--allow-net=deno.land`
   {
     let output = util::deno_cmd()
@@ -4706,3 +4737,97 @@ itest!(explicit_resource_management {
   args: "run --quiet --check run/explicit_resource_management/main.ts",
   output: "run/explicit_resource_management/main.out",
 });
+
+#[test]
+fn otel_export_file() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write(
+    "main.ts",
+    r#"let resolvePort: (port: number) => void;
+const port = new Promise<number>((resolve) => resolvePort = resolve);
+const server = Deno.serve(
+  { port: 0, onListen: ({ port }) => resolvePort(port) },
+  async (req) => {
+    const url = new URL(req.url);
+    if (url.pathname === "/inner") {
+      return new Response(req.headers.get("traceparent"));
+    }
+    await new Promise((resolve) => setTimeout(resolve, 1));
+    const inner = await fetch(new URL("/inner", url));
+    return new Response(await inner.text());
+  },
+);
+const res = await fetch(`http://localhost:${await port}/outer`);
+console.log((await res.text()).startsWith("00-"));
+const unsampled = await fetch(`http://localhost:${await port}/outer`, {
+  headers: {
+    traceparent: "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-00",
+  },
+});
+const [, traceId, , flags] = (await unsampled.text()).split("-");
+console.log(traceId, flags);
+await server.shutdown();
+await new Deno.Command(Deno.execPath(), { args: ["eval", "1"] }).output();
+"#,
+  );
+  context
+    .new_command()
+    .args("run -A --otel-export=spans.jsonl main.ts")
+    .run()
+    .assert_matches_text("true\n0af7651916cd43dd8448eb211c80319c 00\n");
+
+  let spans = temp_dir
+    .read_to_string("spans.jsonl")
+    .lines()
+    .flat_map(|line| {
+      let request: deno_core::serde_json::Value =
+        deno_core::serde_json::from_str(line).unwrap();
+      request["resourceSpans"][0]["scopeSpans"][0]["spans"]
+        .as_array()
+        .unwrap()
+        .clone()
+    })
+    .collect::<Vec<_>>();
+  let find_span = |kind: u64, name: &str, path: Option<&str>| {
+    let path = path.map(
+      |path| json!({ "key": "url.path", "value": { "stringValue": path } }),
+    );
+    spans
+      .iter()
+      .find(|span| {
+        span["kind"] == kind
+          && span["name"].as_str().unwrap().starts_with(name)
+          && path.as_ref().map_or(true, |path| {
+            span["attributes"].as_array().unwrap().contains(path)
+          })
+      })
+      .unwrap_or_else(|| panic!("no {name} span in {spans:#?}"))
+  };
+  let outer_server = find_span(2, "GET", Some("/outer"));
+  let inner_server = find_span(2, "GET", Some("/inner"));
+  let outer_client = spans
+    .iter()
+    .find(|span| span["spanId"] == outer_server["parentSpanId"])
+    .unwrap();
+  let inner_client = spans
+    .iter()
+    .find(|span| span["spanId"] == inner_server["parentSpanId"])
+    .unwrap();
+  // The server span continues the trace propagated by `fetch`, and `fetch`
+  // calls made by the handler continue the trace of the server span.
+  assert_eq!(outer_client["kind"], 3);
+  assert_eq!(inner_client["kind"], 3);
+  assert_eq!(inner_client["parentSpanId"], outer_server["spanId"]);
+  for span in [outer_server, inner_server, inner_client] {
+    assert_eq!(span["traceId"], outer_client["traceId"]);
+  }
+  // Nothing of the trace that was not sampled is exported.
+  assert!(!spans
+    .iter()
+    .any(|span| span["traceId"] == "0af7651916cd43dd8448eb211c80319c"));
+  let spawn = find_span(1, "spawn ", None);
+  assert!(spawn["attributes"].as_array().unwrap().contains(
+    &json!({ "key": "process.exit.code", "value": { "intValue": "0" } })
+  ));
+}
@@ -555,6 +555,14 @@ itest!(package_json_basic {
   exit_code: 0,
 });
 
+itest!(in_memory_fs {
+  args: "test -A --fs=memory test/in_memory_fs/main_test.ts",
+  output: "test/in_memory_fs/main_test.out",
+  envs: env_vars_for_npm_tests(),
+  http_server: true,
+  exit_code: 0,
+});
+
 itest!(test_lock {
   args: "test",
   http_server: true,
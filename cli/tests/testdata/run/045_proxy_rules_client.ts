@@ -0,0 +1,25 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+const client = Deno.createHttpClient({
+  proxy: {
+    url: "http://localhost:4555",
+    bearerToken: "token",
+    rules: [
+      // connected to directly
+      { hosts: ["127.0.0.1"] },
+    ],
+  },
+});
+
+const proxied = await fetch(
+  "http://localhost:4545/run/045_mod.ts",
+  { client },
+);
+await proxied.text();
+const direct = await fetch(
+  "http://127.0.0.1:4545/run/045_mod.ts",
+  { client },
+);
+await direct.text();
+
+client.close();
@@ -112,10 +112,41 @@ async function testFetchProgrammaticProxy() {
   assertEquals(code, 0);
 }
 
+async function testFetchProxyRules() {
+  const { code } = await new Deno.Command(Deno.execPath(), {
+    args: [
+      "run",
+      "--quiet",
+      "--reload",
+      "--allow-net=localhost:4545,127.0.0.1:4545,localhost:4555",
+      "--unstable",
+      "run/045_proxy_rules_client.ts",
+    ],
+  }).output();
+  assertEquals(code, 0);
+}
+
+async function testModuleDownloadConfigProxy() {
+  const { code } = await new Deno.Command(Deno.execPath(), {
+    args: [
+      "cache",
+      "--reload",
+      "--quiet",
+      "--config",
+      "run/045_proxy_config.json",
+      "http://localhost:4545/run/045_mod.ts",
+    ],
+  }).output();
+
+  assertEquals(code, 0);
+}
+
 proxyServer();
 await testFetch();
 await testModuleDownload();
 await testFetchNoProxy();
 await testModuleDownloadNoProxy();
 await testFetchProgrammaticProxy();
+await testFetchProxyRules();
+await testModuleDownloadConfigProxy();
 Deno.exit(0);
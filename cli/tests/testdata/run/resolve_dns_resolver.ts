@@ -0,0 +1,45 @@
+const resolver = new Deno.DnsResolver({
+  nameServer: { ipAddr: "127.0.0.1", port: 4553, protocol: "tcp" },
+  searchDomains: ["example.com"],
+  timeout: 2000,
+});
+
+console.log(JSON.stringify(await resolver.resolve("www", "A")));
+console.log(JSON.stringify(await resolver.resolve("4.3.2.1", "PTR")));
+console.log(
+  JSON.stringify(
+    await Deno.resolveDns("example.com", "TXT", { resolver }),
+  ),
+);
+
+// discover a service through its SRV record and connect to it
+const [srv] = await resolver.resolve("_service._tcp", "SRV");
+console.log(srv.target);
+const listener = Deno.listen({ hostname: "127.0.0.1", port: 0 });
+const { port } = listener.addr as Deno.NetAddr;
+const [conn, accepted] = await Promise.all([
+  Deno.connect({ hostname: srv.target, port, resolver }),
+  listener.accept(),
+]);
+console.log((conn.remoteAddr as Deno.NetAddr).hostname);
+conn.close();
+accepted.close();
+listener.close();
+resolver.close();
+
+try {
+  await Deno.resolveDns("www.example.com", "A", {
+    // @ts-ignore testing invalid resolver
+    resolver: {},
+  });
+} catch (e) {
+  console.log(e.message);
+}
+
+try {
+  new Deno.DnsResolver({
+    nameServer: { ipAddr: "127.0.0.1", protocol: "tls" },
+  });
+} catch (e) {
+  console.log(e.message);
+}
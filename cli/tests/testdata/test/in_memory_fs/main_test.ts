@@ -0,0 +1,21 @@
+import cjsDefault from "npm:@denotest/cjs-default-export";
+import { assertEquals } from "../../../../../test_util/std/testing/asserts.ts";
+
+Deno.test("npm packages load from the real file system", () => {
+  assertEquals(cjsDefault(), 1);
+});
+
+Deno.test("files are written to memory", async () => {
+  await Deno.writeTextFile("./output.txt", "hello");
+  assertEquals(await Deno.readTextFile("./output.txt"), "hello");
+});
+
+Deno.test("files on disk are not visible", () => {
+  let exists = true;
+  try {
+    Deno.statSync(new URL(import.meta.url).pathname);
+  } catch (error) {
+    exists = !(error instanceof Deno.errors.NotFound);
+  }
+  assertEquals(exists, false);
+});
@@ -0,0 +1,5 @@
+// Each listener keeps one `accept` op pending.
+for (let i = 0; i < 20; i++) {
+  const listener = Deno.listen({ hostname: "127.0.0.1", port: 0 });
+  listener.accept();
+}
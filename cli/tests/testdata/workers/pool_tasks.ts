@@ -0,0 +1,19 @@
+export function add(a: number, b: number) {
+  return a + b;
+}
+
+export async function delayed(value: string, ms: number) {
+  await new Promise((resolve) => setTimeout(resolve, ms));
+  return value;
+}
+
+export function fail(message: string) {
+  throw new Error(message);
+}
+
+export function crash() {
+  setTimeout(() => {
+    throw new Error("boom");
+  });
+  return new Promise(() => {});
+}
@@ -0,0 +1,10 @@
+self.onmessage = (e) => {
+  const port = e.ports[0];
+  port.onmessage = (e) => {
+    const counter = new Int32Array(e.data);
+    Atomics.add(counter, 0, 1);
+    Atomics.notify(counter, 0);
+    port.postMessage("done");
+    port.close();
+  };
+};
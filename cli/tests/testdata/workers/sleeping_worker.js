@@ -0,0 +1,4 @@
+self.onmessage = async (e) => {
+  await new Promise((resolve) => setTimeout(resolve, e.data));
+  postMessage("done");
+};
@@ -6,6 +6,7 @@ import {
   assert,
   assertEquals,
   assertMatch,
+  assertRejects,
   assertThrows,
 } from "../../../../test_util/std/testing/asserts.ts";
 import { deferred } from "../../../../test_util/std/async/deferred.ts";
@@ -832,3 +833,205 @@ Deno.test({
     w.terminate();
   },
 });
+
+Deno.test({
+  name: "worker is terminated when exceeding its CPU time limit",
+  fn: async function () {
+    const worker = new Worker(
+      import.meta.resolve("./busy_worker.js"),
+      { type: "module", deno: { limits: { maxCpuMs: 100 } } },
+    );
+    const errorPromise = deferred<ErrorEvent>();
+    worker.onerror = (e) => {
+      e.preventDefault();
+      errorPromise.resolve(e);
+    };
+    worker.postMessage(null);
+    const event = await errorPromise;
+    assertMatch(event.message, /CPU time limit of 100 ms/);
+    worker.terminate();
+  },
+});
+
+Deno.test({
+  name: "worker waiting on timers doesn't use up its CPU time limit",
+  fn: async function () {
+    const worker = new Worker(
+      import.meta.resolve("./sleeping_worker.js"),
+      { type: "module", deno: { limits: { maxCpuMs: 100 } } },
+    );
+    const messagePromise = deferred<string>();
+    worker.onmessage = (e) => messagePromise.resolve(e.data);
+    worker.onerror = (e) => {
+      e.preventDefault();
+      messagePromise.reject(e.message);
+    };
+    worker.postMessage(300);
+    assertEquals(await messagePromise, "done");
+    worker.terminate();
+  },
+});
+
+Deno.test({
+  name: "worker is terminated when exceeding its heap limit",
+  fn: async function () {
+    const worker = new Worker(
+      import.meta.resolve("./heap_hog_worker.js"),
+      { type: "module", deno: { limits: { maxHeapMB: 16 } } },
+    );
+    const errorPromise = deferred<ErrorEvent>();
+    worker.onerror = (e) => {
+      e.preventDefault();
+      errorPromise.resolve(e);
+    };
+    const event = await errorPromise;
+    assertMatch(event.message, /heap limit of 16 MB/);
+    worker.terminate();
+  },
+});
+
+Deno.test({
+  name: "worker is terminated when exceeding its pending ops limit",
+  fn: async function () {
+    const worker = new Worker(
+      import.meta.resolve("./pending_ops_worker.js"),
+      { type: "module", deno: { limits: { maxPendingOps: 10 } } },
+    );
+    const errorPromise = deferred<ErrorEvent>();
+    worker.onerror = (e) => {
+      e.preventDefault();
+      errorPromise.resolve(e);
+    };
+    const event = await errorPromise;
+    assertMatch(event.message, /limit of 10 pending ops/);
+    worker.terminate();
+  },
+});
+
+Deno.test({
+  name: "worker transferable streams",
+  fn: async function () {
+    const worker = new Worker(
+      import.meta.resolve("./transfer_stream.ts"),
+      { type: "module" },
+    );
+    const readable = new ReadableStream<number>({
+      start(controller) {
+        for (let i = 1; i <= 3; i++) {
+          controller.enqueue(i);
+        }
+        controller.close();
+      },
+    });
+    const chunks: number[] = [];
+    const writable = new WritableStream<number>({
+      write(chunk) {
+        chunks.push(chunk);
+      },
+    });
+    const { readable: doubled, writable: sink } = new TransformStream<
+      number,
+      number
+    >();
+    const done = doubled.pipeTo(writable);
+    worker.postMessage({ readable, writable: sink }, [readable, sink]);
+    assert(readable.locked);
+    assert(sink.locked);
+    assertThrows(
+      () => worker.postMessage(readable, [readable]),
+      DOMException,
+      "Can not transfer locked ReadableStream",
+    );
+    await done;
+    assertEquals(chunks, [2, 4, 6]);
+    worker.terminate();
+  },
+});
+
+Deno.test({
+  name: "worker SharedArrayBuffer over a transferred MessagePort",
+  fn: async function () {
+    const worker = new Worker(
+      import.meta.resolve("./shared_array_buffer_port.ts"),
+      { type: "module" },
+    );
+    const channel = new MessageChannel();
+    worker.postMessage(null, [channel.port2]);
+    const sab = new SharedArrayBuffer(4);
+    const counter = new Int32Array(sab);
+    const promise = deferred();
+    channel.port1.onmessage = () => promise.resolve();
+    channel.port1.postMessage(sab);
+    await promise;
+    assertEquals(Atomics.load(counter, 0), 1);
+    channel.port1.close();
+    worker.terminate();
+  },
+});
+
+Deno.test({
+  name: "worker pool runs tasks across workers",
+  fn: async function () {
+    const pool = new Deno.WorkerPool(import.meta.resolve("./pool_tasks.ts"), {
+      size: 2,
+      maxQueue: 1,
+    });
+    assertEquals(pool.size, 2);
+    const results = await Promise.all([
+      pool.run("delayed", ["a", 50]),
+      pool.run("delayed", ["b", 10]),
+      pool.run("add", [1, 2]),
+      pool.run("add", [3, 4]),
+    ]);
+    assertEquals(results, ["a", "b", 3, 7]);
+
+    await assertRejects(() => pool.run("fail", ["nope"]), Error, "nope");
+    await assertRejects(
+      () => pool.run("missing"),
+      TypeError,
+      'Module does not export a function named "missing"',
+    );
+
+    const stats = pool.stats();
+    assertEquals(stats.queued, 0);
+    assertEquals(
+      stats.workers.reduce((sum, w) => sum + w.tasksCompleted, 0),
+      4,
+    );
+    assertEquals(stats.workers.reduce((sum, w) => sum + w.tasksFailed, 0), 2);
+    pool.terminate();
+    await assertRejects(() => pool.run("add", [1, 1]), Error, "terminated");
+  },
+});
+
+Deno.test({
+  name: "worker pool rejects an empty queue",
+  fn: function () {
+    for (const maxQueue of [0, -1, 1.5]) {
+      assertThrows(
+        () =>
+          new Deno.WorkerPool(import.meta.resolve("./pool_tasks.ts"), {
+            size: 1,
+            maxQueue,
+          }),
+        RangeError,
+        "WorkerPool maxQueue must be a positive integer",
+      );
+    }
+  },
+});
+
+Deno.test({
+  name: "worker pool restarts crashed workers",
+  fn: async function () {
+    const pool = new Deno.WorkerPool(import.meta.resolve("./pool_tasks.ts"), {
+      size: 1,
+    });
+    await assertRejects(() => pool.run("crash"), Error, "crashed");
+    assertEquals(await pool.run("add", [2, 2]), 4);
+    const { workers } = pool.stats();
+    assertEquals(workers[0].restarts, 1);
+    assertEquals(workers[0].tasksCompleted, 1);
+    pool.terminate();
+  },
+});
@@ -0,0 +1,11 @@
+self.onmessage = async (e) => {
+  const { readable, writable } = e.data as {
+    readable: ReadableStream<number>;
+    writable: WritableStream<number>;
+  };
+  const writer = writable.getWriter();
+  for await (const chunk of readable) {
+    await writer.write(chunk * 2);
+  }
+  await writer.close();
+};
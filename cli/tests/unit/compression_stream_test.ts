@@ -0,0 +1,86 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+import { assertEquals, assertRejects, assertThrows } from "./test_util.ts";
+
+const input = new TextEncoder().encode("hello world ".repeat(1000));
+
+async function roundTrip(
+  format: string,
+  options?: CompressionStreamOptions,
+): Promise<Uint8Array> {
+  const compressed = await new Response(
+    new Blob([input]).stream().pipeThrough(
+      new CompressionStream(format, options),
+    ),
+  ).arrayBuffer();
+  const decompressed = await new Response(
+    new Blob([compressed]).stream().pipeThrough(
+      new DecompressionStream(format),
+    ),
+  ).arrayBuffer();
+  return new Uint8Array(decompressed);
+}
+
+Deno.test(async function compressionStreamBrotli() {
+  assertEquals(await roundTrip("br"), input);
+  assertEquals(await roundTrip("br", { quality: 0 }), input);
+  assertEquals(await roundTrip("br", { quality: 11 }), input);
+});
+
+Deno.test(async function compressionStreamZstd() {
+  assertEquals(await roundTrip("zstd"), input);
+  assertEquals(await roundTrip("zstd", { quality: 19 }), input);
+});
+
+Deno.test(async function compressionStreamQuality() {
+  assertEquals(await roundTrip("gzip", { quality: 9 }), input);
+  assertEquals(await roundTrip("deflate-raw", { quality: 0 }), input);
+
+  assertThrows(
+    () => new CompressionStream("gzip", { quality: 10 }),
+    RangeError,
+  );
+  assertThrows(
+    () => new CompressionStream("br", { quality: 12 }),
+    RangeError,
+  );
+  assertThrows(
+    () => new CompressionStream("zstd", { quality: 0 }),
+    RangeError,
+  );
+  assertThrows(
+    () => new CompressionStream("lzma"),
+    TypeError,
+  );
+});
+
+Deno.test(async function decompressionStreamBrotliTruncated() {
+  const compressed = new Uint8Array(
+    await new Response(
+      new Blob([input]).stream().pipeThrough(new CompressionStream("br")),
+    ).arrayBuffer(),
+  );
+  await assertRejects(
+    () =>
+      new Response(
+        new Blob([compressed.subarray(0, compressed.length - 4)]).stream()
+          .pipeThrough(new DecompressionStream("br")),
+      ).arrayBuffer(),
+    TypeError,
+  );
+});
+
+Deno.test(async function decompressionStreamZstdTruncated() {
+  const compressed = new Uint8Array(
+    await new Response(
+      new Blob([input]).stream().pipeThrough(new CompressionStream("zstd")),
+    ).arrayBuffer(),
+  );
+  await assertRejects(
+    () =>
+      new Response(
+        new Blob([compressed.subarray(0, compressed.length - 4)]).stream()
+          .pipeThrough(new DecompressionStream("zstd")),
+      ).arrayBuffer(),
+    TypeError,
+  );
+});
@@ -3,9 +3,11 @@ import {
   assert,
   assertEquals,
   assertRejects,
+  assertThrows,
   deferred,
   delay,
   fail,
+  tmpUnixSocketPath,
   unimplemented,
 } from "./test_util.ts";
 import { Buffer } from "../../../test_util/std/io/buffer.ts";
@@ -1588,6 +1590,334 @@ Deno.test(
   },
 );
 
+Deno.test(
+  { permissions: { net: true } },
+  async function createHttpClientRetry() {
+    let attempts = 0;
+    const ac = new AbortController();
+    const listening = deferred();
+    const server = Deno.serve({
+      handler: () => {
+        attempts++;
+        return new Response(attempts < 3 ? "unavailable" : "ok", {
+          status: attempts < 3 ? 503 : 200,
+        });
+      },
+      port: listenPort,
+      signal: ac.signal,
+      onListen: () => listening.resolve(),
+    });
+    await listening;
+
+    const client = Deno.createHttpClient({
+      retry: { maxRetries: 3, initialDelay: 1 },
+    });
+    const res = await fetch(`http://127.0.0.1:${listenPort}/`, { client });
+    assertEquals(await res.text(), "ok");
+    assertEquals(attempts, 3);
+
+    // Non-idempotent requests are sent exactly once
+    attempts = 0;
+    const post = await fetch(`http://127.0.0.1:${listenPort}/`, {
+      method: "POST",
+      body: "data",
+      client,
+    });
+    assertEquals(post.status, 503);
+    await post.body?.cancel();
+    assertEquals(attempts, 1);
+
+    client.close();
+    ac.abort();
+    await server.finished;
+  },
+);
+
+Deno.test(
+  { permissions: { net: true } },
+  async function createHttpClientTimeout() {
+    const ac = new AbortController();
+    const listening = deferred();
+    const server = Deno.serve({
+      handler: async () => {
+        await delay(1000);
+        return new Response("too late");
+      },
+      port: listenPort,
+      signal: ac.signal,
+      onListen: () => listening.resolve(),
+    });
+    await listening;
+
+    const client = Deno.createHttpClient({ timeout: 50 });
+    await assertRejects(
+      () => fetch(`http://127.0.0.1:${listenPort}/`, { client }),
+      TypeError,
+    );
+
+    client.close();
+    ac.abort();
+    await server.finished;
+  },
+);
+
+Deno.test(
+  { permissions: { net: true } },
+  async function createHttpClientMaxRedirects() {
+    const ac = new AbortController();
+    const listening = deferred();
+    const server = Deno.serve({
+      handler: (req) => {
+        const n = Number(new URL(req.url).searchParams.get("n"));
+        return n === 0
+          ? new Response("done")
+          : Response.redirect(
+            `http://127.0.0.1:${listenPort}/?n=${n - 1}`,
+            302,
+          );
+      },
+      port: listenPort,
+      signal: ac.signal,
+      onListen: () => listening.resolve(),
+    });
+    await listening;
+
+    const client = Deno.createHttpClient({ maxRedirects: 2 });
+    const res = await fetch(`http://127.0.0.1:${listenPort}/?n=2`, { client });
+    assertEquals(await res.text(), "done");
+    await assertRejects(
+      () => fetch(`http://127.0.0.1:${listenPort}/?n=3`, { client }),
+      TypeError,
+    );
+
+    client.close();
+    ac.abort();
+    await server.finished;
+  },
+);
+
+Deno.test(function createHttpClientInvalidRetryOptions() {
+  assertThrows(
+    () => Deno.createHttpClient({ timeout: -1 }),
+    TypeError,
+    "timeout must be a non-negative integer.",
+  );
+  assertThrows(
+    // deno-lint-ignore no-explicit-any
+    () => Deno.createHttpClient({ retry: {} as any }),
+    TypeError,
+    "retry.maxRetries must be a non-negative integer.",
+  );
+});
+
+Deno.test(
+  { permissions: { net: true } },
+  async function createHttpClientCache() {
+    const requests: Request[] = [];
+    let version = 1;
+    const ac = new AbortController();
+    const listening = deferred();
+    const server = Deno.serve({
+      handler: (req) => {
+        requests.push(req);
+        const url = new URL(req.url);
+        if (req.method === "POST") {
+          version++;
+          return new Response(null, { status: 204 });
+        }
+        const etag = `"v${version}"`;
+        if (url.pathname === "/revalidate") {
+          if (req.headers.get("if-none-match") === etag) {
+            return new Response(null, {
+              status: 304,
+              headers: { etag, "x-revalidated": "yes" },
+            });
+          }
+          return new Response(`revalidate ${version}`, {
+            headers: { etag, "cache-control": "no-cache" },
+          });
+        }
+        if (url.pathname === "/vary") {
+          return new Response(req.headers.get("accept-language"), {
+            headers: {
+              "cache-control": "max-age=60",
+              vary: "accept-language",
+            },
+          });
+        }
+        return new Response(`fresh ${version}`, {
+          headers: { "cache-control": "max-age=60" },
+        });
+      },
+      port: listenPort,
+      signal: ac.signal,
+      onListen: () => listening.resolve(),
+    });
+    await listening;
+
+    const name = "createHttpClientCache";
+    const client = Deno.createHttpClient({ cache: { name } });
+    const base = `http://127.0.0.1:${listenPort}`;
+
+    // Fresh responses are served from the cache
+    let res = await fetch(`${base}/fresh`, { client });
+    assertEquals(await res.text(), "fresh 1");
+    res = await fetch(`${base}/fresh`, { client });
+    assertEquals(await res.text(), "fresh 1");
+    assert(res.headers.has("age"));
+    assertEquals(requests.length, 1);
+
+    // Unless the request asks for a fresh copy
+    res = await fetch(`${base}/fresh`, {
+      client,
+      headers: { "cache-control": "no-cache" },
+    });
+    assertEquals(await res.text(), "fresh 1");
+    assertEquals(requests.length, 2);
+
+    // Unsafe methods invalidate the stored response
+    res = await fetch(`${base}/fresh`, { client, method: "POST" });
+    await res.body?.cancel();
+    res = await fetch(`${base}/fresh`, { client });
+    assertEquals(await res.text(), "fresh 2");
+    assertEquals(requests.length, 4);
+
+    // Stale responses are revalidated with a conditional request
+    requests.length = 0;
+    res = await fetch(`${base}/revalidate`, { client });
+    assertEquals(await res.text(), "revalidate 2");
+    res = await fetch(`${base}/revalidate`, { client });
+    assertEquals(res.status, 200);
+    assertEquals(res.headers.get("x-revalidated"), "yes");
+    assertEquals(await res.text(), "revalidate 2");
+    assertEquals(requests.length, 2);
+    assertEquals(requests[1].headers.get("if-none-match"), '"v2"');
+
+    // Responses are selected by the request headers named in Vary
+    requests.length = 0;
+    for (const lang of ["en", "en", "de"]) {
+      res = await fetch(`${base}/vary`, {
+        client,
+        headers: { "accept-language": lang },
+      });
+      assertEquals(await res.text(), lang);
+    }
+    assertEquals(requests.length, 2);
+
+    // Clients without the option don't use the cache
+    res = await fetch(`${base}/fresh`);
+    assertEquals(await res.text(), "fresh 2");
+    assertEquals(requests.length, 3);
+
+    client.close();
+    ac.abort();
+    await server.finished;
+    await caches.delete(name);
+  },
+);
+
+Deno.test(function createHttpClientInvalidCacheOptions() {
+  assertThrows(
+    // deno-lint-ignore no-explicit-any
+    () => Deno.createHttpClient({ cache: "yes" as any }),
+    TypeError,
+    "cache must be a boolean or an object.",
+  );
+  assertThrows(
+    // deno-lint-ignore no-explicit-any
+    () => Deno.createHttpClient({ cache: { shared: 1 as any } }),
+    TypeError,
+    "cache.shared must be a boolean.",
+  );
+});
+
+Deno.test(
+  {
+    ignore: Deno.build.os === "windows",
+    permissions: { read: true, write: true },
+  },
+  async function createHttpClientUnixSocket() {
+    const unixSocket = tmpUnixSocketPath();
+    const listener = Deno.listen({ path: unixSocket, transport: "unix" });
+    const server = (async () => {
+      const conn = await listener.accept();
+      const httpConn = Deno.serveHttp(conn);
+      const event = await httpConn.nextRequest();
+      assert(event);
+      const { request, respondWith } = event;
+      assertEquals(request.method, "POST");
+      assertEquals(new URL(request.url).pathname, "/containers/create");
+      assertEquals(request.headers.get("host"), "docker");
+      assertEquals(await request.text(), "{}");
+      await respondWith(Response.json({ Id: "abc" }, { status: 201 }));
+      httpConn.close();
+    })();
+
+    const client = Deno.createHttpClient({ unixSocket });
+    const res = await fetch("http://docker/containers/create", {
+      method: "POST",
+      body: "{}",
+      client,
+    });
+    assertEquals(res.status, 201);
+    assertEquals(await res.json(), { Id: "abc" });
+    await server;
+
+    await assertRejects(
+      () => fetch("https://docker/_ping", { client }),
+      TypeError,
+      "Only http: URLs can be fetched over a Unix socket",
+    );
+
+    client.close();
+    listener.close();
+  },
+);
+
+Deno.test(
+  {
+    ignore: Deno.build.os === "windows",
+    permissions: { read: true, write: true },
+  },
+  async function createHttpClientUnixSocketTimeoutCoversBody() {
+    const unixSocket = tmpUnixSocketPath();
+    const listener = Deno.listen({ path: unixSocket, transport: "unix" });
+    const server = (async () => {
+      const conn = await listener.accept();
+      const httpConn = Deno.serveHttp(conn);
+      const event = await httpConn.nextRequest();
+      assert(event);
+      // Send the head and part of the body, then stall
+      const body = new ReadableStream({
+        start(controller) {
+          controller.enqueue(new TextEncoder().encode("partial"));
+        },
+      });
+      await event.respondWith(new Response(body)).catch(() => {});
+      httpConn.close();
+    })();
+
+    const client = Deno.createHttpClient({ unixSocket, timeout: 200 });
+    const res = await fetch("http://localhost/", { client });
+    assertEquals(res.status, 200);
+    await assertRejects(() => res.text(), TypeError);
+    await server;
+
+    client.close();
+    listener.close();
+  },
+);
+
+Deno.test(
+  { permissions: { read: true, write: false } },
+  function createHttpClientUnixSocketPerm() {
+    assertThrows(
+      () => Deno.createHttpClient({ unixSocket: "/var/run/docker.sock" }),
+      Deno.errors.PermissionDenied,
+    );
+  },
+);
+
 Deno.test({ permissions: { read: false } }, async function fetchFilePerm() {
   await assertRejects(async () => {
     await fetch(import.meta.resolve("../testdata/subdir/json_1.json"));
@@ -1242,3 +1242,128 @@ Deno.test({
   const listener = Deno.listen({ hostname: "localhost", port: "0" });
   listener.close();
 });
+
+Deno.test(
+  { permissions: { net: true } },
+  async function netTcpSocketOptions() {
+    const listener = Deno.listen({
+      hostname: "127.0.0.1",
+      port: 0,
+      recvBufferSize: 256 * 1024,
+      ttl: 64,
+      dscp: 46,
+    });
+    const { port } = listener.addr as Deno.NetAddr;
+    const acceptPromise = listener.accept();
+    const conn = await Deno.connect({
+      hostname: "127.0.0.1",
+      port,
+      sendBufferSize: 256 * 1024,
+      ttl: 32,
+      dscp: 10,
+    });
+    const serverConn = await acceptPromise;
+    await conn.write(new Uint8Array([1, 2, 3]));
+    const buf = new Uint8Array(3);
+    assertEquals(await serverConn.read(buf), 3);
+    assertEquals(buf, new Uint8Array([1, 2, 3]));
+    serverConn.close();
+    conn.close();
+    listener.close();
+  },
+);
+
+Deno.test(
+  { permissions: { net: true } },
+  async function netTcpSocketOptionsOutOfRange() {
+    assertThrows(
+      () => Deno.listen({ port: 0, ttl: 0 }),
+      RangeError,
+      "ttl must be between 1 and 255",
+    );
+    assertThrows(
+      () => Deno.listen({ port: 0, dscp: 64 }),
+      RangeError,
+      "dscp must be between 0 and 63",
+    );
+    await assertRejects(
+      () => Deno.connect({ port: listenPort, recvBufferSize: 0 }),
+      RangeError,
+      "recvBufferSize must be greater than 0",
+    );
+    assertThrows(
+      () => Deno.listen({ hostname: "::1", port: 0, dscp: 10 }),
+      Deno.errors.NotSupported,
+    );
+  },
+);
+
+Deno.test(
+  { ignore: Deno.build.os === "linux", permissions: { net: true } },
+  function netTcpBindToDeviceNotSupported() {
+    assertThrows(
+      () => Deno.listen({ port: 0, bindToDevice: "lo" }),
+      Deno.errors.NotSupported,
+    );
+  },
+);
+
+Deno.test(
+  { ignore: Deno.build.os !== "linux", permissions: { net: true } },
+  async function netUdpListenReusePort() {
+    const port = 4005;
+    const socket1 = Deno.listenDatagram({
+      hostname: "127.0.0.1",
+      port,
+      transport: "udp",
+      reusePort: true,
+      recvBufferSize: 1024 * 1024,
+    });
+    const socket2 = Deno.listenDatagram({
+      hostname: "127.0.0.1",
+      port,
+      transport: "udp",
+      reusePort: true,
+    });
+    const sender = Deno.listenDatagram({
+      hostname: "127.0.0.1",
+      port: 0,
+      transport: "udp",
+      ttl: 8,
+      dscp: 46,
+    });
+    // the datagram is delivered to one of the sockets, the receive of the
+    // other one is rejected when it is closed
+    const received = Promise.race([
+      socket1.receive().catch(() => new Promise<never>(() => {})),
+      socket2.receive().catch(() => new Promise<never>(() => {})),
+    ]);
+    await sender.send(new Uint8Array([1]), {
+      transport: "udp",
+      hostname: "127.0.0.1",
+      port,
+    });
+    const [data] = await received;
+    assertEquals(data, new Uint8Array([1]));
+    sender.close();
+    socket1.close();
+    socket2.close();
+  },
+);
+
+Deno.test(
+  { ignore: Deno.build.os === "linux", permissions: { net: true } },
+  function netUdpListenReusePortNotSupported() {
+    assertThrows(
+      () =>
+        Deno.listenDatagram({
+          hostname: "127.0.0.1",
+          port: 0,
+          transport: "udp",
+          reusePort: true,
+        }),
+      Deno.errors.NotSupported,
+      "reusePort is only supported on Linux for UDP sockets",
+    );
+  },
+);
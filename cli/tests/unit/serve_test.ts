@@ -48,7 +48,11 @@ function onListen<T>(
 async function makeServer(
   handler: (req: Request) => Response | Promise<Response>,
 ): Promise<
-  { finished: Promise<void>; abort: () => void; shutdown: () => Promise<void> }
+  {
+    finished: Promise<void>;
+    abort: () => void;
+    shutdown: (options?: Deno.ServerShutdownOptions) => Promise<void>;
+  }
 > {
   const ac = new AbortController();
   const listeningPromise = deferred();
@@ -66,8 +70,8 @@ async function makeServer(
     abort() {
       ac.abort();
     },
-    async shutdown() {
-      await server.shutdown();
+    async shutdown(options?: Deno.ServerShutdownOptions) {
+      await server.shutdown(options);
     },
   };
 }
@@ -296,6 +300,65 @@ Deno.test(
   },
 );
 
+// Ensure that HTTP/1.1 clients are told not to reuse a connection while the server drains
+Deno.test(
+  { permissions: { net: true } },
+  async function httpServerShutdownConnectionClose() {
+    const waitForShutdown = deferred();
+    const waitForRequest = deferred();
+    const { finished, shutdown } = await makeServer(async (_req) => {
+      waitForRequest.resolve(null);
+      await waitForShutdown;
+      return new Response("ok");
+    });
+
+    const conn = await Deno.connect({ port: servePort });
+    await conn.write(
+      new TextEncoder().encode(`GET / HTTP/1.1\nConnection: keep-alive\n\n`),
+    );
+    await waitForRequest;
+    const s = shutdown();
+    waitForShutdown.resolve(null);
+
+    // The server closes the connection after the response, which ends the stream
+    const response = await new Response(conn.readable).text();
+    assertStringIncludes(response, "connection: close");
+    assertStringIncludes(response, "ok");
+    await s;
+    await finished;
+  },
+);
+
+// Ensure that requests still in flight are cancelled once the shutdown timeout expires
+Deno.test(
+  { permissions: { net: true } },
+  async function httpServerShutdownTimeout() {
+    const waitForRequest = deferred();
+    const release = deferred();
+    const { finished, shutdown } = await makeServer(async (_req) => {
+      waitForRequest.resolve(null);
+      await release;
+      return new Response("too late");
+    });
+
+    const f = fetch(`http://localhost:${servePort}`);
+    await waitForRequest;
+    await shutdown({ timeout: 100 });
+    await assertRejects(() => f, TypeError);
+    release.resolve(null);
+    await finished;
+  },
+);
+
+Deno.test(async function httpServerShutdownInvalidTimeout() {
+  const { finished, shutdown, abort } = await makeServer((_req) =>
+    new Response("ok")
+  );
+  await assertRejects(() => shutdown({ timeout: -1 }), TypeError);
+  abort();
+  await finished;
+});
+
 Deno.test(
   { permissions: { read: true, run: true } },
   async function httpServerUnref() {
@@ -2644,6 +2707,218 @@ for (const testCase of compressionTestCases) {
   );
 }
 
+/** Send a raw HTTP/1.1 GET with the given headers and return the lowercased response head. */
+async function readResponseHead(headers: string): Promise<string> {
+  const conn = await Deno.connect({ port: servePort });
+  const encoder = new TextEncoder();
+  const decoder = new TextDecoder();
+  await conn.write(
+    encoder.encode(
+      "GET / HTTP/1.1\r\nHost: example.domain\r\nConnection: close\r\n" +
+        `${headers}\r\n`,
+    ),
+  );
+  let head = "";
+  const buf = new Uint8Array(1024);
+  while (!head.includes("\r\n\r\n")) {
+    const n = await conn.read(buf);
+    if (n === null) break;
+    head += decoder.decode(buf.subarray(0, n));
+  }
+  conn.close();
+  return head.split("\r\n\r\n")[0].toLowerCase();
+}
+
+const compressionOptionTestCases: {
+  name: string;
+  compression: boolean | Deno.ServeCompressionOptions;
+  acceptEncoding: string;
+  contentType: string;
+  expect: string | null;
+}[] = [
+  {
+    name: "Disabled",
+    compression: false,
+    acceptEncoding: "gzip",
+    contentType: "text/plain",
+    expect: null,
+  },
+  {
+    name: "Zstd",
+    compression: { encodings: ["zstd", "gzip"] },
+    acceptEncoding: "gzip, zstd",
+    contentType: "text/plain",
+    expect: "zstd",
+  },
+  {
+    name: "Preference",
+    compression: { encodings: ["gzip", "br"] },
+    acceptEncoding: "br, gzip",
+    contentType: "text/plain",
+    expect: "gzip",
+  },
+  {
+    name: "ClientQuality",
+    compression: { encodings: ["gzip", "br"] },
+    acceptEncoding: "gzip;q=0.5, br",
+    contentType: "text/plain",
+    expect: "br",
+  },
+  {
+    name: "ContentTypes",
+    compression: { contentTypes: ["Text/Fake"] },
+    acceptEncoding: "gzip",
+    contentType: "text/fake",
+    expect: "gzip",
+  },
+  {
+    name: "MinSize",
+    compression: { minSize: 2048 },
+    acceptEncoding: "gzip",
+    contentType: "text/plain",
+    expect: null,
+  },
+];
+
+for (const testCase of compressionOptionTestCases) {
+  const { name, compression, acceptEncoding, contentType, expect } = testCase;
+  const testName = `httpServerCompressionOption${name}`;
+  Deno.test(
+    { permissions: { net: true } },
+    {
+      [testName]: async function () {
+        const ac = new AbortController();
+        const listeningPromise = deferred();
+        const server = Deno.serve({
+          handler: () =>
+            new Response("a".repeat(1024), {
+              headers: { "content-type": contentType },
+            }),
+          port: servePort,
+          signal: ac.signal,
+          onListen: onListen(listeningPromise),
+          onError: createOnErrorCb(ac),
+          compression,
+        });
+        await listeningPromise;
+
+        const head = await readResponseHead(
+          `Accept-Encoding: ${acceptEncoding}\r\n`,
+        );
+        if (expect === null) {
+          assert(!head.includes("content-encoding:"), head);
+        } else {
+          assert(head.includes(`content-encoding: ${expect}`), head);
+        }
+        // A server that never compresses doesn't need to vary on Accept-Encoding
+        assertEquals(head.includes("vary: accept-encoding"), !!compression);
+
+        ac.abort();
+        await server.finished;
+      },
+    }[testName],
+  );
+}
+
+Deno.test(
+  { permissions: { net: true } },
+  async function httpServerDecompressRequests() {
+    const ac = new AbortController();
+    const listeningPromise = deferred();
+    const server = Deno.serve({
+      handler: async (request) => {
+        assertEquals(request.headers.get("content-encoding"), null);
+        return new Response(await request.text());
+      },
+      port: servePort,
+      signal: ac.signal,
+      onListen: onListen(listeningPromise),
+      onError: createOnErrorCb(ac),
+      compression: { decompressRequests: true },
+    });
+    await listeningPromise;
+
+    const body = await new Response(
+      new Blob(["hello world"]).stream().pipeThrough(
+        new CompressionStream("gzip"),
+      ),
+    ).arrayBuffer();
+    const resp = await fetch(`http://127.0.0.1:${servePort}/`, {
+      method: "POST",
+      headers: { "content-encoding": "gzip" },
+      body,
+    });
+    assertEquals(await resp.text(), "hello world");
+
+    ac.abort();
+    await server.finished;
+  },
+);
+
+Deno.test(
+  { permissions: { net: true } },
+  function httpServerInvalidCompression() {
+    assertThrows(
+      () =>
+        Deno.serve({
+          handler: () => new Response("ok"),
+          port: servePort,
+          // deno-lint-ignore no-explicit-any
+          compression: { encodings: ["deflate"] } as any,
+        }),
+      TypeError,
+      'compression.encodings must be an array of "br", "gzip" or "zstd".',
+    );
+    assertThrows(
+      () =>
+        Deno.serve({
+          handler: () => new Response("ok"),
+          port: servePort,
+          compression: { level: 1.5 },
+        }),
+      TypeError,
+      "compression.level must be a non-negative integer.",
+    );
+  },
+);
+
+Deno.test(
+  {
+    ignore: Deno.build.os === "windows",
+    permissions: { net: true, read: true, write: true },
+  },
+  function httpServerInvalidFd() {
+    const handler = () => new Response("ok");
+    assertThrows(
+      () => Deno.serve({ handler, fd: -1 }),
+      TypeError,
+      'fd must be a non-negative integer or "LISTEN_FDS".',
+    );
+    assertThrows(
+      () => Deno.serve({ handler, fd: 999_999 }),
+      TypeError,
+      "File descriptor 999999 is not a socket",
+    );
+    // The test runner isn't started with systemd socket activation
+    assertThrows(
+      () => Deno.serve({ handler, fd: "LISTEN_FDS" }),
+      Deno.errors.NotFound,
+      "No socket was passed to this process with LISTEN_FDS",
+    );
+  },
+);
+
+Deno.test(
+  { ignore: Deno.build.os !== "windows", permissions: { net: true } },
+  function httpServerFdNotSupported() {
+    assertThrows(
+      () => Deno.serve({ handler: () => new Response("ok"), fd: 3 }),
+      Deno.errors.NotSupported,
+      "Deno.serve() can not listen on a file descriptor on Windows",
+    );
+  },
+);
+
 Deno.test(
   { permissions: { net: true, write: true, read: true } },
   async function httpServerPostFile() {
@@ -2823,6 +3098,495 @@ Deno.test(
   },
 );
 
+Deno.test(
+  { permissions: { read: true, net: true } },
+  async function httpServerWithHttp3() {
+    const ac = new AbortController();
+    const listeningPromise = deferred();
+    const hostname = "127.0.0.1";
+
+    const server = Deno.serve({
+      handler: async (req) =>
+        new Response(`${req.method} ${await req.text()}`),
+      hostname,
+      port: servePort,
+      signal: ac.signal,
+      onListen: onListen(listeningPromise),
+      onError: createOnErrorCb(ac),
+      cert: Deno.readTextFileSync("cli/tests/testdata/tls/localhost.crt"),
+      key: Deno.readTextFileSync("cli/tests/testdata/tls/localhost.key"),
+      http3: true,
+    });
+
+    await listeningPromise;
+    const caCert = Deno.readTextFileSync("cli/tests/testdata/tls/RootCA.pem");
+
+    // HTTP/1.1 and HTTP/2 responses advertise the QUIC listener
+    const tcpClient = Deno.createHttpClient({ caCerts: [caCert] });
+    const tcpResp = await fetch(`https://localhost:${servePort}/`, {
+      client: tcpClient,
+    });
+    assertEquals(
+      tcpResp.headers.get("alt-svc"),
+      `h3=":${servePort}"; ma=86400`,
+    );
+    assertEquals(await tcpResp.text(), "GET ");
+    tcpClient.close();
+
+    ac.abort();
+    await server.finished;
+  },
+);
+
+Deno.test(
+  { permissions: { net: true } },
+  function httpServerHttp3RequiresTls() {
+    assertThrows(
+      () =>
+        Deno.serve({
+          handler: () => new Response(),
+          port: servePort,
+          http3: true,
+        } as Deno.ServeTlsOptions),
+      TypeError,
+      "HTTP/3 requires cert and key to be provided.",
+    );
+  },
+);
+
+Deno.test(
+  { permissions: { read: true, net: true } },
+  async function httpServerTlsClientAuth() {
+    const ac = new AbortController();
+    const listeningPromise = deferred();
+    const cert = Deno.readTextFileSync("cli/tests/testdata/tls/localhost.crt");
+    const key = Deno.readTextFileSync("cli/tests/testdata/tls/localhost.key");
+    const caCert = Deno.readTextFileSync("cli/tests/testdata/tls/RootCA.pem");
+
+    const server = Deno.serve({
+      handler: (_req, info) =>
+        new Response(info.peerCertificates?.[0] ?? "anonymous"),
+      hostname: "127.0.0.1",
+      port: servePort,
+      signal: ac.signal,
+      onListen: onListen(listeningPromise),
+      onError: createOnErrorCb(ac),
+      sni: { "localhost": { cert, key } },
+      clientAuth: { ca: caCert, required: false },
+    } as Deno.ServeTlsOptions);
+
+    await listeningPromise;
+    const client = Deno.createHttpClient({
+      caCerts: [caCert],
+      certChain: cert,
+      privateKey: key,
+    });
+    const resp = await fetch(`https://localhost:${servePort}/`, { client });
+    assertEquals(await resp.text(), cert);
+    client.close();
+
+    // Certificates can be swapped while the server is running.
+    server.setCertificates({ cert, key });
+    const anonymousClient = Deno.createHttpClient({ caCerts: [caCert] });
+    const anonymousResp = await fetch(`https://localhost:${servePort}/`, {
+      client: anonymousClient,
+    });
+    assertEquals(await anonymousResp.text(), "anonymous");
+    anonymousClient.close();
+
+    ac.abort();
+    await server.finished;
+  },
+);
+
+Deno.test(
+  { permissions: { read: true, net: true } },
+  async function httpServerHttp3SetCertificates() {
+    const ac = new AbortController();
+    const listeningPromise = deferred();
+    const cert = Deno.readTextFileSync("cli/tests/testdata/tls/localhost.crt");
+    const key = Deno.readTextFileSync("cli/tests/testdata/tls/localhost.key");
+    const caCert = Deno.readTextFileSync("cli/tests/testdata/tls/RootCA.pem");
+
+    const server = Deno.serve({
+      handler: () => new Response("ok"),
+      hostname: "127.0.0.1",
+      port: servePort,
+      signal: ac.signal,
+      onListen: onListen(listeningPromise),
+      onError: createOnErrorCb(ac),
+      cert,
+      key,
+      http3: true,
+    });
+    await listeningPromise;
+
+    // The QUIC listener has a single certificate, so sni can not be used
+    assertThrows(
+      () => server.setCertificates({ sni: { localhost: { cert, key } } }),
+      TypeError,
+      "HTTP/3 servers require cert and key, and can not use sni.",
+    );
+    // Invalid certificates are rejected before either listener is updated
+    assertThrows(() => server.setCertificates({ cert: "invalid", key }));
+    server.setCertificates({ cert, key });
+
+    const client = Deno.createHttpClient({ caCerts: [caCert] });
+    const resp = await fetch(`https://localhost:${servePort}/`, { client });
+    assertEquals(await resp.text(), "ok");
+    client.close();
+
+    ac.abort();
+    await server.finished;
+  },
+);
+
+Deno.test(
+  { permissions: { net: true } },
+  async function httpServerSetCertificatesRequiresTls() {
+    const ac = new AbortController();
+    const server = Deno.serve({
+      handler: () => new Response(),
+      port: servePort,
+      signal: ac.signal,
+      onListen: () => {},
+    });
+    assertThrows(
+      () => server.setCertificates({}),
+      TypeError,
+      "Only servers listening with TLS have certificates.",
+    );
+    ac.abort();
+    await server.finished;
+  },
+);
+
+Deno.test(
+  { permissions: { net: true } },
+  async function httpServerStats() {
+    const ac = new AbortController();
+    const listeningPromise = deferred();
+    const server = Deno.serve({
+      handler: async (req) => {
+        if (req.method === "POST") {
+          return new Response(await req.text());
+        }
+        return new Response("boom", { status: 500 });
+      },
+      port: servePort,
+      signal: ac.signal,
+      onListen: onListen(listeningPromise),
+      onError: createOnErrorCb(ac),
+    });
+    await listeningPromise;
+
+    const initial = server.stats();
+    assertEquals(initial.totalRequests, 0);
+    assertEquals(initial.openConnections, 0);
+
+    let resp = await fetch(`http://localhost:${servePort}/`, {
+      method: "POST",
+      body: "hello",
+    });
+    assertEquals(await resp.text(), "hello");
+    resp = await fetch(`http://localhost:${servePort}/`);
+    assertEquals(resp.status, 500);
+    await resp.text();
+
+    const stats = server.stats();
+    assertEquals(stats.totalRequests, 2);
+    assertEquals(stats.inFlightRequests, 0);
+    assertEquals(stats.serverErrors, 1);
+    assert(stats.totalConnections >= 1);
+    assert(stats.bytesRead > 0);
+    assert(stats.bytesWritten > 0);
+    assertEquals(
+      stats.latency.reduce((sum, bucket) => sum + bucket.count, 0),
+      2,
+    );
+    assertEquals(stats.latency[stats.latency.length - 1].le, Infinity);
+
+    ac.abort();
+    await server.finished;
+  },
+);
+
+Deno.test(
+  { permissions: { net: true } },
+  async function httpServerStatsWebSocket() {
+    const ac = new AbortController();
+    const listeningPromise = deferred();
+    const server = Deno.serve({
+      handler: (request) => {
+        const { response, socket } = Deno.upgradeWebSocket(request);
+        socket.onmessage = (m) => socket.send(m.data);
+        return response;
+      },
+      port: servePort,
+      signal: ac.signal,
+      onListen: onListen(listeningPromise),
+      onError: createOnErrorCb(ac),
+    });
+    await listeningPromise;
+
+    const opened = deferred();
+    const echoed = deferred();
+    const closed = deferred();
+    const ws = new WebSocket(`ws://localhost:${servePort}`);
+    ws.onopen = () => opened.resolve();
+    ws.onmessage = (m) => echoed.resolve(m.data);
+    ws.onclose = () => closed.resolve();
+    await opened;
+    // The upgraded connection no longer goes through the server's byte counters
+    const stats = server.stats();
+    ws.send("foo");
+    assertEquals(await echoed, "foo");
+    assertEquals(server.stats().bytesRead, stats.bytesRead);
+    assertEquals(stats.totalRequests, 1);
+    assert(stats.bytesRead > 0);
+    assert(stats.bytesWritten > 0);
+    ws.close();
+    await closed;
+
+    ac.abort();
+    await server.finished;
+  },
+);
+
+Deno.test(
+  { permissions: { net: true } },
+  async function httpServerOnConnection() {
+    const ac = new AbortController();
+    const listeningPromise = deferred();
+    const connectionPromise = deferred<Deno.ServeConnectionInfo>();
+    const server = Deno.serve({
+      handler: () => new Response("ok"),
+      port: servePort,
+      signal: ac.signal,
+      onListen: onListen(listeningPromise),
+      onError: createOnErrorCb(ac),
+      onConnection: (info) => connectionPromise.resolve(info),
+    });
+    await listeningPromise;
+
+    const resp = await fetch(`http://127.0.0.1:${servePort}/`);
+    assertEquals(await resp.text(), "ok");
+
+    const info = await connectionPromise;
+    assertEquals(info.remoteAddr.transport, "tcp");
+    assertEquals((info.remoteAddr as Deno.NetAddr).hostname, "127.0.0.1");
+    assertEquals(info.alpnProtocol, null);
+    assertEquals(info.tls, null);
+
+    ac.abort();
+    await server.finished;
+  },
+);
+
+Deno.test(
+  { permissions: { net: true } },
+  async function httpServerMaxRequestBodySize() {
+    const ac = new AbortController();
+    const listeningPromise = deferred();
+    let handlerCalled = false;
+    const server = Deno.serve({
+      handler: () => {
+        handlerCalled = true;
+        return new Response("ok");
+      },
+      port: servePort,
+      signal: ac.signal,
+      onListen: onListen(listeningPromise),
+      onError: createOnErrorCb(ac),
+      maxRequestBodySize: 10,
+    });
+    await listeningPromise;
+
+    const resp = await fetch(`http://127.0.0.1:${servePort}/`, {
+      method: "POST",
+      body: "this body is too large",
+    });
+    assertEquals(resp.status, 413);
+    await resp.body?.cancel();
+    assert(!handlerCalled);
+
+    const small = await fetch(`http://127.0.0.1:${servePort}/`, {
+      method: "POST",
+      body: "small",
+    });
+    assertEquals(small.status, 200);
+    assertEquals(await small.text(), "ok");
+
+    ac.abort();
+    await server.finished;
+  },
+);
+
+Deno.test(
+  { permissions: { net: true } },
+  async function httpServerMaxRequestBodySizeChunked() {
+    const ac = new AbortController();
+    const listeningPromise = deferred();
+    let readError;
+    const server = Deno.serve({
+      handler: async (req) => {
+        try {
+          await req.text();
+        } catch (error) {
+          readError = error;
+        }
+        return new Response("ok");
+      },
+      port: servePort,
+      signal: ac.signal,
+      onListen: onListen(listeningPromise),
+      onError: createOnErrorCb(ac),
+      maxRequestBodySize: 10,
+    });
+    await listeningPromise;
+
+    // Without a Content-Length, the limit is only hit while the body is read
+    const encoder = new TextEncoder();
+    const body = new ReadableStream({
+      start(controller) {
+        controller.enqueue(encoder.encode("this body "));
+        controller.enqueue(encoder.encode("is too large"));
+        controller.close();
+      },
+    });
+    const resp = await fetch(`http://127.0.0.1:${servePort}/`, {
+      method: "POST",
+      body,
+    });
+    assertEquals(resp.status, 413);
+    await resp.body?.cancel();
+    assert(readError);
+
+    ac.abort();
+    await server.finished;
+  },
+);
+
+Deno.test(
+  { permissions: { net: true } },
+  async function httpServerMaxHeaderSize() {
+    const ac = new AbortController();
+    const listeningPromise = deferred();
+    const server = Deno.serve({
+      handler: () => new Response("ok"),
+      port: servePort,
+      signal: ac.signal,
+      onListen: onListen(listeningPromise),
+      onError: createOnErrorCb(ac),
+      maxHeaderSize: 256,
+    });
+    await listeningPromise;
+
+    const conn = await Deno.connect({ port: servePort });
+    const encoder = new TextEncoder();
+    const decoder = new TextDecoder();
+    const req = `GET / HTTP/1.1\r\nHost: example.domain\r\nX-Large: ${
+      "a".repeat(512)
+    }\r\n\r\n`;
+    await conn.write(encoder.encode(req));
+
+    const buf = new Uint8Array(100);
+    const readResult = await conn.read(buf);
+    assert(readResult);
+    const msg = decoder.decode(buf.subarray(0, readResult));
+    assert(msg.startsWith("HTTP/1.1 431 "));
+    conn.close();
+
+    ac.abort();
+    await server.finished;
+  },
+);
+
+Deno.test(
+  { permissions: { net: true } },
+  async function httpServerHeaderReadTimeout() {
+    const ac = new AbortController();
+    const listeningPromise = deferred();
+    const server = Deno.serve({
+      handler: () => new Response("ok"),
+      port: servePort,
+      signal: ac.signal,
+      onListen: onListen(listeningPromise),
+      onError: createOnErrorCb(ac),
+      headerReadTimeout: 100,
+    });
+    await listeningPromise;
+
+    const conn = await Deno.connect({ port: servePort });
+    const encoder = new TextEncoder();
+    const decoder = new TextDecoder();
+    // Never finish sending the headers
+    await conn.write(encoder.encode("GET / HTTP/1.1\r\nHost: example"));
+
+    const buf = new Uint8Array(100);
+    const readResult = await conn.read(buf);
+    assert(readResult);
+    const msg = decoder.decode(buf.subarray(0, readResult));
+    assert(msg.startsWith("HTTP/1.1 408 "));
+    conn.close();
+
+    ac.abort();
+    await server.finished;
+  },
+);
+
+Deno.test(
+  { permissions: { net: true } },
+  async function httpServerIdleTimeout() {
+    const ac = new AbortController();
+    const listeningPromise = deferred();
+    const server = Deno.serve({
+      handler: () => new Response("ok"),
+      port: servePort,
+      signal: ac.signal,
+      onListen: onListen(listeningPromise),
+      onError: createOnErrorCb(ac),
+      idleTimeout: 100,
+    });
+    await listeningPromise;
+
+    const conn = await Deno.connect({ port: servePort });
+    // The server closes the connection without sending anything
+    const readResult = await conn.read(new Uint8Array(100));
+    assertEquals(readResult, null);
+    conn.close();
+
+    ac.abort();
+    await server.finished;
+  },
+);
+
+Deno.test(
+  { permissions: { net: true } },
+  function httpServerInvalidLimits() {
+    assertThrows(
+      () =>
+        Deno.serve({
+          handler: () => new Response("ok"),
+          port: servePort,
+          maxRequestBodySize: -1,
+        }),
+      TypeError,
+      "maxRequestBodySize must be a positive, finite number.",
+    );
+    assertThrows(
+      () =>
+        Deno.serve({
+          handler: () => new Response("ok"),
+          port: servePort,
+          idleTimeout: Infinity,
+        }),
+      TypeError,
+      "idleTimeout must be a positive, finite number.",
+    );
+  },
+);
+
 Deno.test(
   { permissions: { net: true, write: true, read: true } },
   async function httpServerRequestCLTE() {
@@ -53,3 +53,62 @@ Deno.test("correct DataCloneError message", () => {
   // ab2 should not be detached after above failure
   structuredClone(ab2, { transfer: [ab2] });
 });
+
+Deno.test("structuredClone transfers streams", async () => {
+  const readable = new ReadableStream({
+    start(controller) {
+      controller.enqueue("hello");
+      controller.enqueue("world");
+      controller.close();
+    },
+  });
+  const cloned = structuredClone(readable, { transfer: [readable] });
+  assert(readable.locked);
+  assert(cloned instanceof ReadableStream);
+  const chunks = [];
+  for await (const chunk of cloned) {
+    chunks.push(chunk);
+  }
+  assertEquals(chunks, ["hello", "world"]);
+
+  assertThrows(
+    () => {
+      structuredClone(readable, { transfer: [readable] });
+    },
+    DOMException,
+    "Can not transfer locked ReadableStream",
+  );
+});
+
+Deno.test("structuredClone transfers streams nested in the message", () => {
+  const writable = new WritableStream();
+  const readable = new ReadableStream();
+  const cloned = structuredClone(
+    { writable, streams: new Map([["readable", readable]]) },
+    { transfer: [writable, readable] },
+  );
+  assert(cloned.writable instanceof WritableStream);
+  assert(cloned.streams.get("readable") instanceof ReadableStream);
+  assert(writable.locked);
+  assert(readable.locked);
+
+  const stream = new ReadableStream();
+  assertThrows(
+    () => {
+      structuredClone(stream, { transfer: [stream, stream] });
+    },
+    DOMException,
+    "Can not transfer the same stream twice",
+  );
+  assert(!stream.locked);
+});
+
+Deno.test("structuredClone does not treat user data as a stream", () => {
+  const readable = new ReadableStream();
+  const fake = { "[[TransferredStream]]": 0 };
+  const cloned = structuredClone({ readable, fake }, {
+    transfer: [readable],
+  });
+  assert(cloned.readable instanceof ReadableStream);
+  assertEquals(cloned.fake, fake);
+});
@@ -1519,3 +1519,135 @@ Deno.test(
     }, Deno.errors.InvalidData);
   },
 );
+
+Deno.test(
+  { permissions: { net: true } },
+  async function listenTlsSni() {
+    const hostname = "localhost";
+    const port = getPort();
+    const listener = Deno.listenTls({
+      hostname,
+      port,
+      sni: { "LOCALHOST": { cert, key } },
+    });
+    const [serverConn, clientConn] = await Promise.all([
+      listener.accept(),
+      Deno.connectTls({ hostname, port, caCerts }),
+    ]);
+    const [serverInfo, clientInfo] = await Promise.all([
+      serverConn.handshake(),
+      clientConn.handshake(),
+    ]);
+    assertEquals(serverInfo.peerCertificates, null);
+    assertEquals(clientInfo.peerCertificates, [cert]);
+    serverConn.close();
+    clientConn.close();
+    listener.close();
+  },
+);
+
+Deno.test(
+  { permissions: { net: true } },
+  async function listenTlsSetCertificates() {
+    const hostname = "localhost";
+    const port = getPort();
+    // No certificate matches "localhost" until the default one is set.
+    const listener = Deno.listenTls({
+      hostname,
+      port,
+      sni: { "*.example.com": { cert, key } },
+    });
+
+    const [serverConn, clientConn] = await Promise.all([
+      listener.accept(),
+      Deno.connectTls({ hostname, port, caCerts }),
+    ]);
+    await assertRejects(() => serverConn.handshake());
+    await assertRejects(() => clientConn.handshake());
+    serverConn.close();
+    clientConn.close();
+
+    assertThrows(
+      () => listener.setCertificates({ cert }),
+      TypeError,
+      "Both cert and key must be provided.",
+    );
+    listener.setCertificates({ cert, key });
+
+    const [serverConn2, clientConn2] = await Promise.all([
+      listener.accept(),
+      Deno.connectTls({ hostname, port, caCerts }),
+    ]);
+    await Promise.all([serverConn2.handshake(), clientConn2.handshake()]);
+    await clientConn2.write(encoder.encode("hello"));
+    const buf = new Uint8Array(5);
+    assertEquals(await serverConn2.read(buf), 5);
+    assertEquals(decoder.decode(buf), "hello");
+    serverConn2.close();
+    clientConn2.close();
+    listener.close();
+  },
+);
+
+Deno.test(
+  { permissions: { net: true } },
+  function listenTlsCloseByRidReleasesCertificates() {
+    const before = Object.keys(Deno.resources()).length;
+    const listener = Deno.listenTls({ port: getPort(), cert, key });
+    assertEquals(Object.keys(Deno.resources()).length, before + 1);
+    Deno.close(listener.rid);
+    assertEquals(Object.keys(Deno.resources()).length, before);
+    assertThrows(
+      () => listener.setCertificates({ cert, key }),
+      Deno.errors.BadResource,
+      "Listener has been closed",
+    );
+  },
+);
+
+Deno.test(
+  { permissions: { net: true } },
+  async function listenTlsClientAuth() {
+    const hostname = "localhost";
+    const port = getPort();
+    const listener = Deno.listenTls({
+      hostname,
+      port,
+      cert,
+      key,
+      clientAuth: { ca: caCerts },
+    });
+
+    const [serverConn, clientConn] = await Promise.all([
+      listener.accept(),
+      Deno.connectTls({
+        hostname,
+        port,
+        caCerts,
+        certChain: cert,
+        privateKey: key,
+      }),
+    ]);
+    const [serverInfo] = await Promise.all([
+      serverConn.handshake(),
+      clientConn.handshake(),
+    ]);
+    assertEquals(serverInfo.peerCertificates, [cert]);
+    serverConn.close();
+    clientConn.close();
+
+    // Clients without a certificate are turned away.
+    const [serverConn2, clientConn2] = await Promise.all([
+      listener.accept(),
+      Deno.connectTls({ hostname, port, caCerts }),
+    ]);
+    const [serverResult] = await Promise.allSettled([
+      serverConn2.handshake(),
+      clientConn2.handshake(),
+    ]);
+    assertEquals(serverResult.status, "rejected");
+    serverConn2.close();
+    clientConn2.close();
+    listener.close();
+  },
+);
@@ -2006,3 +2006,417 @@ Deno.test(async function testHmacJwkImport() {
     ["sign", "verify"],
   );
 });
+
+Deno.test(async function testEd448SignVerify() {
+  const { privateKey, publicKey } = await crypto.subtle.generateKey(
+    "Ed448",
+    true,
+    ["sign", "verify"],
+  ) as CryptoKeyPair;
+  assertEquals(privateKey.algorithm.name, "Ed448");
+
+  const data = new TextEncoder().encode("hello world");
+  const signature = await crypto.subtle.sign("Ed448", privateKey, data);
+  assertEquals(signature.byteLength, 114);
+  // Ed448 signatures are deterministic.
+  assertEquals(
+    new Uint8Array(await crypto.subtle.sign("Ed448", privateKey, data)),
+    new Uint8Array(signature),
+  );
+  assert(await crypto.subtle.verify("Ed448", publicKey, signature, data));
+  assert(
+    !await crypto.subtle.verify(
+      "Ed448",
+      publicKey,
+      signature,
+      new TextEncoder().encode("hello deno"),
+    ),
+  );
+});
+
+Deno.test(async function testEd448ImportExport() {
+  const { privateKey, publicKey } = await crypto.subtle.generateKey(
+    "Ed448",
+    true,
+    ["sign", "verify"],
+  ) as CryptoKeyPair;
+
+  const raw = await crypto.subtle.exportKey("raw", publicKey);
+  assertEquals(raw.byteLength, 57);
+
+  const spki = await crypto.subtle.exportKey("spki", publicKey);
+  const importedPublicKey = await crypto.subtle.importKey(
+    "spki",
+    spki,
+    "Ed448",
+    true,
+    ["verify"],
+  );
+  assertEquals(
+    new Uint8Array(await crypto.subtle.exportKey("raw", importedPublicKey)),
+    new Uint8Array(raw),
+  );
+
+  const pkcs8 = await crypto.subtle.exportKey("pkcs8", privateKey);
+  const importedPrivateKey = await crypto.subtle.importKey(
+    "pkcs8",
+    pkcs8,
+    "Ed448",
+    true,
+    ["sign"],
+  );
+  assertEquals(
+    new Uint8Array(await crypto.subtle.exportKey("pkcs8", importedPrivateKey)),
+    new Uint8Array(pkcs8),
+  );
+
+  const jwk = await crypto.subtle.exportKey("jwk", privateKey);
+  assertEquals(jwk.kty, "OKP");
+  assertEquals(jwk.crv, "Ed448");
+  assertEquals(
+    jwk.x,
+    (await crypto.subtle.exportKey("jwk", publicKey)).x,
+  );
+
+  const data = new Uint8Array([1, 2, 3]);
+  const signature = await crypto.subtle.sign(
+    "Ed448",
+    importedPrivateKey,
+    data,
+  );
+  assert(
+    await crypto.subtle.verify("Ed448", importedPublicKey, signature, data),
+  );
+
+  await assertRejects(
+    () =>
+      crypto.subtle.importKey("spki", spki, "Ed25519", true, ["verify"]),
+    DOMException,
+    "Invalid key data",
+  );
+});
+
+Deno.test(async function testX448DeriveBits() {
+  const alice = await crypto.subtle.generateKey(
+    "X448",
+    true,
+    ["deriveBits"],
+  ) as CryptoKeyPair;
+  const bob = await crypto.subtle.generateKey(
+    "X448",
+    true,
+    ["deriveBits"],
+  ) as CryptoKeyPair;
+
+  const aliceSecret = await crypto.subtle.deriveBits(
+    { name: "X448", public: bob.publicKey },
+    alice.privateKey,
+    448,
+  );
+  const bobSecret = await crypto.subtle.deriveBits(
+    { name: "X448", public: alice.publicKey },
+    bob.privateKey,
+    448,
+  );
+  assertEquals(aliceSecret.byteLength, 56);
+  assertEquals(new Uint8Array(aliceSecret), new Uint8Array(bobSecret));
+
+  // Round trip both keys of a pair through their DER encodings.
+  const spki = await crypto.subtle.exportKey("spki", bob.publicKey);
+  const pkcs8 = await crypto.subtle.exportKey("pkcs8", alice.privateKey);
+  const publicKey = await crypto.subtle.importKey(
+    "spki",
+    spki,
+    "X448",
+    true,
+    [],
+  );
+  const privateKey = await crypto.subtle.importKey(
+    "pkcs8",
+    pkcs8,
+    "X448",
+    true,
+    ["deriveBits"],
+  );
+  assertEquals(
+    new Uint8Array(
+      await crypto.subtle.deriveBits(
+        { name: "X448", public: publicKey },
+        privateKey,
+        448,
+      ),
+    ),
+    new Uint8Array(aliceSecret),
+  );
+
+  const jwk = await crypto.subtle.exportKey("jwk", alice.publicKey);
+  assertEquals(jwk.crv, "X448");
+});
+
+Deno.test(async function testChaCha20Poly1305Rfc8439() {
+  // https://www.rfc-editor.org/rfc/rfc8439#section-2.8.2
+  const key = await crypto.subtle.importKey(
+    "raw",
+    new Uint8Array(32).map((_, i) => 0x80 + i),
+    "ChaCha20-Poly1305",
+    true,
+    ["encrypt", "decrypt"],
+  );
+  const iv = new Uint8Array([
+    0x07,
+    0x00,
+    0x00,
+    0x00,
+    0x40,
+    0x41,
+    0x42,
+    0x43,
+    0x44,
+    0x45,
+    0x46,
+    0x47,
+  ]);
+  const additionalData = new Uint8Array([
+    0x50,
+    0x51,
+    0x52,
+    0x53,
+    0xc0,
+    0xc1,
+    0xc2,
+    0xc3,
+    0xc4,
+    0xc5,
+    0xc6,
+    0xc7,
+  ]);
+  const data = new TextEncoder().encode(
+    "Ladies and Gentlemen of the class of '99: If I could offer you only " +
+      "one tip for the future, sunscreen would be it.",
+  );
+
+  const cipherText = await crypto.subtle.encrypt(
+    { name: "ChaCha20-Poly1305", iv, additionalData },
+    key,
+    data,
+  );
+  assertEquals(cipherText.byteLength, data.byteLength + 16);
+  assertEquals(
+    new Uint8Array(cipherText, data.byteLength),
+    new Uint8Array([
+      0x1a,
+      0xe1,
+      0x0b,
+      0x59,
+      0x4f,
+      0x09,
+      0xe2,
+      0x6a,
+      0x7e,
+      0x90,
+      0x2e,
+      0xcb,
+      0xd0,
+      0x60,
+      0x06,
+      0x91,
+    ]),
+  );
+
+  const plainText = await crypto.subtle.decrypt(
+    { name: "ChaCha20-Poly1305", iv, additionalData },
+    key,
+    cipherText,
+  );
+  assertEquals(new Uint8Array(plainText), data);
+
+  // The additional data is authenticated.
+  await assertRejects(
+    () =>
+      crypto.subtle.decrypt(
+        { name: "ChaCha20-Poly1305", iv },
+        key,
+        cipherText,
+      ),
+    DOMException,
+    "Decryption failed",
+  );
+});
+
+Deno.test(async function testChaCha20Poly1305KeyGenJwk() {
+  const key = await crypto.subtle.generateKey(
+    "ChaCha20-Poly1305",
+    true,
+    ["encrypt", "decrypt"],
+  ) as CryptoKey;
+  assertEquals(key.algorithm.name, "ChaCha20-Poly1305");
+  assertEquals(key.type, "secret");
+
+  const jwk = await crypto.subtle.exportKey("jwk", key);
+  assertEquals(jwk.kty, "oct");
+  assertEquals(jwk.alg, "C20P");
+
+  const imported = await crypto.subtle.importKey(
+    "jwk",
+    jwk,
+    "ChaCha20-Poly1305",
+    true,
+    ["encrypt", "decrypt"],
+  );
+  const iv = crypto.getRandomValues(new Uint8Array(12));
+  const data = new Uint8Array([1, 2, 3]);
+  const cipherText = await crypto.subtle.encrypt(
+    { name: "ChaCha20-Poly1305", iv },
+    key,
+    data,
+  );
+  assertEquals(
+    new Uint8Array(
+      await crypto.subtle.decrypt(
+        { name: "ChaCha20-Poly1305", iv },
+        imported,
+        cipherText,
+      ),
+    ),
+    data,
+  );
+
+  await assertRejects(
+    () =>
+      crypto.subtle.importKey(
+        "raw",
+        new Uint8Array(16),
+        "ChaCha20-Poly1305",
+        true,
+        ["encrypt"],
+      ),
+    DOMException,
+    "Invalid key length",
+  );
+  await assertRejects(
+    () =>
+      crypto.subtle.encrypt(
+        { name: "ChaCha20-Poly1305", iv: new Uint8Array(16) },
+        key,
+        data,
+      ),
+    DOMException,
+    "Initialization vector length not supported",
+  );
+});
+
+Deno.test(async function testSecp256k1Ecdsa() {
+  const { privateKey, publicKey } = await crypto.subtle.generateKey(
+    { name: "ECDSA", namedCurve: "secp256k1" },
+    true,
+    ["sign", "verify"],
+  ) as CryptoKeyPair;
+
+  const data = new TextEncoder().encode("hello world");
+  const signature = await crypto.subtle.sign(
+    { name: "ECDSA", hash: "SHA-256" },
+    privateKey,
+    data,
+  );
+  assertEquals(signature.byteLength, 64);
+  assert(
+    await crypto.subtle.verify(
+      { name: "ECDSA", hash: "SHA-256" },
+      publicKey,
+      signature,
+      data,
+    ),
+  );
+  await assertRejects(
+    () =>
+      crypto.subtle.sign(
+        { name: "ECDSA", hash: "SHA-384" },
+        privateKey,
+        data,
+      ),
+    DOMException,
+    "Not implemented",
+  );
+
+  const jwk = await crypto.subtle.exportKey("jwk", privateKey);
+  assertEquals(jwk.crv, "secp256k1");
+  assertEquals(jwk.alg, "ES256K");
+  const importedPrivateKey = await crypto.subtle.importKey(
+    "jwk",
+    jwk,
+    { name: "ECDSA", namedCurve: "secp256k1" },
+    true,
+    ["sign"],
+  );
+  const spki = await crypto.subtle.exportKey("spki", publicKey);
+  const importedPublicKey = await crypto.subtle.importKey(
+    "spki",
+    spki,
+    { name: "ECDSA", namedCurve: "secp256k1" },
+    true,
+    ["verify"],
+  );
+  const raw = await crypto.subtle.exportKey("raw", importedPublicKey);
+  assertEquals(raw.byteLength, 65);
+
+  const signature2 = await crypto.subtle.sign(
+    { name: "ECDSA", hash: "SHA-256" },
+    importedPrivateKey,
+    data,
+  );
+  assert(
+    await crypto.subtle.verify(
+      { name: "ECDSA", hash: "SHA-256" },
+      importedPublicKey,
+      signature2,
+      data,
+    ),
+  );
+
+  await assertRejects(
+    () =>
+      crypto.subtle.importKey(
+        "spki",
+        spki,
+        { name: "ECDSA", namedCurve: "P-256" },
+        true,
+        ["verify"],
+      ),
+    DOMException,
+  );
+});
+
+Deno.test(async function testSecp256k1Ecdh() {
+  const alg = { name: "ECDH", namedCurve: "secp256k1" };
+  const alice = await crypto.subtle.generateKey(
+    alg,
+    true,
+    ["deriveBits"],
+  ) as CryptoKeyPair;
+  const bob = await crypto.subtle.generateKey(
+    alg,
+    true,
+    ["deriveBits"],
+  ) as CryptoKeyPair;
+
+  const pkcs8 = await crypto.subtle.exportKey("pkcs8", alice.privateKey);
+  const privateKey = await crypto.subtle.importKey(
+    "pkcs8",
+    pkcs8,
+    alg,
+    true,
+    ["deriveBits"],
+  );
+
+  const aliceSecret = await crypto.subtle.deriveBits(
+    { name: "ECDH", public: bob.publicKey },
+    privateKey,
+    256,
+  );
+  const bobSecret = await crypto.subtle.deriveBits(
+    { name: "ECDH", public: alice.publicKey },
+    bob.privateKey,
+    256,
+  );
+  assertEquals(new Uint8Array(aliceSecret), new Uint8Array(bobSecret));
+});
@@ -222,3 +222,108 @@ Deno.test(
     }
   },
 );
+
+Deno.test(async function websocketCompression() {
+  const promise = deferred();
+  const ac = new AbortController();
+  const listeningPromise = deferred();
+
+  const server = Deno.serve({
+    handler: (req) => {
+      const { response, socket } = Deno.upgradeWebSocket(req, {
+        compression: { clientMaxWindowBits: 10 },
+      });
+      assertEquals(
+        socket.extensions,
+        "permessage-deflate; client_max_window_bits=10",
+      );
+      // Blobs are sent asynchronously, which could reorder the echoes.
+      socket.binaryType = "arraybuffer";
+      socket.onmessage = (e) => socket.send(e.data);
+      socket.onclose = () => ac.abort();
+      socket.onerror = () => fail();
+      return response;
+    },
+    signal: ac.signal,
+    onListen: () => listeningPromise.resolve(),
+    hostname: "localhost",
+    port: servePort,
+  });
+
+  await listeningPromise;
+
+  const text = "hello world ".repeat(1000);
+  const binary = new Uint8Array(100_000).fill(42);
+  const received: (string | Uint8Array)[] = [];
+  const ws = new WebSocket(serveUrl, { compression: true });
+  ws.binaryType = "arraybuffer";
+  ws.onerror = () => fail();
+  ws.onopen = () => {
+    assertEquals(
+      ws.extensions,
+      "permessage-deflate; client_max_window_bits=10",
+    );
+    ws.send(text);
+    ws.send(binary);
+    ws.send(text);
+  };
+  ws.onmessage = (e) => {
+    received.push(
+      typeof e.data === "string" ? e.data : new Uint8Array(e.data),
+    );
+    if (received.length === 3) {
+      ws.close();
+    }
+  };
+  ws.onclose = () => promise.resolve();
+  await Promise.all([promise, server.finished]);
+
+  assertEquals(received, [text, binary, text]);
+});
+
+Deno.test(async function websocketCompressionNotAccepted() {
+  const promise = deferred();
+  const ac = new AbortController();
+  const listeningPromise = deferred();
+
+  const server = Deno.serve({
+    handler: (req) => {
+      const { response, socket } = Deno.upgradeWebSocket(req);
+      socket.onmessage = (e) => socket.send(e.data);
+      socket.onclose = () => ac.abort();
+      socket.onerror = () => fail();
+      return response;
+    },
+    signal: ac.signal,
+    onListen: () => listeningPromise.resolve(),
+    hostname: "localhost",
+    port: servePort,
+  });
+
+  await listeningPromise;
+
+  const ws = new WebSocket(serveUrl, {
+    protocols: [],
+    compression: { serverNoContextTakeover: true },
+  });
+  ws.onerror = () => fail();
+  ws.onopen = () => {
+    assertEquals(ws.extensions, "");
+    ws.send("hello");
+  };
+  ws.onmessage = (e) => {
+    assertEquals(e.data, "hello");
+    ws.close();
+  };
+  ws.onclose = () => promise.resolve();
+  await Promise.all([promise, server.finished]);
+});
+
+Deno.test(function websocketCompressionInvalidWindowBits() {
+  assertThrows(
+    () =>
+      new WebSocket(serveUrl, { compression: { serverMaxWindowBits: 8 } }),
+    TypeError,
+    "serverMaxWindowBits must be an integer between 9 and 15",
+  );
+});
@@ -0,0 +1,1150 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Generates a TypeScript module with `Deno.dlopen` symbol definitions, struct
+//! layouts and pointer helpers from the declarations of a C header.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use deno_core::serde_json;
+
+use super::c_parser::CType;
+use super::c_parser::Declaration;
+use super::c_parser::Field;
+use super::c_parser::FunctionType;
+use super::c_parser::Header;
+use super::c_parser::Primitive;
+use super::c_parser::Value;
+
+/// Array fields longer than this are written as `new Array(n).fill(type)`
+/// in struct definitions.
+const MAX_INLINE_ARRAY_LENGTH: usize = 16;
+
+const POINTER_SIZE: usize = std::mem::size_of::<usize>();
+
+/// A `Deno.NativeType`, where structs are referred to by their tag.
+#[derive(Clone, Debug, PartialEq)]
+enum Native {
+  Scalar(&'static str),
+  Struct(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Position {
+  Parameter,
+  Result,
+  Field,
+  Static,
+}
+
+struct FieldLayout {
+  name: String,
+  offset: usize,
+  ty: Native,
+  /// The length of array fields.
+  length: Option<usize>,
+}
+
+struct StructLayout {
+  size: usize,
+  align: usize,
+  fields: Vec<FieldLayout>,
+}
+
+pub struct Bindings {
+  pub code: String,
+  /// Declarations that were skipped because they cannot be represented.
+  pub warnings: Vec<String>,
+}
+
+pub fn generate(
+  header: &Header,
+  source_name: &str,
+  lib: Option<&str>,
+) -> Bindings {
+  let mut generator = Generator {
+    typedefs: HashMap::new(),
+    structs: HashMap::new(),
+    enums: HashMap::new(),
+    names: HashMap::new(),
+    layouts: HashMap::new(),
+    exported: HashSet::new(),
+    warnings: header.warnings.clone(),
+  };
+  generator.collect(header);
+  let code = generator.emit(header, source_name, lib);
+  Bindings {
+    code,
+    warnings: generator.warnings,
+  }
+}
+
+struct Generator<'a> {
+  typedefs: HashMap<&'a str, &'a CType>,
+  /// The fields of structs by tag, or the reason they are not supported.
+  structs: HashMap<&'a str, Result<&'a [Field], String>>,
+  /// The integer type of enums by tag.
+  enums: HashMap<&'a str, &'static str>,
+  /// The names of the generated definitions of structs and enums by tag.
+  names: HashMap<&'a str, &'a str>,
+  layouts: HashMap<String, StructLayout>,
+  exported: HashSet<String>,
+  warnings: Vec<String>,
+}
+
+impl<'a> Generator<'a> {
+  fn collect(&mut self, header: &'a Header) {
+    for declaration in &header.declarations {
+      match declaration {
+        Declaration::Typedef { name, ty } => {
+          self.typedefs.entry(name).or_insert(ty);
+          // structs and enums are named after their first typedef
+          if let CType::Struct(tag) | CType::Enum(tag) = ty {
+            self.names.entry(tag).or_insert(name);
+          }
+        }
+        Declaration::Struct {
+          tag,
+          is_union,
+          fields,
+          ..
+        } => {
+          let fields = match (is_union, fields) {
+            (true, _) => Err("unions are not supported".to_string()),
+            (false, Ok(fields)) => Ok(fields.as_slice()),
+            (false, Err(err)) => Err(err.clone()),
+          };
+          self.structs.entry(tag).or_insert(fields);
+        }
+        Declaration::Enum { tag, variants, .. } => {
+          let min = variants.iter().map(|(_, v)| *v).min().unwrap_or(0);
+          let max = variants.iter().map(|(_, v)| *v).max().unwrap_or(0);
+          let ty = if min >= i32::MIN as i128 && max <= i32::MAX as i128 {
+            "i32"
+          } else if min >= 0 && max <= u32::MAX as i128 {
+            "u32"
+          } else if min < 0 {
+            "i64"
+          } else {
+            "u64"
+          };
+          self.enums.insert(tag, ty);
+        }
+        _ => {}
+      }
+    }
+
+    // compute the layouts in declaration order, as structs can only contain
+    // structs declared before them
+    for declaration in &header.declarations {
+      let Declaration::Struct { tag, .. } = declaration else {
+        continue;
+      };
+      if self.layouts.contains_key(tag.as_str()) {
+        continue;
+      }
+      if let Err(err) = self.compute_layout(tag) {
+        self.warn(&format!("struct `{}`", self.name(tag)), &err);
+        self.structs.insert(tag, Err(err));
+      }
+    }
+  }
+
+  fn warn(&mut self, what: &str, reason: &str) {
+    self.warnings.push(format!("Skipped {what}: {reason}"));
+  }
+
+  /// The name of the generated definition of a struct or enum.
+  fn name(&self, tag: &str) -> String {
+    match self.names.get(tag) {
+      Some(name) => name.to_string(),
+      None => match tag.strip_prefix("__anonymous_") {
+        Some(index) => format!("Anonymous{index}"),
+        None => tag.to_string(),
+      },
+    }
+  }
+
+  fn compute_layout(&mut self, tag: &str) -> Result<(), String> {
+    let (key, fields) = match self.structs.get_key_value(tag) {
+      Some((key, fields)) => (*key, fields.clone()?),
+      None => return Err("struct is opaque".to_string()),
+    };
+    if fields.is_empty() {
+      return Err("empty structs are not supported".to_string());
+    }
+    // a struct can't contain itself, only a pointer to itself
+    self
+      .structs
+      .insert(key, Err("a struct cannot contain itself".to_string()));
+    let layout = self.compute_fields(fields);
+    self.structs.insert(key, Ok(fields));
+    self.layouts.insert(tag.to_string(), layout?);
+    Ok(())
+  }
+
+  fn compute_fields(
+    &mut self,
+    fields: &'a [Field],
+  ) -> Result<StructLayout, String> {
+    let mut layout = StructLayout {
+      size: 0,
+      align: 1,
+      fields: vec![],
+    };
+    for field in fields {
+      let mut element = &field.ty;
+      let mut length = None;
+      while let CType::Array(inner, array_length) = element {
+        let array_length = array_length.ok_or_else(|| {
+          format!("flexible array member `{}` is not supported", field.name)
+        })?;
+        length = Some(length.unwrap_or(1) * array_length);
+        element = inner;
+      }
+      let ty = self
+        .resolve(element, Position::Field)
+        .map_err(|err| format!("field `{}`: {err}", field.name))?;
+      let (size, align) = match &ty {
+        Native::Scalar(name) => scalar_layout(name),
+        Native::Struct(tag) => {
+          if !self.layouts.contains_key(tag) {
+            self.compute_layout(tag)?;
+          }
+          let nested = &self.layouts[tag];
+          (nested.size, nested.align)
+        }
+      };
+      let offset = align_to(layout.size, align);
+      layout.fields.push(FieldLayout {
+        name: field.name.clone(),
+        offset,
+        ty,
+        length,
+      });
+      layout.size = offset + size * length.unwrap_or(1);
+      layout.align = layout.align.max(align);
+    }
+    layout.size = align_to(layout.size, layout.align);
+    Ok(layout)
+  }
+
+  fn resolve(&self, ty: &CType, position: Position) -> Result<Native, String> {
+    self.resolve_with_depth(ty, position, 0)
+  }
+
+  fn resolve_with_depth(
+    &self,
+    ty: &CType,
+    position: Position,
+    depth: usize,
+  ) -> Result<Native, String> {
+    if depth > 32 {
+      return Err("too many nested typedefs".to_string());
+    }
+    match ty {
+      CType::Primitive(Primitive::Void) => match position {
+        Position::Result => Ok(Native::Scalar("void")),
+        _ => Err("`void` can only be used as a result".to_string()),
+      },
+      CType::Primitive(primitive) => {
+        Ok(Native::Scalar(primitive_type(*primitive)))
+      }
+      CType::Named(name) => match self.typedefs.get(name.as_str()) {
+        Some(ty) => self.resolve_with_depth(ty, position, depth + 1),
+        None => builtin_type(name)
+          .map(Native::Scalar)
+          .ok_or_else(|| format!("unknown type `{name}`")),
+      },
+      CType::Enum(tag) => Ok(Native::Scalar(
+        self.enums.get(tag.as_str()).copied().unwrap_or("i32"),
+      )),
+      CType::Struct(tag) => match self.structs.get(tag.as_str()) {
+        Some(Ok(_)) => Ok(Native::Struct(tag.clone())),
+        Some(Err(err)) => Err(format!(
+          "struct `{}` is not supported, {err}",
+          self.name(tag)
+        )),
+        None => Err(format!(
+          "struct `{tag}` is opaque and can only be used through a pointer"
+        )),
+      },
+      CType::Union(_) => Err("unions are not supported".to_string()),
+      CType::Pointer(inner) => {
+        if self.is_function(inner, depth) {
+          Ok(Native::Scalar(match position {
+            Position::Static => "pointer",
+            _ => "function",
+          }))
+        } else if position == Position::Parameter
+          && matches!(
+            self.resolve_with_depth(inner, Position::Field, depth + 1),
+            Ok(Native::Scalar("u8" | "i8"))
+          )
+        {
+          // byte pointers, like `const char *`, can be passed typed arrays
+          Ok(Native::Scalar("buffer"))
+        } else {
+          Ok(Native::Scalar("pointer"))
+        }
+      }
+      CType::Array(..) => {
+        Err("arrays can only be used as struct fields".to_string())
+      }
+      CType::Function(_) => {
+        Err("functions can only be used through a pointer".to_string())
+      }
+    }
+  }
+
+  fn is_function(&self, ty: &CType, depth: usize) -> bool {
+    match ty {
+      CType::Function(_) => true,
+      CType::Named(name) if depth < 32 => self
+        .typedefs
+        .get(name.as_str())
+        .is_some_and(|ty| self.is_function(ty, depth + 1)),
+      _ => false,
+    }
+  }
+
+  /// Returns `name`, or a warning if it is already used by another
+  /// definition of the module.
+  fn export(&mut self, name: String, what: &str) -> Option<String> {
+    let name = if is_reserved_word(&name) {
+      format!("_{name}")
+    } else {
+      name
+    };
+    if self.exported.insert(name.clone()) {
+      Some(name)
+    } else {
+      self.warn(what, &format!("the name `{name}` is already used"));
+      None
+    }
+  }
+
+  fn emit(
+    &mut self,
+    header: &'a Header,
+    source_name: &str,
+    lib: Option<&str>,
+  ) -> String {
+    let mut out = String::new();
+    writeln!(
+      out,
+      "// Generated by `deno ffi bindgen` from {source_name}. Do not edit."
+    )
+    .unwrap();
+    writeln!(out, "// deno-lint-ignore-file").unwrap();
+
+    self.emit_constants(&mut out, header);
+    self.emit_enums(&mut out, header);
+    self.emit_structs(&mut out, header);
+    self.emit_symbols(&mut out, header, lib);
+    out
+  }
+
+  fn emit_constants(&mut self, out: &mut String, header: &'a Header) {
+    let mut constants = String::new();
+    for declaration in &header.declarations {
+      let Declaration::Constant { name, value } = declaration else {
+        continue;
+      };
+      let literal = match value {
+        Value::Int(value) => int_literal(*value),
+        Value::UInt { value, .. } => int_literal(*value as i128),
+        Value::Float(value) if value.is_finite() => format!("{value:?}"),
+        Value::Float(_) => {
+          self.warn(
+            &format!("constant `{name}`"),
+            "the value is not a finite number",
+          );
+          continue;
+        }
+        Value::Str(value) => serde_json::to_string(value).unwrap(),
+      };
+      let Some(name) = self.export(name.clone(), &format!("constant `{name}`"))
+      else {
+        continue;
+      };
+      writeln!(constants, "export const {name} = {literal};").unwrap();
+    }
+    if !constants.is_empty() {
+      writeln!(out, "\n{}", constants.trim_end()).unwrap();
+    }
+  }
+
+  fn emit_enums(&mut self, out: &mut String, header: &'a Header) {
+    for declaration in &header.declarations {
+      let Declaration::Enum {
+        tag,
+        anonymous,
+        variants,
+      } = declaration
+      else {
+        continue;
+      };
+      if variants.is_empty() {
+        continue;
+      }
+      if *anonymous && !self.names.contains_key(tag.as_str()) {
+        // the enumerators of an unnamed enum are plain constants
+        writeln!(out).unwrap();
+        for (variant, value) in variants {
+          let what = format!("enumerator `{variant}`");
+          if let Some(variant) = self.export(variant.clone(), &what) {
+            writeln!(out, "export const {variant} = {};", int_literal(*value))
+              .unwrap();
+          }
+        }
+        continue;
+      }
+      let what = format!("enum `{}`", self.name(tag));
+      let Some(name) = self.export(self.name(tag), &what) else {
+        continue;
+      };
+      writeln!(out, "\n/** `enum {}` */", self.name(tag)).unwrap();
+      writeln!(out, "export const {name} = {{").unwrap();
+      for (variant, value) in variants {
+        writeln!(out, "  {variant}: {},", int_literal(*value)).unwrap();
+      }
+      writeln!(out, "}} as const;").unwrap();
+      writeln!(
+        out,
+        "export type {name} = typeof {name}[keyof typeof {name}];"
+      )
+      .unwrap();
+    }
+  }
+
+  fn emit_structs(&mut self, out: &mut String, header: &'a Header) {
+    let mut emitted_helpers = false;
+    for declaration in &header.declarations {
+      let Declaration::Struct { tag, .. } = declaration else {
+        continue;
+      };
+      if !self.layouts.contains_key(tag.as_str()) {
+        continue;
+      }
+      let struct_name = self.name(tag);
+      let what = format!("struct `{struct_name}`");
+      let Some(name) = self.export(struct_name.clone(), &what) else {
+        // leave it out of the symbols as well, to keep the module valid
+        self
+          .structs
+          .insert(tag, Err("the name is already used".into()));
+        continue;
+      };
+      if !emitted_helpers {
+        emitted_helpers = true;
+        out.push_str(STRUCT_HELPERS);
+      }
+      let layout = &self.layouts[tag];
+
+      let mut types = vec![];
+      for field in &layout.fields {
+        let ty = self.native_type(&field.ty);
+        match field.length {
+          None => types.push(ty),
+          Some(length) if length <= MAX_INLINE_ARRAY_LENGTH => {
+            types.extend(std::iter::repeat(ty).take(length))
+          }
+          Some(length) => {
+            types.push(format!("...new Array<{ty}>({length}).fill({ty})"))
+          }
+        }
+      }
+      writeln!(
+        out,
+        "\n/** `struct {struct_name}`, {} bytes aligned to {} bytes. */",
+        layout.size, layout.align
+      )
+      .unwrap();
+      writeln!(out, "export const {name} = {{").unwrap();
+      writeln!(out, "  struct: {},", format_list(&types, 2, 2 + 8, ","))
+        .unwrap();
+      writeln!(out, "}} as const;").unwrap();
+
+      writeln!(out, "\nexport interface {name}Value {{").unwrap();
+      for field in &layout.fields {
+        let mut ty = self.value_type(&field.ty);
+        if field.length.is_some() {
+          ty = match ty.contains(' ') {
+            true => format!("({ty})[]"),
+            false => format!("{ty}[]"),
+          };
+        }
+        writeln!(out, "  {}: {ty};", field.name).unwrap();
+      }
+      writeln!(out, "}}").unwrap();
+
+      let size = layout.size;
+      write!(
+        out,
+        r#"
+/** Reads the `{name}` struct in a buffer or in the memory a pointer points
+ * to. */
+export function decode{name}(
+  source: BufferSource | Deno.PointerObject,
+  offset = 0,
+): {name}Value {{
+  return read{name}(toDataView(source, offset + {size}), offset);
+}}
+
+/** Writes the `{name}` struct to a new buffer, or to a buffer or the memory a
+ * pointer points to. */
+export function encode{name}(
+  value: {name}Value,
+  target: BufferSource | Deno.PointerObject = new Uint8Array({size}),
+  offset = 0,
+): Uint8Array {{
+  const view = toDataView(target, offset + {size});
+  write{name}(view, offset, value);
+  return new Uint8Array(view.buffer, view.byteOffset + offset, {size});
+}}
+"#
+      )
+      .unwrap();
+
+      writeln!(
+        out,
+        "\nfunction read{name}(view: DataView, offset: number): {name}Value {{"
+      )
+      .unwrap();
+      writeln!(out, "  return {{").unwrap();
+      for field in &layout.fields {
+        let offset = offset_expression(field.offset);
+        let read = match field.length {
+          None => self.read_expression(&field.ty, &offset),
+          Some(length) => {
+            let element = element_offset(&offset, self.size_of(&field.ty));
+            let element = self.read_expression(&field.ty, &element);
+            let read = format!(
+              "Array.from({{ length: {length} }}, (_, i) => {element})"
+            );
+            if field.name.len() + read.len() + 7 <= 80 {
+              read
+            } else {
+              format!(
+                "Array.from(\n      {{ length: {length} }},\n      (_, i) => {element},\n    )"
+              )
+            }
+          }
+        };
+        writeln!(out, "    {}: {read},", field.name).unwrap();
+      }
+      writeln!(out, "  }};").unwrap();
+      writeln!(out, "}}").unwrap();
+
+      let signature =
+        format!("function write{name}(view: DataView, offset: number, value: {name}Value) {{");
+      if signature.len() <= 80 {
+        writeln!(out, "\n{signature}").unwrap();
+      } else {
+        writeln!(
+          out,
+          "\nfunction write{name}(\n  view: DataView,\n  offset: number,\n  value: {name}Value,\n) {{"
+        )
+        .unwrap();
+      }
+      for field in &layout.fields {
+        let offset = offset_expression(field.offset);
+        let value = format!("value.{}", field.name);
+        match field.length {
+          None => {
+            let statement = self.write_statement(&field.ty, &offset, &value);
+            writeln!(out, "{};", wrap_call(&statement, 2)).unwrap();
+          }
+          Some(length) => {
+            let element = element_offset(&offset, self.size_of(&field.ty));
+            writeln!(out, "  for (let i = 0; i < {length}; i++) {{").unwrap();
+            let statement =
+              self.write_statement(&field.ty, &element, &format!("{value}[i]"));
+            writeln!(out, "{};", wrap_call(&statement, 4)).unwrap();
+            writeln!(out, "  }}").unwrap();
+          }
+        }
+      }
+      writeln!(out, "}}").unwrap();
+    }
+  }
+
+  fn emit_symbols(
+    &mut self,
+    out: &mut String,
+    header: &'a Header,
+    lib: Option<&str>,
+  ) {
+    let mut symbols = String::new();
+    let mut names = HashSet::new();
+    let mut uses_strings = false;
+    for declaration in &header.declarations {
+      match declaration {
+        Declaration::Function { name, ty } => {
+          if !names.insert(name.as_str()) {
+            continue;
+          }
+          match self.function_definition(name, ty) {
+            Ok((definition, has_buffer)) => {
+              uses_strings |= has_buffer;
+              if ty.variadic {
+                writeln!(
+                  symbols,
+                  "  /**\n   * A variadic function. Declare a copy of it for each \
+                   combination of\n   * variadic argument types it is called with, \
+                   using `name: \"{name}\"`.\n   */"
+                )
+                .unwrap();
+              }
+              writeln!(symbols, "  {name}: {definition},").unwrap();
+            }
+            Err(err) => self.warn(&format!("function `{name}`"), &err),
+          }
+        }
+        Declaration::Variable { name, ty } => {
+          if !names.insert(name.as_str()) {
+            continue;
+          }
+          match self.resolve(ty, Position::Static) {
+            Ok(Native::Scalar(ty)) => {
+              writeln!(symbols, "  {name}: {{ type: \"{ty}\" }},").unwrap()
+            }
+            Ok(Native::Struct(_)) => self.warn(
+              &format!("variable `{name}`"),
+              "struct variables are not supported",
+            ),
+            Err(err) => self.warn(&format!("variable `{name}`"), &err),
+          }
+        }
+        _ => {}
+      }
+    }
+
+    if uses_strings {
+      out.push_str(STRING_HELPERS);
+    }
+    writeln!(out, "\nexport const symbols = {{\n{symbols}}} as const;")
+      .unwrap();
+    writeln!(
+      out,
+      "\nexport type Library = Deno.DynamicLibrary<typeof symbols>;"
+    )
+    .unwrap();
+    let path = match lib {
+      Some(lib) => format!(
+        "path: string | URL = {}",
+        serde_json::to_string(lib).unwrap()
+      ),
+      None => "path: string | URL".to_string(),
+    };
+    write!(
+      out,
+      r#"
+/** Opens the library and binds its symbols. */
+export function load({path}): Library {{
+  return Deno.dlopen(path, symbols);
+}}
+"#
+    )
+    .unwrap();
+  }
+
+  /// Returns the symbol definition of a function and whether it has buffer
+  /// parameters.
+  fn function_definition(
+    &self,
+    name: &str,
+    function: &FunctionType,
+  ) -> Result<(String, bool), String> {
+    let mut parameters = vec![];
+    for (index, parameter) in function.parameters.iter().enumerate() {
+      let ty = self
+        .resolve(parameter, Position::Parameter)
+        .map_err(|err| format!("parameter {}: {err}", index + 1))?;
+      parameters.push(ty);
+    }
+    let result = self
+      .resolve(&function.result, Position::Result)
+      .map_err(|err| format!("result: {err}"))?;
+    let has_buffer = parameters.contains(&Native::Scalar("buffer"));
+    let parameters = parameters
+      .iter()
+      .map(|ty| self.native_type(ty))
+      .collect::<Vec<_>>();
+    let result = self.native_type(&result);
+    let variadic = if function.variadic {
+      "variadic: [], "
+    } else {
+      ""
+    };
+    let definition = format!(
+      "{{ parameters: [{}], {variadic}result: {result} }}",
+      parameters.join(", ")
+    );
+    // the `  name: ` prefix and `,` suffix of the entry
+    if name.len() + definition.len() + 5 <= 80 {
+      return Ok((definition, has_buffer));
+    }
+    let mut definition = format!(
+      "{{\n    parameters: {},\n",
+      format_list(&parameters, 4, 4 + 12, ",")
+    );
+    if function.variadic {
+      definition.push_str("    variadic: [],\n");
+    }
+    write!(definition, "    result: {result},\n  }}").unwrap();
+    Ok((definition, has_buffer))
+  }
+
+  fn native_type(&self, ty: &Native) -> String {
+    match ty {
+      Native::Scalar(name) => format!("\"{name}\""),
+      Native::Struct(tag) => self.name(tag),
+    }
+  }
+
+  fn size_of(&self, ty: &Native) -> usize {
+    match ty {
+      Native::Scalar(name) => scalar_layout(name).0,
+      Native::Struct(tag) => self.layouts[tag].size,
+    }
+  }
+
+  /// The TypeScript type of a struct field.
+  fn value_type(&self, ty: &Native) -> String {
+    match ty {
+      Native::Scalar("bool") => "boolean".to_string(),
+      Native::Scalar("i64" | "u64" | "isize" | "usize") => {
+        "number | bigint".to_string()
+      }
+      Native::Scalar("pointer" | "function") => "Deno.PointerValue".to_string(),
+      Native::Scalar(_) => "number".to_string(),
+      Native::Struct(tag) => format!("{}Value", self.name(tag)),
+    }
+  }
+
+  fn read_expression(&self, ty: &Native, offset: &str) -> String {
+    let name = match ty {
+      Native::Scalar(name) => *name,
+      Native::Struct(tag) => {
+        return format!("read{}(view, {offset})", self.name(tag))
+      }
+    };
+    match (name, POINTER_SIZE) {
+      ("bool", _) => format!("view.getUint8({offset}) !== 0"),
+      ("i8", _) => format!("view.getInt8({offset})"),
+      ("u8", _) => format!("view.getUint8({offset})"),
+      ("pointer" | "function", 8) => {
+        format!("Deno.UnsafePointer.create(view.getBigUint64({offset}, LE))")
+      }
+      ("pointer" | "function", _) => {
+        format!("Deno.UnsafePointer.create(view.getUint32({offset}, LE))")
+      }
+      _ => format!("view.get{}({offset}, LE)", data_view_type(name)),
+    }
+  }
+
+  fn write_statement(&self, ty: &Native, offset: &str, value: &str) -> String {
+    let name = match ty {
+      Native::Scalar(name) => *name,
+      Native::Struct(tag) => {
+        return format!("write{}(view, {offset}, {value})", self.name(tag))
+      }
+    };
+    let setter = data_view_type(name);
+    match (name, POINTER_SIZE) {
+      ("bool", _) => format!("view.setUint8({offset}, {value} ? 1 : 0)"),
+      ("i8" | "u8", _) => format!("view.set{setter}({offset}, {value})"),
+      ("pointer" | "function", 8) => format!(
+        "view.setBigUint64({offset}, BigInt(Deno.UnsafePointer.value({value})), LE)"
+      ),
+      ("pointer" | "function", _) => format!(
+        "view.setUint32({offset}, Number(Deno.UnsafePointer.value({value})), LE)"
+      ),
+      ("i64" | "u64", _) | ("isize" | "usize", 8) => {
+        format!("view.set{setter}({offset}, BigInt({value}), LE)")
+      }
+      ("isize" | "usize", _) => {
+        format!("view.set{setter}({offset}, Number({value}), LE)")
+      }
+      _ => format!("view.set{setter}({offset}, {value}, LE)"),
+    }
+  }
+}
+
+const STRUCT_HELPERS: &str = r#"
+const LE = new Uint8Array(new Uint16Array([1]).buffer)[0] === 1;
+
+function toDataView(
+  source: BufferSource | Deno.PointerObject,
+  byteLength: number,
+): DataView {
+  if (source instanceof ArrayBuffer) {
+    return new DataView(source);
+  }
+  if (ArrayBuffer.isView(source)) {
+    return new DataView(source.buffer, source.byteOffset, source.byteLength);
+  }
+  return new DataView(
+    Deno.UnsafePointerView.getArrayBuffer(source, byteLength),
+  );
+}
+"#;
+
+const STRING_HELPERS: &str = r#"
+const encoder = new TextEncoder();
+
+/** Encodes a string as a null-terminated UTF-8 C string. */
+export function cstring(value: string): Uint8Array {
+  return encoder.encode(`${value}\0`);
+}
+
+/** Reads the null-terminated C string a pointer points to. */
+export function readCString(pointer: Deno.PointerValue): string | null {
+  return pointer === null ? null : Deno.UnsafePointerView.getCString(pointer);
+}
+"#;
+
+fn primitive_type(primitive: Primitive) -> &'static str {
+  match primitive {
+    Primitive::Void => "void",
+    Primitive::Bool => "bool",
+    // `char` is unsigned on ARM, except on Apple platforms and Windows
+    Primitive::Char
+      if cfg!(all(
+        any(target_arch = "aarch64", target_arch = "arm"),
+        not(target_vendor = "apple"),
+        not(windows)
+      )) =>
+    {
+      "u8"
+    }
+    Primitive::Char | Primitive::I8 => "i8",
+    Primitive::U8 => "u8",
+    Primitive::I16 => "i16",
+    Primitive::U16 => "u16",
+    Primitive::I32 => "i32",
+    Primitive::U32 => "u32",
+    Primitive::I64 => "i64",
+    Primitive::U64 => "u64",
+    Primitive::ISize => "isize",
+    Primitive::USize => "usize",
+    Primitive::F32 => "f32",
+    Primitive::F64 => "f64",
+  }
+}
+
+/// The types of the C standard library headers, which are usually included
+/// rather than declared by a header.
+fn builtin_type(name: &str) -> Option<&'static str> {
+  Some(match name {
+    "int8_t" => "i8",
+    "uint8_t" => "u8",
+    "int16_t" => "i16",
+    "uint16_t" => "u16",
+    "int32_t" => "i32",
+    "uint32_t" => "u32",
+    "int64_t" | "intmax_t" => "i64",
+    "uint64_t" | "uintmax_t" => "u64",
+    "size_t" | "uintptr_t" => "usize",
+    "ssize_t" | "intptr_t" | "ptrdiff_t" => "isize",
+    "char16_t" => "u16",
+    "char32_t" => "u32",
+    "wchar_t" if cfg!(windows) => "u16",
+    "wchar_t" => "i32",
+    _ => return None,
+  })
+}
+
+fn scalar_layout(name: &str) -> (usize, usize) {
+  use std::mem::align_of;
+  match name {
+    "bool" | "i8" | "u8" => (1, 1),
+    "i16" | "u16" => (2, 2),
+    "i32" | "u32" => (4, 4),
+    "f32" => (4, align_of::<f32>()),
+    "i64" | "u64" => (8, align_of::<u64>()),
+    "f64" => (8, align_of::<f64>()),
+    _ => (POINTER_SIZE, align_of::<usize>()),
+  }
+}
+
+fn data_view_type(name: &str) -> &'static str {
+  match name {
+    "bool" | "u8" => "Uint8",
+    "i8" => "Int8",
+    "i16" => "Int16",
+    "u16" => "Uint16",
+    "i32" => "Int32",
+    "u32" => "Uint32",
+    "i64" => "BigInt64",
+    "u64" => "BigUint64",
+    "f32" => "Float32",
+    "f64" => "Float64",
+    "isize" if POINTER_SIZE == 8 => "BigInt64",
+    "isize" => "Int32",
+    _ if POINTER_SIZE == 8 => "BigUint64",
+    _ => "Uint32",
+  }
+}
+
+fn align_to(offset: usize, align: usize) -> usize {
+  (offset + align - 1) / align * align
+}
+
+fn offset_expression(offset: usize) -> String {
+  if offset == 0 {
+    "offset".to_string()
+  } else {
+    format!("offset + {offset}")
+  }
+}
+
+fn element_offset(offset: &str, size: usize) -> String {
+  if size == 1 {
+    format!("{offset} + i")
+  } else {
+    format!("{offset} + i * {size}")
+  }
+}
+
+/// Puts each argument of a call statement on its own line if it is longer
+/// than 80 columns after `indent` columns.
+fn wrap_call(statement: &str, indent: usize) -> String {
+  let padding = " ".repeat(indent);
+  if indent + statement.len() < 80 {
+    return format!("{padding}{statement}");
+  }
+  let (Some(open), Some(close)) = (statement.find('('), statement.rfind(')'))
+  else {
+    return format!("{padding}{statement}");
+  };
+  let mut out = format!("{padding}{}\n", &statement[..=open]);
+  let mut depth = 0;
+  let mut start = open + 1;
+  for (i, c) in statement[..close].char_indices().skip(open + 1) {
+    match c {
+      '(' | '[' | '{' => depth += 1,
+      ')' | ']' | '}' => depth -= 1,
+      ',' if depth == 0 => {
+        writeln!(out, "{padding}  {},", statement[start..i].trim()).unwrap();
+        start = i + 1;
+      }
+      _ => {}
+    }
+  }
+  writeln!(out, "{padding}  {},", statement[start..close].trim()).unwrap();
+  write!(out, "{padding}{}", &statement[close..]).unwrap();
+  out
+}
+
+fn int_literal(value: i128) -> String {
+  const MAX_SAFE_INTEGER: u128 = (1 << 53) - 1;
+  if value.unsigned_abs() <= MAX_SAFE_INTEGER {
+    value.to_string()
+  } else {
+    format!("{value}n")
+  }
+}
+
+/// Formats an array literal starting at `column` on one line if it fits
+/// within 80 columns before `suffix`, and on a line per item otherwise.
+fn format_list(
+  items: &[String],
+  indent: usize,
+  column: usize,
+  suffix: &str,
+) -> String {
+  let line = format!("[{}]", items.join(", "));
+  if column + line.len() + suffix.len() <= 80 {
+    return line;
+  }
+  let item_indent = " ".repeat(indent + 2);
+  let close_indent = " ".repeat(indent);
+  let mut out = "[\n".to_string();
+  for item in items {
+    writeln!(out, "{item_indent}{item},").unwrap();
+  }
+  out.push_str(&close_indent);
+  out.push(']');
+  out
+}
+
+fn is_reserved_word(name: &str) -> bool {
+  matches!(
+    name,
+    "await"
+      | "break"
+      | "case"
+      | "catch"
+      | "class"
+      | "const"
+      | "continue"
+      | "debugger"
+      | "default"
+      | "delete"
+      | "do"
+      | "else"
+      | "enum"
+      | "export"
+      | "extends"
+      | "false"
+      | "finally"
+      | "for"
+      | "function"
+      | "if"
+      | "implements"
+      | "import"
+      | "in"
+      | "instanceof"
+      | "interface"
+      | "let"
+      | "new"
+      | "null"
+      | "package"
+      | "private"
+      | "protected"
+      | "public"
+      | "return"
+      | "static"
+      | "super"
+      | "switch"
+      | "this"
+      | "throw"
+      | "true"
+      | "try"
+      | "typeof"
+      | "var"
+      | "void"
+      | "while"
+      | "with"
+      | "yield"
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::c_parser::parse;
+  use super::*;
+
+  fn generate_source(source: &str) -> Bindings {
+    generate(&parse(source), "test.h", Some("./libtest.so"))
+  }
+
+  #[test]
+  fn int_literals() {
+    assert_eq!(int_literal(-9007199254740991), "-9007199254740991");
+    assert_eq!(int_literal(9007199254740992), "9007199254740992n");
+    assert_eq!(int_literal(i128::MIN), format!("{}n", i128::MIN));
+  }
+
+  #[test]
+  fn symbols() {
+    let bindings = generate_source(
+      r#"
+      #define VERSION 3
+      #define LIMIT 0x7fffffffffffffffLL
+      typedef void (*callback_t)(int32_t code);
+      typedef enum { RED, GREEN = 0x80000000 } Color;
+      uint8_t add(uint8_t a, uint8_t b);
+      void log_message(const char *message, callback_t callback);
+      Color color(void);
+      int printf(const char *format, ...);
+      extern const int32_t counter;
+      "#,
+    );
+    assert_eq!(bindings.warnings, Vec::<String>::new());
+    let code = bindings.code;
+    assert!(code.contains("export const VERSION = 3;\n"));
+    assert!(code.contains("export const LIMIT = 9223372036854775807n;\n"));
+    assert!(code.contains(
+      "export const Color = {\n  RED: 0,\n  GREEN: 2147483648,\n} as const;\n"
+    ));
+    assert!(code
+      .contains("  add: { parameters: [\"u8\", \"u8\"], result: \"u8\" },\n"));
+    assert!(code.contains(
+      "  log_message: { parameters: [\"buffer\", \"function\"], result: \"void\" },\n"
+    ));
+    assert!(code.contains("  color: { parameters: [], result: \"u32\" },\n"));
+    assert!(code.contains(
+      "  printf: { parameters: [\"buffer\"], variadic: [], result: \"i32\" },\n"
+    ));
+    assert!(code.contains("  counter: { type: \"i32\" },\n"));
+    assert!(code.contains("export function readCString("));
+    assert!(code.contains(
+      "export function load(path: string | URL = \"./libtest.so\"): Library {\n"
+    ));
+  }
+
+  #[test]
+  fn structs() {
+    let bindings = generate_source(
+      r#"
+      typedef struct { uint8_t tag; double value; } Entry;
+      struct Table {
+        int16_t counts[3];
+        Entry first;
+        bool sorted;
+      };
+      Entry lookup(struct Table table, const char *key);
+      "#,
+    );
+    assert_eq!(bindings.warnings, Vec::<String>::new());
+    let code = bindings.code;
+    assert!(code.contains(
+      "/** `struct Entry`, 16 bytes aligned to 8 bytes. */\n\
+       export const Entry = {\n  struct: [\"u8\", \"f64\"],\n} as const;\n"
+    ));
+    assert!(code.contains(
+      "/** `struct Table`, 32 bytes aligned to 8 bytes. */\n\
+       export const Table = {\n  struct: [\"i16\", \"i16\", \"i16\", Entry, \"bool\"],\n} as const;\n"
+    ));
+    assert!(code.contains(
+      "export interface TableValue {\n  counts: number[];\n  first: EntryValue;\n  sorted: boolean;\n}\n"
+    ));
+    assert!(code.contains("    value: view.getFloat64(offset + 8, LE),\n"));
+    assert!(code.contains("    first: readEntry(view, offset + 8),\n"));
+    assert!(code
+      .contains("    view.setInt16(offset + i * 2, value.counts[i], LE);\n"));
+    assert!(
+      code.contains("  view.setUint8(offset + 24, value.sorted ? 1 : 0);\n")
+    );
+    assert!(code.contains(
+      "  lookup: { parameters: [Table, \"buffer\"], result: Entry },\n"
+    ));
+  }
+
+  #[test]
+  fn skipped_declarations() {
+    let bindings = generate_source(
+      r#"
+      typedef struct Handle Handle;
+      struct Flags { unsigned a : 1; };
+      union Number { int i; float f; };
+      struct List { struct List next; };
+      struct Node { struct Node *next; int value; };
+      void close_handle(Handle handle);
+      void set_number(union Number number);
+      void set_flags(struct Flags flags);
+      Handle *open_handle(void);
+      "#,
+    );
+    assert_eq!(
+      bindings.warnings,
+      vec![
+        "Skipped struct `Flags`: bitfields are not supported",
+        "Skipped struct `Number`: unions are not supported",
+        "Skipped struct `List`: field `next`: struct `List` is not supported, a struct cannot contain itself",
+        "Skipped function `close_handle`: parameter 1: struct `Handle` is opaque and can only be used through a pointer",
+        "Skipped function `set_number`: parameter 1: unions are not supported",
+        "Skipped function `set_flags`: parameter 1: struct `Flags` is not supported, bitfields are not supported",
+      ]
+    );
+    assert!(bindings.code.contains("export const Node = {"));
+    assert!(bindings
+      .code
+      .contains("  open_handle: { parameters: [], result: \"pointer\" },\n"));
+  }
+}
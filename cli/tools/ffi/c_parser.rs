@@ -0,0 +1,1698 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! A parser for the subset of C found in library headers: function
+//! prototypes, structs, unions, enums, typedefs, `extern` variables and
+//! constants. Headers are not run through a preprocessor, so `#include` and
+//! conditional directives are ignored and only object-like macros are
+//! expanded.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Primitive {
+  Void,
+  Bool,
+  /// Plain `char`, whose signedness depends on the platform.
+  Char,
+  I8,
+  U8,
+  I16,
+  U16,
+  I32,
+  U32,
+  I64,
+  U64,
+  ISize,
+  USize,
+  F32,
+  F64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CType {
+  Primitive(Primitive),
+  /// A typedef name, possibly declared in a header that was not read.
+  Named(String),
+  Struct(String),
+  Union(String),
+  Enum(String),
+  Pointer(Box<CType>),
+  /// An array with its length, which is `None` for `[]`.
+  Array(Box<CType>, Option<usize>),
+  Function(Box<FunctionType>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FunctionType {
+  pub result: CType,
+  pub parameters: Vec<CType>,
+  pub variadic: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Field {
+  pub name: String,
+  pub ty: CType,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+  Int(i128),
+  /// An `unsigned int` (32 bits) or `unsigned long` (64 bits). Arithmetic on
+  /// it wraps around at its width, like in C.
+  UInt {
+    value: u64,
+    bits: u32,
+  },
+  Float(f64),
+  Str(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Declaration {
+  /// An object-like `#define` or a `static const` variable with a constant
+  /// value.
+  Constant {
+    name: String,
+    value: Value,
+  },
+  /// A struct or union with a body. The fields are an error when a member
+  /// cannot be represented, like a bitfield.
+  Struct {
+    tag: String,
+    anonymous: bool,
+    is_union: bool,
+    fields: Result<Vec<Field>, String>,
+  },
+  Enum {
+    tag: String,
+    anonymous: bool,
+    variants: Vec<(String, i128)>,
+  },
+  Typedef {
+    name: String,
+    ty: CType,
+  },
+  Function {
+    name: String,
+    ty: FunctionType,
+  },
+  Variable {
+    name: String,
+    ty: CType,
+  },
+}
+
+#[derive(Debug, Default)]
+pub struct Header {
+  pub declarations: Vec<Declaration>,
+  /// Declarations that were skipped because they could not be parsed.
+  pub warnings: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+  Ident(String),
+  Number(String),
+  Str(String),
+  Char(String),
+  Punct(&'static str),
+}
+
+/// The width of `int` and `long`, as on 64-bit Linux and macOS.
+const INT_BITS: u32 = 32;
+const LONG_BITS: u32 = 64;
+
+const PUNCTUATORS: &[&str] = &[
+  "...", "<<", ">>", "->", "&&", "||", "==", "!=", "<=", ">=", "##", "{", "}",
+  "(", ")", "[", "]", ";", ",", "*", "=", ":", "<", ">", "+", "-", "/", "%",
+  "|", "&", "~", "^", "!", "?", ".", "#",
+];
+
+/// Keywords that do not change how a declaration is represented.
+const QUALIFIERS: &[&str] = &[
+  "const",
+  "volatile",
+  "restrict",
+  "__restrict",
+  "__restrict__",
+  "__const",
+  "__volatile__",
+  "inline",
+  "__inline",
+  "__inline__",
+  "__forceinline",
+  "register",
+  "auto",
+  "__extension__",
+  "_Noreturn",
+  "noreturn",
+  "__cdecl",
+  "__stdcall",
+  "__fastcall",
+  "__vectorcall",
+  "_Thread_local",
+  "thread_local",
+  "__thread",
+  "_Atomic",
+  "__ptr32",
+  "__ptr64",
+];
+
+/// Compiler extensions that take parenthesized arguments and are dropped
+/// along with them.
+const EXTENSIONS: &[&str] = &[
+  "__attribute__",
+  "__attribute",
+  "__declspec",
+  "__asm__",
+  "__asm",
+  "_Alignas",
+  "alignas",
+  "__pragma",
+  "_Pragma",
+  "_Static_assert",
+  "static_assert",
+];
+
+pub fn parse(source: &str) -> Header {
+  let source =
+    strip_comments(&source.replace("\\\r\n", "").replace("\\\n", ""));
+
+  let mut code = String::with_capacity(source.len());
+  let mut macros = HashMap::new();
+  let mut macro_order = vec![];
+  let mut function_like: HashSet<String> =
+    EXTENSIONS.iter().map(|s| s.to_string()).collect();
+  for line in source.lines() {
+    let Some(directive) = line.trim_start().strip_prefix('#') else {
+      code.push_str(line);
+      code.push('\n');
+      continue;
+    };
+    let Some(define) = directive.trim_start().strip_prefix("define") else {
+      continue;
+    };
+    if !define.starts_with(char::is_whitespace) {
+      continue;
+    }
+    let define = define.trim_start();
+    let name_len = define
+      .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+      .unwrap_or(define.len());
+    let (name, body) = define.split_at(name_len);
+    if name.is_empty() {
+      continue;
+    }
+    if body.starts_with('(') {
+      macros.remove(name);
+      function_like.insert(name.to_string());
+    } else {
+      function_like.remove(name);
+      if macros.insert(name.to_string(), tokenize(body)).is_none() {
+        macro_order.push(name.to_string());
+      }
+    }
+  }
+
+  let expander = Expander {
+    macros: &macros,
+    function_like: &function_like,
+  };
+  let mut header = Header::default();
+  let mut constants = HashMap::new();
+  for name in macro_order {
+    let Some(body) = macros.get(&name) else {
+      continue;
+    };
+    let mut tokens = vec![];
+    expander.expand(body, &mut vec![name.clone()], &mut tokens);
+    // macros that do not evaluate to a value are not constants, like include
+    // guards or `#define API __declspec(dllexport)`
+    if let Ok(value) = evaluate(&tokens, &constants) {
+      constants.insert(name.clone(), value.clone());
+      header
+        .declarations
+        .push(Declaration::Constant { name, value });
+    }
+  }
+
+  let mut tokens = vec![];
+  expander.expand(&tokenize(&code), &mut vec![], &mut tokens);
+  let mut parser = Parser {
+    tokens,
+    pos: 0,
+    anonymous_count: 0,
+    constants,
+    header,
+  };
+  parser.parse_translation_unit();
+  parser.header
+}
+
+fn strip_comments(source: &str) -> String {
+  let mut out = String::with_capacity(source.len());
+  let mut chars = source.chars().peekable();
+  while let Some(c) = chars.next() {
+    match c {
+      '"' | '\'' => {
+        out.push(c);
+        while let Some(next) = chars.next() {
+          out.push(next);
+          if next == '\\' {
+            if let Some(escaped) = chars.next() {
+              out.push(escaped);
+            }
+          } else if next == c || next == '\n' {
+            break;
+          }
+        }
+      }
+      '/' if chars.peek() == Some(&'/') => {
+        while chars.next_if(|c| *c != '\n').is_some() {}
+      }
+      '/' if chars.peek() == Some(&'*') => {
+        chars.next();
+        let mut prev = ' ';
+        for next in chars.by_ref() {
+          if prev == '*' && next == '/' {
+            break;
+          }
+          // keep line breaks, so directives after the comment stay on a line
+          // of their own
+          if next == '\n' {
+            out.push('\n');
+          }
+          prev = next;
+        }
+        out.push(' ');
+      }
+      _ => out.push(c),
+    }
+  }
+  out
+}
+
+fn tokenize(source: &str) -> Vec<Token> {
+  let chars: Vec<char> = source.chars().collect();
+  let mut tokens = vec![];
+  let mut i = 0;
+  while i < chars.len() {
+    let c = chars[i];
+    if c.is_whitespace() {
+      i += 1;
+    } else if c.is_ascii_alphabetic() || c == '_' {
+      let start = i;
+      while i < chars.len()
+        && (chars[i].is_ascii_alphanumeric() || chars[i] == '_')
+      {
+        i += 1;
+      }
+      let ident: String = chars[start..i].iter().collect();
+      // drop the encoding prefix of string and character literals
+      if matches!(ident.as_str(), "L" | "u" | "U" | "u8")
+        && matches!(chars.get(i), Some('"' | '\''))
+      {
+        continue;
+      }
+      tokens.push(Token::Ident(ident));
+    } else if c.is_ascii_digit()
+      || (c == '.' && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit()))
+    {
+      let start = i;
+      while i < chars.len() {
+        let c = chars[i];
+        let is_exponent_sign = matches!(c, '+' | '-')
+          && matches!(chars[i - 1], 'e' | 'E' | 'p' | 'P');
+        if c.is_ascii_alphanumeric()
+          || matches!(c, '.' | '_' | '\'')
+          || is_exponent_sign
+        {
+          i += 1;
+        } else {
+          break;
+        }
+      }
+      tokens.push(Token::Number(chars[start..i].iter().collect()));
+    } else if c == '"' || c == '\'' {
+      let start = i + 1;
+      i += 1;
+      while i < chars.len() && chars[i] != c && chars[i] != '\n' {
+        if chars[i] == '\\' {
+          i += 1;
+        }
+        i += 1;
+      }
+      let text = unescape(&chars[start..i.min(chars.len())]);
+      i += 1;
+      tokens.push(if c == '"' {
+        Token::Str(text)
+      } else {
+        Token::Char(text)
+      });
+    } else if let Some(punct) = PUNCTUATORS.iter().find(|p| {
+      p.chars()
+        .enumerate()
+        .all(|(offset, p)| chars.get(i + offset) == Some(&p))
+    }) {
+      i += punct.len();
+      tokens.push(Token::Punct(punct));
+    } else {
+      // characters C does not use outside of literals
+      i += 1;
+    }
+  }
+  tokens
+}
+
+fn unescape(chars: &[char]) -> String {
+  let mut out = String::new();
+  let mut i = 0;
+  while i < chars.len() {
+    if chars[i] != '\\' || i + 1 == chars.len() {
+      out.push(chars[i]);
+      i += 1;
+      continue;
+    }
+    i += 1;
+    match chars[i] {
+      'n' => out.push('\n'),
+      'r' => out.push('\r'),
+      't' => out.push('\t'),
+      'v' => out.push('\u{b}'),
+      'f' => out.push('\u{c}'),
+      'a' => out.push('\u{7}'),
+      'b' => out.push('\u{8}'),
+      'e' => out.push('\u{1b}'),
+      'x' => {
+        let digits: String = chars[i + 1..]
+          .iter()
+          .take_while(|c| c.is_ascii_hexdigit())
+          .collect();
+        i += digits.len();
+        let code = u32::from_str_radix(&digits, 16).unwrap_or(0);
+        out.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+      }
+      '0'..='7' => {
+        let digits: String = chars[i..]
+          .iter()
+          .take(3)
+          .take_while(|c| matches!(c, '0'..='7'))
+          .collect();
+        i += digits.len() - 1;
+        let code = u32::from_str_radix(&digits, 8).unwrap_or(0);
+        out.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+      }
+      c => out.push(c),
+    }
+    i += 1;
+  }
+  out
+}
+
+/// Returns the index after the bracket closing the one at `open`.
+fn skip_balanced(tokens: &[Token], open: usize) -> usize {
+  let mut depth = 0;
+  let mut i = open;
+  while i < tokens.len() {
+    match tokens[i] {
+      Token::Punct("(" | "[" | "{") => depth += 1,
+      Token::Punct(")" | "]" | "}") => {
+        depth -= 1;
+        if depth == 0 {
+          return i + 1;
+        }
+      }
+      _ => {}
+    }
+    i += 1;
+  }
+  tokens.len()
+}
+
+struct Expander<'a> {
+  macros: &'a HashMap<String, Vec<Token>>,
+  function_like: &'a HashSet<String>,
+}
+
+impl Expander<'_> {
+  /// Replaces object-like macros with their bodies. Invocations of
+  /// function-like macros are dropped, as their expansion is not known.
+  fn expand(
+    &self,
+    tokens: &[Token],
+    active: &mut Vec<String>,
+    out: &mut Vec<Token>,
+  ) {
+    let mut i = 0;
+    while i < tokens.len() {
+      if let Token::Ident(name) = &tokens[i] {
+        if !active.contains(name) {
+          if let Some(body) = self.macros.get(name) {
+            active.push(name.clone());
+            self.expand(body, active, out);
+            active.pop();
+            i += 1;
+            continue;
+          }
+          if self.function_like.contains(name)
+            && tokens.get(i + 1) == Some(&Token::Punct("("))
+          {
+            i = skip_balanced(tokens, i + 1);
+            continue;
+          }
+        }
+      }
+      out.push(tokens[i].clone());
+      i += 1;
+    }
+  }
+}
+
+struct Specifiers {
+  ty: CType,
+  is_typedef: bool,
+  is_static: bool,
+}
+
+struct Parser {
+  tokens: Vec<Token>,
+  pos: usize,
+  anonymous_count: usize,
+  constants: HashMap<String, Value>,
+  header: Header,
+}
+
+impl Parser {
+  fn peek(&self) -> Option<&Token> {
+    self.tokens.get(self.pos)
+  }
+
+  fn peek_punct(&self, punct: &str) -> bool {
+    matches!(self.peek(), Some(Token::Punct(p)) if *p == punct)
+  }
+
+  fn eat(&mut self, punct: &str) -> bool {
+    let found = self.peek_punct(punct);
+    if found {
+      self.pos += 1;
+    }
+    found
+  }
+
+  fn expect(&mut self, punct: &str) -> Result<(), String> {
+    if self.eat(punct) {
+      Ok(())
+    } else {
+      Err(format!("expected `{punct}`"))
+    }
+  }
+
+  /// Returns the index of the next `,`, `;` or closing bracket that is not
+  /// nested in brackets opened after the current position.
+  fn find_end_of_expression(&self) -> usize {
+    let mut i = self.pos;
+    while i < self.tokens.len() {
+      match self.tokens[i] {
+        Token::Punct("(" | "[" | "{") => {
+          i = skip_balanced(&self.tokens, i);
+          continue;
+        }
+        Token::Punct("," | ";" | ")" | "]" | "}") => return i,
+        _ => {}
+      }
+      i += 1;
+    }
+    i
+  }
+
+  fn parse_translation_unit(&mut self) {
+    while self.pos < self.tokens.len() {
+      // `extern "C" {` and the closing brace of C++ guards
+      if matches!(self.peek(), Some(Token::Ident(word)) if word == "extern")
+        && matches!(self.tokens.get(self.pos + 1), Some(Token::Str(_)))
+        && matches!(self.tokens.get(self.pos + 2), Some(Token::Punct("{")))
+      {
+        self.pos += 3;
+        continue;
+      }
+      if self.eat(";") || self.eat("}") {
+        continue;
+      }
+      let start = self.pos;
+      if let Err(err) = self.parse_external_declaration() {
+        self.pos = start;
+        let snippet = self.skip_declaration();
+        self.header.warnings.push(format!("{err} in `{snippet}`"));
+      }
+    }
+  }
+
+  /// Skips a declaration that could not be parsed and returns its beginning.
+  fn skip_declaration(&mut self) -> String {
+    let start = self.pos;
+    while self.pos < self.tokens.len() {
+      match self.tokens[self.pos] {
+        Token::Punct(";") => {
+          self.pos += 1;
+          break;
+        }
+        Token::Punct("{") => {
+          let after_parameters =
+            self.pos > 0 && self.tokens[self.pos - 1] == Token::Punct(")");
+          self.pos = skip_balanced(&self.tokens, self.pos);
+          // the body of a function definition is not followed by a `;`
+          if after_parameters {
+            break;
+          }
+        }
+        Token::Punct("(" | "[") => {
+          self.pos = skip_balanced(&self.tokens, self.pos);
+        }
+        _ => self.pos += 1,
+      }
+    }
+    let end = self.pos.min(start + 6);
+    self.tokens[start..end]
+      .iter()
+      .map(|token| match token {
+        Token::Ident(text) | Token::Number(text) => text.clone(),
+        Token::Str(text) => format!("{text:?}"),
+        Token::Char(text) => format!("'{text}'"),
+        Token::Punct(punct) => punct.to_string(),
+      })
+      .collect::<Vec<_>>()
+      .join(" ")
+  }
+
+  fn parse_external_declaration(&mut self) -> Result<(), String> {
+    let specifiers = self.parse_specifiers()?;
+    if self.eat(";") {
+      return Ok(());
+    }
+    loop {
+      let (name, ty) = self.parse_declarator(specifiers.ty.clone())?;
+      let name = name.ok_or("expected a name")?;
+      if specifiers.is_typedef {
+        self
+          .header
+          .declarations
+          .push(Declaration::Typedef { name, ty });
+      } else if let CType::Function(function) = ty {
+        if self.peek_punct("{") {
+          // an inline function definition
+          self.pos = skip_balanced(&self.tokens, self.pos);
+          return Ok(());
+        }
+        if !specifiers.is_static {
+          self.header.declarations.push(Declaration::Function {
+            name,
+            ty: *function,
+          });
+        }
+      } else if self.eat("=") {
+        let end = self.find_end_of_expression();
+        let value = evaluate(&self.tokens[self.pos..end], &self.constants);
+        self.pos = end;
+        if let (true, Ok(value)) = (specifiers.is_static, value) {
+          self.constants.insert(name.clone(), value.clone());
+          self
+            .header
+            .declarations
+            .push(Declaration::Constant { name, value });
+        }
+      } else if !specifiers.is_static {
+        self
+          .header
+          .declarations
+          .push(Declaration::Variable { name, ty });
+      }
+      if !self.eat(",") {
+        return self.expect(";");
+      }
+    }
+  }
+
+  fn parse_specifiers(&mut self) -> Result<Specifiers, String> {
+    let mut is_typedef = false;
+    let mut is_static = false;
+    let mut signed = None;
+    let mut short = 0;
+    let mut long = 0;
+    let mut base: Option<String> = None;
+    let mut ty = None;
+    while let Some(Token::Ident(word)) = self.peek() {
+      let word = word.clone();
+      let has_type = ty.is_some()
+        || base.is_some()
+        || signed.is_some()
+        || short > 0
+        || long > 0;
+      match word.as_str() {
+        "typedef" => is_typedef = true,
+        "static" => is_static = true,
+        "extern" => {
+          // `extern "C"` on a single declaration
+          if matches!(self.tokens.get(self.pos + 1), Some(Token::Str(_))) {
+            self.pos += 1;
+          }
+        }
+        "signed" | "__signed" | "__signed__" => signed = Some(true),
+        "unsigned" => signed = Some(false),
+        "short" => short += 1,
+        "long" => long += 1,
+        "char" | "int" | "float" | "double" | "void" | "_Bool" | "bool" => {
+          base = Some(word)
+        }
+        "struct" | "union" => {
+          ty = Some(self.parse_struct(word == "union")?);
+          continue;
+        }
+        "enum" => {
+          ty = Some(self.parse_enum()?);
+          continue;
+        }
+        word if QUALIFIERS.contains(&word) => {}
+        // anything else is a typedef name, unless it follows a type
+        _ if !has_type => ty = Some(CType::Named(word)),
+        _ => break,
+      }
+      self.pos += 1;
+    }
+
+    let ty = match ty {
+      Some(ty) => ty,
+      None => CType::Primitive(match (base.as_deref(), signed, short, long) {
+        (None, None, 0, 0) => return Err("expected a type".to_string()),
+        (Some("void"), ..) => Primitive::Void,
+        (Some("_Bool" | "bool"), ..) => Primitive::Bool,
+        (Some("float"), ..) => Primitive::F32,
+        (Some("double"), _, _, 0) => Primitive::F64,
+        (Some("double"), ..) => {
+          return Err("`long double` is not supported".to_string())
+        }
+        (Some("char"), None, ..) => Primitive::Char,
+        (Some("char"), Some(true), ..) => Primitive::I8,
+        (Some("char"), Some(false), ..) => Primitive::U8,
+        (_, Some(false), 1.., _) => Primitive::U16,
+        (_, _, 1.., _) => Primitive::I16,
+        (_, Some(false), _, 2..) => Primitive::U64,
+        (_, _, _, 2..) => Primitive::I64,
+        // `long` is as wide as a pointer, except on Windows
+        (_, Some(false), _, 1) if cfg!(windows) => Primitive::U32,
+        (_, _, _, 1) if cfg!(windows) => Primitive::I32,
+        (_, Some(false), _, 1) => Primitive::USize,
+        (_, _, _, 1) => Primitive::ISize,
+        (_, Some(false), ..) => Primitive::U32,
+        _ => Primitive::I32,
+      }),
+    };
+    Ok(Specifiers {
+      ty,
+      is_typedef,
+      is_static,
+    })
+  }
+
+  fn parse_tag(&mut self) -> (String, bool) {
+    self.pos += 1;
+    match self.peek() {
+      Some(Token::Ident(tag)) => {
+        let tag = tag.clone();
+        self.pos += 1;
+        (tag, false)
+      }
+      _ => {
+        self.anonymous_count += 1;
+        (format!("__anonymous_{}", self.anonymous_count), true)
+      }
+    }
+  }
+
+  fn parse_struct(&mut self, is_union: bool) -> Result<CType, String> {
+    let (tag, anonymous) = self.parse_tag();
+    if anonymous && !self.peek_punct("{") {
+      return Err("expected a struct tag or body".to_string());
+    }
+    if self.peek_punct("{") {
+      let end = skip_balanced(&self.tokens, self.pos);
+      self.pos += 1;
+      let fields = self.parse_fields(end - 1);
+      self.pos = end;
+      self.header.declarations.push(Declaration::Struct {
+        tag: tag.clone(),
+        anonymous,
+        is_union,
+        fields,
+      });
+    }
+    Ok(if is_union {
+      CType::Union(tag)
+    } else {
+      CType::Struct(tag)
+    })
+  }
+
+  fn parse_fields(&mut self, end: usize) -> Result<Vec<Field>, String> {
+    let mut fields = vec![];
+    while self.pos < end {
+      if self.eat(";") {
+        continue;
+      }
+      let specifiers = self.parse_specifiers()?;
+      if self.peek_punct(";") {
+        return Err("anonymous members are not supported".to_string());
+      }
+      loop {
+        let (name, ty) = self.parse_declarator(specifiers.ty.clone())?;
+        if self.peek_punct(":") {
+          return Err("bitfields are not supported".to_string());
+        }
+        fields.push(Field {
+          name: name.ok_or("expected a member name")?,
+          ty,
+        });
+        if !self.eat(",") {
+          break;
+        }
+      }
+      self.expect(";")?;
+    }
+    Ok(fields)
+  }
+
+  fn parse_enum(&mut self) -> Result<CType, String> {
+    let (tag, anonymous) = self.parse_tag();
+    // a C23 fixed underlying type
+    if self.eat(":") {
+      self.parse_specifiers()?;
+    }
+    if anonymous && !self.peek_punct("{") {
+      return Err("expected an enum tag or body".to_string());
+    }
+    if self.peek_punct("{") {
+      let end = skip_balanced(&self.tokens, self.pos) - 1;
+      self.pos += 1;
+      let mut variants = vec![];
+      let mut next = 0;
+      while self.pos < end {
+        let Some(Token::Ident(name)) = self.peek() else {
+          return Err("expected an enumerator".to_string());
+        };
+        let name = name.clone();
+        self.pos += 1;
+        if self.eat("=") {
+          let expression_end = self.find_end_of_expression();
+          let value =
+            evaluate(&self.tokens[self.pos..expression_end], &self.constants);
+          self.pos = expression_end;
+          next = match value {
+            Ok(Value::Int(value)) => value,
+            Ok(Value::UInt { value, .. }) => value as i128,
+            _ => return Err(format!("cannot evaluate the value of `{name}`")),
+          };
+        }
+        self.constants.insert(name.clone(), Value::Int(next));
+        variants.push((name, next));
+        next += 1;
+        if !self.eat(",") && self.pos < end {
+          return Err("expected `,`".to_string());
+        }
+      }
+      self.pos = end + 1;
+      self.header.declarations.push(Declaration::Enum {
+        tag: tag.clone(),
+        anonymous,
+        variants,
+      });
+    }
+    Ok(CType::Enum(tag))
+  }
+
+  fn skip_qualifiers(&mut self) {
+    while matches!(self.peek(), Some(Token::Ident(word)) if QUALIFIERS.contains(&word.as_str()))
+    {
+      self.pos += 1;
+    }
+  }
+
+  /// Parses a declarator, like `*name`, `name[4]` or `(*name)(int)`, that
+  /// derives a type from `ty`. The name is `None` for abstract declarators.
+  fn parse_declarator(
+    &mut self,
+    mut ty: CType,
+  ) -> Result<(Option<String>, CType), String> {
+    while self.eat("*") {
+      self.skip_qualifiers();
+      ty = CType::Pointer(Box::new(ty));
+    }
+    self.skip_qualifiers();
+
+    // a nested declarator, like the `(*name)` of a function pointer, applies
+    // to the type with the suffixes that follow it
+    if self.peek_punct("(")
+      && matches!(self.tokens.get(self.pos + 1), Some(Token::Punct("*" | "(")))
+    {
+      let inner = self.pos + 1;
+      self.pos = skip_balanced(&self.tokens, self.pos);
+      let ty = self.parse_declarator_suffixes(ty)?;
+      let end = self.pos;
+      self.pos = inner;
+      let declarator = self.parse_declarator(ty)?;
+      self.expect(")")?;
+      self.pos = end;
+      return Ok(declarator);
+    }
+
+    let name = match self.peek() {
+      Some(Token::Ident(name)) => {
+        let name = name.clone();
+        self.pos += 1;
+        Some(name)
+      }
+      _ => None,
+    };
+    Ok((name, self.parse_declarator_suffixes(ty)?))
+  }
+
+  fn parse_declarator_suffixes(&mut self, ty: CType) -> Result<CType, String> {
+    enum Suffix {
+      Array(Option<usize>),
+      Function(Vec<CType>, bool),
+    }
+
+    let mut suffixes = vec![];
+    loop {
+      if self.eat("[") {
+        // `static` and qualifiers of array parameters
+        while matches!(self.peek(), Some(Token::Ident(word)) if word == "static" || QUALIFIERS.contains(&word.as_str()))
+        {
+          self.pos += 1;
+        }
+        let end = self.find_end_of_expression();
+        let length = if end == self.pos {
+          None
+        } else {
+          match evaluate(&self.tokens[self.pos..end], &self.constants) {
+            Ok(Value::Int(length)) if length >= 0 => Some(length as usize),
+            Ok(Value::UInt { value, .. }) => Some(value as usize),
+            _ => return Err("cannot evaluate the length of an array".into()),
+          }
+        };
+        self.pos = end;
+        self.expect("]")?;
+        suffixes.push(Suffix::Array(length));
+      } else if self.peek_punct("(") {
+        let (parameters, variadic) = self.parse_parameters()?;
+        suffixes.push(Suffix::Function(parameters, variadic));
+      } else {
+        break;
+      }
+    }
+
+    Ok(
+      suffixes
+        .into_iter()
+        .rev()
+        .fold(ty, |ty, suffix| match suffix {
+          Suffix::Array(length) => CType::Array(Box::new(ty), length),
+          Suffix::Function(parameters, variadic) => {
+            CType::Function(Box::new(FunctionType {
+              result: ty,
+              parameters,
+              variadic,
+            }))
+          }
+        }),
+    )
+  }
+
+  fn parse_parameters(&mut self) -> Result<(Vec<CType>, bool), String> {
+    self.expect("(")?;
+    let mut parameters = vec![];
+    if self.eat(")") {
+      return Ok((parameters, false));
+    }
+    if matches!(self.peek(), Some(Token::Ident(word)) if word == "void")
+      && matches!(self.tokens.get(self.pos + 1), Some(Token::Punct(")")))
+    {
+      self.pos += 2;
+      return Ok((parameters, false));
+    }
+    loop {
+      if self.eat("...") {
+        self.expect(")")?;
+        return Ok((parameters, true));
+      }
+      let specifiers = self.parse_specifiers()?;
+      let (_, ty) = self.parse_declarator(specifiers.ty)?;
+      // array and function parameters are adjusted to pointers
+      parameters.push(match ty {
+        CType::Array(element, _) => CType::Pointer(element),
+        ty @ CType::Function(_) => CType::Pointer(Box::new(ty)),
+        ty => ty,
+      });
+      if !self.eat(",") {
+        self.expect(")")?;
+        return Ok((parameters, false));
+      }
+    }
+  }
+}
+
+/// Evaluates a constant expression, like the value of a `#define` or of an
+/// enumerator.
+fn evaluate(
+  tokens: &[Token],
+  constants: &HashMap<String, Value>,
+) -> Result<Value, String> {
+  if tokens.is_empty() {
+    return Err("expected an expression".to_string());
+  }
+  // adjacent string literals are concatenated
+  if tokens.iter().all(|token| matches!(token, Token::Str(_))) {
+    return Ok(Value::Str(
+      tokens
+        .iter()
+        .map(|token| match token {
+          Token::Str(text) => text.as_str(),
+          _ => unreachable!(),
+        })
+        .collect(),
+    ));
+  }
+  let mut evaluator = Evaluator {
+    tokens,
+    pos: 0,
+    constants,
+  };
+  let value = evaluator.conditional()?;
+  if evaluator.pos != tokens.len() {
+    return Err("unexpected token in expression".to_string());
+  }
+  Ok(value)
+}
+
+struct Evaluator<'a> {
+  tokens: &'a [Token],
+  pos: usize,
+  constants: &'a HashMap<String, Value>,
+}
+
+impl Evaluator<'_> {
+  fn eat(&mut self, punct: &str) -> bool {
+    let found =
+      matches!(self.tokens.get(self.pos), Some(Token::Punct(p)) if *p == punct);
+    if found {
+      self.pos += 1;
+    }
+    found
+  }
+
+  fn conditional(&mut self) -> Result<Value, String> {
+    let condition = self.binary(1)?;
+    if !self.eat("?") {
+      return Ok(condition);
+    }
+    let consequent = self.conditional()?;
+    if !self.eat(":") {
+      return Err("expected `:`".to_string());
+    }
+    let alternate = self.conditional()?;
+    Ok(if is_truthy(&condition)? {
+      consequent
+    } else {
+      alternate
+    })
+  }
+
+  fn binary(&mut self, min_precedence: u8) -> Result<Value, String> {
+    let mut left = self.unary()?;
+    while let Some(Token::Punct(op)) = self.tokens.get(self.pos) {
+      let precedence = match *op {
+        "||" => 1,
+        "&&" => 2,
+        "|" => 3,
+        "^" => 4,
+        "&" => 5,
+        "==" | "!=" => 6,
+        "<" | ">" | "<=" | ">=" => 7,
+        "<<" | ">>" => 8,
+        "+" | "-" => 9,
+        "*" | "/" | "%" => 10,
+        _ => break,
+      };
+      if precedence < min_precedence {
+        break;
+      }
+      self.pos += 1;
+      let right = self.binary(precedence + 1)?;
+      left = apply_binary(op, left, right)?;
+    }
+    Ok(left)
+  }
+
+  fn unary(&mut self) -> Result<Value, String> {
+    if self.eat("-") {
+      return match self.unary()? {
+        Value::Int(value) => value
+          .checked_neg()
+          .map(Value::Int)
+          .ok_or_else(|| "invalid constant expression".to_string()),
+        Value::UInt { value, bits } => Ok(unsigned(-(value as i128), bits)),
+        Value::Float(value) => Ok(Value::Float(-value)),
+        Value::Str(_) => Err("invalid operand".to_string()),
+      };
+    }
+    if self.eat("+") {
+      return self.unary();
+    }
+    if self.eat("~") {
+      return match self.unary()? {
+        Value::UInt { value, bits } => Ok(unsigned(!(value as i128), bits)),
+        value => Ok(Value::Int(!as_int(&value)?)),
+      };
+    }
+    if self.eat("!") {
+      return Ok(Value::Int(!is_truthy(&self.unary()?)? as i128));
+    }
+    if self.eat("(") {
+      // a cast to an arithmetic type
+      let start = self.pos;
+      let mut words = vec![];
+      while let Some(Token::Ident(word)) = self.tokens.get(self.pos) {
+        if !is_type_name(word) {
+          break;
+        }
+        words.push(word.as_str());
+        self.pos += 1;
+      }
+      if !words.is_empty() && self.eat(")") {
+        let value = self.unary()?;
+        if words.iter().any(|word| matches!(*word, "float" | "double")) {
+          return Ok(match value {
+            Value::Int(value) => Value::Float(value as f64),
+            Value::UInt { value, .. } => Value::Float(value as f64),
+            value => value,
+          });
+        }
+        let value = match value {
+          Value::Float(value) => value as i128,
+          value => as_int(&value)?,
+        };
+        return Ok(match int_type(&words) {
+          Some((is_unsigned, bits)) => convert(value, is_unsigned, bits),
+          None => Value::Int(value),
+        });
+      }
+      self.pos = start;
+      let value = self.conditional()?;
+      if !self.eat(")") {
+        return Err("expected `)`".to_string());
+      }
+      return Ok(value);
+    }
+    let token = self.tokens.get(self.pos).ok_or("expected a value")?;
+    self.pos += 1;
+    match token {
+      Token::Number(number) => parse_number(number),
+      Token::Char(text) => Ok(Value::Int(
+        text.chars().next().map(|c| c as i128).unwrap_or_default(),
+      )),
+      Token::Ident(name) => self
+        .constants
+        .get(name)
+        .cloned()
+        .ok_or_else(|| format!("unknown identifier `{name}`")),
+      _ => Err("expected a value".to_string()),
+    }
+  }
+}
+
+fn is_type_name(word: &str) -> bool {
+  matches!(
+    word,
+    "char"
+      | "short"
+      | "int"
+      | "long"
+      | "signed"
+      | "unsigned"
+      | "float"
+      | "double"
+  ) || word.ends_with("_t")
+}
+
+/// The signedness and width of the integer type named by `words`, if known.
+fn int_type(words: &[&str]) -> Option<(bool, u32)> {
+  let is_unsigned = words.contains(&"unsigned");
+  if words.contains(&"long") {
+    return Some((is_unsigned, LONG_BITS));
+  }
+  if words.contains(&"short") {
+    return Some((is_unsigned, 16));
+  }
+  if words.contains(&"char") {
+    return Some((is_unsigned, 8));
+  }
+  match *words.last()? {
+    "int" | "signed" | "unsigned" => Some((is_unsigned, INT_BITS)),
+    "size_t" | "uintptr_t" => Some((true, LONG_BITS)),
+    "ssize_t" | "intptr_t" | "ptrdiff_t" => Some((false, LONG_BITS)),
+    word => {
+      let (is_unsigned, word) = match word.strip_prefix('u') {
+        Some(word) => (true, word),
+        None => (false, word),
+      };
+      let bits = word.strip_prefix("int")?.strip_suffix("_t")?.parse().ok()?;
+      matches!(bits, 8 | 16 | 32 | 64).then_some((is_unsigned, bits))
+    }
+  }
+}
+
+/// Converts `value` to an integer type, the way a cast does. Types narrower
+/// than `int` are promoted to `int` again.
+fn convert(value: i128, is_unsigned: bool, bits: u32) -> Value {
+  if is_unsigned {
+    return match unsigned(value, bits) {
+      Value::UInt { value, bits } if bits < INT_BITS => {
+        Value::Int(value as i128)
+      }
+      value => value,
+    };
+  }
+  let shift = 128 - bits;
+  Value::Int((value << shift) >> shift)
+}
+
+/// An unsigned value of `bits` bits, wrapped around like in C.
+fn unsigned(value: i128, bits: u32) -> Value {
+  Value::UInt {
+    value: value as u64 & mask(bits),
+    bits,
+  }
+}
+
+fn mask(bits: u32) -> u64 {
+  u64::MAX >> (64 - bits)
+}
+
+fn as_int(value: &Value) -> Result<i128, String> {
+  match value {
+    Value::Int(value) => Ok(*value),
+    Value::UInt { value, .. } => Ok(*value as i128),
+    _ => Err("expected an integer".to_string()),
+  }
+}
+
+/// The width of the unsigned type that C converts the operands of a binary
+/// operator to, or `None` if the operation is signed. Signed operands are
+/// assumed to be `int`s, or `long`s if they do not fit.
+fn unsigned_bits(left: &Value, right: &Value) -> Option<u32> {
+  match (left, right) {
+    (Value::UInt { bits: left, .. }, Value::UInt { bits: right, .. }) => {
+      Some(*left.max(right))
+    }
+    (Value::UInt { bits, .. }, Value::Int(signed))
+    | (Value::Int(signed), Value::UInt { bits, .. }) => {
+      let signed_bits = if i32::try_from(*signed).is_ok() {
+        INT_BITS
+      } else {
+        LONG_BITS
+      };
+      (*bits >= signed_bits).then_some(*bits)
+    }
+    _ => None,
+  }
+}
+
+fn is_truthy(value: &Value) -> Result<bool, String> {
+  match value {
+    Value::Int(value) => Ok(*value != 0),
+    Value::UInt { value, .. } => Ok(*value != 0),
+    Value::Float(value) => Ok(*value != 0.0),
+    Value::Str(_) => Err("invalid operand".to_string()),
+  }
+}
+
+fn apply_binary(op: &str, left: Value, right: Value) -> Result<Value, String> {
+  let is_int =
+    |value: &Value| matches!(value, Value::Int(_) | Value::UInt { .. });
+  if is_int(&left) && is_int(&right) {
+    // A shift has the type of its left operand, other operators convert both
+    // operands to a common type first.
+    let bits = match (op, &left) {
+      ("<<" | ">>", Value::UInt { bits, .. }) => Some(*bits),
+      ("<<" | ">>", _) => None,
+      _ => unsigned_bits(&left, &right),
+    };
+    let (left, right) = (as_int(&left)?, as_int(&right)?);
+    if let Some(bits) = bits {
+      return apply_unsigned(op, left, right, bits);
+    }
+    let value = match op {
+      "+" => left.checked_add(right),
+      "-" => left.checked_sub(right),
+      "*" => left.checked_mul(right),
+      "/" => left.checked_div(right),
+      "%" => left.checked_rem(right),
+      "<<" => u32::try_from(right).ok().and_then(|r| left.checked_shl(r)),
+      ">>" => u32::try_from(right).ok().and_then(|r| left.checked_shr(r)),
+      "&" => Some(left & right),
+      "|" => Some(left | right),
+      "^" => Some(left ^ right),
+      _ => Some(compare(op, left.cmp(&right), left != 0, right != 0) as i128),
+    };
+    return value
+      .map(Value::Int)
+      .ok_or_else(|| "invalid constant expression".to_string());
+  }
+  let to_float = |value: &Value| match value {
+    Value::Int(value) => Ok(*value as f64),
+    Value::UInt { value, .. } => Ok(*value as f64),
+    Value::Float(value) => Ok(*value),
+    Value::Str(_) => Err("invalid operand".to_string()),
+  };
+  let (left, right) = (to_float(&left)?, to_float(&right)?);
+  Ok(match op {
+    "+" => Value::Float(left + right),
+    "-" => Value::Float(left - right),
+    "*" => Value::Float(left * right),
+    "/" => Value::Float(left / right),
+    "%" | "<<" | ">>" | "&" | "|" | "^" => {
+      return Err("invalid operand".to_string())
+    }
+    _ => Value::Int(compare(
+      op,
+      left.partial_cmp(&right).ok_or("invalid operand")?,
+      left != 0.0,
+      right != 0.0,
+    ) as i128),
+  })
+}
+
+fn apply_unsigned(
+  op: &str,
+  left: i128,
+  right: i128,
+  bits: u32,
+) -> Result<Value, String> {
+  let shift = u32::try_from(right).ok().filter(|shift| *shift < bits);
+  let (left, right) = (left as u64 & mask(bits), right as u64 & mask(bits));
+  let value = match op {
+    "+" => Some(left.wrapping_add(right)),
+    "-" => Some(left.wrapping_sub(right)),
+    "*" => Some(left.wrapping_mul(right)),
+    "/" => left.checked_div(right),
+    "%" => left.checked_rem(right),
+    "<<" => shift.map(|shift| left << shift),
+    ">>" => shift.map(|shift| left >> shift),
+    "&" => Some(left & right),
+    "|" => Some(left | right),
+    "^" => Some(left ^ right),
+    _ => {
+      return Ok(Value::Int(
+        compare(op, left.cmp(&right), left != 0, right != 0) as i128,
+      ))
+    }
+  };
+  value
+    .map(|value| unsigned(value as i128, bits))
+    .ok_or_else(|| "invalid constant expression".to_string())
+}
+
+fn compare(
+  op: &str,
+  ordering: std::cmp::Ordering,
+  left: bool,
+  right: bool,
+) -> bool {
+  use std::cmp::Ordering::*;
+  match op {
+    "==" => ordering == Equal,
+    "!=" => ordering != Equal,
+    "<" => ordering == Less,
+    ">" => ordering == Greater,
+    "<=" => ordering != Greater,
+    ">=" => ordering != Less,
+    "&&" => left && right,
+    _ => left || right,
+  }
+}
+
+fn parse_number(text: &str) -> Result<Value, String> {
+  let text = text.replace('\'', "").to_ascii_lowercase();
+  let is_hex = text.starts_with("0x");
+  let is_float = if is_hex {
+    text.contains('p')
+  } else {
+    text.contains('.') || text.contains('e')
+  };
+  let invalid = || format!("invalid number `{text}`");
+  if is_float {
+    if is_hex {
+      return Err(invalid());
+    }
+    return text
+      .trim_end_matches(['f', 'l'])
+      .parse::<f64>()
+      .map(Value::Float)
+      .map_err(|_| invalid());
+  }
+  let digits = text.trim_end_matches(['u', 'l', 'z']);
+  let suffix = &text[digits.len()..];
+  let is_unsigned = suffix.contains('u');
+  let is_long = suffix.contains(['l', 'z']);
+  let (digits, radix) = if let Some(digits) = digits.strip_prefix("0x") {
+    (digits, 16)
+  } else if let Some(digits) = digits.strip_prefix("0b") {
+    (digits, 2)
+  } else if digits.len() > 1 && digits.starts_with('0') {
+    (&digits[1..], 8)
+  } else {
+    (digits, 10)
+  };
+  let value = i128::from_str_radix(digits, radix).map_err(|_| invalid())?;
+  // Like in C, the constant has the first of `int`, `unsigned int`, `long`
+  // and `unsigned long` that can represent it. Unsigned types are only used
+  // with a `u` suffix or for constants that are not decimal, and signed ones
+  // only without a `u` suffix.
+  for bits in [INT_BITS, LONG_BITS] {
+    if bits == INT_BITS && is_long {
+      continue;
+    }
+    if !is_unsigned && value < 1 << (bits - 1) {
+      return Ok(Value::Int(value));
+    }
+    if (is_unsigned || radix != 10) && value < 1 << bits {
+      return Ok(unsigned(value, bits));
+    }
+  }
+  // Decimal constants too large for a `long` are kept as they are.
+  if is_unsigned {
+    return Err(invalid());
+  }
+  Ok(Value::Int(value))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn declarations(source: &str) -> Vec<Declaration> {
+    let header = parse(source);
+    assert_eq!(header.warnings, Vec::<String>::new());
+    header.declarations
+  }
+
+  fn constant(name: &str, value: Value) -> Declaration {
+    Declaration::Constant {
+      name: name.to_string(),
+      value,
+    }
+  }
+
+  #[test]
+  fn constants() {
+    assert_eq!(
+      declarations(
+        r#"
+        #ifndef LIB_H
+        #define LIB_H
+        #define VERSION "1." "2"
+        #define SHIFT 4
+        #define FLAG (1u << SHIFT) /* 16 */
+        #define MASK 0xff | \
+          FLAG
+        #define RATIO 1.5f
+        #define NEGATIVE -'a'
+        #define MAX(a, b) ((a) > (b) ? (a) : (b))
+        static const int LIMIT = SHIFT * 2 > 4 ? 010 : 0;
+        #endif
+        "#
+      ),
+      vec![
+        constant("VERSION", Value::Str("1.2".to_string())),
+        constant("SHIFT", Value::Int(4)),
+        constant(
+          "FLAG",
+          Value::UInt {
+            value: 16,
+            bits: 32
+          }
+        ),
+        constant(
+          "MASK",
+          Value::UInt {
+            value: 0xff,
+            bits: 32
+          }
+        ),
+        constant("RATIO", Value::Float(1.5)),
+        constant("NEGATIVE", Value::Int(-97)),
+        constant("LIMIT", Value::Int(8)),
+      ]
+    );
+  }
+
+  #[test]
+  fn unsigned_constants() {
+    let eval = |source: &str| evaluate(&tokenize(source), &HashMap::new());
+    let uint = |value| Ok(Value::UInt { value, bits: 32 });
+    let ulong = |value| Ok(Value::UInt { value, bits: 64 });
+    assert_eq!(eval("~0u"), uint(0xFFFF_FFFF));
+    assert_eq!(eval("-1u"), uint(0xFFFF_FFFF));
+    assert_eq!(eval("0xFFFFFFFFu << 4"), uint(0xFFFF_FFF0));
+    assert_eq!(eval("0xFFFFFFFF"), uint(0xFFFF_FFFF));
+    assert_eq!(eval("0xFFFFFFFF + 1"), uint(0));
+    assert_eq!(eval("1u << 32"), Err("invalid constant expression".into()));
+    assert_eq!(eval("~0ul"), ulong(u64::MAX));
+    assert_eq!(eval("~0ull >> 60"), ulong(0xF));
+    assert_eq!(eval("-1 + 0u"), uint(0xFFFF_FFFF));
+    assert_eq!(eval("0x100000000 + 0u"), Ok(Value::Int(0x1_0000_0000)));
+    assert_eq!(eval("-1 < 0u"), Ok(Value::Int(0)));
+    assert_eq!(eval("(unsigned char)-1"), Ok(Value::Int(0xFF)));
+    assert_eq!(eval("(uint64_t)-1"), ulong(u64::MAX));
+    assert_eq!(eval("4294967295"), Ok(Value::Int(0xFFFF_FFFF)));
+    assert_eq!(eval("-1"), Ok(Value::Int(-1)));
+  }
+
+  #[test]
+  fn functions() {
+    let i32_ = CType::Primitive(Primitive::I32);
+    assert_eq!(
+      declarations(
+        r#"
+        #ifdef _WIN32
+        #define API __declspec(dllexport)
+        #else
+        #define API __attribute__((visibility("default")))
+        #endif
+        #ifdef __cplusplus
+        extern "C" {
+        #endif
+        API int add(int a, int b);
+        extern void (*set_handler(int signal, void (*handler)(int)))(int);
+        int log_message(const char *format, ...);
+        static inline int twice(int x) { return x * 2; }
+        void fill(unsigned char buffer[static 8]);
+        #ifdef __cplusplus
+        }
+        #endif
+        "#
+      ),
+      vec![
+        Declaration::Function {
+          name: "add".to_string(),
+          ty: FunctionType {
+            result: i32_.clone(),
+            parameters: vec![i32_.clone(), i32_.clone()],
+            variadic: false,
+          },
+        },
+        Declaration::Function {
+          name: "set_handler".to_string(),
+          ty: FunctionType {
+            result: CType::Pointer(Box::new(CType::Function(Box::new(
+              FunctionType {
+                result: CType::Primitive(Primitive::Void),
+                parameters: vec![i32_.clone()],
+                variadic: false,
+              }
+            )))),
+            parameters: vec![
+              i32_.clone(),
+              CType::Pointer(Box::new(CType::Function(Box::new(
+                FunctionType {
+                  result: CType::Primitive(Primitive::Void),
+                  parameters: vec![i32_.clone()],
+                  variadic: false,
+                }
+              )))),
+            ],
+            variadic: false,
+          },
+        },
+        Declaration::Function {
+          name: "log_message".to_string(),
+          ty: FunctionType {
+            result: i32_,
+            parameters: vec![CType::Pointer(Box::new(CType::Primitive(
+              Primitive::Char
+            )))],
+            variadic: true,
+          },
+        },
+        Declaration::Function {
+          name: "fill".to_string(),
+          ty: FunctionType {
+            result: CType::Primitive(Primitive::Void),
+            parameters: vec![CType::Pointer(Box::new(CType::Primitive(
+              Primitive::U8
+            )))],
+            variadic: false,
+          },
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn types() {
+    assert_eq!(
+      declarations(
+        r#"
+        typedef struct { unsigned short x, y; } Point;
+        struct Line { Point points[2]; struct Line *next; };
+        typedef union Value { long long i; double f; } Value;
+        typedef enum { RED, GREEN = RED + 4, BLUE } Color;
+        typedef int (*compare_t)(const void *, const void *);
+        extern const uint32_t version;
+        "#
+      ),
+      vec![
+        Declaration::Struct {
+          tag: "__anonymous_1".to_string(),
+          anonymous: true,
+          is_union: false,
+          fields: Ok(vec![
+            Field {
+              name: "x".to_string(),
+              ty: CType::Primitive(Primitive::U16),
+            },
+            Field {
+              name: "y".to_string(),
+              ty: CType::Primitive(Primitive::U16),
+            },
+          ]),
+        },
+        Declaration::Typedef {
+          name: "Point".to_string(),
+          ty: CType::Struct("__anonymous_1".to_string()),
+        },
+        Declaration::Struct {
+          tag: "Line".to_string(),
+          anonymous: false,
+          is_union: false,
+          fields: Ok(vec![
+            Field {
+              name: "points".to_string(),
+              ty: CType::Array(
+                Box::new(CType::Named("Point".to_string())),
+                Some(2)
+              ),
+            },
+            Field {
+              name: "next".to_string(),
+              ty: CType::Pointer(Box::new(CType::Struct("Line".to_string()))),
+            },
+          ]),
+        },
+        Declaration::Struct {
+          tag: "Value".to_string(),
+          anonymous: false,
+          is_union: true,
+          fields: Ok(vec![
+            Field {
+              name: "i".to_string(),
+              ty: CType::Primitive(Primitive::I64),
+            },
+            Field {
+              name: "f".to_string(),
+              ty: CType::Primitive(Primitive::F64),
+            },
+          ]),
+        },
+        Declaration::Typedef {
+          name: "Value".to_string(),
+          ty: CType::Union("Value".to_string()),
+        },
+        Declaration::Enum {
+          tag: "__anonymous_2".to_string(),
+          anonymous: true,
+          variants: vec![
+            ("RED".to_string(), 0),
+            ("GREEN".to_string(), 4),
+            ("BLUE".to_string(), 5),
+          ],
+        },
+        Declaration::Typedef {
+          name: "Color".to_string(),
+          ty: CType::Enum("__anonymous_2".to_string()),
+        },
+        Declaration::Typedef {
+          name: "compare_t".to_string(),
+          ty: CType::Pointer(Box::new(CType::Function(Box::new(
+            FunctionType {
+              result: CType::Primitive(Primitive::I32),
+              parameters: vec![
+                CType::Pointer(Box::new(CType::Primitive(Primitive::Void))),
+                CType::Pointer(Box::new(CType::Primitive(Primitive::Void))),
+              ],
+              variadic: false,
+            }
+          )))),
+        },
+        Declaration::Variable {
+          name: "version".to_string(),
+          ty: CType::Named("uint32_t".to_string()),
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn unsupported_declarations() {
+    let header = parse(
+      r#"
+      struct Flags { unsigned a : 1; unsigned b : 1; };
+      long double precise(void);
+      int after(void);
+      "#,
+    );
+    assert_eq!(
+      header.declarations,
+      vec![
+        Declaration::Struct {
+          tag: "Flags".to_string(),
+          anonymous: false,
+          is_union: false,
+          fields: Err("bitfields are not supported".to_string()),
+        },
+        Declaration::Function {
+          name: "after".to_string(),
+          ty: FunctionType {
+            result: CType::Primitive(Primitive::I32),
+            parameters: vec![],
+            variadic: false,
+          },
+        },
+      ]
+    );
+    assert_eq!(
+      header.warnings,
+      vec!["`long double` is not supported in `long double precise ( void )`"]
+    );
+  }
+}
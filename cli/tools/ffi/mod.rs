@@ -0,0 +1,47 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+mod bindgen;
+mod c_parser;
+
+use std::path::Path;
+
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_runtime::colors;
+
+use crate::args::FfiFlags;
+use crate::args::FfiSubcommand;
+use crate::util::display;
+
+pub async fn ffi(ffi_flags: FfiFlags) -> Result<(), AnyError> {
+  match ffi_flags.subcommand {
+    FfiSubcommand::Bindgen {
+      header,
+      output,
+      lib,
+    } => {
+      let source = std::fs::read_to_string(&header)
+        .with_context(|| format!("Failed reading {header}"))?;
+      let parsed = c_parser::parse(&source);
+      let source_name = Path::new(&header)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or(header);
+      let bindings = bindgen::generate(&parsed, &source_name, lib.as_deref());
+      for warning in &bindings.warnings {
+        log::warn!("{} {}", colors::yellow("Warning"), warning);
+      }
+      match output {
+        Some(output) => {
+          std::fs::write(&output, bindings.code)
+            .with_context(|| format!("Failed writing {}", output.display()))?;
+          log::info!("{} {}", colors::green("Generated"), output.display());
+        }
+        None => {
+          display::write_to_stdout_ignore_sigpipe(bindings.code.as_bytes())?
+        }
+      }
+      Ok(())
+    }
+  }
+}
@@ -342,6 +342,18 @@ async fn resolve_shim_data(
     executable_args.push("--location".to_string());
     executable_args.push(url.to_string());
   }
+  if let Some(quota) = flags.local_storage_quota {
+    executable_args.push("--local-storage-quota".to_string());
+    executable_args.push(quota.to_string());
+  }
+  if let Some(dir) = flags.local_storage_dir.as_ref() {
+    executable_args.push("--local-storage-dir".to_string());
+    executable_args.push(
+      canonicalize_path_maybe_not_exists(&std::env::current_dir()?.join(dir))?
+        .to_string_lossy()
+        .to_string(),
+    );
+  }
   if let Some(CaData::File(ca_file)) = &flags.ca_data {
     executable_args.push("--cert".to_string());
     executable_args.push(ca_file.to_owned())
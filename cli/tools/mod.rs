@@ -6,6 +6,7 @@ pub mod check;
 pub mod compile;
 pub mod coverage;
 pub mod doc;
+pub mod ffi;
 pub mod fmt;
 pub mod info;
 pub mod init;
@@ -14,6 +15,7 @@ pub mod jupyter;
 pub mod lint;
 pub mod repl;
 pub mod run;
+pub mod storage;
 pub mod task;
 pub mod test;
 pub mod upgrade;
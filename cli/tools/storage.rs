@@ -0,0 +1,339 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::resolve_url_or_path;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_runtime::colors;
+use deno_runtime::deno_webstorage::rusqlite::params;
+use deno_runtime::deno_webstorage::rusqlite::Connection;
+use indexmap::IndexMap;
+
+use crate::args::CliOptions;
+use crate::args::Flags;
+use crate::args::StorageFlags;
+use crate::args::StorageKeyResolver;
+use crate::args::StorageSubcommand;
+use crate::factory::CliFactory;
+use crate::util::checksum;
+use crate::util::display;
+
+/// The name of the SQLite database `deno_webstorage` keeps in each origin
+/// folder.
+const LOCAL_STORAGE_FILE_NAME: &str = "local_storage";
+
+pub async fn storage(
+  flags: Flags,
+  storage_flags: StorageFlags,
+) -> Result<(), AnyError> {
+  let factory = CliFactory::from_flags(flags).await?;
+  let cli_options = factory.cli_options();
+  let storage_dir = match cli_options.local_storage_dir() {
+    Some(dir) => dir,
+    None => factory.deno_dir()?.origin_data_folder_path(),
+  };
+
+  match storage_flags.subcommand {
+    StorageSubcommand::List { json } => list_origins(&storage_dir, json),
+    StorageSubcommand::Inspect { target, json } => {
+      let id = resolve_origin_id(cli_options, target)?;
+      inspect_origin(&storage_dir, &id, json)
+    }
+    StorageSubcommand::Export { target, output } => {
+      let id = resolve_origin_id(cli_options, target)?;
+      export_origin(&storage_dir, &id, output.as_deref())
+    }
+    StorageSubcommand::Clear { all: true, .. } => {
+      let origins = find_origins(&storage_dir)?;
+      for id in &origins {
+        clear_origin(&storage_dir, id)?;
+      }
+      log::info!("Cleared localStorage of {} origin(s).", origins.len());
+      Ok(())
+    }
+    StorageSubcommand::Clear { target, all: false } => {
+      let id = resolve_origin_id(cli_options, target)?;
+      if clear_origin(&storage_dir, &id)? {
+        log::info!("Cleared localStorage of {}.", colors::green(&id));
+      } else {
+        log::info!("No localStorage data is stored for {id}.");
+      }
+      Ok(())
+    }
+  }
+}
+
+fn is_origin_id(target: &str) -> bool {
+  target.len() == 64 && target.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Resolves the folder name of a storage origin, using the same rules as
+/// `deno run` to turn a script, `--location` or a configuration file into a
+/// storage key.
+fn resolve_origin_id(
+  cli_options: &CliOptions,
+  target: Option<String>,
+) -> Result<String, AnyError> {
+  if let Some(target) = target.as_deref().filter(|t| is_origin_id(t)) {
+    return Ok(target.to_ascii_lowercase());
+  }
+
+  let resolver = StorageKeyResolver::from_options(cli_options);
+  let maybe_key = match resolver.resolve_storage_key_without_main_module() {
+    Some(maybe_key) => maybe_key,
+    None => {
+      let Some(target) = target else {
+        bail!("Specify a script, a storage id or '--location'.");
+      };
+      let main_module =
+        resolve_url_or_path(&target, cli_options.initial_cwd())?;
+      resolver.resolve_storage_key(&main_module)
+    }
+  };
+  match maybe_key {
+    Some(key) => Ok(checksum::gen(&[key.as_bytes()])),
+    None => bail!(
+      "The storage origin is opaque, so no localStorage data is persisted for it."
+    ),
+  }
+}
+
+fn find_origins(storage_dir: &Path) -> Result<Vec<String>, AnyError> {
+  let entries = match std::fs::read_dir(storage_dir) {
+    Ok(entries) => entries,
+    Err(err) if err.kind() == ErrorKind::NotFound => return Ok(vec![]),
+    Err(err) => {
+      return Err(err)
+        .with_context(|| format!("Failed reading {}", storage_dir.display()))
+    }
+  };
+  let mut origins = vec![];
+  for entry in entries {
+    let entry = entry?;
+    let Some(id) = entry.file_name().to_str().map(|s| s.to_string()) else {
+      continue;
+    };
+    if is_origin_id(&id) && entry.path().join(LOCAL_STORAGE_FILE_NAME).exists()
+    {
+      origins.push(id);
+    }
+  }
+  origins.sort();
+  Ok(origins)
+}
+
+fn open_origin(
+  storage_dir: &Path,
+  id: &str,
+) -> Result<Option<Connection>, AnyError> {
+  let path = storage_dir.join(id).join(LOCAL_STORAGE_FILE_NAME);
+  if !path.exists() {
+    return Ok(None);
+  }
+  let conn = Connection::open(&path)
+    .with_context(|| format!("Failed opening {}", path.display()))?;
+  Ok(Some(conn))
+}
+
+fn read_entries(
+  conn: &Connection,
+) -> Result<IndexMap<String, String>, AnyError> {
+  let mut stmt = conn.prepare("SELECT key, value FROM data")?;
+  let rows = stmt.query_map(params![], |row| Ok((row.get(0)?, row.get(1)?)))?;
+  let mut entries = IndexMap::new();
+  for row in rows {
+    let (key, value) = row?;
+    entries.insert(key, value);
+  }
+  Ok(entries)
+}
+
+fn list_origins(storage_dir: &Path, json: bool) -> Result<(), AnyError> {
+  let mut origins = vec![];
+  for id in find_origins(storage_dir)? {
+    let Some(conn) = open_origin(storage_dir, &id)? else {
+      continue;
+    };
+    // the quota counts the UTF-8 encoded length of keys and values
+    let (keys, bytes): (u64, u64) = conn.query_row(
+      "SELECT COUNT(*),
+        COALESCE(SUM(LENGTH(CAST(key AS BLOB)) + LENGTH(CAST(value AS BLOB))), 0)
+      FROM data",
+      params![],
+      |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    origins.push((id, keys, bytes));
+  }
+
+  if json {
+    let value = origins
+      .iter()
+      .map(|(id, keys, bytes)| {
+        json!({
+          "id": id,
+          "path": storage_dir.join(id),
+          "keys": keys,
+          "bytes": bytes,
+        })
+      })
+      .collect::<Vec<_>>();
+    return display::write_json_to_stdout(&value);
+  }
+
+  if origins.is_empty() {
+    log::info!("No localStorage data found in {}", storage_dir.display());
+    return Ok(());
+  }
+  log::info!("{} {}", colors::bold("Location:"), storage_dir.display());
+  for (id, keys, bytes) in origins {
+    let keys = if keys == 1 {
+      "1 key".to_string()
+    } else {
+      format!("{keys} keys")
+    };
+    log::info!(
+      "{}  {}  {}",
+      colors::green(id),
+      keys,
+      colors::gray(display::human_size(bytes as f64))
+    );
+  }
+  Ok(())
+}
+
+fn inspect_origin(
+  storage_dir: &Path,
+  id: &str,
+  json: bool,
+) -> Result<(), AnyError> {
+  let Some(conn) = open_origin(storage_dir, id)? else {
+    bail!("No localStorage data is stored for {id}.");
+  };
+  let entries = read_entries(&conn)?;
+  if json {
+    return display::write_json_to_stdout(&json!({
+      "id": id,
+      "path": storage_dir.join(id),
+      "entries": entries,
+    }));
+  }
+
+  log::info!("{} {}", colors::bold("Origin:"), colors::green(id));
+  log::info!(
+    "{} {}",
+    colors::bold("Path:"),
+    storage_dir.join(id).join(LOCAL_STORAGE_FILE_NAME).display()
+  );
+  log::info!("");
+  if entries.is_empty() {
+    log::info!("{}", colors::gray("(empty)"));
+  }
+  for (key, value) in entries {
+    log::info!(
+      "{}: {}",
+      colors::cyan(serde_json::to_string(&key)?),
+      serde_json::to_string(&value)?
+    );
+  }
+  Ok(())
+}
+
+fn export_origin(
+  storage_dir: &Path,
+  id: &str,
+  output: Option<&Path>,
+) -> Result<(), AnyError> {
+  let Some(conn) = open_origin(storage_dir, id)? else {
+    bail!("No localStorage data is stored for {id}.");
+  };
+  let entries = read_entries(&conn)?;
+  let text = serde_json::to_string_pretty(&entries)? + "\n";
+  match output {
+    Some(output) => {
+      std::fs::write(output, text)
+        .with_context(|| format!("Failed writing {}", output.display()))?;
+      log::info!(
+        "Exported {} key(s) of {} to {}",
+        entries.len(),
+        colors::green(id),
+        output.display()
+      );
+      Ok(())
+    }
+    None => display::write_to_stdout_ignore_sigpipe(text.as_bytes())
+      .map_err(AnyError::from),
+  }
+}
+
+/// Deletes the `localStorage` database of an origin. Other data in the origin
+/// folder, like the default Deno KV database, is kept. Returns `false` if
+/// there was nothing to delete.
+fn clear_origin(storage_dir: &Path, id: &str) -> Result<bool, AnyError> {
+  let origin_dir = storage_dir.join(id);
+  let mut removed = false;
+  for suffix in ["", "-wal", "-shm"] {
+    let path: PathBuf =
+      origin_dir.join(format!("{LOCAL_STORAGE_FILE_NAME}{suffix}"));
+    match std::fs::remove_file(&path) {
+      Ok(()) => removed = true,
+      Err(err) if err.kind() == ErrorKind::NotFound => {}
+      Err(err) => {
+        return Err(err)
+          .with_context(|| format!("Failed removing {}", path.display()))
+      }
+    }
+  }
+  // only succeeds when nothing else is stored for the origin
+  let _ = std::fs::remove_dir(&origin_dir);
+  Ok(removed)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn origin_ids() {
+    assert!(is_origin_id(&checksum::gen(&[b"https://deno.land"])));
+    assert!(!is_origin_id("main.ts"));
+    assert!(!is_origin_id(&"g".repeat(64)));
+  }
+
+  #[test]
+  fn clear_keeps_other_origin_data() {
+    let temp_dir = test_util::TempDir::new();
+    let storage_dir = temp_dir.path().to_path_buf();
+    let id = checksum::gen(&[b"https://deno.land"]);
+    let origin_dir = storage_dir.join(&id);
+    std::fs::create_dir_all(&origin_dir).unwrap();
+    {
+      let conn =
+        Connection::open(origin_dir.join(LOCAL_STORAGE_FILE_NAME)).unwrap();
+      conn
+        .execute_batch(
+          "CREATE TABLE data (key VARCHAR UNIQUE, value VARCHAR);
+          INSERT INTO data (key, value) VALUES ('a', '1'), ('b', '2');",
+        )
+        .unwrap();
+    }
+    std::fs::write(origin_dir.join("kv.sqlite3"), "").unwrap();
+
+    assert_eq!(find_origins(&storage_dir).unwrap(), vec![id.clone()]);
+    let conn = open_origin(&storage_dir, &id).unwrap().unwrap();
+    let entries = read_entries(&conn).unwrap();
+    drop(conn);
+    assert_eq!(entries.get("a").map(|s| s.as_str()), Some("1"));
+    assert_eq!(entries.len(), 2);
+
+    assert!(clear_origin(&storage_dir, &id).unwrap());
+    assert!(!clear_origin(&storage_dir, &id).unwrap());
+    assert!(find_origins(&storage_dir).unwrap().is_empty());
+    assert!(origin_dir.join("kv.sqlite3").exists());
+  }
+}
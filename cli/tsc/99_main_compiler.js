@@ -34,16 +34,33 @@ delete Object.prototype.__proto__;
     "AtomicOperation",
     "CreateHttpClientOptions",
     "DatagramConn",
+    "DnsResolver",
+    "DnsResolverOptions",
+    "HttpCacheOptions",
     "HttpClient",
     "Kv",
     "KvListIterator",
     "KvU64",
+    "RetryOptions",
+    "ServeCompressionOptions",
+    "ServeConnectionInfo",
+    "ServeFdInit",
+    "ServeFdOptions",
+    "ServeLimits",
+    "ServerShutdownOptions",
+    "ServerStats",
+    "TlsCertificates",
+    "TlsCertifiedKey",
+    "TlsClientAuthOptions",
     "UnsafeCallback",
     "UnsafePointer",
     "UnsafePointerView",
     "UnsafeFnPointer",
     "UnixConnectOptions",
     "UnixListenOptions",
+    "WorkerPool",
+    "WorkerPoolOptions",
+    "WorkerPoolStats",
     "createHttpClient",
     "dlopen",
     "flock",
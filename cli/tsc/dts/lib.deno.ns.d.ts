@@ -5356,6 +5356,12 @@ declare namespace Deno {
      *
      * The default is 120 seconds. Set to `0` to disable timeouts. */
     idleTimeout?: number;
+    /** Accept the `permessage-deflate` extension to compress messages, if
+     * the client offers it. `true` accepts it with the parameters the client
+     * asks for, the options here can further restrict them.
+     *
+     * The default is `false`. */
+    compression?: boolean | WebSocketCompressionOptions;
   }
 
   /**
@@ -5459,9 +5465,37 @@ declare namespace Deno {
       ipAddr: string;
       /** The port number the query will be sent to.
        *
-       * @default {53} */
+       * @default {53 for "udp" and "tcp", 853 for "tls" and 443 for "https"} */
       port?: number;
+      /** The protocol used to talk to the name server. `"tls"` is
+       * DNS-over-TLS and `"https"` is DNS-over-HTTPS.
+       *
+       * @default {"udp"} */
+      protocol?: "udp" | "tcp" | "tls" | "https";
+      /** The name the certificate of the name server is verified against.
+       * Required for `"tls"` and `"https"`. */
+      tlsName?: string;
     };
+    /** The timeout of a single query in milliseconds.
+     *
+     * @default {5000} */
+    timeout?: number;
+    /** The number of times a query is sent before giving up.
+     *
+     * @default {2} */
+    attempts?: number;
+    /** Domains appended to names with fewer than `ndots` dots before they
+     * are looked up as they are. */
+    searchDomains?: string[];
+    /** The number of dots a name needs to be looked up as it is first.
+     *
+     * @default {1} */
+    ndots?: number;
+    /** The number of answers kept in the cache, each for as long as its TTL
+     * allows. `0` disables caching.
+     *
+     * @default {32} */
+    cacheSize?: number;
     /**
      * An abort signal to allow cancellation of the DNS resolution operation.
      * If the signal becomes aborted the resolveDns operation will be stopped
@@ -5553,12 +5587,17 @@ declare namespace Deno {
    *   beyond the range of 16-bit unsigned integer.
    * - the request timed out.
    *
+   * A `"PTR"` query can be given an IP address, which is looked up by its
+   * reverse lookup name (for example `4.4.8.8.in-addr.arpa.` for `8.8.4.4`).
+   *
    * ```ts
    * const a = await Deno.resolveDns("example.com", "A");
    *
    * const aaaa = await Deno.resolveDns("example.com", "AAAA", {
    *   nameServer: { ipAddr: "8.8.8.8", port: 53 },
    * });
+   *
+   * const ptr = await Deno.resolveDns("8.8.4.4", "PTR");
    * ```
    *
    * Requires `allow-net` permission.
@@ -318,6 +318,21 @@ declare namespace Deno {
      *
      * @default {false} */
     optional?: boolean;
+    /** The types of the arguments passed in place of the `...` of a C
+     * variadic function, following the fixed `parameters`. A variadic C
+     * function is declared once for every combination of argument types it
+     * is called with.
+     *
+     * C promotes variadic arguments, so `bool`, `i8`, `u8`, `i16` and `u16`
+     * must be passed as `i32`, and `f32` as `f64`.
+     *
+     * ```ts
+     * const { symbols } = Deno.dlopen("libc.so.6", {
+     *   printf: { parameters: ["buffer"], variadic: ["i32"], result: "i32" },
+     * });
+     * ```
+     */
+    variadic?: readonly NativeType[];
   }
 
   /** **UNSTABLE**: New API, yet to be vetted.
@@ -361,11 +376,23 @@ declare namespace Deno {
    *
    *  @category FFI
    */
-  type FromForeignFunction<T extends ForeignFunction> = T["parameters"] extends
-    readonly [] ? () => StaticForeignSymbolReturnType<T>
-    : (
-      ...args: ToNativeParameterTypes<T["parameters"]>
-    ) => StaticForeignSymbolReturnType<T>;
+  type FromForeignFunction<T extends ForeignFunction> =
+    ForeignFunctionParameters<T> extends readonly []
+      ? () => StaticForeignSymbolReturnType<T>
+      : (
+        ...args: ToNativeParameterTypes<ForeignFunctionParameters<T>>
+      ) => StaticForeignSymbolReturnType<T>;
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * The types of the fixed and variadic parameters of a foreign function.
+   *
+   * @category FFI
+   */
+  type ForeignFunctionParameters<T extends ForeignFunction> = T extends {
+    variadic: infer V extends readonly NativeType[];
+  } ? readonly [...T["parameters"], ...V]
+    : T["parameters"];
 
   /** **UNSTABLE**: New API, yet to be vetted.
    *
@@ -893,6 +920,93 @@ declare namespace Deno {
      * @default {false}
      */
     allowHost?: boolean;
+    /** The number of milliseconds to wait for a connection to be established.
+     * Waits indefinitely if not set. */
+    connectTimeout?: number;
+    /** The number of milliseconds a request may take, from connecting until
+     * the response body has been read. Each retry gets the full timeout.
+     * Waits indefinitely if not set. */
+    timeout?: number;
+    /** Retry idempotent requests (`GET`, `HEAD`, `OPTIONS`, `TRACE`, `PUT`
+     * and `DELETE`) that fail to connect, time out, or receive one of the
+     * retryable status codes. Requests with a streaming body are never
+     * retried. */
+    retry?: RetryOptions;
+    /** The maximum number of redirects `fetch` follows before failing with a
+     * network error.
+     *
+     * @default {20}
+     */
+    maxRedirects?: number;
+    /** Cache responses to `GET` requests according to their `Cache-Control`,
+     * `Expires` and `Vary` headers, and revalidate stale responses with
+     * `ETag` or `Last-Modified` conditional requests. Responses are stored
+     * alongside those of the Cache API (`caches`). Pass `true` to use the
+     * defaults.
+     *
+     * @default {false}
+     */
+    cache?: boolean | HttpCacheOptions;
+    /** Send every request over this Unix domain socket (or named pipe on
+     * Windows) instead of connecting to the host in the URL, for example
+     * `"/var/run/docker.sock"`. The host is still sent in the `Host` header.
+     * Only `http:` URLs are supported; set `http1: false` to speak HTTP/2
+     * with prior knowledge (h2c).
+     *
+     * Requires `allow-read` and `allow-write` permissions for the path. */
+    unixSocket?: string;
+    /** Resolve host names with this resolver instead of the system
+     * resolver. */
+    resolver?: DnsResolver;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * How a {@linkcode Deno.HttpClient} caches responses.
+   *
+   * @category Fetch API
+   */
+  export interface HttpCacheOptions {
+    /** The name of the cache the responses are stored in.
+     *
+     * @default {"deno-http-cache"}
+     */
+    name?: string;
+    /** Behave as a shared cache: responses marked `private` are not stored,
+     * `s-maxage` takes precedence over `max-age`, and responses to requests
+     * with an `Authorization` header are only stored when explicitly allowed.
+     *
+     * @default {false}
+     */
+    shared?: boolean;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * How a {@linkcode Deno.HttpClient} retries failed requests.
+   *
+   * @category Fetch API
+   */
+  export interface RetryOptions {
+    /** The number of times a request is retried. */
+    maxRetries: number;
+    /** The number of milliseconds to wait before the first retry. The delay
+     * doubles after every attempt, unless the server sends a `Retry-After`
+     * header.
+     *
+     * @default {100}
+     */
+    initialDelay?: number;
+    /** The maximum number of milliseconds to wait between attempts.
+     *
+     * @default {10000}
+     */
+    maxDelay?: number;
+    /** Responses with these status codes are retried.
+     *
+     * @default {[429, 502, 503, 504]}
+     */
+    statusCodes?: number[];
   }
 
   /** **UNSTABLE**: New API, yet to be vetted.
@@ -903,10 +1017,52 @@ declare namespace Deno {
    * @category Fetch API
    */
   export interface Proxy {
-    /** The string URL of the proxy server to use. */
+    /** The string URL of the proxy server to use for hosts that no rule
+     * matches. `http:`, `https:`, `socks5:` and `socks5h:` URLs are
+     * supported; with `socks5h:` host names are resolved by the proxy. Those
+     * hosts are connected to directly if it is empty. */
     url: string;
     /** The basic auth credentials to be used against the proxy server. */
     basicAuth?: BasicAuth;
+    /** A token sent to HTTP(S) proxy servers as
+     * `Proxy-Authorization: Bearer <token>`. */
+    bearerToken?: string;
+    /** Per-host rules, like in a PAC file. The first rule matching the host
+     * of a request applies.
+     *
+     * ```ts
+     * const client = Deno.createHttpClient({
+     *   proxy: {
+     *     url: "socks5h://proxy.corp.example.com:1080",
+     *     rules: [
+     *       { hosts: ["*.internal.example.com"], url: "http://10.0.0.1:3128" },
+     *       { hosts: ["localhost", "127.0.0.1"] },
+     *     ],
+     *   },
+     * });
+     * ```
+     */
+    rules?: ProxyRule[];
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * A per-host rule of a {@linkcode Deno.Proxy}.
+   *
+   * @category Fetch API
+   */
+  export interface ProxyRule {
+    /** The hosts the rule applies to. `example.com` matches only that host,
+     * `*.example.com` its subdomains and `*` every host. */
+    hosts: string[];
+    /** The string URL of the proxy server the matching hosts connect
+     * through. They are connected to directly if it is not set. */
+    url?: string;
+    /** The basic auth credentials to be used against the proxy server. */
+    basicAuth?: BasicAuth;
+    /** A token sent to an HTTP(S) proxy server as
+     * `Proxy-Authorization: Bearer <token>`. */
+    bearerToken?: string;
   }
 
   /** **UNSTABLE**: New API, yet to be vetted.
@@ -1049,7 +1205,7 @@ declare namespace Deno {
    *
    * @category Network
    */
-  export interface UdpListenOptions extends ListenOptions {
+  export interface UdpListenOptions extends ListenOptions, SocketOptions {
     /** When `true` the specified address will be reused, even if another
      * process has already bound a socket on it. This effectively steals the
      * socket from the listener.
@@ -1057,6 +1213,16 @@ declare namespace Deno {
      * @default {false} */
     reuseAddress?: boolean;
 
+    /** When `true` the SO_REUSEPORT flag will be set on the socket. This
+     * allows multiple processes to bind the same address and port, and the
+     * kernel distributes incoming datagrams among them.
+     *
+     * This flag is only supported on Linux. On other platforms
+     * {@linkcode Deno.errors.NotSupported} is thrown.
+     *
+     * @default {false} */
+    reusePort?: boolean;
+
     /** When `true`, sent multicast packets will be looped back to the local socket.
      *
      * @default {false} */
@@ -1125,6 +1291,109 @@ declare namespace Deno {
     options: UnixListenOptions & { transport: "unixpacket" },
   ): DatagramConn;
 
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * The options used when creating a {@linkcode Deno.DnsResolver}.
+   *
+   * @category Network
+   */
+  export interface DnsResolverOptions
+    extends Omit<ResolveDnsOptions, "signal" | "resolver"> {}
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * A DNS resolver with its own name servers, timeouts and search domains,
+   * and a cache that keeps answers for as long as their TTL allows. It can
+   * be shared by {@linkcode Deno.resolveDns}, {@linkcode Deno.connect} and
+   * {@linkcode Deno.createHttpClient}, for example to discover a service
+   * through its `SRV` record and connect to it:
+   *
+   * ```ts
+   * const resolver = new Deno.DnsResolver({
+   *   nameServer: { ipAddr: "10.0.0.10" },
+   *   searchDomains: ["svc.cluster.local"],
+   * });
+   * const [srv] = await resolver.resolve("_http._tcp.api", "SRV");
+   * const conn = await Deno.connect({
+   *   hostname: srv.target,
+   *   port: srv.port,
+   *   resolver,
+   * });
+   *
+   * const client = Deno.createHttpClient({ resolver });
+   * await fetch(`http://${srv.target}:${srv.port}/`, { client });
+   * ```
+   *
+   * Requires `allow-net` permission for the name servers.
+   *
+   * @tags allow-net
+   * @category Network
+   */
+  export class DnsResolver {
+    constructor(options?: DnsResolverOptions);
+    /** The resource ID of the resolver. */
+    readonly rid: number;
+    /** Resolves `query` like {@linkcode Deno.resolveDns} does, using this
+     * resolver. */
+    resolve(
+      query: string,
+      recordType: "A" | "AAAA" | "ANAME" | "CNAME" | "NS" | "PTR",
+      options?: { signal?: AbortSignal },
+    ): Promise<string[]>;
+    resolve(
+      query: string,
+      recordType: "CAA",
+      options?: { signal?: AbortSignal },
+    ): Promise<CAARecord[]>;
+    resolve(
+      query: string,
+      recordType: "MX",
+      options?: { signal?: AbortSignal },
+    ): Promise<MXRecord[]>;
+    resolve(
+      query: string,
+      recordType: "NAPTR",
+      options?: { signal?: AbortSignal },
+    ): Promise<NAPTRRecord[]>;
+    resolve(
+      query: string,
+      recordType: "SOA",
+      options?: { signal?: AbortSignal },
+    ): Promise<SOARecord[]>;
+    resolve(
+      query: string,
+      recordType: "SRV",
+      options?: { signal?: AbortSignal },
+    ): Promise<SRVRecord[]>;
+    resolve(
+      query: string,
+      recordType: "TXT",
+      options?: { signal?: AbortSignal },
+    ): Promise<string[][]>;
+    /** Closes the resolver and drops its cache. */
+    close(): void;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * @category Network
+   */
+  export interface ResolveDnsOptions {
+    /** Resolve with this resolver instead of the default one. The other
+     * resolver options are ignored when it is set. */
+    resolver?: DnsResolver;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * @category Network
+   */
+  export interface ConnectOptions {
+    /** Resolve `hostname` with this resolver instead of the system
+     * resolver. */
+    resolver?: DnsResolver;
+  }
+
   /** **UNSTABLE**: New API, yet to be vetted.
    *
    * @category Network
@@ -1201,6 +1470,85 @@ declare namespace Deno {
      * If no ALPN protocol selected, returns `null`.
      */
     alpnProtocol: string | null;
+    /** **UNSTABLE**: New API, yet to be vetted.
+     *
+     * The PEM formatted certificate chain the peer presented, leaf first. On
+     * the server side this is `null` unless the client sent a certificate for
+     * {@linkcode ListenTlsOptions.clientAuth}.
+     */
+    peerCertificates: string[] | null;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * A certificate chain and the private key it was issued for.
+   *
+   * @category Network
+   */
+  export interface TlsCertifiedKey {
+    /** Cert chain in PEM format */
+    cert: string;
+    /** Server private key in PEM format */
+    key: string;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * The certificates a TLS server presents to clients.
+   *
+   * @category Network
+   */
+  export interface TlsCertificates {
+    /** Cert chain in PEM format, presented when the client asks for a server
+     * name that is not in `sni`, or for none at all. */
+    cert?: string;
+    /** Server private key in PEM format */
+    key?: string;
+    /** Certificates keyed by the server name clients ask for with SNI. A name
+     * starting with `*.` matches any single label in its place, eg.
+     * `*.example.com` matches `api.example.com`. */
+    sni?: Record<string, TlsCertifiedKey>;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Verification of client certificates (mutual TLS).
+   *
+   * @category Network
+   */
+  export interface TlsClientAuthOptions {
+    /** The PEM formatted certificates of the CAs that client certificates
+     * must be issued by. */
+    ca: string | string[];
+    /** Reject clients that do not send a certificate. When `false`, such
+     * clients are accepted and their `peerCertificates` are `null`.
+     *
+     * @default {true}
+     */
+    required?: boolean;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * @category Network
+   */
+  export interface ListenTlsOptions {
+    /** Certificates to present to clients that ask for a specific server name
+     * with SNI. `cert` and `key` may be left out when this is set. */
+    sni?: Record<string, TlsCertifiedKey>;
+    /** Ask clients for a certificate and verify it. */
+    clientAuth?: TlsClientAuthOptions;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * @category Network
+   */
+  export interface TlsListener {
+    /** Replace the certificates presented to clients, eg. after renewing
+     * them. Connections that are already established keep using the old
+     * certificates. */
+    setCertificates(certificates: TlsCertificates): void;
   }
 
   /** **UNSTABLE**: New API, yet to be vetted.
@@ -1937,18 +2285,291 @@ declare namespace Deno {
     readonly value: bigint;
   }
 
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Options for {@linkcode Deno.WorkerPool}.
+   *
+   * @category Web Workers
+   */
+  export interface WorkerPoolOptions {
+    /** Number of workers to start. Defaults to
+     * `navigator.hardwareConcurrency`. */
+    size?: number;
+    /** Maximum number of tasks waiting for a free worker. Once reached,
+     * {@linkcode Deno.WorkerPool.run} waits for room in the queue before
+     * enqueueing. Must be at least 1. Defaults to `Infinity`. */
+    maxQueue?: number;
+    /** Prefix of the names given to the workers. Defaults to
+     * `"WorkerPool"`. */
+    name?: string;
+    /** Same as `deno` in `WorkerOptions`, applied to every worker. */
+    deno?: WorkerOptions["deno"];
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Statistics reported by {@linkcode Deno.WorkerPool.stats}.
+   *
+   * @category Web Workers
+   */
+  export interface WorkerPoolStats {
+    /** Number of tasks waiting for a free worker. */
+    queued: number;
+    workers: {
+      id: number;
+      /** Whether the worker is currently running a task. */
+      busy: boolean;
+      tasksCompleted: number;
+      tasksFailed: number;
+      /** How many times the worker crashed and was replaced. */
+      restarts: number;
+    }[];
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * A pool of pre-started module workers that run functions exported by a
+   * module. Each worker runs one task at a time; a worker that crashes is
+   * replaced by a fresh one and its task is rejected.
+   *
+   * ```ts
+   * // hash.ts
+   * export async function sha256(data: Uint8Array) {
+   *   return new Uint8Array(await crypto.subtle.digest("SHA-256", data));
+   * }
+   *
+   * // main.ts
+   * const pool = new Deno.WorkerPool(import.meta.resolve("./hash.ts"), {
+   *   size: 4,
+   * });
+   * const digest = await pool.run("sha256", [new Uint8Array([1, 2, 3])]);
+   * pool.terminate();
+   * ```
+   *
+   * @category Web Workers
+   */
+  export class WorkerPool {
+    constructor(specifier: string | URL, options?: WorkerPoolOptions);
+    /** Number of workers in the pool. */
+    readonly size: number;
+    /** Runs the function exported as `fn` by the pool's module with `args`
+     * in the next free worker, resolving with its (awaited) return value.
+     * Both the arguments and the result are structured cloned. */
+    run<T = unknown>(
+      fn: string,
+      args?: unknown[],
+      options?: StructuredSerializeOptions,
+    ): Promise<T>;
+    stats(): WorkerPoolStats;
+    /** Terminates all workers, rejecting running and queued tasks. */
+    terminate(): void;
+  }
+
   /** An instance of the server created using `Deno.serve()` API.
    *
    * @category HTTP Server
    */
   export interface Server {
     /** Gracefully close the server. No more new connections will be accepted,
-     * while pending requests will be allowed to finish.
+     * while pending requests will be allowed to finish. HTTP/2 clients are
+     * sent a `GOAWAY` frame and HTTP/1.1 responses carry `Connection: close`.
+     *
+     * If a `timeout` is given, requests that are still in flight once it
+     * expires are cancelled and their connections closed.
+     */
+    shutdown(options?: ServerShutdownOptions): Promise<void>;
+
+    /** Returns a snapshot of the server's live metrics. */
+    stats(): ServerStats;
+
+    /** Replace the certificates of an HTTPS server, eg. after renewing them.
+     * Connections that are already established keep using the old
+     * certificates. Throws if the server does not use TLS. Servers started
+     * with `http3` require `cert` and `key`, and can not use `sni`. */
+    setCertificates(certificates: TlsCertificates): void;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Live metrics of a server created with {@linkcode Deno.serve}.
+   *
+   * @category HTTP Server
+   */
+  export interface ServerStats {
+    /** The number of connections that are currently open. */
+    openConnections: number;
+    /** The number of connections accepted since the server started. */
+    totalConnections: number;
+    /** The number of requests that have not been responded to yet. */
+    inFlightRequests: number;
+    /** The number of requests received since the server started. */
+    totalRequests: number;
+    /** The number of responses with a 5xx status code. */
+    serverErrors: number;
+    /** The number of bytes read from connections, including headers. Traffic
+     * on upgraded connections (eg: WebSockets) is not counted. */
+    bytesRead: number;
+    /** The number of bytes written to connections, including headers. Traffic
+     * on upgraded connections (eg: WebSockets) is not counted. */
+    bytesWritten: number;
+    /** A histogram of the time it took the handler to produce a response.
+     * Each bucket counts the requests that took at most `le` milliseconds and
+     * more than the previous bucket's `le`. The last bucket's `le` is
+     * `Infinity`. */
+    latency: { le: number; count: number }[];
+    /** The sum of all request latencies, in milliseconds. */
+    latencySumMs: number;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Information about a connection accepted by {@linkcode Deno.serve}, passed
+   * to the `onConnection` callback.
+   *
+   * @category HTTP Server
+   */
+  export interface ServeConnectionInfo {
+    /** The remote address of the connection. */
+    remoteAddr: Deno.NetAddr | Deno.UnixAddr;
+    /** The protocol negotiated via ALPN, if any. */
+    alpnProtocol: string | null;
+    /** TLS details, if the connection is encrypted. */
+    tls: {
+      /** The server name the client asked for via SNI, if any. */
+      serverName: string | null;
+      protocolVersion: string | null;
+      cipherSuite: string | null;
+      /** The PEM formatted certificate chain the client authenticated with,
+       * if `clientAuth` is set. */
+      peerCertificates: string[] | null;
+    } | null;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Limits that protect a server from slow or oversized requests. Requests
+   * that exceed them are answered directly by the runtime, without invoking
+   * the handler. The only exception is a body without a `Content-Length`,
+   * see `maxRequestBodySize`.
+   *
+   * @category HTTP Server
+   */
+  export interface ServeLimits {
+    /** The maximum size of a request body, in bytes. Requests that declare a
+     * larger `Content-Length` receive a `413 Payload Too Large` response
+     * without invoking the handler. A body without a `Content-Length` is
+     * passed to the handler, and reading it fails once it exceeds the
+     * limit. The client then receives a `413 Payload Too Large` response
+     * instead of the one returned by the handler. */
+    maxRequestBodySize?: number;
+    /** The maximum combined size of the request line and headers, in bytes.
+     * Larger requests receive a `431 Request Header Fields Too Large`
+     * response. */
+    maxHeaderSize?: number;
+    /** The number of milliseconds a client has to send the headers of a
+     * request, once it has started sending one. HTTP/1.1 clients that take
+     * longer receive a `408 Request Timeout` response. */
+    headerReadTimeout?: number;
+    /** The number of milliseconds a connection may stay idle between requests
+     * before it is closed. */
+    idleTimeout?: number;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * How a server compresses responses and decodes compressed requests.
+   *
+   * @category HTTP Server
+   */
+  export interface ServeCompressionOptions {
+    /** The encodings to compress responses with, in order of preference. When
+     * a client accepts several of them equally, the earliest one is used.
+     *
+     * @default {["br", "gzip"]}
+     */
+    encodings?: ("br" | "gzip" | "zstd")[];
+    /** The compression level, clamped to the range each encoding supports.
+     * Defaults to a level suited to on-the-fly compression for each encoding.
+     */
+    level?: number;
+    /** Responses with a known length below this many bytes are not
+     * compressed.
+     *
+     * @default {64}
+     */
+    minSize?: number;
+    /** Content types to compress in addition to the ones known to be
+     * compressible. */
+    contentTypes?: string[];
+    /** Transparently decode request bodies sent with a `gzip`, `br` or `zstd`
+     * `Content-Encoding`. The `Content-Encoding` and `Content-Length` headers
+     * are removed from decoded requests.
+     *
+     * @default {false}
+     */
+    decompressRequests?: boolean;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * @category HTTP Server
+   */
+  export interface ServeOptions extends ServeLimits {
+    /** Called for every accepted connection, before any request on it is
+     * handled. */
+    onConnection?: (info: ServeConnectionInfo) => void;
+    /** Configure response compression, or pass `false` to turn it off.
+     *
+     * @default {true}
+     */
+    compression?: boolean | ServeCompressionOptions;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Options for {@linkcode Deno.Server.shutdown}.
+   *
+   * @category HTTP Server
+   */
+  export interface ServerShutdownOptions {
+    /** The number of milliseconds to wait for in-flight requests to finish
+     * before force-closing their connections. Waits indefinitely if not set.
+     */
+    timeout?: number;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * @category HTTP Server
+   */
+  export interface ServeTlsOptions {
+    /** Also listen for HTTP/3 (QUIC) on the UDP port with the same number as
+     * the TLS listener. HTTP/1.1 and HTTP/2 responses advertise it with an
+     * `Alt-Svc` header.
+     *
+     * @default {false}
      */
-    shutdown(): Promise<void>;
+    http3?: boolean;
+    /** Certificates to present to clients that ask for a specific server name
+     * with SNI. Can not be used together with `http3`. */
+    sni?: Record<string, TlsCertifiedKey>;
+    /** Ask clients for a certificate and verify it. The certificate chain is
+     * available to the handler as {@linkcode ServeHandlerInfo.peerCertificates}.
+     * Can not be used together with `http3`. */
+    clientAuth?: TlsClientAuthOptions;
   }
 
-  export interface ServeUnixOptions {
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * @category HTTP Server
+   */
+  export interface ServeHandlerInfo {
+    /** The PEM formatted certificate chain the client authenticated with, if
+     * the server was started with `clientAuth`. */
+    peerCertificates: string[] | null;
+  }
+
+  export interface ServeUnixOptions extends ServeLimits {
     /** The unix domain socket path to listen on. */
     path: string;
 
@@ -1960,6 +2581,16 @@ declare namespace Deno {
 
     /** The callback which is called when the server starts listening. */
     onListen?: (params: { path: string }) => void;
+
+    /** Called for every accepted connection, before any request on it is
+     * handled. */
+    onConnection?: (info: ServeConnectionInfo) => void;
+
+    /** Configure response compression, or pass `false` to turn it off.
+     *
+     * @default {true}
+     */
+    compression?: boolean | ServeCompressionOptions;
   }
 
   /** Information for a unix domain socket HTTP request.
@@ -2069,6 +2700,80 @@ declare namespace Deno {
     options: ServeUnixInit & ServeUnixOptions,
   ): Server;
 
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * @category HTTP Server
+   */
+  export interface ServeFdOptions extends ServeLimits {
+    /** An inherited file descriptor of a bound TCP or Unix domain socket to
+     * listen on, or `"LISTEN_FDS"` to use the first socket passed by systemd
+     * socket activation (the `LISTEN_FDS` and `LISTEN_PID` environment
+     * variables).
+     *
+     * Requires `allow-net` permission for the address of a TCP socket, or
+     * `allow-read` and `allow-write` permissions for the path of a Unix
+     * domain socket. */
+    fd: number | "LISTEN_FDS";
+
+    /** An {@linkcode AbortSignal} to close the server and all connections. */
+    signal?: AbortSignal;
+
+    /** The handler to invoke when route handlers throw an error. */
+    onError?: (error: unknown) => Response | Promise<Response>;
+
+    /** The callback which is called when the server starts listening. */
+    onListen?: (
+      params: { hostname: string; port: number } | { path: string | null },
+    ) => void;
+
+    /** Called for every accepted connection, before any request on it is
+     * handled. */
+    onConnection?: (info: ServeConnectionInfo) => void;
+
+    /** Configure response compression, or pass `false` to turn it off.
+     *
+     * @default {true}
+     */
+    compression?: boolean | ServeCompressionOptions;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * @category HTTP Server
+   */
+  export interface ServeFdInit {
+    /** The handler to invoke to process each incoming request. */
+    handler: ServeHandler | ServeUnixHandler;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Serves HTTP requests on an inherited socket, for example one passed by
+   * systemd socket activation.
+   *
+   * ```ts
+   * Deno.serve(
+   *   { fd: "LISTEN_FDS" },
+   *   (_req) => new Response("Hello, world"),
+   * );
+   * ```
+   *
+   * @category HTTP Server
+   */
+  export function serve(
+    options: ServeFdOptions,
+    handler: ServeHandler | ServeUnixHandler,
+  ): Server;
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Serves HTTP requests on an inherited socket with the given option bag.
+   *
+   * @category HTTP Server
+   */
+  export function serve(
+    options: ServeFdInit & ServeFdOptions,
+  ): Server;
+
   /**
    * A namespace containing runtime APIs available in Jupyter notebooks.
    *
@@ -2158,6 +2863,33 @@ declare interface WorkerOptions {
   deno?: {
     /** Set to `"none"` to disable all the permissions in the worker. */
     permissions?: Deno.PermissionOptions;
+    /** **UNSTABLE**: New API, yet to be vetted.
+     *
+     * Resource limits for the worker. A worker that exceeds any of them is
+     * terminated and an `error` event describing the exceeded limit is
+     * dispatched on the `Worker` object.
+     *
+     * ```ts
+     * const worker = new Worker(
+     *   new URL("plugin.ts", import.meta.url).href, {
+     *     type: "module",
+     *     deno: {
+     *       limits: { maxHeapMB: 64, maxCpuMs: 1000 },
+     *     },
+     *   }
+     * );
+     * ```
+     */
+    limits?: {
+      /** Maximum size of the worker's heap, in megabytes. */
+      maxHeapMB?: number;
+      /** Maximum CPU time the worker's thread may consume, in
+       * milliseconds. Time spent waiting for I/O, timers or messages doesn't
+       * count towards it. */
+      maxCpuMs?: number;
+      /** Maximum number of async ops that may be pending at the same time. */
+      maxPendingOps?: number;
+    };
   };
 }
 
@@ -2169,6 +2901,7 @@ declare interface WebSocketStreamOptions {
   protocols?: string[];
   signal?: AbortSignal;
   headers?: HeadersInit;
+  compression?: boolean | WebSocketCompressionOptions;
 }
 
 /** **UNSTABLE**: New API, yet to be vetted.
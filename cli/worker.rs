@@ -28,6 +28,7 @@ use deno_runtime::deno_node;
 use deno_runtime::deno_node::NodeResolution;
 use deno_runtime::deno_node::NodeResolutionMode;
 use deno_runtime::deno_node::NodeResolver;
+use deno_runtime::deno_tls::Proxy;
 use deno_runtime::deno_tls::RootCertStoreProvider;
 use deno_runtime::deno_web::BlobStore;
 use deno_runtime::fmt_errors::format_js_error;
@@ -89,8 +90,11 @@ pub struct CliMainWorkerOptions {
   pub location: Option<Url>,
   pub maybe_binary_npm_command_name: Option<String>,
   pub origin_data_folder_path: Option<PathBuf>,
+  pub local_storage_folder_path: Option<PathBuf>,
+  pub local_storage_quota: Option<usize>,
   pub seed: Option<u64>,
   pub unsafely_ignore_certificate_errors: Option<Vec<String>>,
+  pub proxy: Option<Proxy>,
   pub unstable: bool,
   pub maybe_root_package_json_deps: Option<PackageJsonDeps>,
 }
@@ -107,6 +111,7 @@ struct SharedWorkerState {
   module_loader_factory: Box<dyn ModuleLoaderFactory>,
   root_cert_store_provider: Arc<dyn RootCertStoreProvider>,
   fs: Arc<dyn deno_fs::FileSystem>,
+  node_fs: Arc<dyn deno_fs::FileSystem>,
   maybe_inspector_server: Option<Arc<InspectorServer>>,
   maybe_lockfile: Option<Arc<Mutex<Lockfile>>>,
 }
@@ -311,6 +316,7 @@ impl CliMainWorkerFactory {
     module_loader_factory: Box<dyn ModuleLoaderFactory>,
     root_cert_store_provider: Arc<dyn RootCertStoreProvider>,
     fs: Arc<dyn deno_fs::FileSystem>,
+    node_fs: Arc<dyn deno_fs::FileSystem>,
     maybe_inspector_server: Option<Arc<InspectorServer>>,
     maybe_lockfile: Option<Arc<Mutex<Lockfile>>>,
     options: CliMainWorkerOptions,
@@ -328,6 +334,7 @@ impl CliMainWorkerFactory {
         module_loader_factory,
         root_cert_store_provider,
         fs,
+        node_fs,
         maybe_inspector_server,
         maybe_lockfile,
       }),
@@ -391,7 +398,7 @@ impl CliMainWorkerFactory {
 
       // use a fake referrer that can be used to discover the package.json if necessary
       let referrer =
-        ModuleSpecifier::from_directory_path(self.shared.fs.cwd()?)
+        ModuleSpecifier::from_directory_path(self.shared.node_fs.cwd()?)
           .unwrap()
           .join("package.json")?;
       let package_folder = shared
@@ -448,6 +455,13 @@ impl CliMainWorkerFactory {
         .unwrap() // must be set if storage key resolver returns a value
         .join(checksum::gen(&[key.as_bytes()]))
     });
+    let local_storage_dir = maybe_storage_key.as_ref().and_then(|key| {
+      shared
+        .options
+        .local_storage_folder_path
+        .as_ref()
+        .map(|path| path.join(checksum::gen(&[key.as_bytes()])))
+    });
     let cache_storage_dir = maybe_storage_key.map(|key| {
       // TODO(@satyarohith): storage quota management
       // Note: we currently use temp_dir() to avoid managing storage size.
@@ -489,6 +503,7 @@ impl CliMainWorkerFactory {
         .options
         .unsafely_ignore_certificate_errors
         .clone(),
+      proxy: shared.options.proxy.clone(),
       root_cert_store_provider: Some(shared.root_cert_store_provider.clone()),
       seed: shared.options.seed,
       source_map_getter: maybe_source_map_getter,
@@ -499,10 +514,13 @@ impl CliMainWorkerFactory {
       should_wait_for_inspector_session: shared.options.inspect_wait,
       module_loader,
       fs: shared.fs.clone(),
+      node_fs: Some(shared.node_fs.clone()),
       npm_resolver: Some(shared.npm_resolver.clone().into_npm_resolver()),
       get_error_class_fn: Some(&errors::get_error_class_name),
       cache_storage_dir,
       origin_storage_dir,
+      local_storage_dir,
+      local_storage_quota: shared.options.local_storage_quota,
       blob_store: shared.blob_store.clone(),
       broadcast_channel: shared.broadcast_channel.clone(),
       shared_array_buffer_store: Some(shared.shared_array_buffer_store.clone()),
@@ -662,6 +680,7 @@ fn create_web_worker_callback(
         .options
         .unsafely_ignore_certificate_errors
         .clone(),
+      proxy: shared.options.proxy.clone(),
       root_cert_store_provider: Some(shared.root_cert_store_provider.clone()),
       seed: shared.options.seed,
       create_web_worker_cb,
@@ -669,6 +688,7 @@ fn create_web_worker_callback(
       source_map_getter: maybe_source_map_getter,
       module_loader,
       fs: shared.fs.clone(),
+      node_fs: Some(shared.node_fs.clone()),
       npm_resolver: Some(shared.npm_resolver.clone().into_npm_resolver()),
       worker_type: args.worker_type,
       maybe_inspector_server,
@@ -681,6 +701,7 @@ fn create_web_worker_callback(
       ),
       stdio: stdio.clone(),
       cache_storage_dir,
+      limits: args.limits,
     };
 
     WebWorker::bootstrap_from_options(
@@ -0,0 +1,252 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Fan-out of broadcast channel messages to other processes on the same
+//! machine.
+//!
+//! Every process that opts in binds a Unix socket with a random name in a
+//! rendezvous directory shared by all of them. Messages sent in one process
+//! are written to the socket of every other process found in that directory,
+//! which hands them to its own subscribers. The directory is read again at
+//! most once per second, so a process that just joined may miss the messages
+//! sent in the meantime. Sockets left behind by processes that exited without
+//! cleaning up are removed when connecting to them fails.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::parking_lot::Mutex;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::net::UnixListener;
+use tokio::net::UnixStream;
+use tokio::sync::broadcast;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use crate::in_memory_broadcast_channel::Message;
+
+/// Frames larger than this end the connection they were read from, so
+/// messages with a larger name or data are rejected when they are sent.
+const MAX_FRAME_SIZE: usize = 256 << 20;
+
+/// How long the list of peers found in the rendezvous directory is reused.
+const PEER_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+type Outbound = (Arc<String>, Arc<Vec<u8>>);
+
+pub(crate) struct CrossProcessRelay {
+  outbound: mpsc::UnboundedSender<Outbound>,
+  path: PathBuf,
+}
+
+impl CrossProcessRelay {
+  /// Bind this process' socket in `dir` and start relaying on a thread of its
+  /// own, as the channel is shared by workers that each run their own event
+  /// loop. Messages from other processes are sent to `local`.
+  pub fn start(
+    dir: &Path,
+    local: Arc<Mutex<broadcast::Sender<Message>>>,
+  ) -> Result<Self, AnyError> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{}.sock", Uuid::new_v4().simple()));
+    let listener = std::os::unix::net::UnixListener::bind(&path)?;
+    listener.set_nonblocking(true)?;
+    let runtime = tokio::runtime::Builder::new_current_thread()
+      .enable_io()
+      .build()?;
+    let (outbound, outbound_rx) = mpsc::unbounded_channel();
+    let dir = dir.to_owned();
+    let own_path = path.clone();
+    std::thread::Builder::new()
+      .name("broadcast-channel-relay".to_string())
+      .spawn(move || {
+        runtime.block_on(async move {
+          let Ok(listener) = UnixListener::from_std(listener) else {
+            return;
+          };
+          // Stops once every clone of the channel has been dropped.
+          tokio::select! {
+            _ = accept_loop(listener, local) => {},
+            _ = forward_loop(dir, own_path, outbound_rx) => {},
+          }
+        });
+      })?;
+    Ok(Self { outbound, path })
+  }
+
+  pub fn send(
+    &self,
+    name: Arc<String>,
+    data: Arc<Vec<u8>>,
+  ) -> Result<(), AnyError> {
+    if name.len() > MAX_FRAME_SIZE || data.len() > MAX_FRAME_SIZE {
+      return Err(type_error("Broadcast channel message is too large"));
+    }
+    // The relay thread only goes away together with `self`.
+    let _ = self.outbound.send((name, data));
+    Ok(())
+  }
+}
+
+impl Drop for CrossProcessRelay {
+  fn drop(&mut self) {
+    let _ = std::fs::remove_file(&self.path);
+  }
+}
+
+async fn accept_loop(
+  listener: UnixListener,
+  local: Arc<Mutex<broadcast::Sender<Message>>>,
+) {
+  loop {
+    let Ok((stream, _)) = listener.accept().await else {
+      continue;
+    };
+    let local = local.clone();
+    tokio::spawn(async move {
+      let _ = read_messages(stream, &local).await;
+    });
+  }
+}
+
+async fn read_messages(
+  mut stream: UnixStream,
+  local: &Mutex<broadcast::Sender<Message>>,
+) -> std::io::Result<()> {
+  loop {
+    let name = read_frame(&mut stream).await?;
+    let data = read_frame(&mut stream).await?;
+    let name = String::from_utf8(name)
+      .map_err(|err| std::io::Error::new(ErrorKind::InvalidData, err))?;
+    // Subscribers of this process have random ids, so the nil id makes sure
+    // all of them receive the message. Having none is not an error.
+    let _ = local.lock().send(Message {
+      name: Arc::new(name),
+      data: Arc::new(data),
+      uuid: Uuid::nil(),
+    });
+  }
+}
+
+async fn read_frame(stream: &mut UnixStream) -> std::io::Result<Vec<u8>> {
+  let len = stream.read_u32().await? as usize;
+  if len > MAX_FRAME_SIZE {
+    return Err(std::io::Error::new(
+      ErrorKind::InvalidData,
+      "Broadcast channel message is too large",
+    ));
+  }
+  let mut buf = vec![0; len];
+  stream.read_exact(&mut buf).await?;
+  Ok(buf)
+}
+
+async fn forward_loop(
+  dir: PathBuf,
+  own_path: PathBuf,
+  mut outbound: mpsc::UnboundedReceiver<Outbound>,
+) {
+  let mut peers = HashMap::<PathBuf, UnixStream>::new();
+  let mut paths = Vec::new();
+  let mut scanned_at: Option<Instant> = None;
+  while let Some((name, data)) = outbound.recv().await {
+    if scanned_at.map_or(true, |at| at.elapsed() >= PEER_REFRESH_INTERVAL) {
+      let Ok(found) = find_peers(&dir, &own_path) else {
+        continue;
+      };
+      paths = found;
+      peers.retain(|path, _| paths.contains(path));
+      scanned_at = Some(Instant::now());
+    }
+
+    let mut frame = Vec::with_capacity(8 + name.len() + data.len());
+    frame.extend_from_slice(&(name.len() as u32).to_be_bytes());
+    frame.extend_from_slice(name.as_bytes());
+    frame.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    frame.extend_from_slice(&data);
+
+    let mut gone = Vec::new();
+    for path in &paths {
+      // A connection that broke since the last message is reconnected once.
+      for _ in 0..2 {
+        let stream = match peers.entry(path.clone()) {
+          Entry::Occupied(entry) => entry.into_mut(),
+          Entry::Vacant(entry) => match UnixStream::connect(path).await {
+            Ok(stream) => entry.insert(stream),
+            Err(err) => {
+              if err.kind() == ErrorKind::ConnectionRefused {
+                // Nobody listens anymore, the process is gone.
+                let _ = std::fs::remove_file(path);
+                gone.push(path.clone());
+              }
+              break;
+            }
+          },
+        };
+        if stream.write_all(&frame).await.is_ok() {
+          break;
+        }
+        peers.remove(path);
+      }
+    }
+    paths.retain(|path| !gone.contains(path));
+  }
+}
+
+/// The sockets of the other processes in the rendezvous directory.
+fn find_peers(dir: &Path, own_path: &Path) -> std::io::Result<Vec<PathBuf>> {
+  Ok(
+    std::fs::read_dir(dir)?
+      .filter_map(|entry| entry.ok())
+      .map(|entry| entry.path())
+      .filter(|path| {
+        path != own_path && path.extension().is_some_and(|ext| ext == "sock")
+      })
+      .collect(),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::BroadcastChannel;
+  use crate::InMemoryBroadcastChannel;
+
+  #[tokio::test]
+  async fn relays_between_channels() {
+    let dir = tempfile::tempdir().unwrap();
+    let a = InMemoryBroadcastChannel::cross_process(dir.path()).unwrap();
+    let b = InMemoryBroadcastChannel::cross_process(dir.path()).unwrap();
+    let a_resource = a.subscribe().unwrap();
+    let b_resource = b.subscribe().unwrap();
+
+    a.send(&a_resource, "chan".to_string(), vec![1, 2, 3])
+      .await
+      .unwrap();
+    let message = b.recv(&b_resource).await.unwrap();
+    assert_eq!(message, Some(("chan".to_string(), vec![1, 2, 3])));
+
+    b.send(&b_resource, "other".to_string(), vec![4])
+      .await
+      .unwrap();
+    let message = a.recv(&a_resource).await.unwrap();
+    assert_eq!(message, Some(("other".to_string(), vec![4])));
+
+    let too_large = vec![0; super::MAX_FRAME_SIZE + 1];
+    assert!(a
+      .send(&a_resource, "chan".to_string(), too_large)
+      .await
+      .is_err());
+
+    // Sockets are removed when the channels are dropped.
+    drop((a, a_resource, b, b_resource));
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+  }
+}
@@ -1,5 +1,7 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+#[cfg(unix)]
+use std::path::Path;
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -9,10 +11,16 @@ use tokio::sync::broadcast;
 use tokio::sync::mpsc;
 use uuid::Uuid;
 
+#[cfg(unix)]
+use crate::cross_process::CrossProcessRelay;
 use crate::BroadcastChannel;
 
 #[derive(Clone)]
-pub struct InMemoryBroadcastChannel(Arc<Mutex<broadcast::Sender<Message>>>);
+pub struct InMemoryBroadcastChannel {
+  tx: Arc<Mutex<broadcast::Sender<Message>>>,
+  #[cfg(unix)]
+  relay: Option<Arc<CrossProcessRelay>>,
+}
 
 pub struct InMemoryBroadcastChannelResource {
   rx: tokio::sync::Mutex<(
@@ -24,16 +32,35 @@ pub struct InMemoryBroadcastChannelResource {
 }
 
 #[derive(Clone, Debug)]
-struct Message {
-  name: Arc<String>,
-  data: Arc<Vec<u8>>,
-  uuid: Uuid,
+pub(crate) struct Message {
+  pub name: Arc<String>,
+  pub data: Arc<Vec<u8>>,
+  pub uuid: Uuid,
 }
 
 impl Default for InMemoryBroadcastChannel {
   fn default() -> Self {
     let (tx, _) = broadcast::channel(256);
-    Self(Arc::new(Mutex::new(tx)))
+    Self {
+      tx: Arc::new(Mutex::new(tx)),
+      #[cfg(unix)]
+      relay: None,
+    }
+  }
+}
+
+impl InMemoryBroadcastChannel {
+  /// Create a channel that also reaches other processes on this machine
+  /// which created theirs with the same rendezvous directory. Messages of
+  /// more than 256 MiB can not be sent on such a channel.
+  #[cfg(unix)]
+  pub fn cross_process(dir: impl AsRef<Path>) -> Result<Self, AnyError> {
+    let channel = Self::default();
+    let relay = CrossProcessRelay::start(dir.as_ref(), channel.tx.clone())?;
+    Ok(Self {
+      relay: Some(Arc::new(relay)),
+      ..channel
+    })
   }
 }
 
@@ -43,7 +70,7 @@ impl BroadcastChannel for InMemoryBroadcastChannel {
 
   fn subscribe(&self) -> Result<Self::Resource, AnyError> {
     let (cancel_tx, cancel_rx) = mpsc::unbounded_channel();
-    let broadcast_rx = self.0.lock().subscribe();
+    let broadcast_rx = self.tx.lock().subscribe();
     let rx = tokio::sync::Mutex::new((broadcast_rx, cancel_rx));
     let uuid = Uuid::new_v4();
     Ok(Self::Resource {
@@ -66,7 +93,11 @@ impl BroadcastChannel for InMemoryBroadcastChannel {
     let name = Arc::new(name);
     let data = Arc::new(data);
     let uuid = resource.uuid;
-    self.0.lock().send(Message { name, data, uuid })?;
+    #[cfg(unix)]
+    if let Some(relay) = &self.relay {
+      relay.send(name.clone(), data.clone())?;
+    }
+    self.tx.lock().send(Message { name, data, uuid })?;
     Ok(())
   }
 
@@ -19,6 +19,7 @@ import { toInnerResponse } from "ext:deno_fetch/23_response.js";
 import { URLPrototype } from "ext:deno_url/00_url.js";
 import { getHeader } from "ext:deno_fetch/20_headers.js";
 import { readableStreamForRid } from "ext:deno_web/06_streams.js";
+import "ext:deno_cache/02_http_cache.js";
 const {
   op_cache_delete,
   op_cache_match,
@@ -0,0 +1,581 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+// An RFC 9111 private (or shared) HTTP cache for `fetch` requests made through
+// a `Deno.HttpClient` created with the `cache` option. Responses are stored in
+// the same storage that backs the Cache API.
+
+const core = globalThis.Deno.core;
+const primordials = globalThis.__bootstrap.primordials;
+const {
+  ArrayPrototypeFilter,
+  ArrayPrototypeIncludes,
+  ArrayPrototypePush,
+  ArrayPrototypeSome,
+  Date,
+  DateNow,
+  DateParse,
+  DatePrototypeToUTCString,
+  MapPrototypeDelete,
+  MapPrototypeGet,
+  MapPrototypeHas,
+  MapPrototypeSet,
+  MathFloor,
+  MathMax,
+  NumberIsNaN,
+  NumberParseInt,
+  PromisePrototypeCatch,
+  SafeArrayIterator,
+  SafeMap,
+  StringPrototypeEndsWith,
+  StringPrototypeIndexOf,
+  StringPrototypeSlice,
+  StringPrototypeSplit,
+  StringPrototypeStartsWith,
+  StringPrototypeToLowerCase,
+  StringPrototypeTrim,
+} = primordials;
+import { byteLowerCase } from "ext:deno_web/00_infra.js";
+import { readableStreamForRid } from "ext:deno_web/06_streams.js";
+import { getHeader } from "ext:deno_fetch/20_headers.js";
+import { InnerBody } from "ext:deno_fetch/22_body.js";
+import { processUrlList } from "ext:deno_fetch/23_request.js";
+import { redirectStatus } from "ext:deno_fetch/23_response.js";
+import { setHttpCacheHandler } from "ext:deno_fetch/26_fetch.js";
+const {
+  op_cache_delete,
+  op_cache_match,
+  op_cache_put,
+  op_cache_put_finish,
+  op_cache_storage_open,
+} = core.ensureFastOps();
+
+// https://www.rfc-editor.org/rfc/rfc9110#section-15.1
+const HEURISTICALLY_CACHEABLE_STATUS = [
+  200,
+  203,
+  204,
+  404,
+  405,
+  410,
+  414,
+  501,
+];
+
+const SAFE_METHODS = ["GET", "HEAD", "OPTIONS", "TRACE"];
+
+const CONDITIONAL_HEADER_NAMES = [
+  "if-match",
+  "if-none-match",
+  "if-modified-since",
+  "if-unmodified-since",
+  "if-range",
+  "range",
+];
+
+// Headers that a 304 response must not overwrite in the stored response.
+// https://www.rfc-editor.org/rfc/rfc9111#section-3.2
+const NON_UPDATABLE_HEADER_NAMES = [
+  "content-encoding",
+  "content-length",
+  "content-range",
+  "transfer-encoding",
+];
+
+/** @type {Map<string, Promise<number>>} */
+const cacheIds = new SafeMap();
+/** @type {Map<string, Promise<void>>} */
+const pendingStores = new SafeMap();
+
+/**
+ * @param {string} name
+ * @returns {Promise<number>}
+ */
+function openCache(name) {
+  let cacheId = MapPrototypeGet(cacheIds, name);
+  if (cacheId === undefined) {
+    cacheId = op_cache_storage_open(name);
+    // Don't remember failures, storage may become available later.
+    PromisePrototypeCatch(cacheId, () => MapPrototypeDelete(cacheIds, name));
+    MapPrototypeSet(cacheIds, name, cacheId);
+  }
+  return cacheId;
+}
+
+/**
+ * Parse a `Cache-Control` header into a map of lowercased directive names to
+ * their (unquoted) values. Directives without a value map to `null`.
+ * @param {string | null} value
+ * @returns {Map<string, string | null>}
+ */
+function parseCacheControl(value) {
+  const directives = new SafeMap();
+  if (value === null) {
+    return directives;
+  }
+  const parts = StringPrototypeSplit(value, ",");
+  for (let i = 0; i < parts.length; ++i) {
+    const part = StringPrototypeTrim(parts[i]);
+    if (part === "") {
+      continue;
+    }
+    const eq = StringPrototypeIndexOf(part, "=");
+    if (eq === -1) {
+      MapPrototypeSet(directives, StringPrototypeToLowerCase(part), null);
+      continue;
+    }
+    const name = StringPrototypeToLowerCase(
+      StringPrototypeTrim(StringPrototypeSlice(part, 0, eq)),
+    );
+    let arg = StringPrototypeTrim(StringPrototypeSlice(part, eq + 1));
+    if (
+      arg.length >= 2 &&
+      StringPrototypeStartsWith(arg, '"') &&
+      StringPrototypeEndsWith(arg, '"')
+    ) {
+      arg = StringPrototypeSlice(arg, 1, -1);
+    }
+    // Duplicate directives are invalid, the first one wins.
+    if (!MapPrototypeHas(directives, name)) {
+      MapPrototypeSet(directives, name, arg);
+    }
+  }
+  return directives;
+}
+
+/**
+ * Parse a delta-seconds directive value, returning `null` if it is invalid.
+ * @param {Map<string, string | null>} directives
+ * @param {string} name
+ * @returns {number | null}
+ */
+function deltaSeconds(directives, name) {
+  const value = MapPrototypeGet(directives, name);
+  if (value === undefined || value === null) {
+    return null;
+  }
+  const seconds = NumberParseInt(value, 10);
+  return NumberIsNaN(seconds) || seconds < 0 ? null : seconds;
+}
+
+/**
+ * @param {[string, string][]} headerList
+ * @param {string} name
+ * @returns {number | null} milliseconds since the epoch
+ */
+function dateHeader(headerList, name) {
+  const value = getHeader(headerList, name);
+  if (value === null) {
+    return null;
+  }
+  const date = DateParse(value);
+  return NumberIsNaN(date) ? null : date;
+}
+
+/**
+ * @param {[string, string][]} headerList
+ * @param {string[]} names lowercased
+ * @returns {[string, string][]}
+ */
+function withoutHeaders(headerList, names) {
+  return ArrayPrototypeFilter(
+    headerList,
+    (entry) => !ArrayPrototypeIncludes(names, byteLowerCase(entry[0])),
+  );
+}
+
+/**
+ * How long a response is fresh for, in seconds.
+ * https://www.rfc-editor.org/rfc/rfc9111#section-4.2.1
+ * @param {{ status: number, headerList: [string, string][] }} response
+ * @param {Map<string, string | null>} directives
+ * @param {boolean} shared
+ * @returns {number}
+ */
+function freshnessLifetime(response, directives, shared) {
+  if (shared) {
+    const sMaxAge = deltaSeconds(directives, "s-maxage");
+    if (sMaxAge !== null) {
+      return sMaxAge;
+    }
+  }
+  const maxAge = deltaSeconds(directives, "max-age");
+  if (maxAge !== null) {
+    return maxAge;
+  }
+  const date = dateHeader(response.headerList, "date");
+  if (getHeader(response.headerList, "expires") !== null) {
+    const expires = dateHeader(response.headerList, "expires");
+    if (expires === null || date === null) {
+      return 0;
+    }
+    return MathMax(0, MathFloor((expires - date) / 1000));
+  }
+  const lastModified = dateHeader(response.headerList, "last-modified");
+  if (
+    lastModified !== null && date !== null &&
+    (ArrayPrototypeIncludes(HEURISTICALLY_CACHEABLE_STATUS, response.status) ||
+      MapPrototypeHas(directives, "public"))
+  ) {
+    return MathMax(0, MathFloor((date - lastModified) / 10000));
+  }
+  return 0;
+}
+
+/**
+ * The age of a stored response, in seconds.
+ * https://www.rfc-editor.org/rfc/rfc9111#section-4.2.3
+ * @param {[string, string][]} headerList
+ * @returns {number}
+ */
+function currentAge(headerList) {
+  const date = dateHeader(headerList, "date") ?? DateNow();
+  const age = NumberParseInt(getHeader(headerList, "age") ?? "0", 10);
+  return MathMax(0, MathFloor((DateNow() - date) / 1000)) +
+    (NumberIsNaN(age) ? 0 : MathMax(0, age));
+}
+
+/**
+ * Whether a response to a GET request may be stored.
+ * https://www.rfc-editor.org/rfc/rfc9111#section-3
+ * @param {InnerRequest} req
+ * @param {InnerResponse} response
+ * @param {boolean} shared
+ * @returns {boolean}
+ */
+function isStorable(req, response, shared) {
+  if (
+    response.type === "error" || response.status === 206 ||
+    response.status === 304 || redirectStatus(response.status) ||
+    response.status < 200
+  ) {
+    return false;
+  }
+  const directives = parseCacheControl(
+    getHeader(response.headerList, "cache-control"),
+  );
+  if (
+    MapPrototypeHas(directives, "no-store") ||
+    (shared && MapPrototypeHas(directives, "private"))
+  ) {
+    return false;
+  }
+  if (
+    shared && getHeader(req.headerList, "authorization") !== null &&
+    !MapPrototypeHas(directives, "must-revalidate") &&
+    !MapPrototypeHas(directives, "public") &&
+    !MapPrototypeHas(directives, "s-maxage")
+  ) {
+    return false;
+  }
+  const vary = getHeader(response.headerList, "vary");
+  if (
+    vary !== null &&
+    ArrayPrototypeSome(
+      StringPrototypeSplit(vary, ","),
+      (field) => StringPrototypeTrim(field) === "*",
+    )
+  ) {
+    return false;
+  }
+  return MapPrototypeHas(directives, "public") ||
+    MapPrototypeHas(directives, "max-age") ||
+    (shared && MapPrototypeHas(directives, "s-maxage")) ||
+    getHeader(response.headerList, "expires") !== null ||
+    ArrayPrototypeIncludes(HEURISTICALLY_CACHEABLE_STATUS, response.status);
+}
+
+/**
+ * @param {number} cacheId
+ * @param {string} url
+ * @param {[string, string][]} requestHeaders
+ * @param {InnerResponse} response
+ * @returns {Promise<void>}
+ */
+async function store(cacheId, url, requestHeaders, response) {
+  const reader = response.body?.stream.getReader();
+  const rid = await op_cache_put({
+    cacheId,
+    requestUrl: url,
+    requestHeaders,
+    responseHeaders: response.headerList,
+    responseHasBody: response.body !== null,
+    responseStatus: response.status,
+    responseStatusText: response.statusMessage,
+  });
+  if (reader) {
+    try {
+      while (true) {
+        const { value, done } = await reader.read();
+        if (done) {
+          await op_cache_put_finish(rid);
+          break;
+        }
+        await core.writeAll(rid, value);
+      }
+    } finally {
+      core.close(rid);
+    }
+  }
+}
+
+/**
+ * Store `response` in the background, handing the caller a body it can
+ * consume independently of the one being written to the cache.
+ * @param {number} cacheId
+ * @param {string} url
+ * @param {[string, string][]} requestHeaders
+ * @param {InnerResponse} response
+ */
+function storeInBackground(cacheId, url, requestHeaders, response) {
+  const stored = {
+    ...response,
+    body: response.body?.clone() ?? null,
+  };
+  if (getHeader(stored.headerList, "date") === null) {
+    stored.headerList = [
+      ...new SafeArrayIterator(stored.headerList),
+      ["date", DatePrototypeToUTCString(new Date())],
+    ];
+  }
+  const previous = MapPrototypeGet(pendingStores, url);
+  const pending = (async () => {
+    try {
+      await previous;
+      await store(cacheId, url, requestHeaders, stored);
+    } catch {
+      // The cache is best effort, the response was already handed out.
+    } finally {
+      if (MapPrototypeGet(pendingStores, url) === pending) {
+        MapPrototypeDelete(pendingStores, url);
+      }
+    }
+  })();
+  MapPrototypeSet(pendingStores, url, pending);
+}
+
+/**
+ * @param {InnerRequest} req
+ * @param {{ 0: { responseStatus: number, responseStatusText: string, responseHeaders: [string, string][] }, 1: number | null }} match
+ * @param {[string, string][]} headerList
+ * @returns {InnerResponse}
+ */
+function cachedResponse(req, match, headerList) {
+  const { 0: meta, 1: bodyRid } = match;
+  processUrlList(req.urlList, req.urlListProcessed);
+  return {
+    headerList: [
+      ...new SafeArrayIterator(withoutHeaders(headerList, ["age"])),
+      ["age", `${currentAge(headerList)}`],
+    ],
+    status: meta.responseStatus,
+    statusMessage: meta.responseStatusText,
+    body: bodyRid === null
+      ? null
+      : new InnerBody(readableStreamForRid(bodyRid)),
+    type: "basic",
+    url() {
+      if (this.urlList.length == 0) return null;
+      return this.urlList[this.urlList.length - 1];
+    },
+    urlList: req.urlListProcessed,
+  };
+}
+
+/**
+ * @param {InnerRequest} req
+ * @param {(req: InnerRequest) => Promise<InnerResponse>} networkFetch
+ * @returns {Promise<InnerResponse>}
+ */
+async function httpCacheFetch(req, networkFetch) {
+  const { name, shared } = req.httpCache;
+  let cacheId;
+  try {
+    cacheId = await openCache(name);
+  } catch {
+    return networkFetch(req);
+  }
+
+  const url = new URL(req.currentUrl());
+  url.hash = "";
+  const requestUrl = url.href;
+
+  // https://www.rfc-editor.org/rfc/rfc9111#section-4.4
+  if (!ArrayPrototypeIncludes(SAFE_METHODS, req.method)) {
+    const response = await networkFetch(req);
+    if (response.status >= 200 && response.status < 400) {
+      await MapPrototypeGet(pendingStores, requestUrl);
+      await PromisePrototypeCatch(
+        op_cache_delete({ cacheId, requestUrl }),
+        () => {},
+      );
+    }
+    return response;
+  }
+
+  const requestDirectives = parseCacheControl(
+    getHeader(req.headerList, "cache-control"),
+  );
+  if (
+    req.method !== "GET" || MapPrototypeHas(requestDirectives, "no-store") ||
+    ArrayPrototypeSome(
+      req.headerList,
+      (entry) =>
+        ArrayPrototypeIncludes(
+          CONDITIONAL_HEADER_NAMES,
+          byteLowerCase(entry[0]),
+        ),
+    )
+  ) {
+    return networkFetch(req);
+  }
+
+  const requestHeaders = req.headerList;
+  await MapPrototypeGet(pendingStores, requestUrl);
+  let match = null;
+  try {
+    match = await op_cache_match({ cacheId, requestUrl, requestHeaders });
+  } catch {
+    // Treat storage errors as a miss.
+  }
+
+  if (match === null) {
+    if (MapPrototypeHas(requestDirectives, "only-if-cached")) {
+      return gatewayTimeout(req);
+    }
+    return fetchAndStore(req, networkFetch, cacheId, requestUrl);
+  }
+
+  const storedHeaders = match[0].responseHeaders;
+  const responseDirectives = parseCacheControl(
+    getHeader(storedHeaders, "cache-control"),
+  );
+  const lifetime = freshnessLifetime(
+    { status: match[0].responseStatus, headerList: storedHeaders },
+    responseDirectives,
+    shared,
+  );
+  const age = currentAge(storedHeaders);
+  const maxAge = deltaSeconds(requestDirectives, "max-age");
+  const minFresh = deltaSeconds(requestDirectives, "min-fresh") ?? 0;
+  const noCache = MapPrototypeHas(requestDirectives, "no-cache") ||
+    (getHeader(req.headerList, "cache-control") === null &&
+      getHeader(req.headerList, "pragma") === "no-cache") ||
+    MapPrototypeHas(responseDirectives, "no-cache");
+  const fresh = !noCache && age + minFresh < lifetime &&
+    (maxAge === null || age <= maxAge);
+
+  if (fresh || MapPrototypeHas(requestDirectives, "only-if-cached")) {
+    return cachedResponse(req, match, storedHeaders);
+  }
+
+  // https://www.rfc-editor.org/rfc/rfc9111#section-4.3.1
+  const etag = getHeader(storedHeaders, "etag");
+  const lastModified = getHeader(storedHeaders, "last-modified");
+  if (etag === null && lastModified === null) {
+    if (match[1] !== null) {
+      core.tryClose(match[1]);
+    }
+    return fetchAndStore(req, networkFetch, cacheId, requestUrl);
+  }
+  const conditionalHeaders = [...new SafeArrayIterator(requestHeaders)];
+  if (etag !== null) {
+    ArrayPrototypePush(conditionalHeaders, ["if-none-match", etag]);
+  }
+  if (lastModified !== null) {
+    ArrayPrototypePush(conditionalHeaders, [
+      "if-modified-since",
+      lastModified,
+    ]);
+  }
+
+  const redirects = req.urlList.length;
+  let response;
+  req.headerList = conditionalHeaders;
+  try {
+    response = await networkFetch(req);
+  } catch (err) {
+    if (match[1] !== null) {
+      core.tryClose(match[1]);
+    }
+    throw err;
+  } finally {
+    req.headerList = requestHeaders;
+  }
+
+  if (response.status !== 304) {
+    if (match[1] !== null) {
+      core.tryClose(match[1]);
+    }
+    if (
+      req.urlList.length === redirects && isStorable(req, response, shared)
+    ) {
+      storeInBackground(cacheId, requestUrl, requestHeaders, response);
+    }
+    return response;
+  }
+
+  // https://www.rfc-editor.org/rfc/rfc9111#section-4.3.4
+  const updates = withoutHeaders(
+    response.headerList,
+    NON_UPDATABLE_HEADER_NAMES,
+  );
+  const updatedNames = [];
+  for (let i = 0; i < updates.length; ++i) {
+    ArrayPrototypePush(updatedNames, byteLowerCase(updates[i][0]));
+  }
+  const headerList = [
+    ...new SafeArrayIterator(withoutHeaders(storedHeaders, updatedNames)),
+    ...new SafeArrayIterator(updates),
+  ];
+  const revalidated = cachedResponse(req, match, headerList);
+  storeInBackground(cacheId, requestUrl, requestHeaders, {
+    ...revalidated,
+    headerList: withoutHeaders(headerList, ["age"]),
+  });
+  return revalidated;
+}
+
+/**
+ * @param {InnerRequest} req
+ * @param {(req: InnerRequest) => Promise<InnerResponse>} networkFetch
+ * @param {number} cacheId
+ * @param {string} requestUrl
+ * @returns {Promise<InnerResponse>}
+ */
+async function fetchAndStore(req, networkFetch, cacheId, requestUrl) {
+  const requestHeaders = req.headerList;
+  const redirects = req.urlList.length;
+  const response = await networkFetch(req);
+  // Followed redirects are stored by the nested fetches under their own URLs.
+  if (
+    req.urlList.length === redirects &&
+    isStorable(req, response, req.httpCache.shared)
+  ) {
+    storeInBackground(cacheId, requestUrl, requestHeaders, response);
+  }
+  return response;
+}
+
+/**
+ * The response to an `only-if-cached` request that can't be satisfied.
+ * https://www.rfc-editor.org/rfc/rfc9111#section-5.2.1.7
+ * @param {InnerRequest} req
+ * @returns {InnerResponse}
+ */
+function gatewayTimeout(req) {
+  processUrlList(req.urlList, req.urlListProcessed);
+  return {
+    headerList: [],
+    status: 504,
+    statusMessage: "Gateway Timeout",
+    body: null,
+    type: "basic",
+    url() {
+      if (this.urlList.length == 0) return null;
+      return this.urlList[this.urlList.length - 1];
+    },
+    urlList: req.urlListProcessed,
+  };
+}
+
+setHttpCacheHandler(httpCacheFetch);
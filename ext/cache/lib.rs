@@ -6,6 +6,7 @@ use std::rc::Rc;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::op2;
 use deno_core::serde::Deserialize;
@@ -33,7 +34,7 @@ deno_core::extension!(deno_cache,
     op_cache_match<CA>,
     op_cache_delete<CA>,
   ],
-  esm = [ "01_cache.js" ],
+  esm = [ "01_cache.js", "02_http_cache.js" ],
   options = {
     maybe_create_cache: Option<CreateCache<CA>>,
   },
@@ -229,7 +230,10 @@ where
   if let Some(cache) = state.try_borrow::<CA>() {
     Ok(cache.clone())
   } else {
-    let create_cache = state.borrow::<CreateCache<CA>>().clone();
+    let Some(create_cache) = state.try_borrow::<CreateCache<CA>>().cloned()
+    else {
+      return Err(type_error("Cache storage is not available"));
+    };
     let cache = create_cache.0();
     state.put(cache);
     Ok(state.borrow::<CA>().clone())
@@ -50,7 +50,7 @@ const {
 } = primordials;
 
 // P-521 is not yet supported.
-const supportedNamedCurves = ["P-256", "P-384"];
+const supportedNamedCurves = ["P-256", "P-384", "secp256k1"];
 const recognisedUsages = [
   "encrypt",
   "decrypt",
@@ -64,6 +64,7 @@ const recognisedUsages = [
 
 const simpleAlgorithmDictionaries = {
   AesGcmParams: { iv: "BufferSource", additionalData: "BufferSource" },
+  AeadParams: { iv: "BufferSource", additionalData: "BufferSource" },
   RsaHashedKeyGenParams: { hash: "HashAlgorithmIdentifier" },
   EcKeyGenParams: {},
   HmacKeyGenParams: { hash: "HashAlgorithmIdentifier" },
@@ -101,6 +102,9 @@ const supportedAlgorithms = {
     "HMAC": "HmacKeyGenParams",
     "X25519": null,
     "Ed25519": null,
+    "X448": null,
+    "Ed448": null,
+    "ChaCha20-Poly1305": null,
   },
   "sign": {
     "RSASSA-PKCS1-v1_5": null,
@@ -108,6 +112,7 @@ const supportedAlgorithms = {
     "ECDSA": "EcdsaParams",
     "HMAC": null,
     "Ed25519": null,
+    "Ed448": null,
   },
   "verify": {
     "RSASSA-PKCS1-v1_5": null,
@@ -115,6 +120,7 @@ const supportedAlgorithms = {
     "ECDSA": "EcdsaParams",
     "HMAC": null,
     "Ed25519": null,
+    "Ed448": null,
   },
   "importKey": {
     "RSASSA-PKCS1-v1_5": "RsaHashedImportParams",
@@ -131,24 +137,30 @@ const supportedAlgorithms = {
     "AES-KW": null,
     "Ed25519": null,
     "X25519": null,
+    "Ed448": null,
+    "X448": null,
+    "ChaCha20-Poly1305": null,
   },
   "deriveBits": {
     "HKDF": "HkdfParams",
     "PBKDF2": "Pbkdf2Params",
     "ECDH": "EcdhKeyDeriveParams",
     "X25519": "EcdhKeyDeriveParams",
+    "X448": "EcdhKeyDeriveParams",
   },
   "encrypt": {
     "RSA-OAEP": "RsaOaepParams",
     "AES-CBC": "AesCbcParams",
     "AES-GCM": "AesGcmParams",
     "AES-CTR": "AesCtrParams",
+    "ChaCha20-Poly1305": "AeadParams",
   },
   "decrypt": {
     "RSA-OAEP": "RsaOaepParams",
     "AES-CBC": "AesCbcParams",
     "AES-GCM": "AesGcmParams",
     "AES-CTR": "AesCtrParams",
+    "ChaCha20-Poly1305": "AeadParams",
   },
   "get key length": {
     "AES-CBC": "AesDerivedKeyParams",
@@ -190,6 +202,41 @@ const aesJwkAlg = {
   },
 };
 
+// The curves of RFC 7748 and RFC 8032, whose keys are plain byte strings of a
+// fixed length and share their import and export logic.
+const okpCurves = {
+  "Ed25519": {
+    keyLength: 32,
+    importSpki: ops.op_crypto_import_spki_ed25519,
+    importPkcs8: ops.op_crypto_import_pkcs8_ed25519,
+    exportSpki: ops.op_crypto_export_spki_ed25519,
+    exportPkcs8: ops.op_crypto_export_pkcs8_ed25519,
+    jwkX: ops.op_crypto_jwk_x_ed25519,
+  },
+  "Ed448": {
+    keyLength: 57,
+    importSpki: ops.op_crypto_import_spki_ed448,
+    importPkcs8: ops.op_crypto_import_pkcs8_ed448,
+    exportSpki: ops.op_crypto_export_spki_ed448,
+    exportPkcs8: ops.op_crypto_export_pkcs8_ed448,
+    jwkX: ops.op_crypto_jwk_x_ed448,
+  },
+  "X25519": {
+    keyLength: 32,
+    importSpki: ops.op_crypto_import_spki_x25519,
+    importPkcs8: ops.op_crypto_import_pkcs8_x25519,
+    exportSpki: ops.op_crypto_export_spki_x25519,
+    exportPkcs8: ops.op_crypto_export_pkcs8_x25519,
+  },
+  "X448": {
+    keyLength: 56,
+    importSpki: ops.op_crypto_import_spki_x448,
+    importPkcs8: ops.op_crypto_import_pkcs8_x448,
+    exportSpki: ops.op_crypto_export_spki_x448,
+    exportPkcs8: ops.op_crypto_export_pkcs8_x448,
+  },
+};
+
 // See https://www.w3.org/TR/WebCryptoAPI/#dfn-normalize-an-algorithm
 // 18.4.4
 function normalizeAlgorithm(algorithm, op) {
@@ -736,6 +783,55 @@ class SubtleCrypto {
         // 9.
         return TypedArrayPrototypeGetBuffer(plaintext);
       }
+      case "ChaCha20-Poly1305": {
+        normalizedAlgorithm.iv = copyBuffer(normalizedAlgorithm.iv);
+
+        // 1.
+        if (
+          normalizedAlgorithm.tagLength !== undefined &&
+          normalizedAlgorithm.tagLength !== 128
+        ) {
+          throw new DOMException(
+            "Invalid tag length",
+            "OperationError",
+          );
+        }
+
+        // 2.
+        if (TypedArrayPrototypeGetByteLength(data) < 16) {
+          throw new DOMException(
+            "Tag length overflows ciphertext",
+            "OperationError",
+          );
+        }
+
+        // 3.
+        if (TypedArrayPrototypeGetByteLength(normalizedAlgorithm.iv) !== 12) {
+          throw new DOMException(
+            "Initialization vector length not supported",
+            "OperationError",
+          );
+        }
+
+        // 4.
+        if (normalizedAlgorithm.additionalData !== undefined) {
+          normalizedAlgorithm.additionalData = copyBuffer(
+            normalizedAlgorithm.additionalData,
+          );
+        }
+
+        // 5-8.
+        const plaintext = await core.opAsync("op_crypto_decrypt", {
+          key: keyData,
+          algorithm: "ChaCha20-Poly1305",
+          iv: normalizedAlgorithm.iv,
+          additionalData: normalizedAlgorithm.additionalData ||
+            null,
+        }, data);
+
+        // 9.
+        return TypedArrayPrototypeGetBuffer(plaintext);
+      }
       default:
         throw new DOMException("Not implemented", "NotSupportedError");
     }
@@ -844,7 +940,9 @@ class SubtleCrypto {
           (key[_algorithm].namedCurve === "P-256" &&
             hashAlgorithm !== "SHA-256") ||
           (key[_algorithm].namedCurve === "P-384" &&
-            hashAlgorithm !== "SHA-384")
+            hashAlgorithm !== "SHA-384") ||
+          (key[_algorithm].namedCurve === "secp256k1" &&
+            hashAlgorithm !== "SHA-256")
         ) {
           throw new DOMException(
             "Not implemented",
@@ -892,6 +990,26 @@ class SubtleCrypto {
         }
         return TypedArrayPrototypeGetBuffer(signature);
       }
+      case "Ed448": {
+        // 1.
+        if (key[_type] !== "private") {
+          throw new DOMException(
+            "Key type not supported",
+            "InvalidAccessError",
+          );
+        }
+
+        // https://www.rfc-editor.org/rfc/rfc8032#section-5.2.6
+        const SIGNATURE_LEN = 57 * 2;
+        const signature = new Uint8Array(SIGNATURE_LEN);
+        if (!ops.op_crypto_sign_ed448(keyData, data, signature)) {
+          throw new DOMException(
+            "Failed to sign",
+            "OperationError",
+          );
+        }
+        return TypedArrayPrototypeGetBuffer(signature);
+      }
     }
 
     throw new TypeError("unreachable");
@@ -1000,6 +1118,14 @@ class SubtleCrypto {
           ["encrypt", "decrypt", "wrapKey", "unwrapKey"],
         );
       }
+      case "ChaCha20-Poly1305": {
+        return importKeyChaCha20Poly1305(
+          format,
+          keyData,
+          extractable,
+          keyUsages,
+        );
+      }
       case "AES-KW": {
         return importKeyAES(
           format,
@@ -1010,16 +1136,20 @@ class SubtleCrypto {
           ["wrapKey", "unwrapKey"],
         );
       }
-      case "X25519": {
-        return importKeyX25519(
+      case "X25519":
+      case "X448": {
+        return importKeyXDH(
+          algorithmName,
           format,
           keyData,
           extractable,
           keyUsages,
         );
       }
-      case "Ed25519": {
-        return importKeyEd25519(
+      case "Ed25519":
+      case "Ed448": {
+        return importKeyEdDSA(
+          algorithmName,
           format,
           keyData,
           extractable,
@@ -1068,12 +1198,14 @@ class SubtleCrypto {
         result = exportKeyEC(format, key, innerKey);
         break;
       }
-      case "Ed25519": {
-        result = exportKeyEd25519(format, key, innerKey);
+      case "Ed25519":
+      case "Ed448": {
+        result = exportKeyEdDSA(format, key, innerKey);
         break;
       }
-      case "X25519": {
-        result = exportKeyX25519(format, key, innerKey);
+      case "X25519":
+      case "X448": {
+        result = exportKeyXDH(format, key, innerKey);
         break;
       }
       case "AES-CTR":
@@ -1083,6 +1215,10 @@ class SubtleCrypto {
         result = exportKeyAES(format, key, innerKey);
         break;
       }
+      case "ChaCha20-Poly1305": {
+        result = exportKeyChaCha20Poly1305(format, key, innerKey);
+        break;
+      }
       default:
         throw new DOMException("Not implemented", "NotSupportedError");
     }
@@ -1337,7 +1473,8 @@ class SubtleCrypto {
 
         if (
           (key[_algorithm].namedCurve === "P-256" && hash !== "SHA-256") ||
-          (key[_algorithm].namedCurve === "P-384" && hash !== "SHA-384")
+          (key[_algorithm].namedCurve === "P-384" && hash !== "SHA-384") ||
+          (key[_algorithm].namedCurve === "secp256k1" && hash !== "SHA-256")
         ) {
           throw new DOMException(
             "Not implemented",
@@ -1365,6 +1502,17 @@ class SubtleCrypto {
 
         return ops.op_crypto_verify_ed25519(keyData, data, signature);
       }
+      case "Ed448": {
+        // 1.
+        if (key[_type] !== "public") {
+          throw new DOMException(
+            "Key type not supported",
+            "InvalidAccessError",
+          );
+        }
+
+        return ops.op_crypto_verify_ed448(keyData, data, signature);
+      }
     }
 
     throw new TypeError("unreachable");
@@ -2075,6 +2223,129 @@ async function generateKey(normalizedAlgorithm, extractable, usages) {
 
       return { publicKey, privateKey };
     }
+    case "X448": {
+      if (
+        ArrayPrototypeFind(
+          usages,
+          (u) => !ArrayPrototypeIncludes(["deriveKey", "deriveBits"], u),
+        ) !== undefined
+      ) {
+        throw new DOMException("Invalid key usages", "SyntaxError");
+      }
+      const privateKeyData = new Uint8Array(56);
+      const publicKeyData = new Uint8Array(56);
+      ops.op_crypto_generate_x448_keypair(privateKeyData, publicKeyData);
+
+      const handle = {};
+      WeakMapPrototypeSet(KEY_STORE, handle, privateKeyData);
+
+      const publicHandle = {};
+      WeakMapPrototypeSet(KEY_STORE, publicHandle, publicKeyData);
+
+      const algorithm = {
+        name: algorithmName,
+      };
+
+      const publicKey = constructKey(
+        "public",
+        true,
+        usageIntersection(usages, []),
+        algorithm,
+        publicHandle,
+      );
+
+      const privateKey = constructKey(
+        "private",
+        extractable,
+        usageIntersection(usages, ["deriveKey", "deriveBits"]),
+        algorithm,
+        handle,
+      );
+
+      return { publicKey, privateKey };
+    }
+    case "Ed448": {
+      if (
+        ArrayPrototypeFind(
+          usages,
+          (u) => !ArrayPrototypeIncludes(["sign", "verify"], u),
+        ) !== undefined
+      ) {
+        throw new DOMException("Invalid key usages", "SyntaxError");
+      }
+
+      const ED448_KEY_LEN = 57;
+      const privateKeyData = new Uint8Array(ED448_KEY_LEN);
+      const publicKeyData = new Uint8Array(ED448_KEY_LEN);
+      if (
+        !ops.op_crypto_generate_ed448_keypair(privateKeyData, publicKeyData)
+      ) {
+        throw new DOMException("Failed to generate key", "OperationError");
+      }
+
+      const handle = {};
+      WeakMapPrototypeSet(KEY_STORE, handle, privateKeyData);
+
+      const publicHandle = {};
+      WeakMapPrototypeSet(KEY_STORE, publicHandle, publicKeyData);
+
+      const algorithm = {
+        name: algorithmName,
+      };
+
+      const publicKey = constructKey(
+        "public",
+        true,
+        usageIntersection(usages, ["verify"]),
+        algorithm,
+        publicHandle,
+      );
+
+      const privateKey = constructKey(
+        "private",
+        extractable,
+        usageIntersection(usages, ["sign"]),
+        algorithm,
+        handle,
+      );
+
+      return { publicKey, privateKey };
+    }
+    case "ChaCha20-Poly1305": {
+      if (
+        ArrayPrototypeFind(
+          usages,
+          (u) =>
+            !ArrayPrototypeIncludes([
+              "encrypt",
+              "decrypt",
+              "wrapKey",
+              "unwrapKey",
+            ], u),
+        ) !== undefined
+      ) {
+        throw new DOMException("Invalid key usages", "SyntaxError");
+      }
+
+      // Keys are 256 random bits, the same as AES-256 ones.
+      const keyData = await core.opAsync("op_crypto_generate_key", {
+        algorithm: "AES",
+        length: 256,
+      });
+      const handle = {};
+      WeakMapPrototypeSet(KEY_STORE, handle, {
+        type: "secret",
+        data: keyData,
+      });
+
+      return constructKey(
+        "secret",
+        extractable,
+        usages,
+        { name: algorithmName },
+        handle,
+      );
+    }
     case "HMAC": {
       // 1.
       if (
@@ -2132,7 +2403,8 @@ async function generateKey(normalizedAlgorithm, extractable, usages) {
   }
 }
 
-function importKeyEd25519(
+function importKeyEdDSA(
+  algorithmName,
   format,
   keyData,
   extractable,
@@ -2155,7 +2427,7 @@ function importKeyEd25519(
 
       // 2-3.
       const algorithm = {
-        name: "Ed25519",
+        name: algorithmName,
       };
 
       // 4-6.
@@ -2178,8 +2450,8 @@ function importKeyEd25519(
         throw new DOMException("Invalid key usages", "SyntaxError");
       }
 
-      const publicKeyData = new Uint8Array(32);
-      if (!ops.op_crypto_import_spki_ed25519(keyData, publicKeyData)) {
+      const publicKeyData = new Uint8Array(okpCurves[algorithmName].keyLength);
+      if (!okpCurves[algorithmName].importSpki(keyData, publicKeyData)) {
         throw new DOMException("Invalid key data", "DataError");
       }
 
@@ -2187,7 +2459,7 @@ function importKeyEd25519(
       WeakMapPrototypeSet(KEY_STORE, handle, publicKeyData);
 
       const algorithm = {
-        name: "Ed25519",
+        name: algorithmName,
       };
 
       return constructKey(
@@ -2209,8 +2481,8 @@ function importKeyEd25519(
         throw new DOMException("Invalid key usages", "SyntaxError");
       }
 
-      const privateKeyData = new Uint8Array(32);
-      if (!ops.op_crypto_import_pkcs8_ed25519(keyData, privateKeyData)) {
+      const privateKeyData = new Uint8Array(okpCurves[algorithmName].keyLength);
+      if (!okpCurves[algorithmName].importPkcs8(keyData, privateKeyData)) {
         throw new DOMException("Invalid key data", "DataError");
       }
 
@@ -2218,7 +2490,7 @@ function importKeyEd25519(
       WeakMapPrototypeSet(KEY_STORE, handle, privateKeyData);
 
       const algorithm = {
-        name: "Ed25519",
+        name: algorithmName,
       };
 
       return constructKey(
@@ -2268,7 +2540,7 @@ function importKeyEd25519(
       }
 
       // 4.
-      if (jwk.crv !== "Ed25519") {
+      if (jwk.crv !== algorithmName) {
         throw new DOMException("Invalid curve", "DataError");
       }
 
@@ -2325,7 +2597,7 @@ function importKeyEd25519(
         WeakMapPrototypeSet(KEY_STORE, handle, privateKeyData);
 
         const algorithm = {
-          name: "Ed25519",
+          name: algorithmName,
         };
 
         return constructKey(
@@ -2348,7 +2620,7 @@ function importKeyEd25519(
         WeakMapPrototypeSet(KEY_STORE, handle, publicKeyData);
 
         const algorithm = {
-          name: "Ed25519",
+          name: algorithmName,
         };
 
         return constructKey(
@@ -2365,7 +2637,8 @@ function importKeyEd25519(
   }
 }
 
-function importKeyX25519(
+function importKeyXDH(
+  algorithmName,
   format,
   keyData,
   extractable,
@@ -2383,7 +2656,7 @@ function importKeyX25519(
 
       // 2-3.
       const algorithm = {
-        name: "X25519",
+        name: algorithmName,
       };
 
       // 4-6.
@@ -2401,8 +2674,8 @@ function importKeyX25519(
         throw new DOMException("Invalid key usages", "SyntaxError");
       }
 
-      const publicKeyData = new Uint8Array(32);
-      if (!ops.op_crypto_import_spki_x25519(keyData, publicKeyData)) {
+      const publicKeyData = new Uint8Array(okpCurves[algorithmName].keyLength);
+      if (!okpCurves[algorithmName].importSpki(keyData, publicKeyData)) {
         throw new DOMException("Invalid key data", "DataError");
       }
 
@@ -2410,7 +2683,7 @@ function importKeyX25519(
       WeakMapPrototypeSet(KEY_STORE, handle, publicKeyData);
 
       const algorithm = {
-        name: "X25519",
+        name: algorithmName,
       };
 
       return constructKey(
@@ -2432,8 +2705,8 @@ function importKeyX25519(
         throw new DOMException("Invalid key usages", "SyntaxError");
       }
 
-      const privateKeyData = new Uint8Array(32);
-      if (!ops.op_crypto_import_pkcs8_x25519(keyData, privateKeyData)) {
+      const privateKeyData = new Uint8Array(okpCurves[algorithmName].keyLength);
+      if (!okpCurves[algorithmName].importPkcs8(keyData, privateKeyData)) {
         throw new DOMException("Invalid key data", "DataError");
       }
 
@@ -2441,7 +2714,7 @@ function importKeyX25519(
       WeakMapPrototypeSet(KEY_STORE, handle, privateKeyData);
 
       const algorithm = {
-        name: "X25519",
+        name: algorithmName,
       };
 
       return constructKey(
@@ -2483,7 +2756,7 @@ function importKeyX25519(
       }
 
       // 5.
-      if (jwk.crv !== "X25519") {
+      if (jwk.crv !== algorithmName) {
         throw new DOMException("Invalid curve", "DataError");
       }
 
@@ -2535,7 +2808,7 @@ function importKeyX25519(
         WeakMapPrototypeSet(KEY_STORE, handle, privateKeyData);
 
         const algorithm = {
-          name: "X25519",
+          name: algorithmName,
         };
 
         return constructKey(
@@ -2553,7 +2826,7 @@ function importKeyX25519(
         WeakMapPrototypeSet(KEY_STORE, handle, publicKeyData);
 
         const algorithm = {
-          name: "X25519",
+          name: algorithmName,
         };
 
         return constructKey(
@@ -2629,6 +2902,164 @@ function exportKeyAES(
   }
 }
 
+function importKeyChaCha20Poly1305(
+  format,
+  keyData,
+  extractable,
+  keyUsages,
+) {
+  // 1.
+  if (
+    ArrayPrototypeFind(
+      keyUsages,
+      (u) =>
+        !ArrayPrototypeIncludes([
+          "encrypt",
+          "decrypt",
+          "wrapKey",
+          "unwrapKey",
+        ], u),
+    ) !== undefined
+  ) {
+    throw new DOMException("Invalid key usages", "SyntaxError");
+  }
+
+  // 2.
+  let data = keyData;
+
+  switch (format) {
+    case "raw": {
+      break;
+    }
+    case "jwk": {
+      // 1.
+      const jwk = keyData;
+
+      // 2.
+      if (jwk.kty !== "oct") {
+        throw new DOMException(
+          "'kty' property of JsonWebKey must be 'oct'",
+          "DataError",
+        );
+      }
+
+      // Section 6.4.1 of RFC7518
+      if (jwk.k === undefined) {
+        throw new DOMException(
+          "'k' property of JsonWebKey must be present",
+          "DataError",
+        );
+      }
+
+      // 4.
+      const { rawData } = ops.op_crypto_import_key(
+        { algorithm: "AES" },
+        { jwkSecret: jwk },
+      );
+      data = rawData.data;
+
+      // 5.
+      // Section 2 of RFC8439
+      if (jwk.alg !== undefined && jwk.alg !== "C20P") {
+        throw new DOMException("Invalid algorithm", "DataError");
+      }
+
+      // 6.
+      if (
+        keyUsages.length > 0 && jwk.use !== undefined && jwk.use !== "enc"
+      ) {
+        throw new DOMException("Invalid key usages", "DataError");
+      }
+
+      // 7.
+      // Section 4.3 of RFC7517
+      if (jwk.key_ops !== undefined) {
+        if (
+          ArrayPrototypeFind(
+            jwk.key_ops,
+            (u) => !ArrayPrototypeIncludes(recognisedUsages, u),
+          ) !== undefined
+        ) {
+          throw new DOMException(
+            "'key_ops' property of JsonWebKey is invalid",
+            "DataError",
+          );
+        }
+
+        if (
+          !ArrayPrototypeEvery(
+            jwk.key_ops,
+            (u) => ArrayPrototypeIncludes(keyUsages, u),
+          )
+        ) {
+          throw new DOMException(
+            "'key_ops' property of JsonWebKey is invalid",
+            "DataError",
+          );
+        }
+      }
+
+      // 8.
+      if (jwk.ext === false && extractable === true) {
+        throw new DOMException(
+          "'ext' property of JsonWebKey must not be false if extractable is true",
+          "DataError",
+        );
+      }
+
+      break;
+    }
+    default:
+      throw new DOMException("Not implemented", "NotSupportedError");
+  }
+
+  // 3.
+  if (TypedArrayPrototypeGetByteLength(data) !== 32) {
+    throw new DOMException("Invalid key length", "DataError");
+  }
+
+  const handle = {};
+  WeakMapPrototypeSet(KEY_STORE, handle, {
+    type: "secret",
+    data,
+  });
+
+  // 4-7.
+  return constructKey(
+    "secret",
+    extractable,
+    usageIntersection(keyUsages, recognisedUsages),
+    { name: "ChaCha20-Poly1305" },
+    handle,
+  );
+}
+
+function exportKeyChaCha20Poly1305(format, key, innerKey) {
+  switch (format) {
+    case "raw": {
+      return TypedArrayPrototypeGetBuffer(innerKey.data);
+    }
+    case "jwk": {
+      const jwk = {
+        kty: "oct",
+      };
+
+      const data = ops.op_crypto_export_key({
+        format: "jwksecret",
+        algorithm: "AES",
+      }, innerKey);
+      ObjectAssign(jwk, data);
+
+      jwk.alg = "C20P";
+      jwk.key_ops = key.usages;
+      jwk.ext = key[_extractable];
+      return jwk;
+    }
+    default:
+      throw new DOMException("Not implemented", "NotSupportedError");
+  }
+}
+
 function importKeyAES(
   format,
   normalizedAlgorithm,
@@ -3227,6 +3658,10 @@ function importKeyEC(
             algNamedCurve = "P-521";
             break;
           }
+          case "ES256K": {
+            algNamedCurve = "secp256k1";
+            break;
+          }
           default:
             throw new DOMException(
               "Curve algorithm not supported",
@@ -4037,7 +4472,9 @@ function exportKeyRSA(format, key, innerKey) {
   }
 }
 
-function exportKeyEd25519(format, key, innerKey) {
+function exportKeyEdDSA(format, key, innerKey) {
+  const algorithmName = key[_algorithm].name;
+  const curve = okpCurves[algorithmName];
   switch (format) {
     case "raw": {
       // 1.
@@ -4060,7 +4497,7 @@ function exportKeyEd25519(format, key, innerKey) {
         );
       }
 
-      const spkiDer = ops.op_crypto_export_spki_ed25519(innerKey);
+      const spkiDer = curve.exportSpki(innerKey);
       return TypedArrayPrototypeGetBuffer(spkiDer);
     }
     case "pkcs8": {
@@ -4072,19 +4509,22 @@ function exportKeyEd25519(format, key, innerKey) {
         );
       }
 
-      const pkcs8Der = ops.op_crypto_export_pkcs8_ed25519(
-        new Uint8Array([0x04, 0x22, ...new SafeArrayIterator(innerKey)]),
+      const pkcs8Der = curve.exportPkcs8(
+        new Uint8Array([
+          0x04,
+          curve.keyLength,
+          ...new SafeArrayIterator(innerKey),
+        ]),
       );
-      pkcs8Der[15] = 0x20;
       return TypedArrayPrototypeGetBuffer(pkcs8Der);
     }
     case "jwk": {
       const x = key[_type] === "private"
-        ? ops.op_crypto_jwk_x_ed25519(innerKey)
+        ? curve.jwkX(innerKey)
         : ops.op_crypto_base64url_encode(innerKey);
       const jwk = {
         kty: "OKP",
-        crv: "Ed25519",
+        crv: algorithmName,
         x,
         "key_ops": key.usages,
         ext: key[_extractable],
@@ -4099,7 +4539,9 @@ function exportKeyEd25519(format, key, innerKey) {
   }
 }
 
-function exportKeyX25519(format, key, innerKey) {
+function exportKeyXDH(format, key, innerKey) {
+  const algorithmName = key[_algorithm].name;
+  const curve = okpCurves[algorithmName];
   switch (format) {
     case "raw": {
       // 1.
@@ -4122,7 +4564,7 @@ function exportKeyX25519(format, key, innerKey) {
         );
       }
 
-      const spkiDer = ops.op_crypto_export_spki_x25519(innerKey);
+      const spkiDer = curve.exportSpki(innerKey);
       return TypedArrayPrototypeGetBuffer(spkiDer);
     }
     case "pkcs8": {
@@ -4134,10 +4576,13 @@ function exportKeyX25519(format, key, innerKey) {
         );
       }
 
-      const pkcs8Der = ops.op_crypto_export_pkcs8_x25519(
-        new Uint8Array([0x04, 0x22, ...new SafeArrayIterator(innerKey)]),
+      const pkcs8Der = curve.exportPkcs8(
+        new Uint8Array([
+          0x04,
+          curve.keyLength,
+          ...new SafeArrayIterator(innerKey),
+        ]),
       );
-      pkcs8Der[15] = 0x20;
       return TypedArrayPrototypeGetBuffer(pkcs8Der);
     }
     case "jwk": {
@@ -4147,7 +4592,7 @@ function exportKeyX25519(format, key, innerKey) {
       const x = ops.op_crypto_base64url_encode(innerKey);
       const jwk = {
         kty: "OKP",
-        crv: "X25519",
+        crv: algorithmName,
         x,
         "key_ops": key.usages,
         ext: key[_extractable],
@@ -4241,6 +4686,10 @@ function exportKeyEC(format, key, innerKey) {
             algNamedCurve = "ES512";
             break;
           }
+          case "secp256k1": {
+            algNamedCurve = "ES256K";
+            break;
+          }
           default:
             throw new DOMException(
               "Curve algorithm not supported",
@@ -4502,6 +4951,53 @@ async function deriveBits(normalizedAlgorithm, baseKey, length) {
         );
       }
     }
+    case "X448": {
+      // 1.
+      if (baseKey[_type] !== "private") {
+        throw new DOMException("Invalid key type", "InvalidAccessError");
+      }
+      // 2.
+      const publicKey = normalizedAlgorithm.public;
+      // 3.
+      if (publicKey[_type] !== "public") {
+        throw new DOMException("Invalid key type", "InvalidAccessError");
+      }
+      // 4.
+      if (publicKey[_algorithm].name !== baseKey[_algorithm].name) {
+        throw new DOMException(
+          "Algorithm mismatch",
+          "InvalidAccessError",
+        );
+      }
+
+      // 5.
+      const kHandle = baseKey[_handle];
+      const k = WeakMapPrototypeGet(KEY_STORE, kHandle);
+
+      const uHandle = publicKey[_handle];
+      const u = WeakMapPrototypeGet(KEY_STORE, uHandle);
+
+      const secret = new Uint8Array(56);
+      // 6.
+      if (!ops.op_crypto_derive_bits_x448(k, u, secret)) {
+        throw new DOMException("Invalid key", "OperationError");
+      }
+
+      // 7.
+      if (length === null) {
+        return TypedArrayPrototypeGetBuffer(secret);
+      } else if (
+        TypedArrayPrototypeGetByteLength(secret) * 8 < length
+      ) {
+        throw new DOMException("Invalid length", "OperationError");
+      } else {
+        return ArrayBufferPrototypeSlice(
+          TypedArrayPrototypeGetBuffer(secret),
+          0,
+          MathCeil(length / 8),
+        );
+      }
+    }
     default:
       throw new DOMException("Not implemented", "NotSupportedError");
   }
@@ -4666,6 +5162,46 @@ async function encrypt(normalizedAlgorithm, key, data) {
       // 8.
       return TypedArrayPrototypeGetBuffer(cipherText);
     }
+    case "ChaCha20-Poly1305": {
+      normalizedAlgorithm.iv = copyBuffer(normalizedAlgorithm.iv);
+
+      // 1.
+      if (TypedArrayPrototypeGetByteLength(normalizedAlgorithm.iv) !== 12) {
+        throw new DOMException(
+          "Initialization vector length not supported",
+          "OperationError",
+        );
+      }
+
+      // 2.
+      if (
+        normalizedAlgorithm.tagLength !== undefined &&
+        normalizedAlgorithm.tagLength !== 128
+      ) {
+        throw new DOMException(
+          "Invalid tag length",
+          "OperationError",
+        );
+      }
+
+      // 3.
+      if (normalizedAlgorithm.additionalData) {
+        normalizedAlgorithm.additionalData = copyBuffer(
+          normalizedAlgorithm.additionalData,
+        );
+      }
+
+      // 4-5.
+      const cipherText = await core.opAsync("op_crypto_encrypt", {
+        key: keyData,
+        algorithm: "ChaCha20-Poly1305",
+        iv: normalizedAlgorithm.iv,
+        additionalData: normalizedAlgorithm.additionalData || null,
+      }, data);
+
+      // 6.
+      return TypedArrayPrototypeGetBuffer(cipherText);
+    }
     default:
       throw new DOMException("Not implemented", "NotSupportedError");
   }
@@ -5206,6 +5742,10 @@ webidl.converters.AesCbcParams = webidl
 webidl.converters.AesGcmParams = webidl
   .createDictionaryConverter("AesGcmParams", dictAesGcmParams);
 
+// ChaCha20-Poly1305 takes the same parameters as AES-GCM.
+webidl.converters.AeadParams = webidl
+  .createDictionaryConverter("AeadParams", dictAesGcmParams);
+
 webidl.converters.AesCtrParams = webidl
   .createDictionaryConverter("AesCtrParams", dictAesCtrParams);
 
@@ -12,6 +12,7 @@ use aes_gcm::aes::Aes256;
 use aes_gcm::AeadInPlace;
 use aes_gcm::KeyInit;
 use aes_gcm::Nonce;
+use chacha20poly1305::ChaCha20Poly1305;
 use ctr::cipher::StreamCipher;
 use ctr::Ctr128BE;
 use ctr::Ctr32BE;
@@ -73,6 +74,13 @@ pub enum DecryptAlgorithm {
     length: usize,
     tag_length: usize,
   },
+  #[serde(rename = "ChaCha20-Poly1305", rename_all = "camelCase")]
+  ChaCha20Poly1305 {
+    #[serde(with = "serde_bytes")]
+    iv: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    additional_data: Option<Vec<u8>>,
+  },
 }
 
 #[op2(async)]
@@ -100,6 +108,10 @@ pub async fn op_crypto_decrypt(
       length,
       tag_length,
     } => decrypt_aes_gcm(key, length, tag_length, iv, additional_data, &data),
+    DecryptAlgorithm::ChaCha20Poly1305 {
+      iv,
+      additional_data,
+    } => decrypt_chacha20_poly1305(key, iv, additional_data, &data),
   };
   let buf = spawn_blocking(fun).await.unwrap()?;
   Ok(buf.into())
@@ -364,3 +376,42 @@ fn decrypt_aes_gcm(
 
   Ok(plaintext)
 }
+
+fn decrypt_chacha20_poly1305(
+  key: V8RawKeyData,
+  iv: Vec<u8>,
+  additional_data: Option<Vec<u8>>,
+  data: &[u8],
+) -> Result<Vec<u8>, AnyError> {
+  let key = key.as_secret_key()?;
+  let additional_data = additional_data.unwrap_or_default();
+
+  // Fixed 96-bit nonce
+  if iv.len() != 12 {
+    return Err(type_error("iv length not equal to 12"));
+  }
+  let nonce = chacha20poly1305::Nonce::from_slice(&iv);
+
+  // The tag is always 128 bits long.
+  if data.len() < 16 {
+    return Err(operation_error("Decryption failed"));
+  }
+  let sep = data.len() - 16;
+  let tag = chacha20poly1305::Tag::from_slice(&data[sep..]);
+
+  // The actual ciphertext, called plaintext because it is reused in place.
+  let mut plaintext = data[..sep].to_vec();
+
+  let cipher = ChaCha20Poly1305::new_from_slice(key)
+    .map_err(|_| operation_error("Decryption failed"))?;
+  cipher
+    .decrypt_in_place_detached(
+      nonce,
+      additional_data.as_slice(),
+      &mut plaintext,
+      tag,
+    )
+    .map_err(|_| operation_error("Decryption failed"))?;
+
+  Ok(plaintext)
+}
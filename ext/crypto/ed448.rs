@@ -0,0 +1,172 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::error::AnyError;
+use deno_core::op2;
+use deno_core::ToJsBuffer;
+use ed448_goldilocks_plus::Signature;
+use ed448_goldilocks_plus::SigningKey;
+use ed448_goldilocks_plus::VerifyingKey;
+use elliptic_curve::pkcs8::PrivateKeyInfo;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use spki::der::Decode;
+use spki::der::Encode;
+
+const ED448_KEY_LEN: usize = 57;
+
+fn signing_key(pkey: &[u8]) -> Option<SigningKey> {
+  SigningKey::try_from(pkey).ok()
+}
+
+#[op2(fast)]
+pub fn op_crypto_generate_ed448_keypair(
+  #[buffer] pkey: &mut [u8],
+  #[buffer] pubkey: &mut [u8],
+) -> bool {
+  let mut rng = OsRng;
+  rng.fill_bytes(pkey);
+
+  let Some(key) = signing_key(pkey) else {
+    return false;
+  };
+  pubkey.copy_from_slice(&key.verifying_key().to_bytes());
+  true
+}
+
+#[op2(fast)]
+pub fn op_crypto_sign_ed448(
+  #[buffer] key: &[u8],
+  #[buffer] data: &[u8],
+  #[buffer] signature: &mut [u8],
+) -> bool {
+  let Some(key) = signing_key(key) else {
+    return false;
+  };
+  // Pure Ed448 with an empty context, as RFC 8032 recommends.
+  signature.copy_from_slice(&key.sign_raw(data).to_bytes());
+  true
+}
+
+#[op2(fast)]
+pub fn op_crypto_verify_ed448(
+  #[buffer] pubkey: &[u8],
+  #[buffer] data: &[u8],
+  #[buffer] signature: &[u8],
+) -> bool {
+  let Ok(pubkey) = VerifyingKey::try_from(pubkey) else {
+    return false;
+  };
+  let Ok(signature) = Signature::try_from(signature) else {
+    return false;
+  };
+  pubkey.verify_raw(&signature, data).is_ok()
+}
+
+// id-Ed448 OBJECT IDENTIFIER ::= { 1 3 101 113 }
+pub const ED448_OID: const_oid::ObjectIdentifier =
+  const_oid::ObjectIdentifier::new_unwrap("1.3.101.113");
+
+#[op2(fast)]
+pub fn op_crypto_import_spki_ed448(
+  #[buffer] key_data: &[u8],
+  #[buffer] out: &mut [u8],
+) -> bool {
+  // 2-3.
+  let pk_info = match spki::SubjectPublicKeyInfo::from_der(key_data) {
+    Ok(pk_info) => pk_info,
+    Err(_) => return false,
+  };
+  // 4.
+  let alg = pk_info.algorithm.oid;
+  if alg != ED448_OID {
+    return false;
+  }
+  // 5.
+  if pk_info.algorithm.parameters.is_some() {
+    return false;
+  }
+  if pk_info.subject_public_key.len() != ED448_KEY_LEN {
+    return false;
+  }
+  out.copy_from_slice(pk_info.subject_public_key);
+  true
+}
+
+#[op2(fast)]
+pub fn op_crypto_import_pkcs8_ed448(
+  #[buffer] key_data: &[u8],
+  #[buffer] out: &mut [u8],
+) -> bool {
+  // 2-3.
+  // This should probably use OneAsymmetricKey instead
+  let pk_info = match PrivateKeyInfo::from_der(key_data) {
+    Ok(pk_info) => pk_info,
+    Err(_) => return false,
+  };
+  // 4.
+  let alg = pk_info.algorithm.oid;
+  if alg != ED448_OID {
+    return false;
+  }
+  // 5.
+  if pk_info.algorithm.parameters.is_some() {
+    return false;
+  }
+  // 6.
+  // CurvePrivateKey ::= OCTET STRING
+  if pk_info.private_key.len() != ED448_KEY_LEN + 2 {
+    return false;
+  }
+  out.copy_from_slice(&pk_info.private_key[2..]);
+  true
+}
+
+#[op2]
+#[serde]
+pub fn op_crypto_export_spki_ed448(
+  #[buffer] pubkey: &[u8],
+) -> Result<ToJsBuffer, AnyError> {
+  let key_info = spki::SubjectPublicKeyInfo {
+    algorithm: spki::AlgorithmIdentifier {
+      // id-Ed448
+      oid: ED448_OID,
+      parameters: None,
+    },
+    subject_public_key: pubkey,
+  };
+  Ok(key_info.to_vec()?.into())
+}
+
+#[op2]
+#[serde]
+pub fn op_crypto_export_pkcs8_ed448(
+  #[buffer] pkey: &[u8],
+) -> Result<ToJsBuffer, AnyError> {
+  // This should probably use OneAsymmetricKey instead
+  let pk_info = rsa::pkcs8::PrivateKeyInfo {
+    public_key: None,
+    algorithm: rsa::pkcs8::AlgorithmIdentifier {
+      // id-Ed448
+      oid: ED448_OID,
+      parameters: None,
+    },
+    private_key: pkey, // OCTET STRING
+  };
+
+  Ok(pk_info.to_vec()?.into())
+}
+
+// 'x' from Section 2 of RFC 8037
+// https://www.rfc-editor.org/rfc/rfc8037#section-2
+#[op2]
+#[string]
+pub fn op_crypto_jwk_x_ed448(
+  #[buffer] pkey: &[u8],
+) -> Result<String, AnyError> {
+  let key = signing_key(pkey)
+    .ok_or_else(|| deno_core::error::type_error("Invalid Ed448 private key"))?;
+  Ok(base64::encode_config(
+    key.verifying_key().to_bytes(),
+    base64::URL_SAFE_NO_PAD,
+  ))
+}
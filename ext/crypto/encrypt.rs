@@ -13,6 +13,7 @@ use aes_gcm::aes::Aes256;
 use aes_gcm::AeadInPlace;
 use aes_gcm::KeyInit;
 use aes_gcm::Nonce;
+use chacha20poly1305::ChaCha20Poly1305;
 use ctr::Ctr128BE;
 use ctr::Ctr32BE;
 use ctr::Ctr64BE;
@@ -74,6 +75,13 @@ pub enum EncryptAlgorithm {
     ctr_length: usize,
     key_length: usize,
   },
+  #[serde(rename = "ChaCha20-Poly1305", rename_all = "camelCase")]
+  ChaCha20Poly1305 {
+    #[serde(with = "serde_bytes")]
+    iv: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    additional_data: Option<Vec<u8>>,
+  },
 }
 
 #[op2(async)]
@@ -101,6 +109,10 @@ pub async fn op_crypto_encrypt(
       ctr_length,
       key_length,
     } => encrypt_aes_ctr(key, key_length, &counter, ctr_length, &data),
+    EncryptAlgorithm::ChaCha20Poly1305 {
+      iv,
+      additional_data,
+    } => encrypt_chacha20_poly1305(key, iv, additional_data, &data),
   };
   let buf = spawn_blocking(fun).await.unwrap()?;
   Ok(buf.into())
@@ -260,6 +272,34 @@ fn encrypt_aes_gcm(
   Ok(ciphertext)
 }
 
+fn encrypt_chacha20_poly1305(
+  key: V8RawKeyData,
+  iv: Vec<u8>,
+  additional_data: Option<Vec<u8>>,
+  data: &[u8],
+) -> Result<Vec<u8>, AnyError> {
+  let key = key.as_secret_key()?;
+  let additional_data = additional_data.unwrap_or_default();
+
+  // Fixed 96-bit nonce
+  if iv.len() != 12 {
+    return Err(type_error("iv length not equal to 12"));
+  }
+  let nonce = chacha20poly1305::Nonce::from_slice(&iv);
+
+  let cipher = ChaCha20Poly1305::new_from_slice(key)
+    .map_err(|_| operation_error("Encryption failed"))?;
+  let mut ciphertext = data.to_vec();
+  let tag = cipher
+    .encrypt_in_place_detached(nonce, &additional_data, &mut ciphertext)
+    .map_err(|_| operation_error("Encryption failed"))?;
+
+  // C | T
+  ciphertext.extend_from_slice(&tag);
+
+  Ok(ciphertext)
+}
+
 fn encrypt_aes_ctr_gen<B>(
   key: &[u8],
   counter: &[u8],
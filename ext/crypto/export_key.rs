@@ -246,6 +246,11 @@ fn export_key_ec(
 
           point.as_ref().to_vec()
         }
+        EcNamedCurve::Secp256k1 => {
+          let point = key_data.as_ec_public_key_k256()?;
+
+          point.as_ref().to_vec()
+        }
         EcNamedCurve::P521 => {
           return Err(data_error("Unsupported named curve"))
         }
@@ -264,6 +269,11 @@ fn export_key_ec(
 
           point.as_ref().to_vec()
         }
+        EcNamedCurve::Secp256k1 => {
+          let point = key_data.as_ec_public_key_k256()?;
+
+          point.as_ref().to_vec()
+        }
         EcNamedCurve::P521 => {
           return Err(data_error("Unsupported named curve"))
         }
@@ -278,6 +288,10 @@ fn export_key_ec(
           oid: elliptic_curve::ALGORITHM_OID,
           parameters: Some((&p384::NistP384::OID).into()),
         },
+        EcNamedCurve::Secp256k1 => AlgorithmIdentifier {
+          oid: elliptic_curve::ALGORITHM_OID,
+          parameters: Some((&k256::Secp256k1::OID).into()),
+        },
         EcNamedCurve::P521 => {
           return Err(data_error("Unsupported named curve"))
         }
@@ -344,6 +358,24 @@ fn export_key_ec(
           ))
         }
       }
+      EcNamedCurve::Secp256k1 => {
+        let point = key_data.as_ec_public_key_k256()?;
+        let coords = point.coordinates();
+
+        if let k256::elliptic_curve::sec1::Coordinates::Uncompressed { x, y } =
+          coords
+        {
+          Ok(ExportKeyResult::JwkPublicEc {
+            x: bytes_to_b64(x),
+            y: bytes_to_b64(y),
+          })
+        } else {
+          Err(custom_error(
+            "DOMExceptionOperationError",
+            "failed to decode public key",
+          ))
+        }
+      }
       EcNamedCurve::P521 => Err(data_error("Unsupported named curve")),
     },
     ExportKeyFormat::JwkPrivate => {
@@ -395,6 +427,29 @@ fn export_key_ec(
             Err(data_error("expected valid public EC key"))
           }
         }
+
+        EcNamedCurve::Secp256k1 => {
+          let ec_key =
+            k256::SecretKey::from_pkcs8_der(private_key).map_err(|_| {
+              custom_error(
+                "DOMExceptionOperationError",
+                "failed to decode private key",
+              )
+            })?;
+
+          let point = ec_key.public_key().to_encoded_point(false);
+          if let elliptic_curve::sec1::Coordinates::Uncompressed { x, y } =
+            point.coordinates()
+          {
+            Ok(ExportKeyResult::JwkPrivateEc {
+              x: bytes_to_b64(x),
+              y: bytes_to_b64(y),
+              d: bytes_to_b64(&ec_key.to_be_bytes()),
+            })
+          } else {
+            Err(data_error("expected valid public EC key"))
+          }
+        }
         _ => Err(not_supported_error("Unsupported namedCurve")),
       }
     }
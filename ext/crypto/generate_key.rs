@@ -5,6 +5,7 @@ use deno_core::op2;
 use deno_core::unsync::spawn_blocking;
 use deno_core::ToJsBuffer;
 use elliptic_curve::rand_core::OsRng;
+use k256::pkcs8::EncodePrivateKey;
 use num_traits::FromPrimitive;
 use once_cell::sync::Lazy;
 use ring::rand::SecureRandom;
@@ -85,6 +86,14 @@ fn generate_key_rsa(
 }
 
 fn generate_key_ec(named_curve: EcNamedCurve) -> Result<Vec<u8>, AnyError> {
+  if named_curve == EcNamedCurve::Secp256k1 {
+    // ring has no support for secp256k1.
+    let pkcs8 = k256::SecretKey::random(&mut OsRng)
+      .to_pkcs8_der()
+      .map_err(|_| operation_error("Failed to generate EC key"))?;
+    return Ok(pkcs8.as_bytes().to_vec());
+  }
+
   let curve = match named_curve {
     EcNamedCurve::P256 => &ring::signature::ECDSA_P256_SHA256_FIXED_SIGNING,
     EcNamedCurve::P384 => &ring::signature::ECDSA_P384_SHA384_FIXED_SIGNING,
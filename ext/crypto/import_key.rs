@@ -5,6 +5,8 @@ use deno_core::op2;
 use deno_core::JsBuffer;
 use deno_core::ToJsBuffer;
 use elliptic_curve::pkcs8::PrivateKeyInfo;
+use elliptic_curve::sec1::ToEncodedPoint;
+use p256::pkcs8::DecodePrivateKey;
 use p256::pkcs8::EncodePrivateKey;
 use ring::signature::EcdsaKeyPair;
 use rsa::pkcs1::UIntRef;
@@ -500,6 +502,12 @@ fn import_key_ec_jwk_to_point(
 
       p384::EncodedPoint::from_affine_coordinates(&x, &y, false).to_bytes()
     }
+    EcNamedCurve::Secp256k1 => {
+      let x = decode_b64url_to_field_bytes::<k256::Secp256k1>(&x)?;
+      let y = decode_b64url_to_field_bytes::<k256::Secp256k1>(&y)?;
+
+      k256::EncodedPoint::from_affine_coordinates(&x, &y, false).to_bytes()
+    }
     _ => return Err(not_supported_error("Unsupported named curve")),
   };
 
@@ -534,6 +542,22 @@ fn import_key_ec_jwk(
 
           pk.to_pkcs8_der()?
         }
+        EcNamedCurve::Secp256k1 => {
+          let d = decode_b64url_to_field_bytes::<k256::Secp256k1>(&d)?;
+          let pk = k256::SecretKey::from_be_bytes(&d)?;
+          // ring can't validate secp256k1 keys, compare the public point
+          // derived from `d` with the given one instead.
+          let public_key = pk.public_key().to_encoded_point(false);
+          if public_key.as_bytes() != point_bytes.as_slice() {
+            return Err(data_error("invalid JWK private key"));
+          }
+
+          return Ok(ImportKeyResult::Ec {
+            raw_data: RustRawKeyData::Private(
+              pk.to_pkcs8_der()?.as_bytes().to_vec().into(),
+            ),
+          });
+        }
         EcNamedCurve::P521 => {
           return Err(data_error("Unsupported named curve"))
         }
@@ -543,7 +567,7 @@ fn import_key_ec_jwk(
       let key_alg = match named_curve {
         EcNamedCurve::P256 => CryptoNamedCurve::P256.try_into()?,
         EcNamedCurve::P384 => CryptoNamedCurve::P256.try_into()?,
-        EcNamedCurve::P521 => {
+        EcNamedCurve::P521 | EcNamedCurve::Secp256k1 => {
           return Err(data_error("Unsupported named curve"))
         }
       };
@@ -605,6 +629,16 @@ fn import_key_ec(
             return Err(data_error("invalid P-384 elliptic curve point"));
           }
         }
+        EcNamedCurve::Secp256k1 => {
+          // 1-2.
+          let point = k256::EncodedPoint::from_bytes(&data).map_err(|_| {
+            data_error("invalid secp256k1 elliptic curve point")
+          })?;
+          // 3.
+          if point.is_identity() {
+            return Err(data_error("invalid secp256k1 elliptic curve point"));
+          }
+        }
         _ => return Err(not_supported_error("Unsupported named curve")),
       };
       Ok(ImportKeyResult::Ec {
@@ -615,7 +649,7 @@ fn import_key_ec(
       // 2-7
       // Deserialize PKCS8 - validate structure, extracts named_curve
       let named_curve_alg = match named_curve {
-        EcNamedCurve::P256 | EcNamedCurve::P384 => {
+        EcNamedCurve::P256 | EcNamedCurve::P384 | EcNamedCurve::Secp256k1 => {
           let pk = PrivateKeyInfo::from_der(data.as_ref())
             .map_err(|_| data_error("expected valid PKCS#8 data"))?;
           pk.algorithm
@@ -637,21 +671,32 @@ fn import_key_ec(
         ID_SECP384R1_OID => Some(EcNamedCurve::P384),
         // id-secp521r1
         ID_SECP521R1_OID => Some(EcNamedCurve::P521),
+        // secp256k1
+        ID_SECP256K1_OID => Some(EcNamedCurve::Secp256k1),
         _ => None,
       };
 
       // 10.
       if let Some(pk_named_curve) = pk_named_curve {
-        let signing_alg = match pk_named_curve {
-          EcNamedCurve::P256 => CryptoNamedCurve::P256.try_into()?,
-          EcNamedCurve::P384 => CryptoNamedCurve::P384.try_into()?,
+        match pk_named_curve {
+          EcNamedCurve::P256 | EcNamedCurve::P384 => {
+            let signing_alg = match pk_named_curve {
+              EcNamedCurve::P256 => CryptoNamedCurve::P256,
+              _ => CryptoNamedCurve::P384,
+            }
+            .try_into()?;
+
+            // deserialize pkcs8 using ring crate, to VALIDATE public key
+            let _private_key = EcdsaKeyPair::from_pkcs8(signing_alg, &data)?;
+          }
+          EcNamedCurve::Secp256k1 => {
+            k256::SecretKey::from_pkcs8_der(&data)
+              .map_err(|_| data_error("invalid secp256k1 private key"))?;
+          }
           EcNamedCurve::P521 => {
             return Err(data_error("Unsupported named curve"))
           }
-        };
-
-        // deserialize pkcs8 using ring crate, to VALIDATE public key
-        let _private_key = EcdsaKeyPair::from_pkcs8(signing_alg, &data)?;
+        }
 
         // 11.
         if named_curve != pk_named_curve {
@@ -695,6 +740,8 @@ fn import_key_ec(
         ID_SECP384R1_OID => Some(EcNamedCurve::P384),
         // id-secp521r1
         ID_SECP521R1_OID => Some(EcNamedCurve::P521),
+        // secp256k1
+        ID_SECP256K1_OID => Some(EcNamedCurve::Secp256k1),
         _ => None,
       };
 
@@ -730,6 +777,18 @@ fn import_key_ec(
 
             point.as_bytes().len()
           }
+          EcNamedCurve::Secp256k1 => {
+            let point =
+              k256::EncodedPoint::from_bytes(&*encoded_key).map_err(|_| {
+                data_error("invalid secp256k1 elliptic curve SPKI data")
+              })?;
+
+            if point.is_identity() {
+              return Err(data_error("invalid secp256k1 elliptic curve point"));
+            }
+
+            point.as_bytes().len()
+          }
           _ => return Err(not_supported_error("Unsupported named curve")),
         };
 
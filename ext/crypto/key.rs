@@ -1,5 +1,6 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use deno_core::error::AnyError;
 use ring::agreement::Algorithm as RingAlgorithm;
 use ring::digest;
 use ring::hkdf;
@@ -9,6 +10,8 @@ use ring::signature::EcdsaVerificationAlgorithm;
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::shared::not_supported_error;
+
 #[derive(Serialize, Deserialize, Copy, Clone)]
 #[serde(rename_all = "camelCase")]
 pub enum KeyType {
@@ -35,35 +38,59 @@ pub enum CryptoNamedCurve {
   P256,
   #[serde(rename = "P-384")]
   P384,
+  #[serde(rename = "secp256k1")]
+  Secp256k1,
 }
 
-impl From<CryptoNamedCurve> for &RingAlgorithm {
-  fn from(curve: CryptoNamedCurve) -> &'static RingAlgorithm {
+// ring has no support for secp256k1, it goes through the k256 crate instead.
+impl TryFrom<CryptoNamedCurve> for &RingAlgorithm {
+  type Error = AnyError;
+
+  fn try_from(
+    curve: CryptoNamedCurve,
+  ) -> Result<&'static RingAlgorithm, AnyError> {
     match curve {
-      CryptoNamedCurve::P256 => &ring::agreement::ECDH_P256,
-      CryptoNamedCurve::P384 => &ring::agreement::ECDH_P384,
+      CryptoNamedCurve::P256 => Ok(&ring::agreement::ECDH_P256),
+      CryptoNamedCurve::P384 => Ok(&ring::agreement::ECDH_P384),
+      CryptoNamedCurve::Secp256k1 => {
+        Err(not_supported_error("Unsupported named curve"))
+      }
     }
   }
 }
 
-impl From<CryptoNamedCurve> for &EcdsaSigningAlgorithm {
-  fn from(curve: CryptoNamedCurve) -> &'static EcdsaSigningAlgorithm {
+impl TryFrom<CryptoNamedCurve> for &EcdsaSigningAlgorithm {
+  type Error = AnyError;
+
+  fn try_from(
+    curve: CryptoNamedCurve,
+  ) -> Result<&'static EcdsaSigningAlgorithm, AnyError> {
     match curve {
       CryptoNamedCurve::P256 => {
-        &ring::signature::ECDSA_P256_SHA256_FIXED_SIGNING
+        Ok(&ring::signature::ECDSA_P256_SHA256_FIXED_SIGNING)
       }
       CryptoNamedCurve::P384 => {
-        &ring::signature::ECDSA_P384_SHA384_FIXED_SIGNING
+        Ok(&ring::signature::ECDSA_P384_SHA384_FIXED_SIGNING)
+      }
+      CryptoNamedCurve::Secp256k1 => {
+        Err(not_supported_error("Unsupported named curve"))
       }
     }
   }
 }
 
-impl From<CryptoNamedCurve> for &EcdsaVerificationAlgorithm {
-  fn from(curve: CryptoNamedCurve) -> &'static EcdsaVerificationAlgorithm {
+impl TryFrom<CryptoNamedCurve> for &EcdsaVerificationAlgorithm {
+  type Error = AnyError;
+
+  fn try_from(
+    curve: CryptoNamedCurve,
+  ) -> Result<&'static EcdsaVerificationAlgorithm, AnyError> {
     match curve {
-      CryptoNamedCurve::P256 => &ring::signature::ECDSA_P256_SHA256_FIXED,
-      CryptoNamedCurve::P384 => &ring::signature::ECDSA_P384_SHA384_FIXED,
+      CryptoNamedCurve::P256 => Ok(&ring::signature::ECDSA_P256_SHA256_FIXED),
+      CryptoNamedCurve::P384 => Ok(&ring::signature::ECDSA_P384_SHA384_FIXED),
+      CryptoNamedCurve::Secp256k1 => {
+        Err(not_supported_error("Unsupported named curve"))
+      }
     }
   }
 }
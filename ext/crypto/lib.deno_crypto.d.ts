@@ -80,6 +80,17 @@ declare interface AesGcmParams extends Algorithm {
   tagLength?: number;
 }
 
+/** Parameters of ChaCha20-Poly1305 encryption and decryption. The `iv` is
+ * the 96-bit nonce and the tag is always 128 bits long.
+ *
+ * @category Web Crypto API
+ */
+declare interface AeadParams extends Algorithm {
+  iv: BufferSource;
+  additionalData?: BufferSource;
+  tagLength?: number;
+}
+
 /** @category Web Crypto API */
 declare interface AesCtrParams extends Algorithm {
   counter: BufferSource;
@@ -299,7 +310,8 @@ declare interface SubtleCrypto {
       | RsaOaepParams
       | AesCbcParams
       | AesGcmParams
-      | AesCtrParams,
+      | AesCtrParams
+      | AeadParams,
     key: CryptoKey,
     data: BufferSource,
   ): Promise<ArrayBuffer>;
@@ -309,7 +321,8 @@ declare interface SubtleCrypto {
       | RsaOaepParams
       | AesCbcParams
       | AesGcmParams
-      | AesCtrParams,
+      | AesCtrParams
+      | AeadParams,
     key: CryptoKey,
     data: BufferSource,
   ): Promise<ArrayBuffer>;
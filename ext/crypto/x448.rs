@@ -0,0 +1,142 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::error::AnyError;
+use deno_core::op2;
+use deno_core::ToJsBuffer;
+use elliptic_curve::pkcs8::PrivateKeyInfo;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use spki::der::Decode;
+use spki::der::Encode;
+use x448::PublicKey;
+use x448::Secret;
+
+const X448_KEY_LEN: usize = 56;
+
+#[op2(fast)]
+pub fn op_crypto_generate_x448_keypair(
+  #[buffer] pkey: &mut [u8],
+  #[buffer] pubkey: &mut [u8],
+) {
+  let mut rng = OsRng;
+  rng.fill_bytes(pkey);
+  // https://www.rfc-editor.org/rfc/rfc7748#section-6.2
+  // pubkey = x448(a, 5), the secret is clamped as part of the ladder.
+  let secret = Secret::from_bytes(pkey).expect("Expected byteLength 56");
+  pubkey.copy_from_slice(PublicKey::from(&secret).as_bytes());
+}
+
+#[op2(fast)]
+pub fn op_crypto_derive_bits_x448(
+  #[buffer] k: &[u8],
+  #[buffer] u: &[u8],
+  #[buffer] secret: &mut [u8],
+) -> bool {
+  let Some(k) = Secret::from_bytes(k) else {
+    return false;
+  };
+  // Low order points are rejected here, and an all-zero shared secret by
+  // `as_diffie_hellman`.
+  let Some(u) = PublicKey::from_bytes(u) else {
+    return false;
+  };
+  let Some(sh_sec) = k.as_diffie_hellman(&u) else {
+    return false;
+  };
+  secret.copy_from_slice(sh_sec.as_bytes());
+  true
+}
+
+// id-X448 OBJECT IDENTIFIER ::= { 1 3 101 111 }
+pub const X448_OID: const_oid::ObjectIdentifier =
+  const_oid::ObjectIdentifier::new_unwrap("1.3.101.111");
+
+#[op2(fast)]
+pub fn op_crypto_import_spki_x448(
+  #[buffer] key_data: &[u8],
+  #[buffer] out: &mut [u8],
+) -> bool {
+  // 2-3.
+  let pk_info = match spki::SubjectPublicKeyInfo::from_der(key_data) {
+    Ok(pk_info) => pk_info,
+    Err(_) => return false,
+  };
+  // 4.
+  let alg = pk_info.algorithm.oid;
+  if alg != X448_OID {
+    return false;
+  }
+  // 5.
+  if pk_info.algorithm.parameters.is_some() {
+    return false;
+  }
+  if pk_info.subject_public_key.len() != X448_KEY_LEN {
+    return false;
+  }
+  out.copy_from_slice(pk_info.subject_public_key);
+  true
+}
+
+#[op2(fast)]
+pub fn op_crypto_import_pkcs8_x448(
+  #[buffer] key_data: &[u8],
+  #[buffer] out: &mut [u8],
+) -> bool {
+  // 2-3.
+  // This should probably use OneAsymmetricKey instead
+  let pk_info = match PrivateKeyInfo::from_der(key_data) {
+    Ok(pk_info) => pk_info,
+    Err(_) => return false,
+  };
+  // 4.
+  let alg = pk_info.algorithm.oid;
+  if alg != X448_OID {
+    return false;
+  }
+  // 5.
+  if pk_info.algorithm.parameters.is_some() {
+    return false;
+  }
+  // 6.
+  // CurvePrivateKey ::= OCTET STRING
+  if pk_info.private_key.len() != X448_KEY_LEN + 2 {
+    return false;
+  }
+  out.copy_from_slice(&pk_info.private_key[2..]);
+  true
+}
+
+#[op2]
+#[serde]
+pub fn op_crypto_export_spki_x448(
+  #[buffer] pubkey: &[u8],
+) -> Result<ToJsBuffer, AnyError> {
+  let key_info = spki::SubjectPublicKeyInfo {
+    algorithm: spki::AlgorithmIdentifier {
+      // id-X448
+      oid: X448_OID,
+      parameters: None,
+    },
+    subject_public_key: pubkey,
+  };
+  Ok(key_info.to_vec()?.into())
+}
+
+#[op2]
+#[serde]
+pub fn op_crypto_export_pkcs8_x448(
+  #[buffer] pkey: &[u8],
+) -> Result<ToJsBuffer, AnyError> {
+  // This should probably use OneAsymmetricKey instead
+  let pk_info = rsa::pkcs8::PrivateKeyInfo {
+    public_key: None,
+    algorithm: rsa::pkcs8::AlgorithmIdentifier {
+      // id-X448
+      oid: X448_OID,
+      parameters: None,
+    },
+    private_key: pkey, // OCTET STRING
+  };
+
+  Ok(pk_info.to_vec()?.into())
+}
@@ -12,6 +12,23 @@
 
 const core = globalThis.Deno.core;
 const ops = core.ops;
+const primordials = globalThis.__bootstrap.primordials;
+const {
+  ArrayIsArray,
+  ArrayPrototypeEvery,
+  NumberIsInteger,
+  TypeError,
+} = primordials;
+
+/**
+ * @param {unknown} value
+ * @param {string} name
+ */
+function validateNonNegativeInteger(value, name) {
+  if (value !== undefined && (!NumberIsInteger(value) || value < 0)) {
+    throw new TypeError(`${name} must be a non-negative integer.`);
+  }
+}
 
 /**
  * @param {Deno.CreateHttpClientOptions} options
@@ -19,19 +36,89 @@ const ops = core.ops;
  */
 function createHttpClient(options) {
   options.caCerts ??= [];
+  validateNonNegativeInteger(options.connectTimeout, "connectTimeout");
+  validateNonNegativeInteger(options.timeout, "timeout");
+  validateNonNegativeInteger(options.maxRedirects, "maxRedirects");
+  if (
+    options.unixSocket !== undefined && typeof options.unixSocket !== "string"
+  ) {
+    throw new TypeError("unixSocket must be a string.");
+  }
+  const retry = options.retry;
+  if (retry !== undefined) {
+    if (typeof retry !== "object" || retry === null) {
+      throw new TypeError("retry must be an object.");
+    }
+    validateNonNegativeInteger(retry.maxRetries, "retry.maxRetries");
+    if (retry.maxRetries === undefined) {
+      throw new TypeError("retry.maxRetries must be a non-negative integer.");
+    }
+    validateNonNegativeInteger(retry.initialDelay, "retry.initialDelay");
+    validateNonNegativeInteger(retry.maxDelay, "retry.maxDelay");
+    if (
+      retry.statusCodes !== undefined &&
+      (!ArrayIsArray(retry.statusCodes) ||
+        !ArrayPrototypeEvery(
+          retry.statusCodes,
+          (code) => NumberIsInteger(code) && code >= 100 && code <= 599,
+        ))
+    ) {
+      throw new TypeError("retry.statusCodes must be an array of status codes.");
+    }
+  }
+  if (
+    options.resolver !== undefined &&
+    typeof options.resolver?.rid !== "number"
+  ) {
+    throw new TypeError("resolver must be a Deno.DnsResolver.");
+  }
+  const cache = httpCacheOptions(options.cache);
   return new HttpClient(
-    ops.op_fetch_custom_client(
-      options,
-    ),
+    ops.op_fetch_custom_client({
+      ...options,
+      resolverRid: options.resolver?.rid,
+    }),
+    options.maxRedirects ?? 20,
+    cache,
   );
 }
 
+/**
+ * @param {boolean | Deno.HttpCacheOptions | undefined} cache
+ * @returns {{ name: string, shared: boolean } | null}
+ */
+function httpCacheOptions(cache) {
+  if (cache === undefined || cache === false) {
+    return null;
+  }
+  if (cache === true) {
+    cache = {};
+  }
+  if (typeof cache !== "object" || cache === null) {
+    throw new TypeError("cache must be a boolean or an object.");
+  }
+  if (cache.name !== undefined && typeof cache.name !== "string") {
+    throw new TypeError("cache.name must be a string.");
+  }
+  if (cache.shared !== undefined && typeof cache.shared !== "boolean") {
+    throw new TypeError("cache.shared must be a boolean.");
+  }
+  return {
+    name: cache.name ?? "deno-http-cache",
+    shared: cache.shared ?? false,
+  };
+}
+
 class HttpClient {
   /**
    * @param {number} rid
+   * @param {number} maxRedirects
+   * @param {{ name: string, shared: boolean } | null} cache
    */
-  constructor(rid) {
+  constructor(rid, maxRedirects = 20, cache = null) {
     this.rid = rid;
+    this.maxRedirects = maxRedirects;
+    this.cache = cache;
   }
   close() {
     core.close(this.rid);
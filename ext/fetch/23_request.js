@@ -79,6 +79,8 @@ function processUrlList(urlList, urlListProcessed) {
  * @property {(() => string)[]} urlList
  * @property {string[]} urlListProcessed
  * @property {number | null} clientRid NOTE: non standard extension for `Deno.HttpClient`.
+ * @property {number} maxRedirects NOTE: non standard extension for `Deno.HttpClient`.
+ * @property {{ name: string, shared: boolean } | null} httpCache NOTE: non standard extension for `Deno.HttpClient`.
  * @property {Blob | null} blobUrlEntry
  */
 
@@ -127,6 +129,8 @@ function newInnerRequest(method, url, headerList, body, maybeBlob) {
     urlList: [typeof url === "string" ? () => url : url],
     urlListProcessed: [],
     clientRid: null,
+    maxRedirects: 20,
+    httpCache: null,
     blobUrlEntry,
     url() {
       if (this.urlListProcessed[0] === undefined) {
@@ -178,6 +182,8 @@ function cloneInnerRequest(request, skipBody = false) {
     urlList: [() => request.url()],
     urlListProcessed: [request.url()],
     clientRid: request.clientRid,
+    maxRedirects: request.maxRedirects,
+    httpCache: request.httpCache,
     blobUrlEntry: request.blobUrlEntry,
     url() {
       if (this.urlListProcessed[0] === undefined) {
@@ -354,6 +360,8 @@ class Request {
         );
       }
       request.clientRid = init.client?.rid ?? null;
+      request.maxRedirects = init.client?.maxRedirects ?? 20;
+      request.httpCache = init.client?.cache ?? null;
     }
 
     // 27.
@@ -0,0 +1,91 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+// @ts-check
+/// <reference path="../../core/lib.deno_core.d.ts" />
+/// <reference path="./internal.d.ts" />
+
+// Carries the `traceparent` of the `Deno.serve` request being handled across
+// `await`s, so that `fetch` calls made while handling it continue its trace.
+// The promise hooks doing so are only installed once a traced request is
+// handled, so this costs nothing unless `--otel-export` is used.
+
+const core = globalThis.Deno.core;
+const ops = core.ops;
+const primordials = globalThis.__bootstrap.primordials;
+const {
+  ArrayPrototypePop,
+  ArrayPrototypePush,
+  FunctionPrototypeCall,
+  Symbol,
+} = primordials;
+
+const _traceparent = Symbol("[[traceparent]]");
+
+/** @type {string | null} */
+let currentTraceparent = null;
+/** @type {(string | null)[]} */
+const traceparentStack = [];
+/** @type {boolean | undefined} */
+let tracingEnabled;
+let hooksInstalled = false;
+
+/**
+ * @returns {boolean}
+ */
+function isTracingEnabled() {
+  if (tracingEnabled === undefined) {
+    tracingEnabled = ops.op_fetch_tracing_enabled();
+  }
+  return tracingEnabled;
+}
+
+function installHooks() {
+  hooksInstalled = true;
+  core.setPromiseHooks(
+    (promise) => {
+      if (currentTraceparent !== null) {
+        promise[_traceparent] = currentTraceparent;
+      }
+    },
+    (promise) => {
+      ArrayPrototypePush(traceparentStack, currentTraceparent);
+      currentTraceparent = promise[_traceparent] ?? null;
+    },
+    () => {
+      currentTraceparent = ArrayPrototypePop(traceparentStack) ?? null;
+    },
+    undefined,
+  );
+}
+
+/**
+ * Calls `fn` with `arg`, with `traceparent` as the trace context of
+ * everything it does, including its promise continuations.
+ *
+ * @template T, R
+ * @param {string} traceparent
+ * @param {(arg: T) => R} fn
+ * @param {T} arg
+ * @returns {R}
+ */
+function runWithTraceparent(traceparent, fn, arg) {
+  if (!hooksInstalled) {
+    installHooks();
+  }
+  const previous = currentTraceparent;
+  currentTraceparent = traceparent;
+  try {
+    return FunctionPrototypeCall(fn, undefined, arg);
+  } finally {
+    currentTraceparent = previous;
+  }
+}
+
+/**
+ * @returns {string | null}
+ */
+function getTraceparent() {
+  return currentTraceparent;
+}
+
+export { getTraceparent, isTracingEnabled, runWithTraceparent };
@@ -31,6 +31,7 @@ import {
   toInnerResponse,
 } from "ext:deno_fetch/23_response.js";
 import * as abortSignal from "ext:deno_web/03_abort_signal.js";
+import { getTraceparent } from "ext:deno_fetch/25_trace_context.js";
 const primordials = globalThis.__bootstrap.primordials;
 const {
   ArrayPrototypePush,
@@ -78,6 +79,7 @@ function opFetch(method, url, headers, clientRid, hasBody, bodyLength, body) {
     hasBody,
     bodyLength,
     body,
+    getTraceparent(),
   );
 }
 
@@ -108,6 +110,18 @@ function createResponseBodyStream(responseBodyRid, terminator) {
   return readable;
 }
 
+/** @type {((req: InnerRequest, networkFetch: (req: InnerRequest) => Promise<InnerResponse>) => Promise<InnerResponse>) | null} */
+let httpCacheHandler = null;
+
+/**
+ * Installed by `ext:deno_cache` to serve requests made through a
+ * `Deno.HttpClient` created with the `cache` option.
+ * @param {(req: InnerRequest, networkFetch: (req: InnerRequest) => Promise<InnerResponse>) => Promise<InnerResponse>} handler
+ */
+function setHttpCacheHandler(handler) {
+  httpCacheHandler = handler;
+}
+
 /**
  * @param {InnerRequest} req
  * @param {boolean} recursive
@@ -143,6 +157,22 @@ async function mainFetch(req, recursive, terminator) {
     };
   }
 
+  if (req.httpCache !== null && httpCacheHandler !== null) {
+    return httpCacheHandler(
+      req,
+      (req) => httpNetworkFetch(req, recursive, terminator),
+    );
+  }
+  return httpNetworkFetch(req, recursive, terminator);
+}
+
+/**
+ * @param {InnerRequest} req
+ * @param {boolean} recursive
+ * @param {AbortSignal} terminator
+ * @returns {Promise<InnerResponse>}
+ */
+async function httpNetworkFetch(req, recursive, terminator) {
   /** @type {ReadableStream<Uint8Array> | Uint8Array | null} */
   let reqBody = null;
 
@@ -372,8 +402,10 @@ function httpRedirectFetch(request, response, terminator) {
   if (locationURL.protocol !== "https:" && locationURL.protocol !== "http:") {
     return networkError("Can not redirect to a non HTTP(s) url");
   }
-  if (request.redirectCount === 20) {
-    return networkError("Maximum number of redirects (20) reached");
+  if (request.redirectCount >= request.maxRedirects) {
+    return networkError(
+      `Maximum number of redirects (${request.maxRedirects}) reached`,
+    );
   }
   request.redirectCount++;
   if (
@@ -594,4 +626,4 @@ function handleWasmStreaming(source, rid) {
   }
 }
 
-export { fetch, handleWasmStreaming };
+export { fetch, handleWasmStreaming, setHttpCacheHandler };
@@ -0,0 +1,43 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::net::SocketAddr;
+
+use reqwest::dns::Addrs;
+use reqwest::dns::Name;
+use reqwest::dns::Resolve;
+use reqwest::dns::Resolving;
+
+/// Resolves the host names of an HTTP client with a `Deno.DnsResolver`
+/// instead of the system resolver, sharing its name servers and cache.
+#[derive(Clone)]
+pub struct DnsResolver(deno_net::dns::Resolver);
+
+impl DnsResolver {
+  pub fn new(resolver: deno_net::dns::Resolver) -> Self {
+    Self(resolver)
+  }
+}
+
+impl std::fmt::Debug for DnsResolver {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("DnsResolver").finish_non_exhaustive()
+  }
+}
+
+impl Resolve for DnsResolver {
+  fn resolve(&self, name: Name) -> Resolving {
+    let resolver = self.0.clone();
+    Box::pin(async move {
+      let lookup = resolver.lookup_ip(name.as_str()).await?;
+      // hyper replaces the port with the one of the request URL
+      let addrs: Addrs = Box::new(
+        lookup
+          .into_iter()
+          .map(|ip| SocketAddr::new(ip, 0))
+          .collect::<Vec<_>>()
+          .into_iter(),
+      );
+      Ok(addrs)
+    })
+  }
+}
@@ -1,6 +1,9 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+mod dns;
 mod fs_fetch_handler;
+mod local_socket;
+mod retry;
 
 use std::borrow::Cow;
 use std::cell::RefCell;
@@ -11,6 +14,7 @@ use std::path::PathBuf;
 use std::pin::Pin;
 use std::rc::Rc;
 use std::sync::Arc;
+use std::time::Duration;
 
 use deno_core::anyhow::Error;
 use deno_core::error::type_error;
@@ -38,8 +42,13 @@ use deno_core::OpState;
 use deno_core::RcRef;
 use deno_core::Resource;
 use deno_core::ResourceId;
+use deno_telemetry::Span;
+use deno_telemetry::SpanContext;
+use deno_telemetry::SpanKind;
+use deno_telemetry::TRACEPARENT;
 use deno_tls::rustls::RootCertStore;
 use deno_tls::Proxy;
+use deno_tls::ProxyServer;
 use deno_tls::RootCertStoreProvider;
 
 use data_url::DataUrl;
@@ -68,7 +77,13 @@ use tokio::sync::mpsc;
 pub use data_url;
 pub use reqwest;
 
+pub use dns::DnsResolver;
 pub use fs_fetch_handler::FsFetchHandler;
+pub use retry::RetryPolicy;
+
+use crate::local_socket::LocalSocketClient;
+use crate::retry::is_idempotent;
+use crate::retry::send_with_retry;
 
 #[derive(Clone)]
 pub struct Options {
@@ -113,6 +128,7 @@ deno_core::extension!(deno_fetch,
     op_fetch_send,
     op_fetch_response_upgrade,
     op_fetch_custom_client<FP>,
+    op_fetch_tracing_enabled,
   ],
   esm = [
     "20_headers.js",
@@ -121,6 +137,7 @@ deno_core::extension!(deno_fetch,
     "22_http_client.js",
     "23_request.js",
     "23_response.js",
+    "25_trace_context.js",
     "26_fetch.js"
   ],
   options = {
@@ -173,6 +190,7 @@ pub trait FetchPermissions {
     api_name: &str,
   ) -> Result<(), AnyError>;
   fn check_read(&mut self, _p: &Path, api_name: &str) -> Result<(), AnyError>;
+  fn check_write(&mut self, _p: &Path, api_name: &str) -> Result<(), AnyError>;
 }
 
 pub fn get_declaration() -> PathBuf {
@@ -207,6 +225,9 @@ pub fn get_or_create_client_from_state(
         pool_idle_timeout: None,
         http1: true,
         http2: true,
+        connect_timeout: None,
+        timeout: None,
+        dns_resolver: None,
       },
     )?;
     state.put::<reqwest::Client>(client.clone());
@@ -214,6 +235,11 @@ pub fn get_or_create_client_from_state(
   }
 }
 
+#[op2(fast)]
+pub fn op_fetch_tracing_enabled() -> bool {
+  deno_telemetry::is_enabled()
+}
+
 #[op2]
 #[serde]
 #[allow(clippy::too_many_arguments)]
@@ -226,15 +252,22 @@ pub fn op_fetch<FP>(
   has_body: bool,
   #[number] body_length: Option<u64>,
   #[buffer] data: Option<JsBuffer>,
+  #[string] traceparent: Option<String>,
 ) -> Result<FetchReturn, AnyError>
 where
   FP: FetchPermissions + 'static,
 {
-  let (client, allow_host) = if let Some(rid) = client_rid {
+  let (client, allow_host, retry, local_socket) = if let Some(rid) = client_rid
+  {
     let r = state.resource_table.get::<HttpClientResource>(rid)?;
-    (r.client.clone(), r.allow_host)
+    (
+      r.client.clone(),
+      r.allow_host,
+      r.retry.clone(),
+      r.local_socket.clone(),
+    )
   } else {
-    (get_or_create_client_from_state(state)?, false)
+    (get_or_create_client_from_state(state)?, false, None, None)
   };
 
   let method = Method::from_bytes(&method)?;
@@ -269,8 +302,12 @@ where
       (request_rid, None, maybe_cancel_handle_rid)
     }
     "http" | "https" => {
-      let permissions = state.borrow_mut::<FP>();
-      permissions.check_net_url(&url, "fetch()")?;
+      // Requests over a Unix socket never reach the network, access to the
+      // socket was checked when the client was created.
+      if local_socket.is_none() {
+        let permissions = state.borrow_mut::<FP>();
+        permissions.check_net_url(&url, "fetch()")?;
+      }
 
       // Make sure that we have a valid URI early, as reqwest's `RequestBuilder::send`
       // internally uses `expect_uri`, which panics instead of returning a usable `Result`.
@@ -278,6 +315,24 @@ where
         return Err(type_error("Invalid URL"));
       }
 
+      // A `traceparent` set by the caller takes precedence over the trace of
+      // the request handler making this request.
+      let parent_span = headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(TRACEPARENT.as_bytes()))
+        .map(|(_, value)| &value[..])
+        .or(traceparent.as_ref().map(|value| value.as_bytes()))
+        .and_then(SpanContext::from_traceparent);
+      let mut span =
+        Span::start(method.as_str(), SpanKind::Client, parent_span);
+      if let Some(span) = &mut span {
+        span.set_attribute("http.request.method", method.as_str());
+        span.set_attribute("url.full", url.as_str());
+        if let Some(host) = url.host_str() {
+          span.set_attribute("server.address", host);
+        }
+      }
+
       let mut request = client.request(method.clone(), url);
 
       let request_body_rid = if has_body {
@@ -336,6 +391,14 @@ where
         header_map
           .insert(ACCEPT_ENCODING, HeaderValue::from_static("identity"));
       }
+      if let Some(span) = &span {
+        // Replaces a `traceparent` set by the caller, which is now the parent
+        // of this span.
+        header_map.insert(
+          TRACEPARENT,
+          HeaderValue::from_str(&span.context().to_traceparent()).unwrap(),
+        );
+      }
       request = request.headers(header_map);
 
       let options = state.borrow::<Options>();
@@ -346,13 +409,27 @@ where
 
       let cancel_handle = CancelHandle::new_rc();
       let cancel_handle_ = cancel_handle.clone();
+      let retry = retry.filter(|_| is_idempotent(&method));
 
       let fut = async move {
-        request
-          .send()
+        let res = send_with_retry(request, retry, local_socket)
           .or_cancel(cancel_handle_)
           .await
-          .map(|res| res.map_err(|err| type_error(err.to_string())))
+          .map(|res| res.map_err(|err| type_error(err.to_string())));
+        if let Some(mut span) = span {
+          match &res {
+            Ok(Ok(res)) => {
+              let status = res.status();
+              span.set_attribute("http.response.status_code", status.as_u16());
+              if status.is_server_error() {
+                span.set_error(status.to_string());
+              }
+            }
+            Ok(Err(err)) => span.set_error(err.to_string()),
+            Err(_) => span.set_error("request was cancelled"),
+          }
+        }
+        res
       };
 
       let request_rid = state
@@ -773,6 +850,8 @@ impl Resource for FetchResponseResource {
 pub struct HttpClientResource {
   pub client: Client,
   pub allow_host: bool,
+  pub retry: Option<RetryPolicy>,
+  pub local_socket: Option<LocalSocketClient>,
 }
 
 impl Resource for HttpClientResource {
@@ -782,8 +861,18 @@ impl Resource for HttpClientResource {
 }
 
 impl HttpClientResource {
-  fn new(client: Client, allow_host: bool) -> Self {
-    Self { client, allow_host }
+  fn new(
+    client: Client,
+    allow_host: bool,
+    retry: Option<RetryPolicy>,
+    local_socket: Option<LocalSocketClient>,
+  ) -> Self {
+    Self {
+      client,
+      allow_host,
+      retry,
+      local_socket,
+    }
   }
 }
 
@@ -809,6 +898,11 @@ pub struct CreateHttpClientArgs {
   http2: bool,
   #[serde(default)]
   allow_host: bool,
+  connect_timeout: Option<u64>,
+  timeout: Option<u64>,
+  retry: Option<RetryPolicy>,
+  unix_socket: Option<PathBuf>,
+  resolver_rid: Option<ResourceId>,
 }
 
 fn default_true() -> bool {
@@ -824,10 +918,18 @@ pub fn op_fetch_custom_client<FP>(
 where
   FP: FetchPermissions + 'static,
 {
-  if let Some(proxy) = args.proxy.clone() {
+  if let Some(proxy) = &args.proxy {
     let permissions = state.borrow_mut::<FP>();
-    let url = Url::parse(&proxy.url)?;
-    permissions.check_net_url(&url, "Deno.createHttpClient()")?;
+    for (_, server) in proxy.servers() {
+      let url = Url::parse(server.url)?;
+      permissions.check_net_url(&url, "Deno.createHttpClient()")?;
+    }
+  }
+
+  if let Some(path) = &args.unix_socket {
+    let permissions = state.borrow_mut::<FP>();
+    permissions.check_read(path, "Deno.createHttpClient()")?;
+    permissions.check_write(path, "Deno.createHttpClient()")?;
   }
 
   let client_cert_chain_and_key = {
@@ -845,6 +947,16 @@ where
     }
   };
 
+  let dns_resolver = args
+    .resolver_rid
+    .map(|rid| {
+      state
+        .resource_table
+        .get::<deno_net::dns::DnsResolverResource>(rid)
+        .map(|resource| DnsResolver::new(resource.resolver().clone()))
+    })
+    .transpose()?;
+
   let options = state.borrow::<Options>();
   let ca_certs = args
     .ca_certs
@@ -852,32 +964,43 @@ where
     .map(|cert| cert.into_bytes())
     .collect::<Vec<_>>();
 
-  let client = create_http_client(
-    &options.user_agent,
-    CreateHttpClientOptions {
-      root_cert_store: options.root_cert_store()?,
-      ca_certs,
-      proxy: args.proxy,
-      unsafely_ignore_certificate_errors: options
-        .unsafely_ignore_certificate_errors
-        .clone(),
-      client_cert_chain_and_key,
-      pool_max_idle_per_host: args.pool_max_idle_per_host,
-      pool_idle_timeout: args.pool_idle_timeout.and_then(
-        |timeout| match timeout {
-          PoolIdleTimeout::State(true) => None,
-          PoolIdleTimeout::State(false) => Some(None),
-          PoolIdleTimeout::Specify(specify) => Some(Some(specify)),
-        },
-      ),
-      http1: args.http1,
-      http2: args.http2,
-    },
-  )?;
+  let client_options = CreateHttpClientOptions {
+    root_cert_store: options.root_cert_store()?,
+    ca_certs,
+    proxy: args.proxy,
+    unsafely_ignore_certificate_errors: options
+      .unsafely_ignore_certificate_errors
+      .clone(),
+    client_cert_chain_and_key,
+    pool_max_idle_per_host: args.pool_max_idle_per_host,
+    pool_idle_timeout: args.pool_idle_timeout.and_then(
+      |timeout| match timeout {
+        PoolIdleTimeout::State(true) => None,
+        PoolIdleTimeout::State(false) => Some(None),
+        PoolIdleTimeout::Specify(specify) => Some(Some(specify)),
+      },
+    ),
+    http1: args.http1,
+    http2: args.http2,
+    connect_timeout: args.connect_timeout.map(Duration::from_millis),
+    timeout: args.timeout.map(Duration::from_millis),
+    dns_resolver,
+  };
 
-  let rid = state
-    .resource_table
-    .add(HttpClientResource::new(client, args.allow_host));
+  let local_socket = args
+    .unix_socket
+    .map(|path| {
+      LocalSocketClient::new(path, &options.user_agent, &client_options)
+    })
+    .transpose()?;
+  let client = create_http_client(&options.user_agent, client_options)?;
+
+  let rid = state.resource_table.add(HttpClientResource::new(
+    client,
+    args.allow_host,
+    args.retry,
+    local_socket,
+  ));
   Ok(rid)
 }
 
@@ -892,6 +1015,11 @@ pub struct CreateHttpClientOptions {
   pub pool_idle_timeout: Option<Option<u64>>,
   pub http1: bool,
   pub http2: bool,
+  pub connect_timeout: Option<Duration>,
+  /// Applies to the whole request, from connecting until the response body has been read.
+  pub timeout: Option<Duration>,
+  /// Resolves host names instead of the system resolver.
+  pub dns_resolver: Option<DnsResolver>,
 }
 
 impl Default for CreateHttpClientOptions {
@@ -906,10 +1034,53 @@ impl Default for CreateHttpClientOptions {
       pool_idle_timeout: None,
       http1: true,
       http2: true,
+      connect_timeout: None,
+      timeout: None,
+      dns_resolver: None,
     }
   }
 }
 
+/// Creates the reqwest proxy for one of the servers of `proxy`. It only
+/// intercepts the requests to hosts that `proxy` routes through `server`.
+fn create_reqwest_proxy(
+  proxy: &Proxy,
+  index: usize,
+  server: ProxyServer,
+) -> Result<reqwest::Proxy, AnyError> {
+  let mut url = Url::parse(server.url)?;
+  let is_socks = match url.scheme() {
+    "http" | "https" => false,
+    "socks5" | "socks5h" => true,
+    scheme => {
+      return Err(type_error(format!("Unsupported proxy scheme: {scheme}")))
+    }
+  };
+  if let Some(basic_auth) = server.basic_auth {
+    // reqwest only reads the credentials of SOCKS proxies from the URL
+    url
+      .set_username(&basic_auth.username)
+      .and_then(|_| url.set_password(Some(&basic_auth.password)))
+      .map_err(|_| type_error("Invalid proxy URL"))?;
+  }
+
+  let rules = proxy.clone();
+  let mut reqwest_proxy = reqwest::Proxy::custom(move |target| {
+    let host = target.host_str()?;
+    (rules.rule_index(host) == index).then(|| url.clone())
+  });
+  if let Some(bearer_token) = server.bearer_token {
+    if is_socks {
+      return Err(type_error(
+        "`bearerToken` is not supported by SOCKS proxies",
+      ));
+    }
+    let value = HeaderValue::from_str(&format!("Bearer {bearer_token}"))?;
+    reqwest_proxy = reqwest_proxy.custom_http_auth(value);
+  }
+  Ok(reqwest_proxy)
+}
+
 /// Create new instance of async reqwest::Client. This client supports
 /// proxies and doesn't follow redirects.
 pub fn create_http_client(
@@ -940,12 +1111,13 @@ pub fn create_http_client(
     .use_preconfigured_tls(tls_config);
 
   if let Some(proxy) = options.proxy {
-    let mut reqwest_proxy = reqwest::Proxy::all(&proxy.url)?;
-    if let Some(basic_auth) = &proxy.basic_auth {
-      reqwest_proxy =
-        reqwest_proxy.basic_auth(&basic_auth.username, &basic_auth.password);
+    for (index, server) in proxy.servers() {
+      builder = builder.proxy(create_reqwest_proxy(&proxy, index, server)?);
     }
-    builder = builder.proxy(reqwest_proxy);
+  }
+
+  if let Some(dns_resolver) = options.dns_resolver {
+    builder = builder.dns_resolver(Arc::new(dns_resolver));
   }
 
   if let Some(pool_max_idle_per_host) = options.pool_max_idle_per_host {
@@ -958,6 +1130,14 @@ pub fn create_http_client(
     );
   }
 
+  if let Some(connect_timeout) = options.connect_timeout {
+    builder = builder.connect_timeout(connect_timeout);
+  }
+
+  if let Some(timeout) = options.timeout {
+    builder = builder.timeout(timeout);
+  }
+
   match (options.http1, options.http2) {
     (true, false) => builder = builder.http1_only(),
     (false, true) => builder = builder.http2_prior_knowledge(),
@@ -0,0 +1,257 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Transport for `Deno.HttpClient`s created with the `unixSocket` option.
+//! `reqwest` can only connect over TCP, so these clients send their requests
+//! through a `hyper` client whose connector always opens the configured Unix
+//! domain socket (or named pipe on Windows), whatever the host of the URL.
+
+use std::future::Future;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::Context;
+use std::task::Poll;
+use std::time::Duration;
+
+use bytes::Bytes;
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::futures::Stream;
+use http::Uri;
+use hyper::client::connect::Connected;
+use hyper::client::connect::Connection;
+use hyper::service::Service;
+use reqwest::header::HeaderValue;
+use reqwest::header::USER_AGENT;
+use reqwest::Body;
+use reqwest::RequestBuilder;
+use reqwest::Response;
+use reqwest::ResponseBuilderExt;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncWrite;
+use tokio::io::ReadBuf;
+use tokio::time::Instant;
+use tokio::time::Sleep;
+
+use crate::CreateHttpClientOptions;
+
+#[cfg(unix)]
+type RawStream = tokio::net::UnixStream;
+#[cfg(windows)]
+type RawStream = tokio::net::windows::named_pipe::NamedPipeClient;
+
+#[cfg(unix)]
+async fn connect(path: &Path) -> io::Result<RawStream> {
+  tokio::net::UnixStream::connect(path).await
+}
+
+#[cfg(windows)]
+async fn connect(path: &Path) -> io::Result<RawStream> {
+  tokio::net::windows::named_pipe::ClientOptions::new().open(path)
+}
+
+pub struct LocalSocketStream(RawStream);
+
+impl AsyncRead for LocalSocketStream {
+  fn poll_read(
+    mut self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    buf: &mut ReadBuf<'_>,
+  ) -> Poll<io::Result<()>> {
+    Pin::new(&mut self.0).poll_read(cx, buf)
+  }
+}
+
+impl AsyncWrite for LocalSocketStream {
+  fn poll_write(
+    mut self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    buf: &[u8],
+  ) -> Poll<io::Result<usize>> {
+    Pin::new(&mut self.0).poll_write(cx, buf)
+  }
+
+  fn poll_flush(
+    mut self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+  ) -> Poll<io::Result<()>> {
+    Pin::new(&mut self.0).poll_flush(cx)
+  }
+
+  fn poll_shutdown(
+    mut self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+  ) -> Poll<io::Result<()>> {
+    Pin::new(&mut self.0).poll_shutdown(cx)
+  }
+}
+
+impl Connection for LocalSocketStream {
+  fn connected(&self) -> Connected {
+    Connected::new()
+  }
+}
+
+#[derive(Clone)]
+pub struct LocalSocketConnector {
+  path: Arc<PathBuf>,
+  connect_timeout: Option<Duration>,
+}
+
+impl Service<Uri> for LocalSocketConnector {
+  type Response = LocalSocketStream;
+  type Error = io::Error;
+  type Future =
+    Pin<Box<dyn Future<Output = io::Result<LocalSocketStream>> + Send>>;
+
+  fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+    Poll::Ready(Ok(()))
+  }
+
+  fn call(&mut self, _uri: Uri) -> Self::Future {
+    let path = self.path.clone();
+    let connect_timeout = self.connect_timeout;
+    Box::pin(async move {
+      let stream = match connect_timeout {
+        Some(timeout) => tokio::time::timeout(timeout, connect(&path))
+          .await
+          .map_err(|_| timed_out("connecting"))??,
+        None => connect(&path).await?,
+      };
+      Ok(LocalSocketStream(stream))
+    })
+  }
+}
+
+fn timed_out(what: &str) -> io::Error {
+  io::Error::new(io::ErrorKind::TimedOut, format!("{what} timed out"))
+}
+
+#[derive(Clone)]
+pub struct LocalSocketClient {
+  client: hyper::Client<LocalSocketConnector, Body>,
+  user_agent: HeaderValue,
+  timeout: Option<Duration>,
+}
+
+impl LocalSocketClient {
+  pub fn new(
+    path: PathBuf,
+    user_agent: &str,
+    options: &CreateHttpClientOptions,
+  ) -> Result<Self, AnyError> {
+    if options.proxy.is_some() {
+      return Err(type_error(
+        "`proxy` can not be used together with `unixSocket`",
+      ));
+    }
+
+    let mut builder = hyper::Client::builder();
+    match (options.http1, options.http2) {
+      // There is no TLS to negotiate the protocol with, so HTTP/2 is only
+      // used when it is the only one allowed (h2c with prior knowledge).
+      (false, true) => {
+        builder.http2_only(true);
+      }
+      (true, _) => {}
+      (false, false) => {
+        return Err(type_error("Either `http1` or `http2` needs to be true"))
+      }
+    }
+    if let Some(pool_max_idle_per_host) = options.pool_max_idle_per_host {
+      builder.pool_max_idle_per_host(pool_max_idle_per_host);
+    }
+    if let Some(pool_idle_timeout) = options.pool_idle_timeout {
+      builder.pool_idle_timeout(pool_idle_timeout.map(Duration::from_millis));
+    }
+
+    let connector = LocalSocketConnector {
+      path: Arc::new(path),
+      connect_timeout: options.connect_timeout,
+    };
+    Ok(Self {
+      client: builder.build(connector),
+      user_agent: HeaderValue::from_str(user_agent)?,
+      timeout: options.timeout,
+    })
+  }
+
+  /// Send the request built by `request` over the socket. Only the URL path
+  /// and host matter, the host is only used for the `Host` header.
+  pub async fn send(
+    &self,
+    request: RequestBuilder,
+  ) -> Result<Response, AnyError> {
+    let mut request = request.build()?;
+    if request.url().scheme() != "http" {
+      return Err(type_error(
+        "Only http: URLs can be fetched over a Unix socket",
+      ));
+    }
+    let url = request.url().clone();
+    // Like reqwest's, the timeout covers the whole request, including reading
+    // the response body.
+    let deadline = request
+      .timeout()
+      .copied()
+      .or(self.timeout)
+      .map(|timeout| Instant::now() + timeout);
+    request
+      .headers_mut()
+      .entry(USER_AGENT)
+      .or_insert_with(|| self.user_agent.clone());
+
+    let request = http::Request::<Body>::try_from(request)?;
+    let response = self.client.request(request);
+    let response = match deadline {
+      Some(deadline) => tokio::time::timeout_at(deadline, response)
+        .await
+        .map_err(|_| timed_out("request"))??,
+      None => response.await?,
+    };
+
+    let (parts, body) = response.into_parts();
+    let mut builder = http::Response::builder()
+      .status(parts.status)
+      .version(parts.version)
+      .url(url);
+    if let Some(headers) = builder.headers_mut() {
+      *headers = parts.headers;
+    }
+    let body = match deadline {
+      Some(deadline) => Body::wrap_stream(DeadlineBody {
+        body,
+        sleep: Box::pin(tokio::time::sleep_until(deadline)),
+      }),
+      None => Body::wrap_stream(body),
+    };
+    Ok(Response::from(builder.body(body)?))
+  }
+}
+
+/// A response body that fails once the deadline of its request has passed.
+struct DeadlineBody {
+  body: hyper::Body,
+  sleep: Pin<Box<Sleep>>,
+}
+
+impl Stream for DeadlineBody {
+  type Item = Result<Bytes, AnyError>;
+
+  fn poll_next(
+    mut self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+  ) -> Poll<Option<Self::Item>> {
+    if let Poll::Ready(chunk) = Pin::new(&mut self.body).poll_next(cx) {
+      return Poll::Ready(chunk.map(|chunk| chunk.map_err(AnyError::from)));
+    }
+    match self.sleep.as_mut().poll(cx) {
+      Poll::Ready(()) => {
+        Poll::Ready(Some(Err(timed_out("reading the response body").into())))
+      }
+      Poll::Pending => Poll::Pending,
+    }
+  }
+}
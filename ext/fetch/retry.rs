@@ -0,0 +1,134 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::io;
+use std::time::Duration;
+
+use deno_core::error::AnyError;
+use reqwest::header::RETRY_AFTER;
+use reqwest::Method;
+use reqwest::RequestBuilder;
+use reqwest::Response;
+use serde::Deserialize;
+
+use crate::local_socket::LocalSocketClient;
+
+fn default_initial_delay() -> u64 {
+  100
+}
+
+fn default_max_delay() -> u64 {
+  10_000
+}
+
+fn default_status_codes() -> Vec<u16> {
+  vec![429, 502, 503, 504]
+}
+
+/// How a `Deno.HttpClient` retries idempotent requests that failed to connect, timed out or were
+/// answered with one of `status_codes`. Delays are in milliseconds.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryPolicy {
+  pub max_retries: u32,
+  #[serde(default = "default_initial_delay")]
+  pub initial_delay: u64,
+  #[serde(default = "default_max_delay")]
+  pub max_delay: u64,
+  #[serde(default = "default_status_codes")]
+  pub status_codes: Vec<u16>,
+}
+
+impl RetryPolicy {
+  /// Exponential backoff, starting at `initial_delay` and capped at `max_delay`.
+  fn backoff(&self, attempt: u32) -> Duration {
+    let delay = self
+      .initial_delay
+      .saturating_mul(2u64.saturating_pow(attempt))
+      .min(self.max_delay);
+    Duration::from_millis(delay)
+  }
+
+  /// Servers may tell us how long to wait with `Retry-After`. We only understand the number of
+  /// seconds form, and never wait longer than `max_delay`.
+  fn retry_after(&self, res: &Response) -> Option<Duration> {
+    let seconds = res
+      .headers()
+      .get(RETRY_AFTER)?
+      .to_str()
+      .ok()?
+      .trim()
+      .parse::<u64>()
+      .ok()?;
+    Some(Duration::from_millis(
+      seconds.saturating_mul(1000).min(self.max_delay),
+    ))
+  }
+}
+
+/// Methods that can be safely sent again (RFC 9110, section 9.2.2).
+pub fn is_idempotent(method: &Method) -> bool {
+  matches!(
+    *method,
+    Method::GET
+      | Method::HEAD
+      | Method::OPTIONS
+      | Method::TRACE
+      | Method::PUT
+      | Method::DELETE
+  )
+}
+
+/// Errors worth trying again: the server couldn't be reached or didn't answer
+/// in time.
+fn is_transient(err: &AnyError) -> bool {
+  if let Some(err) = err.downcast_ref::<reqwest::Error>() {
+    err.is_connect() || err.is_timeout()
+  } else if let Some(err) = err.downcast_ref::<hyper::Error>() {
+    err.is_connect() || err.is_timeout()
+  } else if let Some(err) = err.downcast_ref::<io::Error>() {
+    err.kind() == io::ErrorKind::TimedOut
+  } else {
+    false
+  }
+}
+
+async fn send(
+  request: RequestBuilder,
+  local_socket: Option<&LocalSocketClient>,
+) -> Result<Response, AnyError> {
+  match local_socket {
+    Some(client) => client.send(request).await,
+    None => Ok(request.send().await?),
+  }
+}
+
+/// Send a request, retrying according to `policy`. Requests with a streaming body can't be cloned,
+/// so they are only ever sent once.
+pub async fn send_with_retry(
+  request: RequestBuilder,
+  policy: Option<RetryPolicy>,
+  local_socket: Option<LocalSocketClient>,
+) -> Result<Response, AnyError> {
+  let local_socket = local_socket.as_ref();
+  let Some(policy) = policy else {
+    return send(request, local_socket).await;
+  };
+
+  let mut attempt = 0;
+  while attempt < policy.max_retries {
+    let Some(next) = request.try_clone() else {
+      break;
+    };
+    let delay = match send(next, local_socket).await {
+      Ok(res) if policy.status_codes.contains(&res.status().as_u16()) => policy
+        .retry_after(&res)
+        .unwrap_or_else(|| policy.backoff(attempt)),
+      Ok(res) => return Ok(res),
+      Err(err) if is_transient(&err) => policy.backoff(attempt),
+      Err(err) => return Err(err),
+    };
+    tokio::time::sleep(delay).await;
+    attempt += 1;
+  }
+  send(request, local_socket).await
+}
@@ -319,6 +319,19 @@ class UnsafeFnPointer {
   }
 }
 
+/**
+ * The types of all arguments of a call, fixed and variadic.
+ */
+function getParameterTypes(definition) {
+  if (!definition.variadic) {
+    return definition.parameters;
+  }
+  return [
+    ...new SafeArrayIterator(definition.parameters),
+    ...new SafeArrayIterator(definition.variadic),
+  ];
+}
+
 function isReturnedAsBigInt(type) {
   return type === "u64" || type === "i64" ||
     type === "usize" || type === "isize";
@@ -539,7 +552,7 @@ class DynamicLibrary {
 
       if (needsUnpacking && !isNonBlocking) {
         const call = this.symbols[symbol];
-        const parameters = symbols[symbol].parameters;
+        const parameters = getParameterTypes(symbols[symbol]);
         const vi = new Int32Array(2);
         const vui = new Uint32Array(TypedArrayPrototypeGetBuffer(vi));
         const b = new BigInt64Array(TypedArrayPrototypeGetBuffer(vi));
@@ -569,7 +582,7 @@ class DynamicLibrary {
         )(vi, vui, b, call, NumberIsSafeInteger, Number);
       } else if (isStructResult && !isNonBlocking) {
         const call = this.symbols[symbol];
-        const parameters = symbols[symbol].parameters;
+        const parameters = getParameterTypes(symbols[symbol]);
         const params = ArrayPrototypeJoin(
           ArrayPrototypeMap(parameters, (_, index) => `p${index}`),
           ", ",
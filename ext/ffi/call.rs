@@ -293,7 +293,8 @@ where
   };
 
   let symbol = PtrSymbol::new(pointer, &def)?;
-  let call_args = ffi_parse_args(scope, parameters, &def.parameters)?;
+  let parameter_types = def.parameter_types();
+  let call_args = ffi_parse_args(scope, parameters, &parameter_types)?;
   let out_buffer_ptr = out_buffer_as_ptr(scope, out_buffer);
 
   let join_handle = spawn_blocking(move || {
@@ -302,7 +303,7 @@ where
       call_args,
       &cif,
       ptr,
-      &def.parameters,
+      &parameter_types,
       def.result,
       out_buffer_ptr,
     )
@@ -389,7 +390,8 @@ where
   };
 
   let symbol = PtrSymbol::new(pointer, &def)?;
-  let call_args = ffi_parse_args(scope, parameters, &def.parameters)?;
+  let parameter_types = def.parameter_types();
+  let call_args = ffi_parse_args(scope, parameters, &parameter_types)?;
 
   let out_buffer_ptr = out_buffer_as_ptr(scope, out_buffer);
 
@@ -397,7 +399,7 @@ where
     call_args,
     &symbol.cif,
     symbol.ptr,
-    &def.parameters,
+    &parameter_types,
     def.result.clone(),
     out_buffer_ptr,
   )?;
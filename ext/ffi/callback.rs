@@ -53,15 +53,7 @@ impl PtrSymbol {
     def: &ForeignFunction,
   ) -> Result<Self, AnyError> {
     let ptr = libffi::middle::CodePtr::from_ptr(fn_ptr as _);
-    let cif = libffi::middle::Cif::new(
-      def
-        .parameters
-        .clone()
-        .into_iter()
-        .map(libffi::middle::Type::try_from)
-        .collect::<Result<Vec<_>, _>>()?,
-      def.result.clone().try_into()?,
-    );
+    let cif = def.cif()?;
 
     Ok(Self { cif, ptr })
   }
@@ -293,15 +285,17 @@ unsafe fn do_ffi_callback(
       | NativeType::Buffer
       | NativeType::Function
       | NativeType::U64
-      | NativeType::I64 => {
-        *(result as *mut usize) = 0;
+      | NativeType::I64
+      | NativeType::USize
+      | NativeType::ISize => {
+        *(result as *mut u64) = 0;
+      }
+      NativeType::Struct(_) => {
+        ptr::write_bytes(result as *mut u8, 0, (*cif.rtype).size);
       }
       NativeType::Void => {
         // nop
       }
-      _ => {
-        unreachable!();
-      }
     };
 
     return;
@@ -471,33 +465,33 @@ unsafe fn do_ffi_callback(
       }
     }
     NativeType::Struct(_) => {
-      let size;
-      let pointer = if let Ok(value) =
+      let struct_size = (*cif.rtype).size;
+      let buffer = if let Ok(value) =
         v8::Local::<v8::ArrayBufferView>::try_from(value)
       {
-        let byte_offset = value.byte_offset();
-        let ab = value
-          .buffer(scope)
-          .expect("Unable to deserialize result parameter.");
-        size = value.byte_length();
-        ab.data()
-          .expect("Unable to deserialize result parameter.")
-          .as_ptr()
-          .add(byte_offset)
+        value.buffer(scope).and_then(|ab| ab.data()).map(|data| {
+          (
+            data.as_ptr().add(value.byte_offset()) as *const u8,
+            value.byte_length(),
+          )
+        })
       } else if let Ok(value) = v8::Local::<v8::ArrayBuffer>::try_from(value) {
-        size = value.byte_length();
         value
           .data()
-          .expect("Unable to deserialize result parameter.")
-          .as_ptr()
+          .map(|data| (data.as_ptr() as *const u8, value.byte_length()))
       } else {
-        panic!("Unable to deserialize result parameter.");
+        // TODO(@aapoalas): Start throwing errors into JS about invalid callback return values.
+        None
       };
-      std::ptr::copy_nonoverlapping(
-        pointer as *mut u8,
-        result as *mut u8,
-        std::cmp::min(size, (*cif.rtype).size),
-      );
+      // Bytes not covered by the returned buffer are left zeroed.
+      ptr::write_bytes(result as *mut u8, 0, struct_size);
+      if let Some((pointer, size)) = buffer {
+        ptr::copy_nonoverlapping(
+          pointer,
+          result as *mut u8,
+          std::cmp::min(size, struct_size),
+        );
+      }
     }
     NativeType::Void => {
       // nop
@@ -7,6 +7,7 @@ use crate::symbol::Symbol;
 use crate::turbocall;
 use crate::FfiPermissions;
 use deno_core::error::generic_error;
+use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::op2;
 use deno_core::serde_v8;
@@ -79,6 +80,56 @@ pub struct ForeignFunction {
   #[serde(rename = "optional")]
   #[serde(default = "default_optional")]
   optional: bool,
+  /// The types of the arguments passed in place of the `...` of a C variadic
+  /// function, following the fixed `parameters`.
+  pub variadic: Option<Vec<NativeType>>,
+}
+
+impl ForeignFunction {
+  /// The types of all arguments of a call, fixed and variadic.
+  pub fn parameter_types(&self) -> Vec<NativeType> {
+    let mut parameters = self.parameters.clone();
+    if let Some(variadic) = &self.variadic {
+      parameters.extend(variadic.iter().cloned());
+    }
+    parameters
+  }
+
+  pub fn cif(&self) -> Result<libffi::middle::Cif, AnyError> {
+    let args = self
+      .parameter_types()
+      .into_iter()
+      .map(libffi::middle::Type::try_from)
+      .collect::<Result<Vec<_>, _>>()?;
+    let result = self.result.clone().try_into()?;
+    let Some(variadic) = &self.variadic else {
+      return Ok(libffi::middle::Cif::new(args, result));
+    };
+    // C promotes variadic arguments smaller than an int or a double, so the
+    // callee never reads them with their own type.
+    for native_type in variadic {
+      let (name, promoted) = match native_type {
+        NativeType::Bool => ("bool", "i32"),
+        NativeType::I8 => ("i8", "i32"),
+        NativeType::U8 => ("u8", "i32"),
+        NativeType::I16 => ("i16", "i32"),
+        NativeType::U16 => ("u16", "i32"),
+        NativeType::F32 => ("f32", "f64"),
+        NativeType::Void => {
+          return Err(type_error("Variadic arguments cannot be void"))
+        }
+        _ => continue,
+      };
+      return Err(type_error(format!(
+        "Variadic arguments of type {name} are promoted by C, use {promoted} instead"
+      )));
+    }
+    Ok(libffi::middle::Cif::new_variadic(
+      args,
+      self.parameters.len(),
+      result,
+    ))
+  }
 }
 
 fn default_callback() -> bool {
@@ -189,23 +240,16 @@ where
           }?;
 
         let ptr = libffi::middle::CodePtr::from_ptr(fn_ptr as _);
-        let cif = libffi::middle::Cif::new(
-          foreign_fn
-            .parameters
-            .clone()
-            .into_iter()
-            .map(libffi::middle::Type::try_from)
-            .collect::<Result<Vec<_>, _>>()?,
-          foreign_fn.result.clone().try_into()?,
-        );
+        let cif = foreign_fn.cif()?;
 
         let func_key = v8::String::new(scope, &symbol_key).unwrap();
         let sym = Box::new(Symbol {
           cif,
           ptr,
-          parameter_types: foreign_fn.parameters,
+          parameter_types: foreign_fn.parameter_types(),
           result_type: foreign_fn.result,
           can_callback: foreign_fn.callback,
+          is_variadic: foreign_fn.variadic.is_some(),
         });
 
         resource.symbols.insert(symbol_key, sym.clone());
@@ -470,6 +514,32 @@ mod tests {
     }
   }
 
+  #[test]
+  fn test_variadic_foreign_function() {
+    let function: ForeignFunction = serde_json::from_value(json! {{
+      "parameters": ["buffer"],
+      "variadic": ["i32", "f64"],
+      "result": "i32"
+    }})
+    .expect("Failed to parse");
+    assert_eq!(
+      function.parameter_types(),
+      vec![NativeType::Buffer, NativeType::I32, NativeType::F64]
+    );
+    assert!(function.cif().is_ok());
+
+    let function: ForeignFunction = serde_json::from_value(json! {{
+      "parameters": ["buffer"],
+      "variadic": ["u8"],
+      "result": "i32"
+    }})
+    .expect("Failed to parse");
+    assert_eq!(
+      function.cif().unwrap_err().to_string(),
+      "Variadic arguments of type u8 are promoted by C, use i32 instead"
+    );
+  }
+
   #[test]
   fn test_serialize_foreign_symbol_failures() {
     let error = serde_json::from_value::<ForeignSymbol>(json! {{
@@ -71,6 +71,7 @@ pub struct Symbol {
   pub parameter_types: Vec<NativeType>,
   pub result_type: NativeType,
   pub can_callback: bool,
+  pub is_variadic: bool,
 }
 
 #[allow(clippy::non_send_fields_in_send_ty)]
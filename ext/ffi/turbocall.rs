@@ -20,6 +20,7 @@ pub(crate) fn is_compatible(sym: &Symbol) -> bool {
     all(target_arch = "x86_64", target_family = "windows"),
     all(target_arch = "aarch64", target_vendor = "apple")
   )) && !sym.can_callback
+    && !sym.is_variadic
     && !matches!(sym.result_type, NativeType::Struct(_))
     && !sym
       .parameter_types
@@ -1525,6 +1526,7 @@ mod tests {
       parameter_types: parameters,
       result_type: ret,
       can_callback: false,
+      is_variadic: false,
     }
   }
 
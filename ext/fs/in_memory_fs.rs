@@ -0,0 +1,1404 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::io;
+use std::io::SeekFrom;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use deno_core::BufMutView;
+use deno_core::BufView;
+use deno_core::ResourceHandleFd;
+use deno_io::fs::File;
+use deno_io::fs::FsError;
+use deno_io::fs::FsResult;
+use deno_io::fs::FsStat;
+
+use crate::interface::FsDirEntry;
+use crate::interface::FsFileType;
+use crate::sync::MaybeArcMutex;
+use crate::FileSystem;
+use crate::OpenOptions;
+
+const ROOT_INO: u64 = 1;
+const MAX_SYMLINK_DEPTH: usize = 40;
+const BLOCK_SIZE: u64 = 4096;
+const DEFAULT_MAX_FILE_SIZE: u64 = 1 << 30;
+
+const S_IFREG: u32 = 0o100000;
+const S_IFDIR: u32 = 0o040000;
+const S_IFLNK: u32 = 0o120000;
+
+/// A `FileSystem` that keeps all files, directories and symlinks in memory.
+///
+/// This is useful for embedders and tests that want to run code against a
+/// hermetic file system. Permission bits, ownership, hard links, symlinks,
+/// timestamps and advisory file locks are all tracked, but permission bits
+/// are only checked against the owner class, since there is a single user.
+///
+/// Unlike on a real file system, `lock_sync` does not block while another
+/// handle holds a conflicting lock. The handle holding it usually belongs to
+/// the same isolate, which could never release it, so the call fails with
+/// `WouldBlock` instead. `lock_async` waits for the lock as usual.
+///
+/// Files are stored contiguously, so writes and truncations that would grow
+/// a file past the maximum file size (1 GiB unless changed with
+/// [`InMemoryFs::with_max_file_size`]) fail with `EFBIG`.
+#[derive(Debug, Clone)]
+pub struct InMemoryFs(MaybeArcMutex<FsState>);
+
+impl Default for InMemoryFs {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl InMemoryFs {
+  /// Creates an empty file system containing only `/` and `/tmp`, with `/`
+  /// as the current working directory.
+  pub fn new() -> Self {
+    let now = now_ms();
+    let mut inodes = HashMap::new();
+    inodes.insert(
+      ROOT_INO,
+      Inode::new(NodeKind::Directory(BTreeMap::new()), 0o755, now),
+    );
+    let mut state = FsState {
+      inodes,
+      next_ino: ROOT_INO + 1,
+      cwd: root_path(),
+      umask: 0o022,
+      max_file_size: DEFAULT_MAX_FILE_SIZE,
+    };
+    state
+      .mkdir(&root_path().join("tmp"), false, 0o1777)
+      .expect("creating /tmp in an empty file system");
+    // the umask must not apply to the sticky tmp directory
+    let tmp = state.lookup(&root_path().join("tmp"), true).unwrap().1;
+    state.inode_mut(tmp).unwrap().mode = 0o1777;
+    Self(MaybeArcMutex::new(state))
+  }
+
+  /// Creates an empty file system where `cwd` (and all of its ancestors)
+  /// exist and `cwd` is the current working directory.
+  pub fn with_cwd(cwd: &Path) -> FsResult<Self> {
+    let fs = Self::new();
+    {
+      let mut state = fs.0.lock();
+      state.mkdir(cwd, true, 0o755)?;
+      let (path, _) = state.lookup(cwd, true)?;
+      state.cwd = path;
+    }
+    Ok(fs)
+  }
+
+  /// Sets the size in bytes that no file may grow past.
+  pub fn with_max_file_size(self, max_file_size: u64) -> Self {
+    self.0.lock().max_file_size = max_file_size;
+    self
+  }
+
+  fn open(&self, path: &Path, options: OpenOptions) -> FsResult<InMemoryFile> {
+    let mut state = self.0.lock();
+    let ino = state.open(path, options)?;
+    Ok(InMemoryFile {
+      fs: self.clone(),
+      ino,
+      pos: Cell::new(0),
+      readable: options.read,
+      writable: options.write || options.append,
+      append: options.append,
+      held_lock: Cell::new(None),
+    })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl FileSystem for InMemoryFs {
+  fn cwd(&self) -> FsResult<PathBuf> {
+    Ok(self.0.lock().cwd.clone())
+  }
+
+  fn tmp_dir(&self) -> FsResult<PathBuf> {
+    Ok(root_path().join("tmp"))
+  }
+
+  fn chdir(&self, path: &Path) -> FsResult<()> {
+    let mut state = self.0.lock();
+    let (path, ino) = state.lookup(path, true)?;
+    if !state.inode(ino)?.is_dir() {
+      return Err(not_a_directory().into());
+    }
+    state.cwd = path;
+    Ok(())
+  }
+
+  fn umask(&self, mask: Option<u32>) -> FsResult<u32> {
+    let mut state = self.0.lock();
+    let prev = state.umask;
+    if let Some(mask) = mask {
+      state.umask = mask & 0o777;
+    }
+    Ok(prev)
+  }
+
+  fn open_sync(
+    &self,
+    path: &Path,
+    options: OpenOptions,
+  ) -> FsResult<Rc<dyn File>> {
+    Ok(Rc::new(self.open(path, options)?))
+  }
+  async fn open_async(
+    &self,
+    path: PathBuf,
+    options: OpenOptions,
+  ) -> FsResult<Rc<dyn File>> {
+    self.open_sync(&path, options)
+  }
+
+  fn mkdir_sync(
+    &self,
+    path: &Path,
+    recursive: bool,
+    mode: u32,
+  ) -> FsResult<()> {
+    self.0.lock().mkdir(path, recursive, mode)
+  }
+  async fn mkdir_async(
+    &self,
+    path: PathBuf,
+    recursive: bool,
+    mode: u32,
+  ) -> FsResult<()> {
+    self.mkdir_sync(&path, recursive, mode)
+  }
+
+  fn chmod_sync(&self, path: &Path, mode: u32) -> FsResult<()> {
+    let mut state = self.0.lock();
+    let (_, ino) = state.lookup(path, true)?;
+    state.inode_mut(ino)?.mode = mode & 0o7777;
+    Ok(())
+  }
+  async fn chmod_async(&self, path: PathBuf, mode: u32) -> FsResult<()> {
+    self.chmod_sync(&path, mode)
+  }
+
+  fn chown_sync(
+    &self,
+    path: &Path,
+    uid: Option<u32>,
+    gid: Option<u32>,
+  ) -> FsResult<()> {
+    let mut state = self.0.lock();
+    let (_, ino) = state.lookup(path, true)?;
+    let inode = state.inode_mut(ino)?;
+    if let Some(uid) = uid {
+      inode.uid = uid;
+    }
+    if let Some(gid) = gid {
+      inode.gid = gid;
+    }
+    Ok(())
+  }
+  async fn chown_async(
+    &self,
+    path: PathBuf,
+    uid: Option<u32>,
+    gid: Option<u32>,
+  ) -> FsResult<()> {
+    self.chown_sync(&path, uid, gid)
+  }
+
+  fn remove_sync(&self, path: &Path, recursive: bool) -> FsResult<()> {
+    self.0.lock().remove(path, recursive)
+  }
+  async fn remove_async(&self, path: PathBuf, recursive: bool) -> FsResult<()> {
+    self.remove_sync(&path, recursive)
+  }
+
+  fn copy_file_sync(&self, oldpath: &Path, newpath: &Path) -> FsResult<()> {
+    self.0.lock().copy_file(oldpath, newpath)
+  }
+  async fn copy_file_async(
+    &self,
+    oldpath: PathBuf,
+    newpath: PathBuf,
+  ) -> FsResult<()> {
+    self.copy_file_sync(&oldpath, &newpath)
+  }
+
+  fn stat_sync(&self, path: &Path) -> FsResult<FsStat> {
+    let state = self.0.lock();
+    let (_, ino) = state.lookup(path, true)?;
+    state.stat(ino)
+  }
+  async fn stat_async(&self, path: PathBuf) -> FsResult<FsStat> {
+    self.stat_sync(&path)
+  }
+
+  fn lstat_sync(&self, path: &Path) -> FsResult<FsStat> {
+    let state = self.0.lock();
+    let (_, ino) = state.lookup(path, false)?;
+    state.stat(ino)
+  }
+  async fn lstat_async(&self, path: PathBuf) -> FsResult<FsStat> {
+    self.lstat_sync(&path)
+  }
+
+  fn realpath_sync(&self, path: &Path) -> FsResult<PathBuf> {
+    let (path, _) = self.0.lock().lookup(path, true)?;
+    Ok(path)
+  }
+  async fn realpath_async(&self, path: PathBuf) -> FsResult<PathBuf> {
+    self.realpath_sync(&path)
+  }
+
+  fn read_dir_sync(&self, path: &Path) -> FsResult<Vec<FsDirEntry>> {
+    self.0.lock().read_dir(path)
+  }
+  async fn read_dir_async(&self, path: PathBuf) -> FsResult<Vec<FsDirEntry>> {
+    self.read_dir_sync(&path)
+  }
+
+  fn rename_sync(&self, oldpath: &Path, newpath: &Path) -> FsResult<()> {
+    self.0.lock().rename(oldpath, newpath)
+  }
+  async fn rename_async(
+    &self,
+    oldpath: PathBuf,
+    newpath: PathBuf,
+  ) -> FsResult<()> {
+    self.rename_sync(&oldpath, &newpath)
+  }
+
+  fn link_sync(&self, oldpath: &Path, newpath: &Path) -> FsResult<()> {
+    self.0.lock().link(oldpath, newpath)
+  }
+  async fn link_async(
+    &self,
+    oldpath: PathBuf,
+    newpath: PathBuf,
+  ) -> FsResult<()> {
+    self.link_sync(&oldpath, &newpath)
+  }
+
+  fn symlink_sync(
+    &self,
+    oldpath: &Path,
+    newpath: &Path,
+    _file_type: Option<FsFileType>,
+  ) -> FsResult<()> {
+    let mut state = self.0.lock();
+    let (parent, name) = state.lookup_parent(newpath)?;
+    state.check_access(parent, 0o2)?;
+    let now = now_ms();
+    state.insert(
+      parent,
+      name,
+      Inode::new(NodeKind::Symlink(oldpath.to_path_buf()), 0o777, now),
+    )?;
+    Ok(())
+  }
+  async fn symlink_async(
+    &self,
+    oldpath: PathBuf,
+    newpath: PathBuf,
+    file_type: Option<FsFileType>,
+  ) -> FsResult<()> {
+    self.symlink_sync(&oldpath, &newpath, file_type)
+  }
+
+  fn read_link_sync(&self, path: &Path) -> FsResult<PathBuf> {
+    let state = self.0.lock();
+    let (_, ino) = state.lookup(path, false)?;
+    match &state.inode(ino)?.kind {
+      NodeKind::Symlink(target) => Ok(target.clone()),
+      _ => Err(invalid_argument().into()),
+    }
+  }
+  async fn read_link_async(&self, path: PathBuf) -> FsResult<PathBuf> {
+    self.read_link_sync(&path)
+  }
+
+  fn truncate_sync(&self, path: &Path, len: u64) -> FsResult<()> {
+    let mut state = self.0.lock();
+    let (_, ino) = state.lookup(path, true)?;
+    state.check_access(ino, 0o2)?;
+    Ok(state.truncate(ino, len)?)
+  }
+  async fn truncate_async(&self, path: PathBuf, len: u64) -> FsResult<()> {
+    self.truncate_sync(&path, len)
+  }
+
+  fn utime_sync(
+    &self,
+    path: &Path,
+    atime_secs: i64,
+    atime_nanos: u32,
+    mtime_secs: i64,
+    mtime_nanos: u32,
+  ) -> FsResult<()> {
+    let mut state = self.0.lock();
+    let (_, ino) = state.lookup(path, true)?;
+    let inode = state.inode_mut(ino)?;
+    inode.atime = to_ms(atime_secs, atime_nanos);
+    inode.mtime = to_ms(mtime_secs, mtime_nanos);
+    Ok(())
+  }
+  async fn utime_async(
+    &self,
+    path: PathBuf,
+    atime_secs: i64,
+    atime_nanos: u32,
+    mtime_secs: i64,
+    mtime_nanos: u32,
+  ) -> FsResult<()> {
+    self.utime_sync(&path, atime_secs, atime_nanos, mtime_secs, mtime_nanos)
+  }
+}
+
+#[derive(Debug)]
+enum NodeKind {
+  File(Vec<u8>),
+  Directory(BTreeMap<String, u64>),
+  Symlink(PathBuf),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LockState {
+  Unlocked,
+  Shared(usize),
+  Exclusive,
+}
+
+#[derive(Debug)]
+struct Inode {
+  kind: NodeKind,
+  /// Permission bits, without the file type bits.
+  mode: u32,
+  uid: u32,
+  gid: u32,
+  /// Number of directory entries referring to this inode.
+  nlink: u64,
+  /// Number of open `InMemoryFile`s referring to this inode.
+  open_handles: usize,
+  lock: LockState,
+  atime: u64,
+  mtime: u64,
+  birthtime: u64,
+}
+
+impl Inode {
+  fn new(kind: NodeKind, mode: u32, now: u64) -> Self {
+    Self {
+      kind,
+      mode,
+      uid: 0,
+      gid: 0,
+      nlink: 0,
+      open_handles: 0,
+      lock: LockState::Unlocked,
+      atime: now,
+      mtime: now,
+      birthtime: now,
+    }
+  }
+
+  fn is_dir(&self) -> bool {
+    matches!(self.kind, NodeKind::Directory(_))
+  }
+
+  fn len(&self) -> u64 {
+    match &self.kind {
+      NodeKind::File(data) => data.len() as u64,
+      NodeKind::Directory(_) => BLOCK_SIZE,
+      NodeKind::Symlink(target) => target.as_os_str().len() as u64,
+    }
+  }
+}
+
+/// A single component of a path that is being resolved.
+enum Part {
+  Root,
+  Current,
+  Parent,
+  Name(String),
+}
+
+fn to_parts(path: &Path) -> Vec<Part> {
+  path
+    .components()
+    .map(|component| match component {
+      Component::Prefix(_) | Component::RootDir => Part::Root,
+      Component::CurDir => Part::Current,
+      Component::ParentDir => Part::Parent,
+      Component::Normal(name) => {
+        Part::Name(name.to_string_lossy().into_owned())
+      }
+    })
+    .collect()
+}
+
+#[derive(Debug)]
+struct FsState {
+  inodes: HashMap<u64, Inode>,
+  next_ino: u64,
+  cwd: PathBuf,
+  umask: u32,
+  max_file_size: u64,
+}
+
+impl FsState {
+  fn inode(&self, ino: u64) -> io::Result<&Inode> {
+    self.inodes.get(&ino).ok_or_else(not_found)
+  }
+
+  fn inode_mut(&mut self, ino: u64) -> io::Result<&mut Inode> {
+    self.inodes.get_mut(&ino).ok_or_else(not_found)
+  }
+
+  fn entries(&self, ino: u64) -> io::Result<&BTreeMap<String, u64>> {
+    match &self.inode(ino)?.kind {
+      NodeKind::Directory(entries) => Ok(entries),
+      _ => Err(not_a_directory()),
+    }
+  }
+
+  fn entries_mut(
+    &mut self,
+    ino: u64,
+  ) -> io::Result<&mut BTreeMap<String, u64>> {
+    match &mut self.inode_mut(ino)?.kind {
+      NodeKind::Directory(entries) => Ok(entries),
+      _ => Err(not_a_directory()),
+    }
+  }
+
+  fn absolute(&self, path: &Path) -> PathBuf {
+    if path.has_root() {
+      path.to_path_buf()
+    } else {
+      self.cwd.join(path)
+    }
+  }
+
+  /// Resolves `path` to its canonical path and inode. Symlinks in
+  /// intermediate components are always followed, while a symlink in the
+  /// final component is only followed when `follow_last` is set.
+  fn lookup(
+    &self,
+    path: &Path,
+    follow_last: bool,
+  ) -> io::Result<(PathBuf, u64)> {
+    let mut resolved: Vec<(String, u64)> = Vec::new();
+    let mut pending = to_parts(&self.absolute(path));
+    pending.reverse();
+    let mut depth = 0;
+    while let Some(part) = pending.pop() {
+      let name = match part {
+        Part::Root => {
+          resolved.clear();
+          continue;
+        }
+        Part::Current => continue,
+        Part::Parent => {
+          resolved.pop();
+          continue;
+        }
+        Part::Name(name) => name,
+      };
+      let dir = resolved.last().map(|(_, ino)| *ino).unwrap_or(ROOT_INO);
+      let child = *self.entries(dir)?.get(&name).ok_or_else(not_found)?;
+      if let NodeKind::Symlink(target) = &self.inode(child)?.kind {
+        if follow_last || !pending.is_empty() {
+          depth += 1;
+          if depth > MAX_SYMLINK_DEPTH {
+            return Err(filesystem_loop());
+          }
+          let mut target_parts = to_parts(target);
+          target_parts.reverse();
+          pending.extend(target_parts);
+          continue;
+        }
+      }
+      resolved.push((name, child));
+    }
+    let mut canonical = root_path();
+    for (name, _) in &resolved {
+      canonical.push(name);
+    }
+    let ino = resolved.last().map(|(_, ino)| *ino).unwrap_or(ROOT_INO);
+    Ok((canonical, ino))
+  }
+
+  /// Resolves the directory that contains `path`, returning its inode and the
+  /// name of the final component of `path`.
+  fn lookup_parent(&self, path: &Path) -> io::Result<(u64, String)> {
+    let path = self.absolute(path);
+    let name = match path.file_name() {
+      Some(name) => name.to_string_lossy().into_owned(),
+      None => return Err(invalid_argument()),
+    };
+    let parent = path.parent().unwrap_or_else(|| Path::new("/"));
+    let (_, parent_ino) = self.lookup(parent, true)?;
+    if !self.inode(parent_ino)?.is_dir() {
+      return Err(not_a_directory());
+    }
+    Ok((parent_ino, name))
+  }
+
+  /// Checks the given `r`/`w`/`x` bits (`0o4`, `0o2`, `0o1`) against the
+  /// owner class of the inode's permission bits.
+  fn check_access(&self, ino: u64, mask: u32) -> io::Result<()> {
+    let owner_bits = (self.inode(ino)?.mode >> 6) & 0o7;
+    if owner_bits & mask == mask {
+      Ok(())
+    } else {
+      Err(permission_denied())
+    }
+  }
+
+  /// Links a new inode into `parent` under `name`.
+  fn insert(
+    &mut self,
+    parent: u64,
+    name: String,
+    mut inode: Inode,
+  ) -> io::Result<u64> {
+    if self.entries(parent)?.contains_key(&name) {
+      return Err(already_exists());
+    }
+    let ino = self.next_ino;
+    self.next_ino += 1;
+    inode.nlink = 1;
+    self.inodes.insert(ino, inode);
+    self.entries_mut(parent)?.insert(name, ino);
+    self.touch(parent);
+    Ok(ino)
+  }
+
+  /// Removes the directory entry `name` from `parent`, releasing the inode
+  /// it referred to (and any directory contents) once nothing refers to it.
+  fn unlink(&mut self, parent: u64, name: &str) -> io::Result<()> {
+    let ino = self
+      .entries_mut(parent)?
+      .remove(name)
+      .ok_or_else(not_found)?;
+    self.touch(parent);
+    self.release(ino);
+    Ok(())
+  }
+
+  fn release(&mut self, ino: u64) {
+    let Some(inode) = self.inodes.get_mut(&ino) else {
+      return;
+    };
+    inode.nlink = inode.nlink.saturating_sub(1);
+    if inode.nlink > 0 {
+      return;
+    }
+    if let NodeKind::Directory(entries) = &mut inode.kind {
+      let children = std::mem::take(entries);
+      for child in children.into_values() {
+        self.release(child);
+      }
+    }
+    self.collect(ino);
+  }
+
+  /// Drops an inode once it is neither linked nor open.
+  fn collect(&mut self, ino: u64) {
+    if let Some(inode) = self.inodes.get(&ino) {
+      if inode.nlink == 0 && inode.open_handles == 0 {
+        self.inodes.remove(&ino);
+      }
+    }
+  }
+
+  fn touch(&mut self, ino: u64) {
+    if let Some(inode) = self.inodes.get_mut(&ino) {
+      inode.mtime = now_ms();
+    }
+  }
+
+  fn open(&mut self, path: &Path, options: OpenOptions) -> io::Result<u64> {
+    let writable = options.write || options.append;
+    let ino = match self.lookup(path, true) {
+      Ok(_) if options.create_new => return Err(already_exists()),
+      Ok((_, ino)) => {
+        if self.inode(ino)?.is_dir() && writable {
+          return Err(is_a_directory());
+        }
+        let mut mask = 0;
+        if options.read {
+          mask |= 0o4;
+        }
+        if writable {
+          mask |= 0o2;
+        }
+        self.check_access(ino, mask)?;
+        if options.truncate && writable {
+          self.truncate(ino, 0)?;
+        }
+        ino
+      }
+      Err(err) if err.kind() == io::ErrorKind::NotFound => {
+        if !(options.create || options.create_new) || !writable {
+          return Err(err);
+        }
+        let (parent, name) = self.lookup_parent(path)?;
+        self.check_access(parent, 0o2)?;
+        let mode = options.mode.unwrap_or(0o666) & 0o7777 & !self.umask;
+        self.insert(
+          parent,
+          name,
+          Inode::new(NodeKind::File(Vec::new()), mode, now_ms()),
+        )?
+      }
+      Err(err) => return Err(err),
+    };
+    self.inode_mut(ino)?.open_handles += 1;
+    Ok(ino)
+  }
+
+  fn mkdir(&mut self, path: &Path, recursive: bool, mode: u32) -> FsResult<()> {
+    let mode = mode & 0o7777 & !self.umask;
+    if recursive {
+      let path = self.absolute(path);
+      for ancestor in path.ancestors().collect::<Vec<_>>().into_iter().rev() {
+        match self.lookup(ancestor, true) {
+          Ok((_, ino)) if self.inode(ino)?.is_dir() => {}
+          Ok(_) => return Err(already_exists().into()),
+          Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            self.mkdir(ancestor, false, mode)?;
+          }
+          Err(err) => return Err(err.into()),
+        }
+      }
+      return Ok(());
+    }
+    let (parent, name) = self.lookup_parent(path)?;
+    self.check_access(parent, 0o2)?;
+    self.insert(
+      parent,
+      name,
+      Inode::new(NodeKind::Directory(BTreeMap::new()), mode, now_ms()),
+    )?;
+    Ok(())
+  }
+
+  fn remove(&mut self, path: &Path, recursive: bool) -> FsResult<()> {
+    let (parent, name) = self.lookup_parent(path)?;
+    let ino = *self.entries(parent)?.get(&name).ok_or_else(not_found)?;
+    if let NodeKind::Directory(entries) = &self.inode(ino)?.kind {
+      if !entries.is_empty() && !recursive {
+        return Err(directory_not_empty().into());
+      }
+    }
+    self.check_access(parent, 0o2)?;
+    self.unlink(parent, &name)?;
+    Ok(())
+  }
+
+  fn copy_file(&mut self, oldpath: &Path, newpath: &Path) -> FsResult<()> {
+    let (_, from) = self.lookup(oldpath, true)?;
+    self.check_access(from, 0o4)?;
+    let (data, mode) = match &self.inode(from)?.kind {
+      NodeKind::File(data) => (data.clone(), self.inode(from)?.mode),
+      _ => return Err(is_a_directory().into()),
+    };
+    let options = OpenOptions {
+      read: false,
+      write: true,
+      create: true,
+      truncate: true,
+      append: false,
+      create_new: false,
+      mode: Some(mode),
+    };
+    let to = self.open(newpath, options)?;
+    let inode = self.inode_mut(to)?;
+    inode.open_handles -= 1;
+    inode.kind = NodeKind::File(data);
+    inode.mode = mode;
+    inode.mtime = now_ms();
+    Ok(())
+  }
+
+  fn stat(&self, ino: u64) -> FsResult<FsStat> {
+    let inode = self.inode(ino)?;
+    let (file_type, nlink) = match &inode.kind {
+      NodeKind::File(_) => (S_IFREG, inode.nlink),
+      NodeKind::Symlink(_) => (S_IFLNK, inode.nlink),
+      NodeKind::Directory(entries) => {
+        let subdirs = entries
+          .values()
+          .filter(|child| {
+            self.inodes.get(child).map(|c| c.is_dir()).unwrap_or(false)
+          })
+          .count() as u64;
+        (S_IFDIR, 2 + subdirs)
+      }
+    };
+    let size = inode.len();
+    Ok(FsStat {
+      is_file: matches!(inode.kind, NodeKind::File(_)),
+      is_directory: inode.is_dir(),
+      is_symlink: matches!(inode.kind, NodeKind::Symlink(_)),
+      size,
+      mtime: Some(inode.mtime),
+      atime: Some(inode.atime),
+      birthtime: Some(inode.birthtime),
+      dev: 0,
+      ino,
+      mode: file_type | inode.mode,
+      nlink,
+      uid: inode.uid,
+      gid: inode.gid,
+      rdev: 0,
+      blksize: BLOCK_SIZE,
+      blocks: (size + 511) / 512,
+      is_block_device: false,
+      is_char_device: false,
+      is_fifo: false,
+      is_socket: false,
+    })
+  }
+
+  fn read_dir(&self, path: &Path) -> FsResult<Vec<FsDirEntry>> {
+    let (_, ino) = self.lookup(path, true)?;
+    let entries = self.entries(ino)?;
+    self.check_access(ino, 0o4)?;
+    let mut result = Vec::with_capacity(entries.len());
+    for (name, child) in entries {
+      let child = self.inode(*child)?;
+      result.push(FsDirEntry {
+        name: name.clone(),
+        is_file: matches!(child.kind, NodeKind::File(_)),
+        is_directory: child.is_dir(),
+        is_symlink: matches!(child.kind, NodeKind::Symlink(_)),
+      });
+    }
+    Ok(result)
+  }
+
+  fn rename(&mut self, oldpath: &Path, newpath: &Path) -> FsResult<()> {
+    let (old_parent, old_name) = self.lookup_parent(oldpath)?;
+    let (new_parent, new_name) = self.lookup_parent(newpath)?;
+    let ino = *self
+      .entries(old_parent)?
+      .get(&old_name)
+      .ok_or_else(not_found)?;
+    self.check_access(old_parent, 0o2)?;
+    self.check_access(new_parent, 0o2)?;
+    let is_dir = self.inode(ino)?.is_dir();
+    if is_dir {
+      // a directory can't be moved into one of its own descendants
+      let (old_canonical, _) = self.lookup(oldpath, false)?;
+      let newpath = self.absolute(newpath);
+      let new_parent = newpath.parent().unwrap_or_else(|| Path::new("/"));
+      let (new_parent_canonical, _) = self.lookup(new_parent, true)?;
+      if new_parent_canonical.starts_with(&old_canonical) {
+        return Err(invalid_argument().into());
+      }
+    }
+    if let Some(&existing) = self.entries(new_parent)?.get(&new_name) {
+      if existing == ino {
+        return Ok(());
+      }
+      match (&self.inode(existing)?.kind, is_dir) {
+        (NodeKind::Directory(entries), true) => {
+          if !entries.is_empty() {
+            return Err(directory_not_empty().into());
+          }
+        }
+        (NodeKind::Directory(_), false) => {
+          return Err(is_a_directory().into());
+        }
+        (_, true) => return Err(not_a_directory().into()),
+        (_, false) => {}
+      }
+      self.unlink(new_parent, &new_name)?;
+    }
+    self.entries_mut(old_parent)?.remove(&old_name);
+    self.entries_mut(new_parent)?.insert(new_name, ino);
+    self.touch(old_parent);
+    self.touch(new_parent);
+    Ok(())
+  }
+
+  fn link(&mut self, oldpath: &Path, newpath: &Path) -> FsResult<()> {
+    let (_, ino) = self.lookup(oldpath, false)?;
+    if self.inode(ino)?.is_dir() {
+      return Err(permission_denied().into());
+    }
+    let (parent, name) = self.lookup_parent(newpath)?;
+    self.check_access(parent, 0o2)?;
+    if self.entries(parent)?.contains_key(&name) {
+      return Err(already_exists().into());
+    }
+    self.entries_mut(parent)?.insert(name, ino);
+    self.inode_mut(ino)?.nlink += 1;
+    self.touch(parent);
+    Ok(())
+  }
+
+  fn truncate(&mut self, ino: u64, len: u64) -> io::Result<()> {
+    if len > self.max_file_size {
+      return Err(file_too_large());
+    }
+    let inode = self.inode_mut(ino)?;
+    match &mut inode.kind {
+      NodeKind::File(data) => data.resize(len as usize, 0),
+      _ => return Err(is_a_directory()),
+    }
+    inode.mtime = now_ms();
+    Ok(())
+  }
+}
+
+/// An open handle to a file in an `InMemoryFs`.
+pub struct InMemoryFile {
+  fs: InMemoryFs,
+  ino: u64,
+  pos: Cell<u64>,
+  readable: bool,
+  writable: bool,
+  append: bool,
+  /// The lock held through this handle, if any. `Some(true)` is exclusive.
+  held_lock: Cell<Option<bool>>,
+}
+
+impl InMemoryFile {
+  fn read_to_buf(&self, buf: &mut [u8]) -> FsResult<usize> {
+    if !self.readable {
+      return Err(bad_file_descriptor().into());
+    }
+    let mut state = self.fs.0.lock();
+    let inode = state.inode_mut(self.ino)?;
+    let data = match &inode.kind {
+      NodeKind::File(data) => data,
+      _ => return Err(is_a_directory().into()),
+    };
+    let pos = std::cmp::min(self.pos.get(), data.len() as u64) as usize;
+    let nread = std::cmp::min(buf.len(), data.len() - pos);
+    buf[..nread].copy_from_slice(&data[pos..pos + nread]);
+    inode.atime = now_ms();
+    self.pos.set((pos + nread) as u64);
+    Ok(nread)
+  }
+
+  fn read_to_end(&self) -> FsResult<Vec<u8>> {
+    if !self.readable {
+      return Err(bad_file_descriptor().into());
+    }
+    let mut state = self.fs.0.lock();
+    let inode = state.inode_mut(self.ino)?;
+    let data = match &inode.kind {
+      NodeKind::File(data) => data,
+      _ => return Err(is_a_directory().into()),
+    };
+    let pos = std::cmp::min(self.pos.get(), data.len() as u64) as usize;
+    let buf = data[pos..].to_vec();
+    self.pos.set(data.len() as u64);
+    inode.atime = now_ms();
+    Ok(buf)
+  }
+
+  fn write_from_buf(&self, buf: &[u8]) -> FsResult<usize> {
+    if !self.writable {
+      return Err(bad_file_descriptor().into());
+    }
+    let mut state = self.fs.0.lock();
+    let max_file_size = state.max_file_size;
+    let inode = state.inode_mut(self.ino)?;
+    let data = match &mut inode.kind {
+      NodeKind::File(data) => data,
+      _ => return Err(is_a_directory().into()),
+    };
+    let pos = if self.append {
+      data.len() as u64
+    } else {
+      self.pos.get()
+    };
+    let end = pos
+      .checked_add(buf.len() as u64)
+      .filter(|end| *end <= max_file_size)
+      .ok_or_else(file_too_large)?;
+    let (pos, end) = (pos as usize, end as usize);
+    if data.len() < end {
+      data.resize(end, 0);
+    }
+    data[pos..end].copy_from_slice(buf);
+    inode.mtime = now_ms();
+    self.pos.set(end as u64);
+    Ok(buf.len())
+  }
+
+  fn seek(&self, pos: SeekFrom) -> FsResult<u64> {
+    let new_pos = match pos {
+      SeekFrom::Start(offset) => {
+        self.pos.set(offset);
+        return Ok(offset);
+      }
+      SeekFrom::End(offset) => {
+        let state = self.fs.0.lock();
+        state.inode(self.ino)?.len() as i64 + offset
+      }
+      SeekFrom::Current(offset) => self.pos.get() as i64 + offset,
+    };
+    if new_pos < 0 {
+      return Err(invalid_argument().into());
+    }
+    self.pos.set(new_pos as u64);
+    Ok(new_pos as u64)
+  }
+
+  /// Attempts to take the lock without waiting. Returns `false` when another
+  /// handle holds a conflicting lock.
+  fn try_lock(&self, exclusive: bool) -> FsResult<bool> {
+    let mut state = self.fs.0.lock();
+    let inode = state.inode_mut(self.ino)?;
+    let held = self.held_lock.get();
+    let next = match (inode.lock, held, exclusive) {
+      (_, Some(held), _) if held == exclusive => return Ok(true),
+      (LockState::Unlocked, _, true) => LockState::Exclusive,
+      (LockState::Unlocked, _, false) => LockState::Shared(1),
+      // downgrading our own exclusive lock
+      (LockState::Exclusive, Some(true), false) => LockState::Shared(1),
+      // upgrading our own shared lock when nobody else holds one
+      (LockState::Shared(1), Some(false), true) => LockState::Exclusive,
+      (LockState::Shared(n), None, false) => LockState::Shared(n + 1),
+      _ => return Ok(false),
+    };
+    inode.lock = next;
+    self.held_lock.set(Some(exclusive));
+    Ok(true)
+  }
+
+  fn unlock(&self) -> FsResult<()> {
+    let Some(exclusive) = self.held_lock.take() else {
+      return Ok(());
+    };
+    let mut state = self.fs.0.lock();
+    let inode = state.inode_mut(self.ino)?;
+    inode.lock = match inode.lock {
+      LockState::Shared(n) if !exclusive && n > 1 => LockState::Shared(n - 1),
+      _ => LockState::Unlocked,
+    };
+    Ok(())
+  }
+
+  fn utime(
+    &self,
+    atime_secs: i64,
+    atime_nanos: u32,
+    mtime_secs: i64,
+    mtime_nanos: u32,
+  ) -> FsResult<()> {
+    let mut state = self.fs.0.lock();
+    let inode = state.inode_mut(self.ino)?;
+    inode.atime = to_ms(atime_secs, atime_nanos);
+    inode.mtime = to_ms(mtime_secs, mtime_nanos);
+    Ok(())
+  }
+}
+
+impl Drop for InMemoryFile {
+  fn drop(&mut self) {
+    let _ = self.unlock();
+    let mut state = self.fs.0.lock();
+    if let Some(inode) = state.inodes.get_mut(&self.ino) {
+      inode.open_handles -= 1;
+    }
+    state.collect(self.ino);
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl File for InMemoryFile {
+  fn read_sync(self: Rc<Self>, buf: &mut [u8]) -> FsResult<usize> {
+    self.read_to_buf(buf)
+  }
+  async fn read_byob(
+    self: Rc<Self>,
+    mut buf: BufMutView,
+  ) -> FsResult<(usize, BufMutView)> {
+    let nread = self.read_to_buf(&mut buf)?;
+    Ok((nread, buf))
+  }
+
+  fn write_sync(self: Rc<Self>, buf: &[u8]) -> FsResult<usize> {
+    self.write_from_buf(buf)
+  }
+  async fn write(
+    self: Rc<Self>,
+    buf: BufView,
+  ) -> FsResult<deno_core::WriteOutcome> {
+    let nwritten = self.write_from_buf(&buf)?;
+    Ok(deno_core::WriteOutcome::Full { nwritten })
+  }
+
+  fn write_all_sync(self: Rc<Self>, buf: &[u8]) -> FsResult<()> {
+    self.write_from_buf(buf)?;
+    Ok(())
+  }
+  async fn write_all(self: Rc<Self>, buf: BufView) -> FsResult<()> {
+    self.write_from_buf(&buf)?;
+    Ok(())
+  }
+
+  fn read_all_sync(self: Rc<Self>) -> FsResult<Vec<u8>> {
+    self.read_to_end()
+  }
+  async fn read_all_async(self: Rc<Self>) -> FsResult<Vec<u8>> {
+    self.read_to_end()
+  }
+
+  fn chmod_sync(self: Rc<Self>, mode: u32) -> FsResult<()> {
+    let mut state = self.fs.0.lock();
+    state.inode_mut(self.ino)?.mode = mode & 0o7777;
+    Ok(())
+  }
+  async fn chmod_async(self: Rc<Self>, mode: u32) -> FsResult<()> {
+    self.chmod_sync(mode)
+  }
+
+  fn seek_sync(self: Rc<Self>, pos: SeekFrom) -> FsResult<u64> {
+    self.seek(pos)
+  }
+  async fn seek_async(self: Rc<Self>, pos: SeekFrom) -> FsResult<u64> {
+    self.seek(pos)
+  }
+
+  fn datasync_sync(self: Rc<Self>) -> FsResult<()> {
+    Ok(())
+  }
+  async fn datasync_async(self: Rc<Self>) -> FsResult<()> {
+    Ok(())
+  }
+
+  fn sync_sync(self: Rc<Self>) -> FsResult<()> {
+    Ok(())
+  }
+  async fn sync_async(self: Rc<Self>) -> FsResult<()> {
+    Ok(())
+  }
+
+  fn stat_sync(self: Rc<Self>) -> FsResult<FsStat> {
+    self.fs.0.lock().stat(self.ino)
+  }
+  async fn stat_async(self: Rc<Self>) -> FsResult<FsStat> {
+    self.stat_sync()
+  }
+
+  // See the `InMemoryFs` docs for why a contended synchronous lock fails
+  // with `WouldBlock` rather than blocking.
+  fn lock_sync(self: Rc<Self>, exclusive: bool) -> FsResult<()> {
+    if self.try_lock(exclusive)? {
+      Ok(())
+    } else {
+      Err(would_block().into())
+    }
+  }
+  async fn lock_async(self: Rc<Self>, exclusive: bool) -> FsResult<()> {
+    let mut backoff = Duration::from_millis(1);
+    while !self.try_lock(exclusive)? {
+      tokio::time::sleep(backoff).await;
+      backoff = std::cmp::min(backoff * 2, Duration::from_millis(50));
+    }
+    Ok(())
+  }
+
+  fn unlock_sync(self: Rc<Self>) -> FsResult<()> {
+    self.unlock()
+  }
+  async fn unlock_async(self: Rc<Self>) -> FsResult<()> {
+    self.unlock()
+  }
+
+  fn truncate_sync(self: Rc<Self>, len: u64) -> FsResult<()> {
+    if !self.writable {
+      return Err(invalid_argument().into());
+    }
+    Ok(self.fs.0.lock().truncate(self.ino, len)?)
+  }
+  async fn truncate_async(self: Rc<Self>, len: u64) -> FsResult<()> {
+    self.truncate_sync(len)
+  }
+
+  fn utime_sync(
+    self: Rc<Self>,
+    atime_secs: i64,
+    atime_nanos: u32,
+    mtime_secs: i64,
+    mtime_nanos: u32,
+  ) -> FsResult<()> {
+    self.utime(atime_secs, atime_nanos, mtime_secs, mtime_nanos)
+  }
+  async fn utime_async(
+    self: Rc<Self>,
+    atime_secs: i64,
+    atime_nanos: u32,
+    mtime_secs: i64,
+    mtime_nanos: u32,
+  ) -> FsResult<()> {
+    self.utime(atime_secs, atime_nanos, mtime_secs, mtime_nanos)
+  }
+
+  // lower level functionality
+  fn as_stdio(self: Rc<Self>) -> FsResult<std::process::Stdio> {
+    Err(FsError::NotSupported)
+  }
+  fn backing_fd(self: Rc<Self>) -> Option<ResourceHandleFd> {
+    None
+  }
+  fn try_clone_inner(self: Rc<Self>) -> FsResult<Rc<dyn File>> {
+    Ok(self)
+  }
+}
+
+fn root_path() -> PathBuf {
+  PathBuf::from("/")
+}
+
+fn now_ms() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|t| t.as_millis() as u64)
+    .unwrap_or(0)
+}
+
+fn to_ms(secs: i64, nanos: u32) -> u64 {
+  if secs < 0 {
+    return 0;
+  }
+  secs as u64 * 1000 + (nanos / 1_000_000) as u64
+}
+
+macro_rules! os_error {
+  ($name:ident, $errno:ident, $kind:ident, $msg:literal) => {
+    fn $name() -> io::Error {
+      #[cfg(unix)]
+      {
+        io::Error::from_raw_os_error(libc::$errno)
+      }
+      #[cfg(not(unix))]
+      {
+        io::Error::new(io::ErrorKind::$kind, $msg)
+      }
+    }
+  };
+}
+
+os_error!(not_found, ENOENT, NotFound, "No such file or directory");
+os_error!(already_exists, EEXIST, AlreadyExists, "File exists");
+os_error!(
+  permission_denied,
+  EACCES,
+  PermissionDenied,
+  "Permission denied"
+);
+os_error!(not_a_directory, ENOTDIR, Other, "Not a directory");
+os_error!(is_a_directory, EISDIR, Other, "Is a directory");
+os_error!(directory_not_empty, ENOTEMPTY, Other, "Directory not empty");
+os_error!(invalid_argument, EINVAL, InvalidInput, "Invalid argument");
+os_error!(bad_file_descriptor, EBADF, Other, "Bad file descriptor");
+os_error!(file_too_large, EFBIG, Other, "File too large");
+os_error!(
+  filesystem_loop,
+  ELOOP,
+  Other,
+  "Too many levels of symbolic links"
+);
+os_error!(
+  would_block,
+  EWOULDBLOCK,
+  WouldBlock,
+  "Resource temporarily unavailable"
+);
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn write_options() -> OpenOptions {
+    OpenOptions::write(true, false, false, None)
+  }
+
+  #[test]
+  fn read_write_files() {
+    let fs = InMemoryFs::new();
+    fs.mkdir_sync(Path::new("/a/b"), true, 0o755).unwrap();
+    fs.write_file_sync(Path::new("/a/b/c.txt"), write_options(), b"hello")
+      .unwrap();
+    assert_eq!(
+      fs.read_file_sync(Path::new("/a/b/c.txt")).unwrap(),
+      b"hello"
+    );
+
+    fs.chdir(Path::new("/a")).unwrap();
+    assert_eq!(fs.cwd().unwrap(), PathBuf::from("/a"));
+    assert_eq!(
+      fs.read_file_sync(Path::new("b/../b/c.txt")).unwrap(),
+      b"hello"
+    );
+
+    let append = OpenOptions::write(true, true, false, None);
+    fs.write_file_sync(Path::new("b/c.txt"), append, b" world")
+      .unwrap();
+    assert_eq!(
+      fs.read_text_file_sync(Path::new("/a/b/c.txt")).unwrap(),
+      "hello world"
+    );
+
+    let entries = fs.read_dir_sync(Path::new("/a/b")).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].name, "c.txt");
+    assert!(entries[0].is_file);
+
+    let err = fs.remove_sync(Path::new("/a"), false).unwrap_err();
+    assert_eq!(
+      err.into_io_error().to_string(),
+      directory_not_empty().to_string()
+    );
+    fs.remove_sync(Path::new("/a"), true).unwrap();
+    assert!(!fs.exists_sync(Path::new("/a/b/c.txt")));
+  }
+
+  #[test]
+  fn seek_and_truncate() {
+    let fs = InMemoryFs::new();
+    let mut options = write_options();
+    options.read = true;
+    let file = fs.open_sync(Path::new("/file"), options).unwrap();
+    file.clone().write_all_sync(b"0123456789").unwrap();
+    file.clone().seek_sync(SeekFrom::Start(2)).unwrap();
+    let mut buf = [0; 2];
+    file.clone().read_sync(&mut buf).unwrap();
+    assert_eq!(&buf, b"23");
+    assert_eq!(file.clone().seek_sync(SeekFrom::End(-1)).unwrap(), 9);
+    assert!(file.clone().seek_sync(SeekFrom::Current(-20)).is_err());
+    file.clone().truncate_sync(4).unwrap();
+    assert_eq!(file.clone().stat_sync().unwrap().size, 4);
+    // writing past the end fills the gap with zeroes
+    file.clone().seek_sync(SeekFrom::Start(6)).unwrap();
+    file.clone().write_all_sync(b"x").unwrap();
+    assert_eq!(fs.read_file_sync(Path::new("/file")).unwrap(), b"0123\0\0x");
+  }
+
+  #[test]
+  fn max_file_size() {
+    let fs = InMemoryFs::new().with_max_file_size(8);
+    let file = fs.open_sync(Path::new("/file"), write_options()).unwrap();
+    file.clone().truncate_sync(8).unwrap();
+    assert!(file.clone().truncate_sync(9).is_err());
+    assert!(file.clone().truncate_sync(u64::MAX).is_err());
+    assert!(fs.truncate_sync(Path::new("/file"), 9).is_err());
+
+    file.clone().seek_sync(SeekFrom::Start(6)).unwrap();
+    assert_eq!(file.clone().write_sync(b"xy").unwrap(), 2);
+    assert!(file.clone().write_sync(b"z").is_err());
+    file.clone().seek_sync(SeekFrom::Start(u64::MAX)).unwrap();
+    assert!(file.clone().write_sync(b"z").is_err());
+    assert_eq!(fs.stat_sync(Path::new("/file")).unwrap().size, 8);
+  }
+
+  #[test]
+  fn permissions_and_umask() {
+    let fs = InMemoryFs::new();
+    fs.umask(Some(0o077)).unwrap();
+    fs.write_file_sync(Path::new("/secret"), write_options(), b"data")
+      .unwrap();
+    let stat = fs.stat_sync(Path::new("/secret")).unwrap();
+    assert_eq!(stat.mode, S_IFREG | 0o600);
+
+    fs.chmod_sync(Path::new("/secret"), 0o200).unwrap();
+    let err = fs.read_file_sync(Path::new("/secret")).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+
+    fs.chown_sync(Path::new("/secret"), Some(1000), None)
+      .unwrap();
+    let stat = fs.stat_sync(Path::new("/secret")).unwrap();
+    assert_eq!((stat.uid, stat.gid), (1000, 0));
+  }
+
+  #[test]
+  fn symlinks_and_hard_links() {
+    let fs = InMemoryFs::new();
+    fs.mkdir_sync(Path::new("/dir"), false, 0o755).unwrap();
+    fs.write_file_sync(Path::new("/dir/file"), write_options(), b"data")
+      .unwrap();
+    fs.symlink_sync(Path::new("dir"), Path::new("/link"), None)
+      .unwrap();
+    assert_eq!(fs.read_file_sync(Path::new("/link/file")).unwrap(), b"data");
+    assert_eq!(
+      fs.realpath_sync(Path::new("/link/file")).unwrap(),
+      PathBuf::from("/dir/file")
+    );
+    assert!(fs.lstat_sync(Path::new("/link")).unwrap().is_symlink);
+    assert!(fs.stat_sync(Path::new("/link")).unwrap().is_directory);
+    assert_eq!(
+      fs.read_link_sync(Path::new("/link")).unwrap(),
+      PathBuf::from("dir")
+    );
+
+    fs.symlink_sync(Path::new("/loop"), Path::new("/loop"), None)
+      .unwrap();
+    assert!(fs.stat_sync(Path::new("/loop")).is_err());
+
+    fs.link_sync(Path::new("/dir/file"), Path::new("/hard"))
+      .unwrap();
+    assert_eq!(fs.stat_sync(Path::new("/hard")).unwrap().nlink, 2);
+    fs.remove_sync(Path::new("/dir/file"), false).unwrap();
+    assert_eq!(fs.read_file_sync(Path::new("/hard")).unwrap(), b"data");
+    assert_eq!(fs.stat_sync(Path::new("/hard")).unwrap().nlink, 1);
+  }
+
+  #[test]
+  fn rename_and_timestamps() {
+    let fs = InMemoryFs::new();
+    fs.mkdir_sync(Path::new("/a"), false, 0o755).unwrap();
+    fs.write_file_sync(Path::new("/a/f"), write_options(), b"1")
+      .unwrap();
+    fs.utime_sync(Path::new("/a/f"), 1, 0, 2, 5_000_000)
+      .unwrap();
+    let stat = fs.stat_sync(Path::new("/a/f")).unwrap();
+    assert_eq!(stat.atime, Some(1000));
+    assert_eq!(stat.mtime, Some(2005));
+
+    fs.rename_sync(Path::new("/a"), Path::new("/b")).unwrap();
+    assert!(fs.exists_sync(Path::new("/b/f")));
+    assert!(!fs.exists_sync(Path::new("/a")));
+    assert!(fs.rename_sync(Path::new("/b"), Path::new("/b/c")).is_err());
+
+    fs.copy_file_sync(Path::new("/b/f"), Path::new("/g"))
+      .unwrap();
+    assert_eq!(fs.read_file_sync(Path::new("/g")).unwrap(), b"1");
+  }
+
+  #[test]
+  fn file_locking() {
+    let fs = InMemoryFs::new();
+    fs.write_file_sync(Path::new("/f"), write_options(), b"")
+      .unwrap();
+    let a = fs.open_sync(Path::new("/f"), OpenOptions::read()).unwrap();
+    let b = fs.open_sync(Path::new("/f"), OpenOptions::read()).unwrap();
+    a.clone().lock_sync(false).unwrap();
+    b.clone().lock_sync(false).unwrap();
+    assert!(b.clone().lock_sync(true).is_err());
+    a.clone().unlock_sync().unwrap();
+    b.clone().lock_sync(true).unwrap();
+    assert!(a.clone().lock_sync(false).is_err());
+    drop(b);
+    a.clone().lock_sync(true).unwrap();
+  }
+}
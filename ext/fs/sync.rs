@@ -36,6 +36,12 @@ mod inner {
     }
   }
 
+  impl<T> Clone for MaybeArcMutex<T> {
+    fn clone(&self) -> Self {
+      Self(self.0.clone())
+    }
+  }
+
   impl<'lock, T> MaybeArcMutex<T> {
     pub fn lock(&'lock self) -> MaybeArcMutexGuard<'lock, T> {
       MaybeArcMutexGuard(self.0.lock().unwrap())
@@ -77,6 +83,12 @@ mod inner {
     }
   }
 
+  impl<T> Clone for MaybeArcMutex<T> {
+    fn clone(&self) -> Self {
+      Self(self.0.clone())
+    }
+  }
+
   impl<'lock, T> MaybeArcMutex<T> {
     pub fn lock(&'lock self) -> MaybeArcMutexGuard<'lock, T> {
       MaybeArcMutexGuard(self.0.borrow_mut())
@@ -13,6 +13,10 @@ import {
   toInnerResponse,
 } from "ext:deno_fetch/23_response.js";
 import { fromInnerRequest, toInnerRequest } from "ext:deno_fetch/23_request.js";
+import {
+  isTracingEnabled,
+  runWithTraceparent,
+} from "ext:deno_fetch/25_trace_context.js";
 import { AbortController } from "ext:deno_web/03_abort_signal.js";
 import {
   _eventLoop,
@@ -34,14 +38,32 @@ import {
   ReadableStreamPrototype,
   resourceForReadableStream,
 } from "ext:deno_web/06_streams.js";
-import { listen, listenOptionApiName, TcpConn } from "ext:deno_net/01_net.js";
-import { listenTls } from "ext:deno_net/02_tls.js";
+import {
+  listen,
+  listenFd,
+  listenOptionApiName,
+  TcpConn,
+} from "ext:deno_net/01_net.js";
+import {
+  detachCertificates,
+  listenTls,
+  releaseCertificates,
+  TlsListener,
+} from "ext:deno_net/02_tls.js";
 const {
+  ArrayIsArray,
+  ArrayPrototypeEvery,
+  ArrayPrototypeIncludes,
   ArrayPrototypePush,
+  ArrayPrototypeSome,
+  MathFloor,
+  NumberIsFinite,
+  NumberIsInteger,
   ObjectHasOwn,
   ObjectPrototypeIsPrototypeOf,
   PromisePrototypeCatch,
   Symbol,
+  StringPrototypeToLowerCase,
   SymbolFor,
   TypeError,
   Uint8Array,
@@ -51,9 +73,14 @@ const {
 const {
   op_http_get_request_headers,
   op_http_get_request_method_and_url,
+  op_http_get_request_peer_certificates,
+  op_http_get_request_traceparent,
+  op_http_next_connection,
   op_http_read_request_body,
   op_http_serve,
   op_http_serve_on,
+  op_http_serve_h3,
+  op_http_set_h3_certificates,
   op_http_set_promise_complete,
   op_http_set_response_body_bytes,
   op_http_set_response_body_resource,
@@ -61,6 +88,7 @@ const {
   op_http_set_response_header,
   op_http_set_response_headers,
   op_http_set_response_trailers,
+  op_http_stats,
   op_http_upgrade_raw,
   op_http_upgrade_websocket_next,
   op_http_try_wait,
@@ -127,6 +155,7 @@ class InnerRequest {
   #body;
   #upgraded;
   #urlValue;
+  #peerCertificates;
 
   constructor(slabId, context) {
     this.#slabId = slabId;
@@ -292,6 +321,18 @@ class InnerRequest {
     };
   }
 
+  get peerCertificates() {
+    if (this.#peerCertificates === undefined) {
+      if (this.#slabId === undefined) {
+        throw new TypeError("request closed");
+      }
+      this.#peerCertificates = op_http_get_request_peer_certificates(
+        this.#slabId,
+      );
+    }
+    return this.#peerCertificates;
+  }
+
   get method() {
     if (this.#methodAndUri === undefined) {
       if (this.#slabId === undefined) {
@@ -339,6 +380,7 @@ class InnerRequest {
 
 class CallbackContext {
   abortController;
+  altSvc;
   scheme;
   fallbackHost;
   serverRid;
@@ -356,6 +398,7 @@ class CallbackContext {
       { once: true },
     );
     this.abortController = new AbortController();
+    this.altSvc = null;
     this.serverRid = args[0];
     this.scheme = args[1];
     this.fallbackHost = args[2];
@@ -381,6 +424,13 @@ class ServeHandlerInfo {
   get remoteAddr() {
     return this.#inner.remoteAddr;
   }
+  get peerCertificates() {
+    return this.#inner.peerCertificates;
+  }
+}
+
+function isAltSvcHeader(header) {
+  return StringPrototypeToLowerCase(header[0]) === "alt-svc";
 }
 
 function fastSyncResponseOrStream(req, respBody, status) {
@@ -438,7 +488,7 @@ function mapToCallback(context, callback, onError) {
   const hasCallback = callback.length > 0;
   const hasOneCallback = callback.length === 1;
 
-  return async function (req) {
+  const handleRequest = async function (req) {
     // Get the response from the user-provided callback. If that fails, use onError. If that fails, return a fallback
     // 500 error.
     let innerRequest;
@@ -495,10 +545,107 @@ function mapToCallback(context, callback, onError) {
         op_http_set_response_headers(req, headers);
       }
     }
+    if (
+      context.altSvc !== null &&
+      !(headers && ArrayPrototypeSome(headers, isAltSvcHeader))
+    ) {
+      op_http_set_response_header(req, "alt-svc", context.altSvc);
+    }
 
     fastSyncResponseOrStream(req, inner.body, status);
     innerRequest?.close();
   };
+
+  if (!isTracingEnabled()) {
+    return handleRequest;
+  }
+  // Let `fetch` calls made by the handler continue the trace of the request.
+  return function (req) {
+    const traceparent = op_http_get_request_traceparent(req);
+    if (traceparent === null) {
+      return handleRequest(req);
+    }
+    return runWithTraceparent(traceparent, handleRequest, req);
+  };
+}
+
+/**
+ * Read one of the numeric limit options of `Deno.serve`, which must be a positive, finite number.
+ */
+function serveLimit(options, name) {
+  const value = options[name];
+  if (value === undefined) {
+    return null;
+  }
+  if (typeof value !== "number" || !NumberIsFinite(value) || value <= 0) {
+    throw new TypeError(`${name} must be a positive, finite number.`);
+  }
+  return MathFloor(value);
+}
+
+const COMPRESSION_ENCODINGS = ["br", "gzip", "zstd"];
+
+/**
+ * Normalize the `compression` option of `Deno.serve`. `null` leaves the defaults in place.
+ */
+function serveCompression(compression) {
+  if (compression === undefined || compression === true) {
+    return null;
+  }
+  if (compression === false) {
+    return { enabled: false };
+  }
+  if (typeof compression !== "object" || compression === null) {
+    throw new TypeError("compression must be a boolean or an object.");
+  }
+  const config = { enabled: true };
+  const { encodings, level, minSize, contentTypes, decompressRequests } =
+    compression;
+  if (encodings !== undefined) {
+    if (
+      !ArrayIsArray(encodings) ||
+      !ArrayPrototypeEvery(
+        encodings,
+        (encoding) => ArrayPrototypeIncludes(COMPRESSION_ENCODINGS, encoding),
+      )
+    ) {
+      throw new TypeError(
+        'compression.encodings must be an array of "br", "gzip" or "zstd".',
+      );
+    }
+    config.encodings = encodings;
+  }
+  if (level !== undefined) {
+    if (!NumberIsInteger(level) || level < 0) {
+      throw new TypeError(
+        "compression.level must be a non-negative integer.",
+      );
+    }
+    config.level = level;
+  }
+  if (minSize !== undefined) {
+    if (!NumberIsInteger(minSize) || minSize < 0) {
+      throw new TypeError(
+        "compression.minSize must be a non-negative integer.",
+      );
+    }
+    config.minSize = minSize;
+  }
+  if (contentTypes !== undefined) {
+    if (
+      !ArrayIsArray(contentTypes) ||
+      !ArrayPrototypeEvery(contentTypes, (type) => typeof type === "string")
+    ) {
+      throw new TypeError(
+        "compression.contentTypes must be an array of strings.",
+      );
+    }
+    config.contentTypes = contentTypes;
+  }
+  if (decompressRequests !== undefined) {
+    config.decompressRequests = !!decompressRequests;
+  }
+  return config;
 }
 
 function serve(arg1, arg2) {
@@ -527,8 +674,17 @@ function serve(arg1, arg2) {
     options = {};
   }
 
-  const wantsHttps = options.cert || options.key;
+  const limits = {
+    maxRequestBodySize: serveLimit(options, "maxRequestBodySize"),
+    maxHeaderSize: serveLimit(options, "maxHeaderSize"),
+    headerReadTimeout: serveLimit(options, "headerReadTimeout"),
+    idleTimeout: serveLimit(options, "idleTimeout"),
+  };
+  const compression = serveCompression(options.compression);
+
+  const wantsHttps = options.cert || options.key || options.sni;
   const wantsUnix = ObjectHasOwn(options, "path");
+  const wantsFd = ObjectHasOwn(options, "fd");
   const signal = options.signal;
   const onError = options.onError ?? function (error) {
     console.error(error);
@@ -542,13 +698,56 @@ function serve(arg1, arg2) {
       [listenOptionApiName]: "Deno.serve",
     });
     const path = listener.addr.path;
-    return serveHttpOnListener(listener, signal, handler, onError, () => {
-      if (options.onListen) {
-        options.onListen({ path });
-      } else {
-        console.log(`Listening on ${path}`);
-      }
-    });
+    return serveHttpOnListener(
+      listener,
+      signal,
+      handler,
+      onError,
+      () => {
+        if (options.onListen) {
+          options.onListen({ path });
+        } else {
+          console.log(`Listening on ${path}`);
+        }
+      },
+      { onConnection: options.onConnection, limits, compression },
+    );
+  }
+
+  if (wantsFd) {
+    const fd = options.fd;
+    if (fd !== "LISTEN_FDS" && (!NumberIsInteger(fd) || fd < 0)) {
+      throw new TypeError(
+        'fd must be a non-negative integer or "LISTEN_FDS".',
+      );
+    }
+    if (wantsHttps || wantsUnix) {
+      throw new TypeError(
+        "fd can not be used together with cert, key or path.",
+      );
+    }
+    const listener = listenFd(fd, "Deno.serve");
+    const addr = listener.addr;
+    return serveHttpOnListener(
+      listener,
+      signal,
+      handler,
+      onError,
+      () => {
+        if (addr.transport === "unix") {
+          if (options.onListen) {
+            options.onListen({ path: addr.path });
+          } else {
+            console.log(`Listening on ${addr.path ?? `fd ${fd}`}`);
+          }
+        } else if (options.onListen) {
+          options.onListen({ hostname: addr.hostname, port: addr.port });
+        } else {
+          console.log(`Listening on http://${addr.hostname}:${addr.port}/`);
+        }
+      },
+      { onConnection: options.onConnection, limits, compression },
+    );
   }
 
   const listenOpts = {
@@ -568,18 +767,43 @@ function serve(arg1, arg2) {
     );
   }
 
+  if (options.http3 && !wantsHttps) {
+    throw new TypeError("HTTP/3 requires cert and key to be provided.");
+  }
+  if (options.http3 && (options.sni || options.clientAuth)) {
+    throw new TypeError(
+      "HTTP/3 can not be used together with sni or clientAuth.",
+    );
+  }
+  if (options.clientAuth && !wantsHttps) {
+    throw new TypeError("clientAuth requires cert and key to be provided.");
+  }
+
   let listener;
+  let http3;
   if (wantsHttps) {
-    if (!options.cert || !options.key) {
+    // With sni, the default certificate is optional
+    if (!options.cert !== !options.key) {
       throw new TypeError(
         "Both cert and key must be provided to enable HTTPS.",
       );
     }
     listenOpts.cert = options.cert;
     listenOpts.key = options.key;
+    listenOpts.sni = options.sni;
+    listenOpts.clientAuth = options.clientAuth;
     listenOpts.alpnProtocols = ["h2", "http/1.1"];
     listener = listenTls(listenOpts);
     listenOpts.port = listener.addr.port;
+    if (options.http3) {
+      // QUIC binds the UDP port with the same number as the TLS listener
+      http3 = {
+        hostname: listener.addr.hostname,
+        port: listener.addr.port,
+        cert: options.cert,
+        key: options.key,
+      };
+    }
   } else {
     listener = listen(listenOpts);
     listenOpts.port = listener.addr.port;
@@ -601,25 +825,109 @@ function serve(arg1, arg2) {
     }
   };
 
-  return serveHttpOnListener(listener, signal, handler, onError, onListen);
+  return serveHttpOnListener(
+    listener,
+    signal,
+    handler,
+    onError,
+    onListen,
+    { http3, onConnection: options.onConnection, limits, compression },
+  );
 }
 
 /**
- * Serve HTTP/1.1 and/or HTTP/2 on an arbitrary listener.
+ * Serve HTTP/1.1 and/or HTTP/2 on an arbitrary listener, optionally with an HTTP/3 listener on the
+ * matching UDP port.
  */
-function serveHttpOnListener(listener, signal, handler, onError, onListen) {
-  const context = new CallbackContext(
-    signal,
-    op_http_serve(listener.rid),
-    listener,
-  );
+function serveHttpOnListener(
+  listener,
+  signal,
+  handler,
+  onError,
+  onListen,
+  { http3, onConnection, limits = {}, compression = null } = {},
+) {
+  if (onConnection !== undefined && typeof onConnection !== "function") {
+    throw new TypeError("onConnection must be a function.");
+  }
+  const isTls = ObjectPrototypeIsPrototypeOf(TlsListener.prototype, listener);
+  if (isTls) {
+    listener[detachCertificates]();
+  }
+  let serverRid;
+  try {
+    serverRid = op_http_serve(listener.rid, {
+      trackConnections: onConnection !== undefined,
+      maxRequestBodySize: limits.maxRequestBodySize ?? null,
+      maxHeaderSize: limits.maxHeaderSize ?? null,
+      headerReadTimeout: limits.headerReadTimeout ?? null,
+      idleTimeout: limits.idleTimeout ?? null,
+      compression,
+    });
+  } catch (error) {
+    if (isTls) {
+      listener[releaseCertificates]();
+    }
+    throw error;
+  }
+  const context = new CallbackContext(signal, serverRid, listener);
+  if (http3 !== undefined) {
+    let port;
+    try {
+      port = op_http_serve_h3(context.serverRid, http3);
+    } catch (error) {
+      context.close();
+      throw error;
+    }
+    context.altSvc = `h3=":${port}"; ma=86400`;
+  }
   const callback = mapToCallback(context, handler, onError);
+  if (onConnection !== undefined) {
+    watchConnections(context, onConnection);
+  }
 
   onListen(context.scheme);
 
   return serveHttpOn(context, callback);
 }
 
+/**
+ * Calls `onConnection` for every connection the server accepts, until it stops listening.
+ */
+async function watchConnections(context, onConnection) {
+  const promiseIdSymbol = SymbolFor("Deno.core.internalPromiseId");
+  while (true) {
+    let info;
+    try {
+      const promise = op_http_next_connection(context.serverRid);
+      // The request loop is what keeps the event loop alive, not this one
+      core.unrefOp(promise[promiseIdSymbol]);
+      info = await promise;
+    } catch {
+      break;
+    }
+    if (info === null) {
+      break;
+    }
+    const remoteAddr = info.transport === "unix"
+      ? { transport: "unix", path: context.listener.addr.path }
+      : {
+        transport: info.transport,
+        hostname: info.hostname,
+        port: info.port,
+      };
+    try {
+      onConnection({
+        remoteAddr,
+        alpnProtocol: info.alpnProtocol,
+        tls: info.tls,
+      });
+    } catch (error) {
+      console.error("Exception in onConnection", error);
+    }
+  }
+}
+
 /**
  * Serve HTTP/1.1 and/or HTTP/2 on an arbitrary connection.
  */
@@ -684,18 +992,55 @@ function serveHttpOn(context, callback) {
 
     if (!context.closed && !context.closing) {
       context.closed = true;
-      await op_http_close(rid, false);
+      await op_http_close(rid, false, null);
       context.close();
     }
+    const listener = context.listener;
+    if (ObjectPrototypeIsPrototypeOf(TlsListener.prototype, listener)) {
+      listener[releaseCertificates]();
+    }
   })();
 
   return {
     finished,
-    async shutdown() {
+    stats() {
+      return op_http_stats(context.serverRid);
+    },
+    setCertificates(certificates) {
+      if (
+        !ObjectPrototypeIsPrototypeOf(TlsListener.prototype, context.listener)
+      ) {
+        throw new TypeError(
+          "Only servers listening with TLS have certificates.",
+        );
+      }
+      if (context.altSvc !== null) {
+        // The HTTP/3 listener has a single certificate of its own
+        const { cert, key, sni } = certificates ?? {};
+        if (sni !== undefined || cert === undefined || key === undefined) {
+          throw new TypeError(
+            "HTTP/3 servers require cert and key, and can not use sni.",
+          );
+        }
+        op_http_set_h3_certificates(context.serverRid, cert, key);
+      }
+      context.listener.setCertificates(certificates);
+    },
+    async shutdown(options = undefined) {
+      let timeout = options?.timeout ?? null;
+      if (timeout !== null) {
+        if (!NumberIsFinite(timeout) || timeout < 0) {
+          throw new TypeError(
+            "Shutdown timeout must be a non-negative, finite number of milliseconds.",
+          );
+        }
+        timeout = MathFloor(timeout);
+      }
       if (!context.closed && !context.closing) {
-        // Shut this HTTP server down gracefully
+        // Shut this HTTP server down gracefully: stop accepting connections, let in-flight requests
+        // finish and force-cancel whatever is left once the timeout expires.
         context.closing = true;
-        await op_http_close(context.serverRid, true);
+        await op_http_close(context.serverRid, true, timeout);
         context.closed = true;
       }
     },
@@ -23,6 +23,7 @@ import {
 import { AbortController } from "ext:deno_web/03_abort_signal.js";
 import {
   _eventLoop,
+  _extensions,
   _idleTimeoutDuration,
   _idleTimeoutTimeout,
   _protocol,
@@ -32,6 +33,7 @@ import {
   _server,
   _serverHandleIdleTimeout,
   SERVER,
+  toCompressionOptions,
   WebSocket,
 } from "ext:deno_websocket/01_websocket.js";
 import { TcpConn, UnixConn } from "ext:deno_net/01_net.js";
@@ -371,6 +373,7 @@ function createRespondWith(
         const wsRid = await core.opAsync(
           "op_http_upgrade_websocket",
           streamRid,
+          resp.headers.get("sec-websocket-extensions"),
         );
         ws[_rid] = wsRid;
         ws[_protocol] = resp.headers.get("sec-websocket-protocol");
@@ -457,9 +460,30 @@ function upgradeWebSocket(request, options = {}) {
     }
   }
 
+  const compression = toCompressionOptions(
+    options.compression,
+    "Failed to upgrade WebSocket",
+    "options.compression",
+  );
+  let extensions = "";
+  const offers = request.headers.get("sec-websocket-extensions");
+  if (compression !== null && offers !== null) {
+    extensions = ops.op_http_websocket_negotiate_extensions(
+      offers,
+      compression,
+    ) ?? "";
+    if (extensions !== "") {
+      ArrayPrototypePush(r.headerList, [
+        "sec-websocket-extensions",
+        extensions,
+      ]);
+    }
+  }
+
   const socket = webidl.createBranded(WebSocket);
   setEventTargetData(socket);
   socket[_server] = true;
+  socket[_extensions] = extensions;
   socket[_idleTimeoutDuration] = options.idleTimeout ?? 120;
   socket[_idleTimeoutTimeout] = null;
 
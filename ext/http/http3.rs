@@ -0,0 +1,290 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+//! HTTP/3 (QUIC) support for `Deno.serve`. Requests accepted here are pushed into the same slab as
+//! HTTP/1.1 and HTTP/2 requests, so the JavaScript side cannot tell them apart.
+use crate::http_next::decode_request_body;
+use crate::http_next::HttpCompressionConfig;
+use crate::http_next::HttpConnectionInfo;
+use crate::http_next::HttpLifetime;
+use crate::http_next::HttpTlsInfo;
+use crate::limits::limit_body;
+use crate::limits::rejection_response;
+use crate::limits::HttpServeLimits;
+use crate::request_body::BodyStream;
+use crate::request_properties::HttpConnectionProperties;
+use crate::slab::new_slab_future_from_stream;
+use crate::slab::RefCount;
+use crate::slab::SlabId;
+use bytes::Buf;
+use bytes::Bytes;
+use deno_core::error::custom_error;
+use deno_core::error::generic_error;
+use deno_core::error::AnyError;
+use deno_core::futures::future::poll_fn;
+use deno_core::futures::stream;
+use deno_core::unsync::spawn;
+use deno_core::CancelFuture;
+use deno_core::CancelTryFuture;
+use deno_net::raw::NetworkStreamType;
+use deno_tls::load_certs;
+use deno_tls::load_private_keys;
+use deno_tls::rustls::ServerConfig;
+use h3::server::RequestStream;
+use http::header::CONTENT_LENGTH;
+use http::StatusCode;
+use hyper1::body::Body;
+use hyper1::body::SizeHint;
+use quinn::crypto::rustls::HandshakeData;
+use std::io::BufReader;
+use std::net::ToSocketAddrs;
+use std::pin::Pin;
+use std::sync::Arc;
+
+pub const TLS_ALPN_HTTP_3: &[u8] = b"h3";
+
+/// Connection-specific headers are not allowed in HTTP/3 responses (RFC 9114, section 4.2).
+const CONNECTION_SPECIFIC_HEADERS: [&str; 5] = [
+  "connection",
+  "keep-alive",
+  "proxy-connection",
+  "transfer-encoding",
+  "upgrade",
+];
+
+type H3Stream = RequestStream<h3_quinn::BidiStream<Bytes>, Bytes>;
+
+/// Bind a QUIC endpoint that only speaks `h3`.
+pub fn create_endpoint(
+  hostname: &str,
+  port: u16,
+  cert: &str,
+  key: &str,
+) -> Result<quinn::Endpoint, AnyError> {
+  let bind_addr = (hostname, port)
+    .to_socket_addrs()?
+    .next()
+    .ok_or_else(|| generic_error("No resolved address found"))?;
+  let config = server_config(cert, key)?;
+  Ok(quinn::Endpoint::server(config, bind_addr)?)
+}
+
+/// The configuration of a QUIC endpoint that only speaks `h3`, presenting the given certificate.
+pub fn server_config(
+  cert: &str,
+  key: &str,
+) -> Result<quinn::ServerConfig, AnyError> {
+  let cert_chain = load_certs(&mut BufReader::new(cert.as_bytes()))?;
+  let key_der = load_private_keys(key.as_bytes())?.remove(0);
+  let mut tls_config = ServerConfig::builder()
+    .with_safe_defaults()
+    .with_no_client_auth()
+    .with_single_cert(cert_chain, key_der)
+    .map_err(|e| {
+      custom_error(
+        "InvalidData",
+        format!("Error creating TLS certificate: {:?}", e),
+      )
+    })?;
+  tls_config.alpn_protocols = vec![TLS_ALPN_HTTP_3.to_vec()];
+  // 0-RTT stays disabled (rustls' default): early data can be replayed by an attacker, and requests
+  // are handed to JavaScript without knowing whether they arrived before the handshake completed.
+  Ok(quinn::ServerConfig::with_crypto(Arc::new(tls_config)))
+}
+
+/// Accept QUIC connections until the listener is cancelled. Connections that are already open are
+/// allowed to drain unless the connection cancel handle fires.
+pub(crate) async fn serve_http3(
+  endpoint: quinn::Endpoint,
+  lifetime: HttpLifetime,
+  tx: tokio::sync::mpsc::Sender<SlabId>,
+) -> Result<(), AnyError> {
+  let local_port = endpoint.local_addr()?.port();
+  while let Some(connecting) = endpoint.accept().await {
+    let remote_address = connecting.remote_address();
+    let request_info = HttpConnectionProperties {
+      peer_address: remote_address.ip().to_string().into(),
+      peer_port: Some(remote_address.port()),
+      local_port: Some(local_port),
+      stream_type: NetworkStreamType::Tls,
+      peer_certificates: None,
+    };
+    spawn(
+      serve_http3_connection(
+        connecting,
+        request_info,
+        lifetime.clone(),
+        tx.clone(),
+      )
+      .try_or_cancel(lifetime.connection_cancel_handle.clone()),
+    );
+  }
+  Ok(())
+}
+
+async fn serve_http3_connection(
+  connecting: quinn::Connecting,
+  request_info: HttpConnectionProperties,
+  lifetime: HttpLifetime,
+  tx: tokio::sync::mpsc::Sender<SlabId>,
+) -> Result<(), AnyError> {
+  let _connection = lifetime.refcount.0.connection_opened();
+  let connection = connecting.await?;
+  if let Some(connection_tx) = &lifetime.connection_tx {
+    let server_name = connection
+      .handshake_data()
+      .and_then(|data| data.downcast::<HandshakeData>().ok())
+      .and_then(|data| data.server_name);
+    let _ = connection_tx.send(HttpConnectionInfo {
+      transport: "udp",
+      hostname: request_info.peer_address.to_string(),
+      port: request_info.peer_port,
+      alpn_protocol: Some("h3".to_owned()),
+      tls: Some(HttpTlsInfo {
+        server_name,
+        // QUIC always uses TLS 1.3
+        protocol_version: Some("TLSv1_3".to_owned()),
+        cipher_suite: None,
+        peer_certificates: None,
+      }),
+    });
+  }
+  let mut connection: h3::server::Connection<h3_quinn::Connection, Bytes> =
+    h3::server::Connection::new(h3_quinn::Connection::new(connection)).await?;
+
+  let mut shutting_down = false;
+  loop {
+    let accepted = if shutting_down {
+      connection.accept().await
+    } else {
+      let accepted = connection
+        .accept()
+        .or_cancel(lifetime.listen_cancel_handle.clone())
+        .await;
+      match accepted {
+        Ok(accepted) => accepted,
+        Err(_) => {
+          // Send a GOAWAY and keep serving the requests that were already in flight
+          shutting_down = true;
+          connection.shutdown(0).await?;
+          continue;
+        }
+      }
+    };
+    let Some((request, stream)) = accepted? else {
+      return Ok(());
+    };
+    spawn(serve_http3_request(
+      request,
+      stream,
+      request_info.clone(),
+      lifetime.refcount.clone(),
+      tx.clone(),
+      lifetime.limits,
+      lifetime.compression.clone(),
+    ));
+  }
+}
+
+async fn serve_http3_request(
+  request: http::Request<()>,
+  stream: H3Stream,
+  request_info: HttpConnectionProperties,
+  refcount: RefCount,
+  tx: tokio::sync::mpsc::Sender<SlabId>,
+  limits: HttpServeLimits,
+  compression: Arc<HttpCompressionConfig>,
+) -> Result<(), AnyError> {
+  let bytes = refcount.0.bytes().clone();
+  let (mut send, recv) = stream.split();
+  let (mut request_parts, ()) = request.into_parts();
+  if let Some(status) =
+    limits.check_request(&request_parts.headers, &request_parts.uri)
+  {
+    let mut response = http::Response::new(());
+    *response.status_mut() = status;
+    send.send_response(response).await?;
+    send.finish().await?;
+    return Ok(());
+  }
+  let decoding = compression.request_decoding(&request_parts.headers);
+  request_parts.extensions.insert(compression);
+  let mut size_hint = request_parts
+    .headers
+    .get(CONTENT_LENGTH)
+    .and_then(|value| value.to_str().ok())
+    .and_then(|value| value.parse().ok())
+    .map(SizeHint::with_exact)
+    .unwrap_or_default();
+  let read_bytes = bytes.clone();
+  let request_body = stream::unfold(Some(recv), move |recv| {
+    let read_bytes = read_bytes.clone();
+    async move {
+      let mut recv = recv?;
+      loop {
+        match recv.recv_data().await {
+          Ok(Some(mut data)) => {
+            // Ensure that we never yield an empty frame
+            if !data.has_remaining() {
+              continue;
+            }
+            read_bytes.add_read(data.remaining());
+            let bytes = data.copy_to_bytes(data.remaining());
+            break Some((Ok(bytes), Some(recv)));
+          }
+          Ok(None) => break None,
+          Err(e) => break Some((Err(AnyError::from(e)), None)),
+        }
+      }
+    }
+  });
+
+  let mut request_body: BodyStream = Box::pin(request_body);
+  if let Some(encoding) = decoding {
+    request_body =
+      decode_request_body(&mut request_parts, request_body, &encoding);
+    size_hint = SizeHint::default();
+  }
+  let (request_body, body_too_large) = match limits.max_request_body_size {
+    Some(max) => {
+      let (limited, exceeded) = limit_body(request_body, max);
+      (limited, Some(exceeded))
+    }
+    None => (request_body, None),
+  };
+
+  let mut response = new_slab_future_from_stream(
+    request_parts,
+    request_body,
+    size_hint,
+    request_info,
+    refcount,
+    tx,
+  )
+  .await;
+  if body_too_large.is_some_and(|exceeded| exceeded.get()) {
+    response = rejection_response(StatusCode::PAYLOAD_TOO_LARGE);
+  }
+
+  let (mut response_parts, mut body) = response.into_parts();
+  for name in CONNECTION_SPECIFIC_HEADERS {
+    response_parts.headers.remove(name);
+  }
+  send
+    .send_response(http::Response::from_parts(response_parts, ()))
+    .await?;
+  while let Some(frame) = poll_fn(|cx| Pin::new(&mut body).poll_frame(cx)).await
+  {
+    match frame?.into_data() {
+      Ok(data) => {
+        bytes.add_written(data.len());
+        send.send_data(Bytes::from(data)).await?
+      }
+      Err(frame) => {
+        if let Ok(trailers) = frame.into_trailers() {
+          send.send_trailers(trailers).await?;
+        }
+      }
+    }
+  }
+  send.finish().await?;
+  Ok(())
+}
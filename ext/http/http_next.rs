@@ -1,9 +1,19 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 use crate::compressible::is_content_compressible;
 use crate::extract_network_stream;
+use crate::http3::create_endpoint;
+use crate::http3::serve_http3;
+use crate::http3::server_config;
 use crate::hyper_util_tokioio::TokioIo;
+use crate::limits::limit_body;
+use crate::limits::rejection_response;
+use crate::limits::ConnectionLimits;
+use crate::limits::HttpServeLimits;
 use crate::network_buffered_stream::NetworkStreamPrefixCheck;
-use crate::request_body::HttpRequestBody;
+use crate::request_body::decode_body;
+use crate::request_body::incoming_body_stream;
+use crate::request_body::is_decodable;
+use crate::request_body::BodyStream;
 use crate::request_properties::HttpConnectionProperties;
 use crate::request_properties::HttpListenProperties;
 use crate::request_properties::HttpPropertyExtractor;
@@ -11,16 +21,18 @@ use crate::response_body::Compression;
 use crate::response_body::ResponseBytes;
 use crate::response_body::ResponseBytesInner;
 use crate::slab::new_slab_future;
+use crate::slab::new_slab_future_from_stream;
 use crate::slab::slab_get;
 use crate::slab::slab_init;
 use crate::slab::HttpRequestBodyAutocloser;
 use crate::slab::RefCount;
 use crate::slab::SlabId;
+use crate::stats::HttpServerStatsSnapshot;
 use crate::websocket_upgrade::WebSocketUpgrade;
 use crate::LocalExecutor;
 use cache_control::CacheControl;
+use deno_core::error::bad_resource_id;
 use deno_core::error::AnyError;
-use deno_core::futures::TryFutureExt;
 use deno_core::op2;
 use deno_core::serde_v8::from_v8;
 use deno_core::unsync::spawn;
@@ -40,16 +52,23 @@ use deno_core::Resource;
 use deno_core::ResourceId;
 use deno_net::ops_tls::TlsStream;
 use deno_net::raw::NetworkStream;
+use deno_net::raw::NetworkStreamType;
+use deno_tls::certificate_to_pem;
+use deno_tls::rustls::Connection;
 use deno_websocket::ws_create_server_stream;
 use fly_accept_encoding::Encoding;
 use http::header::ACCEPT_ENCODING;
 use http::header::CACHE_CONTROL;
+use http::header::CONNECTION;
 use http::header::CONTENT_ENCODING;
 use http::header::CONTENT_LENGTH;
 use http::header::CONTENT_RANGE;
 use http::header::CONTENT_TYPE;
+use http::request::Parts;
 use http::HeaderMap;
+use hyper1::body::Body;
 use hyper1::body::Incoming;
+use hyper1::body::SizeHint;
 use hyper1::header::COOKIE;
 use hyper1::http::HeaderName;
 use hyper1::http::HeaderValue;
@@ -59,6 +78,8 @@ use hyper1::service::service_fn;
 use hyper1::service::HttpService;
 use hyper1::StatusCode;
 use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde::Serialize;
 use smallvec::SmallVec;
 use std::borrow::Cow;
 use std::cell::RefCell;
@@ -66,6 +87,7 @@ use std::future::Future;
 use std::io;
 use std::pin::Pin;
 use std::rc::Rc;
+use std::sync::Arc;
 use std::time::Duration;
 
 use tokio::io::AsyncReadExt;
@@ -195,7 +217,11 @@ pub async fn op_http_upgrade_websocket_next(
 
   let response = http.response();
   *response.status_mut() = StatusCode::SWITCHING_PROTOCOLS;
+  let mut extensions = None;
   for (name, value) in headers {
+    if name.eq_ignore_ascii_case(b"sec-websocket-extensions") {
+      extensions = Some(String::from_utf8_lossy(&value).into_owned());
+    }
     response.headers_mut().append(
       HeaderName::from_bytes(&name).unwrap(),
       HeaderValue::from_bytes(&value).unwrap(),
@@ -208,7 +234,12 @@ pub async fn op_http_upgrade_websocket_next(
 
   // Stage 3: take the extracted raw network stream and upgrade it to a websocket, then return it
   let (stream, bytes) = extract_network_stream(upgraded);
-  ws_create_server_stream(&mut state.borrow_mut(), stream, bytes)
+  ws_create_server_stream(
+    &mut state.borrow_mut(),
+    stream,
+    bytes,
+    extensions.as_deref(),
+  )
 }
 
 #[op2(fast)]
@@ -286,6 +317,29 @@ where
   v8::Array::new_with_elements(scope, vec.as_slice())
 }
 
+/// The certificate chain the client authenticated with, if the server asked
+/// for one.
+#[op2]
+#[serde]
+pub fn op_http_get_request_peer_certificates(
+  #[smi] slab_id: SlabId,
+) -> Option<Vec<String>> {
+  let http = slab_get(slab_id);
+  let peer_certificates = http.request_info().peer_certificates.as_ref()?;
+  Some(peer_certificates.to_vec())
+}
+
+/// The `traceparent` of the server span tracing the request, which outgoing
+/// `fetch` calls made while handling it continue.
+#[op2]
+#[serde]
+pub fn op_http_get_request_traceparent(
+  #[smi] slab_id: SlabId,
+) -> Option<String> {
+  let http = slab_get(slab_id);
+  http.span_context().map(|context| context.to_traceparent())
+}
+
 #[op2]
 #[serde]
 pub fn op_http_get_request_header(
@@ -372,8 +426,8 @@ pub fn op_http_read_request_body(
   #[smi] slab_id: SlabId,
 ) -> ResourceId {
   let mut http = slab_get(slab_id);
-  let rid = if let Some(incoming) = http.take_body() {
-    let body_resource = Rc::new(HttpRequestBody::new(incoming));
+  let rid = if let Some(body) = http.take_body() {
+    let body_resource = Rc::new(body);
     state.borrow_mut().resource_table.add_rc(body_resource)
   } else {
     // This should not be possible, but rather than panicking we'll return an invalid
@@ -451,14 +505,130 @@ pub fn op_http_set_response_trailers(
   *http.trailers().borrow_mut() = Some(trailer_map);
 }
 
+/// The encodings a server may use for its responses.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+pub enum HttpCompressionEncoding {
+  #[serde(rename = "br")]
+  Brotli,
+  #[serde(rename = "gzip")]
+  GZip,
+  #[serde(rename = "zstd")]
+  Zstd,
+}
+
+const DEFAULT_COMPRESSION_ENCODINGS: [HttpCompressionEncoding; 2] = [
+  HttpCompressionEncoding::Brotli,
+  HttpCompressionEncoding::GZip,
+];
+
+impl HttpCompressionEncoding {
+  fn accept_encoding(&self) -> Encoding {
+    match self {
+      Self::Brotli => Encoding::Brotli,
+      Self::GZip => Encoding::Gzip,
+      Self::Zstd => Encoding::Zstd,
+    }
+  }
+}
+
+/// The compression settings of a server, passed in as the `compression` option of `Deno.serve`.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HttpCompressionConfig {
+  enabled: bool,
+  /// The encodings we're willing to use, in order of preference.
+  encodings: Vec<HttpCompressionEncoding>,
+  /// Clamped to the range each encoding supports.
+  level: Option<u32>,
+  /// By the time we add compression headers and Accept-Encoding, it probably doesn't make sense
+  /// to compress stuff that's smaller than this.
+  min_size: usize,
+  /// Content types to compress in addition to the ones we know are compressible.
+  content_types: Vec<String>,
+  decompress_requests: bool,
+}
+
+impl Default for HttpCompressionConfig {
+  fn default() -> Self {
+    Self {
+      enabled: true,
+      encodings: DEFAULT_COMPRESSION_ENCODINGS.to_vec(),
+      level: None,
+      min_size: 64,
+      content_types: vec![],
+      decompress_requests: false,
+    }
+  }
+}
+
+impl HttpCompressionConfig {
+  fn compression(&self, encoding: HttpCompressionEncoding) -> Compression {
+    match encoding {
+      HttpCompressionEncoding::Brotli => Compression::Brotli(
+        self
+          .level
+          .map_or(Compression::DEFAULT_BROTLI_LEVEL, |level| level.min(11)),
+      ),
+      HttpCompressionEncoding::GZip => Compression::GZip(
+        self
+          .level
+          .map_or(Compression::DEFAULT_GZIP_LEVEL, |level| level.min(9)),
+      ),
+      HttpCompressionEncoding::Zstd => Compression::Zstd(
+        self.level.map_or(Compression::DEFAULT_ZSTD_LEVEL, |level| {
+          level.clamp(1, 22) as i32
+        }),
+      ),
+    }
+  }
+
+  fn is_extra_content_type(&self, content_type: &HeaderValue) -> bool {
+    let Ok(content_type) = content_type.to_str() else {
+      return false;
+    };
+    let essence = content_type.split(';').next().unwrap().trim();
+    self
+      .content_types
+      .iter()
+      .any(|extra| extra.eq_ignore_ascii_case(essence))
+  }
+
+  /// The `Content-Encoding` of a request body, if the server wants it decoded and we know how.
+  pub(crate) fn request_decoding(&self, headers: &HeaderMap) -> Option<String> {
+    if !self.decompress_requests {
+      return None;
+    }
+    let encoding = headers
+      .get(CONTENT_ENCODING)?
+      .to_str()
+      .ok()?
+      .trim()
+      .to_ascii_lowercase();
+    is_decodable(encoding.as_bytes()).then_some(encoding)
+  }
+}
+
+/// Decodes a request body, removing the headers that described its encoded form.
+pub(crate) fn decode_request_body(
+  parts: &mut Parts,
+  body: BodyStream,
+  encoding: &str,
+) -> BodyStream {
+  parts.headers.remove(CONTENT_ENCODING);
+  parts.headers.remove(CONTENT_LENGTH);
+  decode_body(body, encoding.as_bytes())
+}
+
 fn is_request_compressible(
   length: Option<usize>,
   headers: &HeaderMap,
+  config: &HttpCompressionConfig,
 ) -> Compression {
+  if !config.enabled || config.encodings.is_empty() {
+    return Compression::None;
+  }
   if let Some(length) = length {
-    // By the time we add compression headers and Accept-Encoding, it probably doesn't make sense
-    // to compress stuff that's smaller than this.
-    if length < 64 {
+    if length < config.min_size {
       return Compression::None;
     }
   }
@@ -467,34 +637,58 @@ fn is_request_compressible(
     return Compression::None;
   };
 
-  match accept_encoding.to_str().unwrap() {
-    // Firefox and Chrome send this -- no need to parse
-    "gzip, deflate, br" => return Compression::Brotli,
-    "gzip" => return Compression::GZip,
-    "br" => return Compression::Brotli,
-    _ => (),
+  if config.encodings == DEFAULT_COMPRESSION_ENCODINGS {
+    match accept_encoding.to_str().unwrap() {
+      // Firefox and Chrome send this -- no need to parse
+      "gzip, deflate, br" => {
+        return config.compression(HttpCompressionEncoding::Brotli)
+      }
+      "gzip" => return config.compression(HttpCompressionEncoding::GZip),
+      "br" => return config.compression(HttpCompressionEncoding::Brotli),
+      _ => (),
+    }
   }
 
-  // Fall back to the expensive parser
-  let accepted = fly_accept_encoding::encodings_iter(headers).filter(|r| {
-    matches!(
-      r,
-      Ok((
-        Some(Encoding::Identity | Encoding::Gzip | Encoding::Brotli),
-        _
-      ))
-    )
-  });
-  match fly_accept_encoding::preferred(accepted) {
-    Ok(Some(fly_accept_encoding::Encoding::Gzip)) => Compression::GZip,
-    Ok(Some(fly_accept_encoding::Encoding::Brotli)) => Compression::Brotli,
-    _ => Compression::None,
+  // Fall back to the expensive parser, picking the encoding the client rates highest. Ties go to
+  // the encoding that the server prefers.
+  let accepted = fly_accept_encoding::encodings_iter(headers)
+    .filter_map(|r| r.ok())
+    .collect::<Vec<_>>();
+  let quality = |encoding: Encoding| {
+    accepted
+      .iter()
+      .find(|(accepted, _)| *accepted == Some(encoding))
+      .or_else(|| accepted.iter().find(|(accepted, _)| accepted.is_none()))
+      .map(|(_, q)| *q)
+  };
+  let mut best: Option<(HttpCompressionEncoding, f32)> = None;
+  for encoding in &config.encodings {
+    let Some(q) = quality(encoding.accept_encoding()) else {
+      continue;
+    };
+    if q > 0.0 && best.map_or(true, |(_, best_q)| q > best_q) {
+      best = Some((*encoding, q));
+    }
+  }
+  match best {
+    Some((_, q))
+      if quality(Encoding::Identity).is_some_and(|identity| identity > q) =>
+    {
+      Compression::None
+    }
+    Some((encoding, _)) => config.compression(encoding),
+    None => Compression::None,
   }
 }
 
-fn is_response_compressible(headers: &HeaderMap) -> bool {
+fn is_response_compressible(
+  headers: &HeaderMap,
+  config: &HttpCompressionConfig,
+) -> bool {
   if let Some(content_type) = headers.get(CONTENT_TYPE) {
-    if !is_content_compressible(content_type) {
+    if !is_content_compressible(content_type)
+      && !config.is_extra_content_type(content_type)
+    {
       return false;
     }
   } else {
@@ -521,19 +715,18 @@ fn is_response_compressible(headers: &HeaderMap) -> bool {
 fn modify_compressibility_from_response(
   compression: Compression,
   headers: &mut HeaderMap,
+  config: &HttpCompressionConfig,
 ) -> Compression {
-  ensure_vary_accept_encoding(headers);
-  if compression == Compression::None {
-    return Compression::None;
+  // A server that never compresses has no reason to vary on Accept-Encoding
+  if config.enabled {
+    ensure_vary_accept_encoding(headers);
   }
-  if !is_response_compressible(headers) {
+  let Some(encoding) = compression.content_encoding() else {
     return Compression::None;
-  }
-  let encoding = match compression {
-    Compression::Brotli => "br",
-    Compression::GZip => "gzip",
-    _ => unreachable!(),
   };
+  if !is_response_compressible(headers, config) {
+    return Compression::None;
+  }
   weaken_etag(headers);
   headers.remove(CONTENT_LENGTH);
   headers.insert(CONTENT_ENCODING, HeaderValue::from_static(encoding));
@@ -586,11 +779,20 @@ fn set_response(
   // do all of this work to send the response.
   if !http.cancelled() {
     let resource = http.take_resource();
+    let config = http
+      .request_parts()
+      .extensions
+      .get::<Arc<HttpCompressionConfig>>()
+      .cloned()
+      .unwrap_or_default();
     let compression =
-      is_request_compressible(length, &http.request_parts().headers);
+      is_request_compressible(length, &http.request_parts().headers, &config);
     let response = http.response();
-    let compression =
-      modify_compressibility_from_response(compression, response.headers_mut());
+    let compression = modify_compressibility_from_response(
+      compression,
+      response.headers_mut(),
+      &config,
+    );
     response
       .body_mut()
       .initialize(response_fn(compression), resource);
@@ -705,11 +907,15 @@ fn serve_http11_unconditional(
   io: impl HttpServeStream,
   svc: impl HttpService<Incoming, ResBody = ResponseBytes> + 'static,
   cancel: Rc<CancelHandle>,
+  limits: &ConnectionLimits,
 ) -> impl Future<Output = Result<(), hyper1::Error>> + 'static {
-  let conn = http1::Builder::new()
-    .keep_alive(true)
-    .writev(*USE_WRITEV)
-    .serve_connection(TokioIo::new(io), svc)
+  let mut builder = http1::Builder::new();
+  builder.keep_alive(true).writev(*USE_WRITEV);
+  if let Some(max_buf_size) = limits.limits.http1_max_buf_size() {
+    builder.max_buf_size(max_buf_size);
+  }
+  let conn = builder
+    .serve_connection(TokioIo::new(limits.wrap(io, true)), svc)
     .with_upgrades();
 
   async {
@@ -727,9 +933,15 @@ fn serve_http2_unconditional(
   io: impl HttpServeStream,
   svc: impl HttpService<Incoming, ResBody = ResponseBytes> + 'static,
   cancel: Rc<CancelHandle>,
+  limits: &ConnectionLimits,
 ) -> impl Future<Output = Result<(), hyper1::Error>> + 'static {
+  let mut builder = http2::Builder::new(LocalExecutor);
+  if let Some(max_header_size) = limits.limits.max_header_size {
+    builder
+      .max_header_list_size(max_header_size.try_into().unwrap_or(u32::MAX));
+  }
   let conn =
-    http2::Builder::new(LocalExecutor).serve_connection(TokioIo::new(io), svc);
+    builder.serve_connection(TokioIo::new(limits.wrap(io, false)), svc);
   async {
     match conn.or_abort(cancel).await {
       Err(mut conn) => {
@@ -745,20 +957,105 @@ async fn serve_http2_autodetect(
   io: impl HttpServeStream,
   svc: impl HttpService<Incoming, ResBody = ResponseBytes> + 'static,
   cancel: Rc<CancelHandle>,
+  limits: &ConnectionLimits,
 ) -> Result<(), AnyError> {
   let prefix = NetworkStreamPrefixCheck::new(io, HTTP2_PREFIX);
-  let (matches, io) = prefix.match_prefix().await?;
+  // Don't let a client that never sends enough bytes to detect the protocol hold on to us
+  let detect_timeout = limits
+    .limits
+    .header_read_timeout
+    .into_iter()
+    .chain(limits.limits.idle_timeout)
+    .min();
+  let (matches, io) = match detect_timeout {
+    Some(timeout) => {
+      match tokio::time::timeout(timeout, prefix.match_prefix()).await {
+        Ok(res) => res?,
+        Err(_) => return Ok(()),
+      }
+    }
+    None => prefix.match_prefix().await?,
+  };
   if matches {
-    serve_http2_unconditional(io, svc, cancel)
+    serve_http2_unconditional(io, svc, cancel, limits)
       .await
       .map_err(|e| e.into())
   } else {
-    serve_http11_unconditional(io, svc, cancel)
+    serve_http11_unconditional(io, svc, cancel, limits)
       .await
       .map_err(|e| e.into())
   }
 }
 
+/// Creates the hyper service that pushes each request into the slab. Requests that violate the
+/// server's limits are rejected here, before they reach JavaScript. Once the listener has been
+/// cancelled (ie: the server is draining), HTTP/1.1 responses carry `Connection: close` so that clients
+/// don't try to reuse the connection. HTTP/2 connections receive a GOAWAY from hyper instead.
+fn slab_service(
+  request_info: HttpConnectionProperties,
+  refcount: RefCount,
+  listen_cancel_handle: Rc<CancelHandle>,
+  tx: tokio::sync::mpsc::Sender<SlabId>,
+  limits: ConnectionLimits,
+  compression: Arc<HttpCompressionConfig>,
+) -> impl HttpService<Incoming, ResBody = ResponseBytes> + 'static {
+  service_fn(move |mut req: Request| {
+    let is_http1 = req.version() < hyper1::Version::HTTP_2;
+    let listen_cancel_handle = listen_cancel_handle.clone();
+    let request_info = request_info.clone();
+    let refcount = refcount.clone();
+    let tx = tx.clone();
+    let max_request_body_size = limits.limits.max_request_body_size;
+    let rejection = limits.limits.check_request(req.headers(), req.uri());
+    let decoding = compression.request_decoding(req.headers());
+    req.extensions_mut().insert(compression.clone());
+    limits.activity.track(async move {
+      if let Some(status) = rejection {
+        return Ok(rejection_response(status));
+      }
+      let mut response =
+        if decoding.is_none() && max_request_body_size.is_none() {
+          new_slab_future(req, request_info, refcount, tx).await?
+        } else {
+          let (mut parts, body) = req.into_parts();
+          let mut size_hint = body.size_hint();
+          let mut body = incoming_body_stream(body);
+          if let Some(encoding) = decoding {
+            body = decode_request_body(&mut parts, body, &encoding);
+            size_hint = SizeHint::default();
+          }
+          let mut body_too_large = None;
+          if let Some(max) = max_request_body_size {
+            let (limited, exceeded) = limit_body(body, max);
+            body = limited;
+            body_too_large = Some(exceeded);
+          }
+          let response = new_slab_future_from_stream(
+            parts,
+            body,
+            size_hint,
+            request_info,
+            refcount,
+            tx,
+          )
+          .await;
+          // A body without a `Content-Length` only turns out to be too large while it's being read
+          if body_too_large.is_some_and(|exceeded| exceeded.get()) {
+            rejection_response(StatusCode::PAYLOAD_TOO_LARGE)
+          } else {
+            response
+          }
+        };
+      if is_http1 && listen_cancel_handle.is_canceled() {
+        response
+          .headers_mut()
+          .insert(CONNECTION, HeaderValue::from_static("close"));
+      }
+      Ok::<_, hyper::Error>(response)
+    })
+  })
+}
+
 fn serve_https(
   mut io: TlsStream,
   request_info: HttpConnectionProperties,
@@ -769,27 +1066,71 @@ fn serve_https(
     refcount,
     connection_cancel_handle,
     listen_cancel_handle,
+    connection_tx,
+    limits,
+    compression,
   } = lifetime;
 
-  let svc = service_fn(move |req: Request| {
-    new_slab_future(req, request_info.clone(), refcount.clone(), tx.clone())
-  });
+  let stats = refcount.0.clone();
+  let connection = stats.connection_opened();
+  let remote = HttpConnectionInfo::remote(&request_info);
+  let limits = ConnectionLimits::new(limits);
   spawn(
-    async {
+    async move {
+      let _connection = connection;
       io.handshake().await?;
       // If the client specifically negotiates a protocol, we will use it. If not, we'll auto-detect
       // based on the prefix bytes
-      let handshake = io.get_ref().1.alpn_protocol();
+      let (_, tls) = io.get_ref();
+      let handshake = tls.alpn_protocol();
+      let peer_certificates: Option<Vec<String>> = tls
+        .peer_certificates()
+        .map(|certs| certs.iter().map(certificate_to_pem).collect());
+      let svc = slab_service(
+        HttpConnectionProperties {
+          peer_certificates: peer_certificates.clone().map(Rc::from),
+          ..request_info
+        },
+        refcount,
+        listen_cancel_handle.clone(),
+        tx,
+        limits.clone(),
+        compression,
+      );
+      if let Some(connection_tx) = connection_tx {
+        let server_name = match tls {
+          Connection::Server(tls) => tls.server_name().map(str::to_owned),
+          Connection::Client(_) => None,
+        };
+        let _ = connection_tx.send(HttpConnectionInfo {
+          alpn_protocol: handshake
+            .map(|alpn| String::from_utf8_lossy(alpn).into_owned()),
+          tls: Some(HttpTlsInfo {
+            server_name,
+            protocol_version: tls
+              .protocol_version()
+              .map(|version| format!("{version:?}")),
+            cipher_suite: tls
+              .negotiated_cipher_suite()
+              .map(|suite| format!("{:?}", suite.suite())),
+            peer_certificates,
+          }),
+          ..remote
+        });
+      }
       if handshake == Some(TLS_ALPN_HTTP_2) {
-        serve_http2_unconditional(io, svc, listen_cancel_handle)
+        let io = stats.count_bytes(io);
+        serve_http2_unconditional(io, svc, listen_cancel_handle, &limits)
           .await
           .map_err(|e| e.into())
       } else if handshake == Some(TLS_ALPN_HTTP_11) {
-        serve_http11_unconditional(io, svc, listen_cancel_handle)
+        let io = stats.count_bytes(io);
+        serve_http11_unconditional(io, svc, listen_cancel_handle, &limits)
           .await
           .map_err(|e| e.into())
       } else {
-        serve_http2_autodetect(io, svc, listen_cancel_handle).await
+        let io = stats.count_bytes(io);
+        serve_http2_autodetect(io, svc, listen_cancel_handle, &limits).await
       }
     }
     .try_or_cancel(connection_cancel_handle),
@@ -806,14 +1147,31 @@ fn serve_http(
     refcount,
     connection_cancel_handle,
     listen_cancel_handle,
+    connection_tx,
+    limits,
+    compression,
   } = lifetime;
 
-  let svc = service_fn(move |req: Request| {
-    new_slab_future(req, request_info.clone(), refcount.clone(), tx.clone())
-  });
+  if let Some(connection_tx) = connection_tx {
+    let _ = connection_tx.send(HttpConnectionInfo::remote(&request_info));
+  }
+  let connection = refcount.0.connection_opened();
+  let io = refcount.0.count_bytes(io);
+  let limits = ConnectionLimits::new(limits);
+  let svc = slab_service(
+    request_info,
+    refcount,
+    listen_cancel_handle.clone(),
+    tx,
+    limits.clone(),
+    compression,
+  );
   spawn(
-    serve_http2_autodetect(io, svc, listen_cancel_handle)
-      .try_or_cancel(connection_cancel_handle),
+    async move {
+      let _connection = connection;
+      serve_http2_autodetect(io, svc, listen_cancel_handle, &limits).await
+    }
+    .try_or_cancel(connection_cancel_handle),
   )
 }
 
@@ -846,10 +1204,53 @@ where
 }
 
 #[derive(Clone)]
-struct HttpLifetime {
-  connection_cancel_handle: Rc<CancelHandle>,
-  listen_cancel_handle: Rc<CancelHandle>,
-  refcount: RefCount,
+pub(crate) struct HttpLifetime {
+  pub(crate) connection_cancel_handle: Rc<CancelHandle>,
+  pub(crate) listen_cancel_handle: Rc<CancelHandle>,
+  pub(crate) refcount: RefCount,
+  /// Only present if JavaScript asked to be told about new connections.
+  pub(crate) connection_tx:
+    Option<tokio::sync::mpsc::UnboundedSender<HttpConnectionInfo>>,
+  pub(crate) limits: HttpServeLimits,
+  pub(crate) compression: Arc<HttpCompressionConfig>,
+}
+
+/// Details about an accepted connection, passed to the `onConnection` callback.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HttpConnectionInfo {
+  pub(crate) transport: &'static str,
+  pub(crate) hostname: String,
+  pub(crate) port: Option<u16>,
+  pub(crate) alpn_protocol: Option<String>,
+  pub(crate) tls: Option<HttpTlsInfo>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HttpTlsInfo {
+  pub(crate) server_name: Option<String>,
+  pub(crate) protocol_version: Option<String>,
+  pub(crate) cipher_suite: Option<String>,
+  pub(crate) peer_certificates: Option<Vec<String>>,
+}
+
+impl HttpConnectionInfo {
+  /// The peer of a connection, before anything has been negotiated.
+  fn remote(request_info: &HttpConnectionProperties) -> Self {
+    let transport = match request_info.stream_type {
+      NetworkStreamType::Tcp | NetworkStreamType::Tls => "tcp",
+      #[cfg(unix)]
+      NetworkStreamType::Unix => "unix",
+    };
+    Self {
+      transport,
+      hostname: request_info.peer_address.to_string(),
+      port: request_info.peer_port,
+      alpn_protocol: None,
+      tls: None,
+    }
+  }
 }
 
 struct HttpJoinHandle {
@@ -857,17 +1258,44 @@ struct HttpJoinHandle {
   connection_cancel_handle: Rc<CancelHandle>,
   listen_cancel_handle: Rc<CancelHandle>,
   rx: AsyncRefCell<tokio::sync::mpsc::Receiver<SlabId>>,
+  /// Allows additional listeners (ie: HTTP/3) to feed this server without keeping the channel open.
+  tx: tokio::sync::mpsc::WeakSender<SlabId>,
+  connection_tx: Option<tokio::sync::mpsc::UnboundedSender<HttpConnectionInfo>>,
+  connection_rx: AsyncRefCell<
+    Option<tokio::sync::mpsc::UnboundedReceiver<HttpConnectionInfo>>,
+  >,
   refcount: RefCount,
+  limits: HttpServeLimits,
+  compression: Arc<HttpCompressionConfig>,
+  /// The QUIC endpoint started by `op_http_serve_h3`, if any.
+  h3_endpoint: RefCell<Option<quinn::Endpoint>>,
 }
 
 impl HttpJoinHandle {
-  fn new(rx: tokio::sync::mpsc::Receiver<SlabId>) -> Self {
+  fn new(
+    tx: &tokio::sync::mpsc::Sender<SlabId>,
+    rx: tokio::sync::mpsc::Receiver<SlabId>,
+    options: &HttpServeOptions,
+  ) -> Self {
+    let (connection_tx, connection_rx) = if options.track_connections {
+      let (connection_tx, connection_rx) =
+        tokio::sync::mpsc::unbounded_channel();
+      (Some(connection_tx), Some(connection_rx))
+    } else {
+      (None, None)
+    };
     Self {
       join_handle: AsyncRefCell::new(None),
       connection_cancel_handle: CancelHandle::new_rc(),
       listen_cancel_handle: CancelHandle::new_rc(),
       rx: AsyncRefCell::new(rx),
+      tx: tx.downgrade(),
+      connection_tx,
+      connection_rx: AsyncRefCell::new(connection_rx),
       refcount: RefCount::default(),
+      limits: options.limits(),
+      compression: Arc::new(options.compression.clone().unwrap_or_default()),
+      h3_endpoint: RefCell::new(None),
     }
   }
 
@@ -876,6 +1304,9 @@ impl HttpJoinHandle {
       connection_cancel_handle: self.connection_cancel_handle.clone(),
       listen_cancel_handle: self.listen_cancel_handle.clone(),
       refcount: self.refcount.clone(),
+      connection_tx: self.connection_tx.clone(),
+      limits: self.limits,
+      compression: self.compression.clone(),
     }
   }
 
@@ -908,11 +1339,36 @@ impl Drop for HttpJoinHandle {
   }
 }
 
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpServeOptions {
+  track_connections: bool,
+  max_request_body_size: Option<u64>,
+  max_header_size: Option<usize>,
+  /// In milliseconds.
+  header_read_timeout: Option<u64>,
+  /// In milliseconds.
+  idle_timeout: Option<u64>,
+  compression: Option<HttpCompressionConfig>,
+}
+
+impl HttpServeOptions {
+  fn limits(&self) -> HttpServeLimits {
+    HttpServeLimits {
+      max_request_body_size: self.max_request_body_size,
+      max_header_size: self.max_header_size,
+      header_read_timeout: self.header_read_timeout.map(Duration::from_millis),
+      idle_timeout: self.idle_timeout.map(Duration::from_millis),
+    }
+  }
+}
+
 #[op2]
 #[serde]
 pub fn op_http_serve<HTTP>(
   state: Rc<RefCell<OpState>>,
   #[smi] listener_rid: ResourceId,
+  #[serde] options: HttpServeOptions,
 ) -> Result<(ResourceId, &'static str, String), AnyError>
 where
   HTTP: HttpPropertyExtractor,
@@ -925,7 +1381,8 @@ where
   let listen_properties = HTTP::listen_properties_from_listener(&listener)?;
 
   let (tx, rx) = tokio::sync::mpsc::channel(10);
-  let resource: Rc<HttpJoinHandle> = Rc::new(HttpJoinHandle::new(rx));
+  let resource: Rc<HttpJoinHandle> =
+    Rc::new(HttpJoinHandle::new(&tx, rx, &options));
   let listen_cancel_clone = resource.listen_cancel_handle();
 
   let lifetime = resource.lifetime();
@@ -976,7 +1433,8 @@ where
   let listen_properties = HTTP::listen_properties_from_connection(&connection)?;
 
   let (tx, rx) = tokio::sync::mpsc::channel(10);
-  let resource: Rc<HttpJoinHandle> = Rc::new(HttpJoinHandle::new(rx));
+  let resource: Rc<HttpJoinHandle> =
+    Rc::new(HttpJoinHandle::new(&tx, rx, &Default::default()));
 
   let handle: JoinHandle<Result<(), deno_core::anyhow::Error>> =
     serve_http_on::<HTTP>(
@@ -998,6 +1456,107 @@ where
   ))
 }
 
+#[derive(Deserialize)]
+pub struct ServeHttp3Args {
+  hostname: String,
+  port: u16,
+  cert: String,
+  key: String,
+}
+
+/// Start an HTTP/3 listener on UDP that feeds requests into an existing server. The address is
+/// always the one the TLS listener is bound to, so no additional permissions are required.
+#[op2]
+pub fn op_http_serve_h3(
+  state: Rc<RefCell<OpState>>,
+  #[smi] rid: ResourceId,
+  #[serde] args: ServeHttp3Args,
+) -> Result<u16, AnyError> {
+  state
+    .borrow()
+    .feature_checker
+    .check_legacy_unstable_or_exit("Deno.serve.http3");
+
+  let join_handle = state
+    .borrow_mut()
+    .resource_table
+    .get::<HttpJoinHandle>(rid)?;
+  let Some(tx) = join_handle.tx.upgrade() else {
+    return Err(bad_resource_id());
+  };
+
+  let endpoint =
+    create_endpoint(&args.hostname, args.port, &args.cert, &args.key)?;
+  let port = endpoint.local_addr()?.port();
+  *join_handle.h3_endpoint.borrow_mut() = Some(endpoint.clone());
+
+  let listen_cancel_handle = join_handle.listen_cancel_handle();
+  spawn(
+    serve_http3(endpoint, join_handle.lifetime(), tx)
+      .try_or_cancel(listen_cancel_handle),
+  );
+
+  Ok(port)
+}
+
+/// Replace the certificate presented by the HTTP/3 listener of a server. Connections that are
+/// already established keep using the old one.
+#[op2]
+pub fn op_http_set_h3_certificates(
+  state: &mut OpState,
+  #[smi] rid: ResourceId,
+  #[string] cert: &str,
+  #[string] key: &str,
+) -> Result<(), AnyError> {
+  let join_handle = state.resource_table.get::<HttpJoinHandle>(rid)?;
+  let endpoint = join_handle.h3_endpoint.borrow();
+  let Some(endpoint) = endpoint.as_ref() else {
+    return Err(bad_resource_id());
+  };
+  endpoint.set_server_config(Some(server_config(cert, key)?));
+  Ok(())
+}
+
+/// Waits for the next accepted connection on a server created with `trackConnections`. Returns
+/// `None` once the server stops listening.
+#[op2(async)]
+#[serde]
+pub async fn op_http_next_connection(
+  state: Rc<RefCell<OpState>>,
+  #[smi] rid: ResourceId,
+) -> Result<Option<HttpConnectionInfo>, AnyError> {
+  let join_handle = state
+    .borrow_mut()
+    .resource_table
+    .get::<HttpJoinHandle>(rid)?;
+
+  let cancel = join_handle.listen_cancel_handle();
+  let next = async {
+    let mut connection_rx =
+      RcRef::map(&join_handle, |this| &this.connection_rx)
+        .borrow_mut()
+        .await;
+    match connection_rx.as_mut() {
+      Some(connection_rx) => connection_rx.recv().await,
+      None => None,
+    }
+  }
+  .or_cancel(cancel)
+  .unwrap_or_else(|_| None)
+  .await;
+  Ok(next)
+}
+
+#[op2]
+#[serde]
+pub fn op_http_stats(
+  state: &mut OpState,
+  #[smi] rid: ResourceId,
+) -> Result<HttpServerStatsSnapshot, AnyError> {
+  let join_handle = state.resource_table.get::<HttpJoinHandle>(rid)?;
+  Ok(join_handle.refcount.0.snapshot())
+}
+
 /// Synchronous, non-blocking call to see if there are any further HTTP requests. If anything
 /// goes wrong in this method we return [`SlabId::MAX`] and let the async handler pick up the real error.
 #[op2(fast)]
@@ -1093,11 +1652,19 @@ pub fn op_http_cancel(
   Ok(())
 }
 
+/// Async spin on the refcount while we wait for everything to drain.
+async fn wait_for_drain(join_handle: &HttpJoinHandle) {
+  while Rc::strong_count(&join_handle.refcount.0) > 1 {
+    tokio::time::sleep(Duration::from_millis(10)).await;
+  }
+}
+
 #[op2(async)]
 pub async fn op_http_close(
   state: Rc<RefCell<OpState>>,
   #[smi] rid: ResourceId,
   graceful: bool,
+  #[serde] timeout: Option<u64>,
 ) -> Result<(), AnyError> {
   let join_handle = state
     .borrow_mut()
@@ -1117,10 +1684,18 @@ pub async fn op_http_close(
     join_handle.connection_cancel_handle().cancel();
   }
 
-  // Async spin on the refcount while we wait for everything to drain
-  while Rc::strong_count(&join_handle.refcount.0) > 1 {
-    tokio::time::sleep(Duration::from_millis(10)).await;
+  // Wait for in-flight requests to drain. If they haven't finished by the deadline, force-cancel
+  // the remaining connections and wait for them to unwind.
+  if let Some(timeout) = timeout {
+    let deadline = Duration::from_millis(timeout);
+    if tokio::time::timeout(deadline, wait_for_drain(&join_handle))
+      .await
+      .is_err()
+    {
+      join_handle.connection_cancel_handle().cancel();
+    }
   }
+  wait_for_drain(&join_handle).await;
 
   let mut join_handle = RcRef::map(&join_handle, |this| &this.join_handle)
     .borrow_mut()
@@ -36,6 +36,7 @@ use deno_core::Resource;
 use deno_core::ResourceId;
 use deno_core::StringOrBuffer;
 use deno_net::raw::NetworkStream;
+use deno_websocket::deflate::DeflateParams;
 use deno_websocket::ws_create_server_stream;
 use flate2::write::GzEncoder;
 use flate2::Compression;
@@ -75,16 +76,20 @@ use tokio::io::AsyncWriteExt;
 use crate::network_buffered_stream::NetworkBufferedStream;
 use crate::reader_stream::ExternallyAbortableReaderStream;
 use crate::reader_stream::ShutdownHandle;
+use crate::stats::CountingStream;
 
 pub mod compressible;
+mod http3;
 mod http_next;
 mod hyper_util_tokioio;
+mod limits;
 mod network_buffered_stream;
 mod reader_stream;
 mod request_body;
 mod request_properties;
 mod response_body;
 mod slab;
+mod stats;
 mod websocket_upgrade;
 
 pub use request_properties::DefaultHttpPropertyExtractor;
@@ -103,15 +108,21 @@ deno_core::extension!(
     op_http_shutdown,
     op_http_upgrade_websocket,
     op_http_websocket_accept_header,
+    op_http_websocket_negotiate_extensions,
     op_http_write_headers,
     op_http_write_resource,
     op_http_write,
     http_next::op_http_get_request_header,
+    http_next::op_http_get_request_peer_certificates,
+    http_next::op_http_get_request_traceparent,
     http_next::op_http_get_request_headers,
     http_next::op_http_get_request_method_and_url<HTTP>,
+    http_next::op_http_next_connection,
     http_next::op_http_read_request_body,
     http_next::op_http_serve_on<HTTP>,
     http_next::op_http_serve<HTTP>,
+    http_next::op_http_serve_h3,
+    http_next::op_http_set_h3_certificates,
     http_next::op_http_set_promise_complete,
     http_next::op_http_set_response_body_bytes,
     http_next::op_http_set_response_body_resource,
@@ -119,6 +130,7 @@ deno_core::extension!(
     http_next::op_http_set_response_header,
     http_next::op_http_set_response_headers,
     http_next::op_http_set_response_trailers,
+    http_next::op_http_stats,
     http_next::op_http_track,
     http_next::op_http_upgrade_websocket_next,
     http_next::op_http_upgrade_raw,
@@ -993,11 +1005,23 @@ fn op_http_websocket_accept_header(
   Ok(base64::encode(digest))
 }
 
+/// Returns the `Sec-WebSocket-Extensions` header to accept the compression a
+/// client offered, if any.
+#[op2]
+#[serde]
+fn op_http_websocket_negotiate_extensions(
+  #[string] offers: String,
+  #[serde] compression: DeflateParams,
+) -> Result<Option<String>, AnyError> {
+  deno_websocket::deflate::negotiate(&offers, &compression)
+}
+
 #[op2(async)]
 #[smi]
 async fn op_http_upgrade_websocket(
   state: Rc<RefCell<OpState>>,
   #[smi] rid: ResourceId,
+  #[string] extensions: Option<String>,
 ) -> Result<ResourceId, AnyError> {
   let stream = state
     .borrow_mut()
@@ -1014,8 +1038,12 @@ async fn op_http_upgrade_websocket(
 
   let (transport, bytes) =
     extract_network_stream(hyper::upgrade::on(request).await?);
-  let ws_rid =
-    ws_create_server_stream(&mut state.borrow_mut(), transport, bytes)?;
+  let ws_rid = ws_create_server_stream(
+    &mut state.borrow_mut(),
+    transport,
+    bytes,
+    extensions.as_deref(),
+  )?;
   Ok(ws_rid)
 }
 
@@ -1108,25 +1136,59 @@ fn maybe_extract_network_stream<
 
   match upgraded.downcast::<NetworkBufferedStream<T>>() {
     Ok((stream, upgraded_bytes)) => {
-      // Both the upgrade and the stream might have unread bytes
       let (io, stream_bytes) = stream.into_inner();
-      let bytes = match (stream_bytes.is_empty(), upgraded_bytes.is_empty()) {
-        (false, false) => Bytes::default(),
-        (true, false) => upgraded_bytes,
-        (false, true) => stream_bytes,
-        (true, true) => {
-          // The upgraded bytes come first as they have already been read
-          let mut v = upgraded_bytes.to_vec();
-          v.append(&mut stream_bytes.to_vec());
-          Bytes::from(v)
-        }
-      };
-      Ok((io.into(), bytes))
+      Ok((io.into(), unread_bytes(upgraded_bytes, stream_bytes)))
+    }
+    Err(x) => Err(x),
+  }
+}
+
+/// Like [`maybe_extract_network_stream`], for the connections of `Deno.serve`, which enforce
+/// their timeouts with a [`limits::TimeoutStream`] and count their bytes with a
+/// [`CountingStream`]. Neither applies to upgraded connections.
+fn maybe_extract_served_network_stream<
+  T: Into<NetworkStream> + AsyncRead + AsyncWrite + Unpin + 'static,
+  U: CanDowncastUpgrade,
+>(
+  upgraded: U,
+) -> Result<(NetworkStream, Bytes), U> {
+  let upgraded =
+    match upgraded.downcast::<limits::TimeoutStream<CountingStream<T>>>() {
+      Ok((stream, bytes)) => {
+        return Ok((stream.into_inner().into_inner().into(), bytes))
+      }
+      Err(x) => x,
+    };
+
+  match upgraded
+    .downcast::<limits::TimeoutStream<NetworkBufferedStream<CountingStream<T>>>>()
+  {
+    Ok((stream, upgraded_bytes)) => {
+      let (io, stream_bytes) = stream.into_inner().into_inner();
+      Ok((
+        io.into_inner().into(),
+        unread_bytes(upgraded_bytes, stream_bytes),
+      ))
     }
     Err(x) => Err(x),
   }
 }
 
+/// Both the upgrade and the stream might have unread bytes.
+fn unread_bytes(upgraded_bytes: Bytes, stream_bytes: Bytes) -> Bytes {
+  match (stream_bytes.is_empty(), upgraded_bytes.is_empty()) {
+    (false, false) => Bytes::default(),
+    (true, false) => upgraded_bytes,
+    (false, true) => stream_bytes,
+    (true, true) => {
+      // The upgraded bytes come first as they have already been read
+      let mut v = upgraded_bytes.to_vec();
+      v.append(&mut stream_bytes.to_vec());
+      Bytes::from(v)
+    }
+  }
+}
+
 fn extract_network_stream<U: CanDowncastUpgrade>(
   upgraded: U,
 ) -> (NetworkStream, Bytes) {
@@ -1153,6 +1215,34 @@ fn extract_network_stream<U: CanDowncastUpgrade>(
       Ok(res) => return res,
       Err(x) => x,
     };
+  let upgraded =
+    match maybe_extract_served_network_stream::<tokio::net::TcpStream, _>(
+      upgraded,
+    ) {
+      Ok(res) => return res,
+      Err(x) => x,
+    };
+  let upgraded = match maybe_extract_served_network_stream::<
+    deno_net::ops_tls::TlsStream,
+    _,
+  >(upgraded)
+  {
+    Ok(res) => return res,
+    Err(x) => x,
+  };
+  #[cfg(unix)]
+  let upgraded =
+    match maybe_extract_served_network_stream::<tokio::net::UnixStream, _>(
+      upgraded,
+    ) {
+      Ok(res) => return res,
+      Err(x) => x,
+    };
+  let upgraded =
+    match maybe_extract_served_network_stream::<NetworkStream, _>(upgraded) {
+      Ok(res) => return res,
+      Err(x) => x,
+    };
 
   // TODO(mmastrac): HTTP/2 websockets may yield an un-downgradable type
   drop(upgraded);
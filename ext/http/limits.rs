@@ -0,0 +1,344 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+//! Request size limits and connection timeouts for `Deno.serve`. Violations that are visible in the
+//! request head are answered directly from Rust, so they never reach the JavaScript handler. A body
+//! without a `Content-Length` can only be measured while the handler reads it; the handler sees the
+//! read fail, and its response is replaced with a 413.
+use crate::request_body::BodyStream;
+use crate::response_body::ResponseBytes;
+use crate::slab::Response;
+use bytes::Bytes;
+use deno_core::error::AnyError;
+use deno_core::futures::StreamExt;
+use http::header::CONNECTION;
+use http::header::CONTENT_LENGTH;
+use http::HeaderMap;
+use http::HeaderValue;
+use http::StatusCode;
+use http::Uri;
+use pin_project::pin_project;
+use std::cell::Cell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::task::ready;
+use std::task::Context;
+use std::task::Poll;
+use std::time::Duration;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncWrite;
+use tokio::io::ReadBuf;
+use tokio::time::Instant;
+use tokio::time::Sleep;
+
+/// hyper refuses to use a read buffer smaller than this for HTTP/1.1.
+const MIN_HTTP1_BUF_SIZE: usize = 8192;
+
+const REQUEST_TIMEOUT_RESPONSE: &[u8] =
+  b"HTTP/1.1 408 Request Timeout\r\nconnection: close\r\ncontent-length: 0\r\n\r\n";
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HttpServeLimits {
+  pub max_request_body_size: Option<u64>,
+  pub max_header_size: Option<usize>,
+  pub header_read_timeout: Option<Duration>,
+  pub idle_timeout: Option<Duration>,
+}
+
+impl HttpServeLimits {
+  /// The read buffer size to give hyper, if the header size is limited.
+  pub fn http1_max_buf_size(&self) -> Option<usize> {
+    self
+      .max_header_size
+      .map(|size| size.max(MIN_HTTP1_BUF_SIZE))
+  }
+
+  /// Check the request head against the limits, returning the status to reject it with.
+  pub fn check_request(
+    &self,
+    headers: &HeaderMap,
+    uri: &Uri,
+  ) -> Option<StatusCode> {
+    if let Some(max_header_size) = self.max_header_size {
+      let header_size = headers
+        .iter()
+        .map(|(name, value)| name.as_str().len() + value.len())
+        .sum::<usize>()
+        + uri.to_string().len();
+      if header_size > max_header_size {
+        return Some(StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE);
+      }
+    }
+    if let Some(max_request_body_size) = self.max_request_body_size {
+      let content_length = headers
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+      if content_length.is_some_and(|length| length > max_request_body_size) {
+        return Some(StatusCode::PAYLOAD_TOO_LARGE);
+      }
+    }
+    None
+  }
+}
+
+/// A bodiless response for a request that was rejected before it reached JavaScript. The connection
+/// is closed afterwards, as the rest of the request may still be in flight.
+pub fn rejection_response(status: StatusCode) -> Response {
+  let mut response = Response::new(ResponseBytes::default());
+  *response.status_mut() = status;
+  response
+    .headers_mut()
+    .insert(CONNECTION, HeaderValue::from_static("close"));
+  response
+}
+
+/// Fails a body stream once it has produced more than `max` bytes. This catches bodies that don't
+/// declare a `Content-Length` up front. The returned flag is raised when that happens, so that the
+/// response can be replaced with a 413.
+pub fn limit_body(body: BodyStream, max: u64) -> (BodyStream, Rc<Cell<bool>>) {
+  let exceeded = Rc::new(Cell::new(false));
+  let flag = exceeded.clone();
+  let mut total = 0u64;
+  let body = Box::pin(body.map(move |chunk| {
+    let chunk: Bytes = chunk?;
+    total += chunk.len() as u64;
+    if total > max {
+      flag.set(true);
+      return Err(AnyError::msg(format!(
+        "Request body exceeds the limit of {max} bytes"
+      )));
+    }
+    Ok(chunk)
+  }));
+  (body, exceeded)
+}
+
+/// Counts the requests on a connection whose response head has not been produced yet. While there
+/// are any, the connection is neither idle nor waiting for headers.
+#[derive(Default)]
+pub struct RequestActivity {
+  active: AtomicUsize,
+  started: AtomicUsize,
+}
+
+impl RequestActivity {
+  pub fn track<F: Future>(
+    self: &Arc<Self>,
+    future: F,
+  ) -> impl Future<Output = F::Output> {
+    struct Guard(Arc<RequestActivity>);
+    impl Drop for Guard {
+      fn drop(&mut self) {
+        self.0.active.fetch_sub(1, Ordering::Relaxed);
+      }
+    }
+
+    self.started.fetch_add(1, Ordering::Relaxed);
+    self.active.fetch_add(1, Ordering::Relaxed);
+    let guard = Guard(self.clone());
+    async move {
+      let _guard = guard;
+      future.await
+    }
+  }
+
+  fn is_active(&self) -> bool {
+    self.active.load(Ordering::Relaxed) > 0
+  }
+
+  fn started(&self) -> usize {
+    self.started.load(Ordering::Relaxed)
+  }
+}
+
+/// Enforces the header read and idle timeouts on a connection stream.
+///
+/// The idle deadline moves forward whenever bytes are read or written. The header deadline starts
+/// when the connection opens, or when the first byte of the next request arrives, and is cleared
+/// once a request reaches the service. Header timeouts on HTTP/1.1 are answered with a 408; all
+/// other timeouts simply end the connection.
+#[pin_project]
+pub struct TimeoutStream<S> {
+  #[pin]
+  io: S,
+  activity: Arc<RequestActivity>,
+  header_read_timeout: Option<Duration>,
+  idle_timeout: Option<Duration>,
+  header_deadline: Option<Instant>,
+  idle_deadline: Option<Instant>,
+  sleep: Pin<Box<Sleep>>,
+  /// The number of requests that had reached the service when we last looked.
+  seen_requests: usize,
+  /// Once timed out, the remainder of the 408 response (if any) that has yet to be written.
+  timed_out: Option<&'static [u8]>,
+}
+
+impl<S> TimeoutStream<S> {
+  pub fn new(
+    io: S,
+    limits: &HttpServeLimits,
+    activity: Arc<RequestActivity>,
+    is_http1: bool,
+  ) -> Self {
+    let now = Instant::now();
+    // Header timeouts are meaningless for multiplexed protocols
+    let header_read_timeout = if is_http1 {
+      limits.header_read_timeout
+    } else {
+      None
+    };
+    Self {
+      io,
+      activity,
+      header_read_timeout,
+      idle_timeout: limits.idle_timeout,
+      header_deadline: header_read_timeout.map(|timeout| now + timeout),
+      idle_deadline: limits.idle_timeout.map(|timeout| now + timeout),
+      sleep: Box::pin(tokio::time::sleep_until(now)),
+      seen_requests: 0,
+      timed_out: None,
+    }
+  }
+
+  /// Unwrap the connection stream, eg: when the connection is upgraded and the timeouts no longer
+  /// apply.
+  pub fn into_inner(self) -> S {
+    self.io
+  }
+}
+
+impl<S: AsyncRead + AsyncWrite> AsyncRead for TimeoutStream<S> {
+  fn poll_read(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    buf: &mut ReadBuf<'_>,
+  ) -> Poll<std::io::Result<()>> {
+    let mut this = self.project();
+
+    loop {
+      // Once we've timed out, flush the 408 (if any) and report EOF so that hyper closes up
+      if let Some(remaining) = this.timed_out {
+        while !remaining.is_empty() {
+          let n = ready!(this.io.as_mut().poll_write(cx, remaining))?;
+          *remaining = &remaining[n..];
+        }
+        return Poll::Ready(Ok(()));
+      }
+
+      // A request that reached the service had its headers read in time
+      let started = this.activity.started();
+      if started != *this.seen_requests {
+        *this.seen_requests = started;
+        *this.header_deadline = None;
+      }
+      let active = this.activity.is_active();
+
+      let before = buf.filled().len();
+      match this.io.as_mut().poll_read(cx, buf) {
+        Poll::Ready(Ok(())) => {
+          if buf.filled().len() > before {
+            let now = Instant::now();
+            *this.idle_deadline =
+              this.idle_timeout.map(|timeout| now + timeout);
+            if !active && this.header_deadline.is_none() {
+              *this.header_deadline =
+                this.header_read_timeout.map(|timeout| now + timeout);
+            }
+          }
+          return Poll::Ready(Ok(()));
+        }
+        Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+        Poll::Pending => {}
+      }
+
+      // Nothing to read: see whether one of the deadlines passes before something arrives. The
+      // idle deadline doesn't apply while a request is being handled.
+      let idle_deadline = if active { None } else { *this.idle_deadline };
+      let deadline = match (*this.header_deadline, idle_deadline) {
+        (Some(a), Some(b)) => a.min(b),
+        (Some(a), None) | (None, Some(a)) => a,
+        (None, None) => return Poll::Pending,
+      };
+      if this.sleep.deadline() != deadline {
+        this.sleep.as_mut().reset(deadline);
+      }
+      ready!(this.sleep.as_mut().poll(cx));
+
+      let header_timed_out = this
+        .header_deadline
+        .is_some_and(|header_deadline| header_deadline <= deadline);
+      *this.timed_out = Some(if header_timed_out {
+        REQUEST_TIMEOUT_RESPONSE
+      } else {
+        b""
+      });
+    }
+  }
+}
+
+impl<S: AsyncWrite> AsyncWrite for TimeoutStream<S> {
+  fn poll_write(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    buf: &[u8],
+  ) -> Poll<std::io::Result<usize>> {
+    let this = self.project();
+    let n = ready!(this.io.poll_write(cx, buf))?;
+    *this.idle_deadline =
+      this.idle_timeout.map(|timeout| Instant::now() + timeout);
+    Poll::Ready(Ok(n))
+  }
+
+  fn poll_write_vectored(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    bufs: &[std::io::IoSlice<'_>],
+  ) -> Poll<std::io::Result<usize>> {
+    let this = self.project();
+    let n = ready!(this.io.poll_write_vectored(cx, bufs))?;
+    *this.idle_deadline =
+      this.idle_timeout.map(|timeout| Instant::now() + timeout);
+    Poll::Ready(Ok(n))
+  }
+
+  fn is_write_vectored(&self) -> bool {
+    self.io.is_write_vectored()
+  }
+
+  fn poll_flush(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+  ) -> Poll<std::io::Result<()>> {
+    self.project().io.poll_flush(cx)
+  }
+
+  fn poll_shutdown(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+  ) -> Poll<std::io::Result<()>> {
+    self.project().io.poll_shutdown(cx)
+  }
+}
+
+/// The limits of a server, along with the request activity of one of its connections.
+#[derive(Clone)]
+pub struct ConnectionLimits {
+  pub limits: HttpServeLimits,
+  pub activity: Arc<RequestActivity>,
+}
+
+impl ConnectionLimits {
+  pub fn new(limits: HttpServeLimits) -> Self {
+    Self {
+      limits,
+      activity: Default::default(),
+    }
+  }
+
+  pub fn wrap<S>(&self, io: S, is_http1: bool) -> TimeoutStream<S> {
+    TimeoutStream::new(io, &self.limits, self.activity.clone(), is_http1)
+  }
+}
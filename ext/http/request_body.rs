@@ -1,4 +1,7 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+use async_compression::tokio::bufread::BrotliDecoder;
+use async_compression::tokio::bufread::GzipDecoder;
+use async_compression::tokio::bufread::ZstdDecoder;
 use bytes::Bytes;
 use deno_core::error::AnyError;
 use deno_core::futures::stream::Peekable;
@@ -13,13 +16,25 @@ use hyper1::body::Body;
 use hyper1::body::Incoming;
 use hyper1::body::SizeHint;
 use std::borrow::Cow;
+use std::io::ErrorKind;
 use std::pin::Pin;
 use std::rc::Rc;
 use std::task::ready;
 use std::task::Poll;
+use tokio::io::AsyncRead;
+use tokio_util::io::ReaderStream;
+use tokio_util::io::StreamReader;
 
-/// Converts a hyper incoming body stream into a stream of [`Bytes`] that we can use to read in V8.
-struct ReadFuture(Incoming);
+/// A request body that is not read from hyper directly, such as the receive half of an HTTP/3 request
+/// stream or a body that is being decoded.
+pub type BodyStream = Pin<Box<dyn Stream<Item = Result<Bytes, AnyError>>>>;
+
+/// Converts a hyper incoming body stream (or an already-converted [`BodyStream`]) into a stream of
+/// [`Bytes`] that we can use to read in V8.
+enum ReadFuture {
+  Incoming(Incoming),
+  Stream(BodyStream),
+}
 
 impl Stream for ReadFuture {
   type Item = Result<Bytes, AnyError>;
@@ -29,9 +44,12 @@ impl Stream for ReadFuture {
     cx: &mut std::task::Context<'_>,
   ) -> Poll<Option<Self::Item>> {
     // Loop until we receive a non-empty frame from Hyper
-    let this = self.get_mut();
+    let incoming = match self.get_mut() {
+      ReadFuture::Incoming(incoming) => incoming,
+      ReadFuture::Stream(stream) => return stream.as_mut().poll_next(cx),
+    };
     loop {
-      let res = ready!(Pin::new(&mut this.0).poll_frame(cx));
+      let res = ready!(Pin::new(&mut *incoming).poll_frame(cx));
       break match res {
         Some(Ok(frame)) => {
           if let Ok(data) = frame.into_data() {
@@ -50,12 +68,50 @@ impl Stream for ReadFuture {
   }
 }
 
+/// Converts a hyper incoming body into a [`BodyStream`], so that it can be wrapped.
+pub fn incoming_body_stream(body: Incoming) -> BodyStream {
+  Box::pin(ReadFuture::Incoming(body))
+}
+
+/// Whether [`decode_body`] knows how to decode the given `Content-Encoding`.
+pub fn is_decodable(content_encoding: &[u8]) -> bool {
+  matches!(content_encoding, b"gzip" | b"x-gzip" | b"br" | b"zstd")
+}
+
+/// Decodes a body stream according to its `Content-Encoding`. The encoding must be one that
+/// [`is_decodable`] accepts.
+pub fn decode_body(body: BodyStream, content_encoding: &[u8]) -> BodyStream {
+  let reader =
+    StreamReader::new(body.map(|chunk| {
+      chunk.map_err(|e| std::io::Error::new(ErrorKind::Other, e))
+    }));
+  let decoded: Pin<Box<dyn AsyncRead>> = match content_encoding {
+    b"gzip" | b"x-gzip" => Box::pin(GzipDecoder::new(reader)),
+    b"br" => Box::pin(BrotliDecoder::new(reader)),
+    b"zstd" => Box::pin(ZstdDecoder::new(reader)),
+    _ => unreachable!("undecodable content encoding"),
+  };
+  Box::pin(
+    ReaderStream::new(decoded).map(|chunk| chunk.map_err(AnyError::from)),
+  )
+}
+
 pub struct HttpRequestBody(AsyncRefCell<Peekable<ReadFuture>>, SizeHint);
 
 impl HttpRequestBody {
   pub fn new(body: Incoming) -> Self {
     let size_hint = body.size_hint();
-    Self(AsyncRefCell::new(ReadFuture(body).peekable()), size_hint)
+    Self(
+      AsyncRefCell::new(ReadFuture::Incoming(body).peekable()),
+      size_hint,
+    )
+  }
+
+  pub fn from_stream(body: BodyStream, size_hint: SizeHint) -> Self {
+    Self(
+      AsyncRefCell::new(ReadFuture::Stream(body).peekable()),
+      size_hint,
+    )
   }
 
   async fn read(self: Rc<Self>, limit: usize) -> Result<BufView, AnyError> {
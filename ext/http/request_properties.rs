@@ -32,6 +32,8 @@ pub struct HttpConnectionProperties {
   pub peer_port: Option<u16>,
   pub local_port: Option<u16>,
   pub stream_type: NetworkStreamType,
+  /// The PEM encoded certificate chain a TLS client authenticated with.
+  pub peer_certificates: Option<Rc<[String]>>,
 }
 
 pub struct HttpRequestProperties {
@@ -173,6 +175,7 @@ impl HttpPropertyExtractor for DefaultHttpPropertyExtractor {
       peer_port,
       local_port,
       stream_type,
+      peer_certificates: None,
     }
   }
 
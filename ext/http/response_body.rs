@@ -22,6 +22,9 @@ use hyper1::body::Body;
 use hyper1::body::Frame;
 use hyper1::body::SizeHint;
 use pin_project::pin_project;
+use zstd::stream::raw::InBuffer;
+use zstd::stream::raw::Operation;
+use zstd::stream::raw::OutBuffer;
 
 use crate::slab::HttpRequestBodyAutocloser;
 
@@ -111,11 +114,36 @@ trait PollFrame: Unpin {
   fn size_hint(&self) -> SizeHint;
 }
 
-#[derive(PartialEq, Eq)]
+/// The encoding to apply to a response, along with the compression level for it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Compression {
   None,
-  GZip,
-  Brotli,
+  GZip(u32),
+  Brotli(u32),
+  Zstd(i32),
+}
+
+impl Compression {
+  /// We're using gzip compression level 1, as higher levels don't produce significant size
+  /// differences. This is probably the reason why nginx's default gzip compression level is also 1:
+  ///
+  /// https://nginx.org/en/docs/http/ngx_http_gzip_module.html#gzip_comp_level
+  pub const DEFAULT_GZIP_LEVEL: u32 = 1;
+  /// Quality level 6 is based on google's nginx default value for on-the-fly compression
+  /// https://github.com/google/ngx_brotli#brotli_comp_level
+  pub const DEFAULT_BROTLI_LEVEL: u32 = 6;
+  /// zstd's own default level, which is already tuned for speed.
+  pub const DEFAULT_ZSTD_LEVEL: i32 = 3;
+
+  /// The value of the `Content-Encoding` header for this compression.
+  pub fn content_encoding(&self) -> Option<&'static str> {
+    match self {
+      Self::None => None,
+      Self::GZip(..) => Some("gzip"),
+      Self::Brotli(..) => Some("br"),
+      Self::Zstd(..) => Some("zstd"),
+    }
+  }
 }
 
 pub enum ResponseStream {
@@ -150,6 +178,8 @@ pub enum ResponseBytesInner {
   GZipStream(GZipResponseStream),
   /// A Brotli stream.
   BrotliStream(BrotliResponseStream),
+  /// A zstd stream.
+  ZstdStream(ZstdResponseStream),
 }
 
 impl std::fmt::Debug for ResponseBytesInner {
@@ -161,6 +191,7 @@ impl std::fmt::Debug for ResponseBytesInner {
       Self::UncompressedStream(..) => f.write_str("Uncompressed"),
       Self::GZipStream(..) => f.write_str("GZip"),
       Self::BrotliStream(..) => f.write_str("Brotli"),
+      Self::ZstdStream(..) => f.write_str("Zstd"),
     }
   }
 }
@@ -217,6 +248,7 @@ impl ResponseBytesInner {
       Self::Done | Self::Empty | Self::Bytes(..) => {}
       Self::BrotliStream(stm) => stm.abort(),
       Self::GZipStream(stm) => stm.abort(),
+      Self::ZstdStream(stm) => stm.abort(),
       Self::UncompressedStream(stm) => stm.abort(),
     }
   }
@@ -229,16 +261,22 @@ impl ResponseBytesInner {
       Self::UncompressedStream(res) => res.size_hint(),
       Self::GZipStream(..) => SizeHint::default(),
       Self::BrotliStream(..) => SizeHint::default(),
+      Self::ZstdStream(..) => SizeHint::default(),
     }
   }
 
   fn from_stream(compression: Compression, stream: ResponseStream) -> Self {
     match compression {
-      Compression::GZip => Self::GZipStream(GZipResponseStream::new(stream)),
-      Compression::Brotli => {
-        Self::BrotliStream(BrotliResponseStream::new(stream))
+      Compression::GZip(level) => {
+        Self::GZipStream(GZipResponseStream::new(stream, level))
+      }
+      Compression::Brotli(level) => {
+        Self::BrotliStream(BrotliResponseStream::new(stream, level))
       }
-      _ => Self::UncompressedStream(stream),
+      Compression::Zstd(level) => {
+        Self::ZstdStream(ZstdResponseStream::new(stream, level))
+      }
+      Compression::None => Self::UncompressedStream(stream),
     }
   }
 
@@ -254,49 +292,43 @@ impl ResponseBytesInner {
   }
 
   pub fn from_bufview(compression: Compression, buf: BufView) -> Self {
-    match compression {
-      Compression::GZip => {
-        let mut writer =
-          GzEncoder::new(Vec::new(), flate2::Compression::fast());
-        writer.write_all(&buf).unwrap();
-        Self::Bytes(BufView::from(writer.finish().unwrap()))
-      }
-      Compression::Brotli => {
-        // quality level 6 is based on google's nginx default value for
-        // on-the-fly compression
-        // https://github.com/google/ngx_brotli#brotli_comp_level
-        // lgwin 22 is equivalent to brotli window size of (2**22)-16 bytes
-        // (~4MB)
-        let mut writer =
-          brotli::CompressorWriter::new(Vec::new(), 65 * 1024, 6, 22);
-        writer.write_all(&buf).unwrap();
-        writer.flush().unwrap();
-        Self::Bytes(BufView::from(writer.into_inner()))
-      }
-      _ => Self::Bytes(buf),
+    match compress_buf(compression, &buf) {
+      Some(compressed) => Self::Bytes(BufView::from(compressed)),
+      None => Self::Bytes(buf),
     }
   }
 
   pub fn from_vec(compression: Compression, vec: Vec<u8>) -> Self {
-    match compression {
-      Compression::GZip => {
-        let mut writer =
-          GzEncoder::new(Vec::new(), flate2::Compression::fast());
-        writer.write_all(&vec).unwrap();
-        Self::Bytes(BufView::from(writer.finish().unwrap()))
-      }
-      Compression::Brotli => {
-        let mut writer =
-          brotli::CompressorWriter::new(Vec::new(), 65 * 1024, 6, 22);
-        writer.write_all(&vec).unwrap();
-        writer.flush().unwrap();
-        Self::Bytes(BufView::from(writer.into_inner()))
-      }
-      _ => Self::Bytes(BufView::from(vec)),
+    match compress_buf(compression, &vec) {
+      Some(compressed) => Self::Bytes(BufView::from(compressed)),
+      None => Self::Bytes(BufView::from(vec)),
     }
   }
 }
 
+/// Compress a complete response body in one go. Returns `None` if no compression was requested.
+fn compress_buf(compression: Compression, buf: &[u8]) -> Option<Vec<u8>> {
+  match compression {
+    Compression::GZip(level) => {
+      let mut writer =
+        GzEncoder::new(Vec::new(), flate2::Compression::new(level));
+      writer.write_all(buf).unwrap();
+      Some(writer.finish().unwrap())
+    }
+    Compression::Brotli(level) => {
+      // lgwin 22 is equivalent to brotli window size of (2**22)-16 bytes
+      // (~4MB)
+      let mut writer =
+        brotli::CompressorWriter::new(Vec::new(), 65 * 1024, level, 22);
+      writer.write_all(buf).unwrap();
+      writer.flush().unwrap();
+      Some(writer.into_inner())
+    }
+    Compression::Zstd(level) => Some(zstd::bulk::compress(buf, level).unwrap()),
+    Compression::None => None,
+  }
+}
+
 impl Body for ResponseBytes {
   type Data = BufView;
   type Error = AnyError;
@@ -328,6 +360,9 @@ impl Body for ResponseBytes {
         ResponseBytesInner::BrotliStream(stm) => {
           ready!(Pin::new(stm).poll_frame(cx))
         }
+        ResponseBytesInner::ZstdStream(stm) => {
+          ready!(Pin::new(stm).poll_frame(cx))
+        }
       };
       // This is where we retry the NoData response
       if matches!(res, ResponseStreamResult::NoData) {
@@ -477,9 +512,9 @@ pub struct GZipResponseStream {
 }
 
 impl GZipResponseStream {
-  pub fn new(underlying: ResponseStream) -> Self {
+  pub fn new(underlying: ResponseStream, level: u32) -> Self {
     Self {
-      stm: flate2::Compress::new(flate2::Compression::fast(), false),
+      stm: flate2::Compress::new(flate2::Compression::new(level), false),
       crc: flate2::Crc::new(),
       next_buf: None,
       partial: None,
@@ -495,11 +530,6 @@ impl GZipResponseStream {
 
 /// This is a minimal GZip header suitable for serving data from a webserver. We don't need to provide
 /// most of the information. We're skipping header name, CRC, etc, and providing a null timestamp.
-///
-/// We're using compression level 1, as higher levels don't produce significant size differences. This
-/// is probably the reason why nginx's default gzip compression level is also 1:
-///
-/// https://nginx.org/en/docs/http/ngx_http_gzip_module.html#gzip_comp_level
 static GZIP_HEADER: Bytes =
   Bytes::from_static(&[0x1f, 0x8b, 0x08, 0, 0, 0, 0, 0, 0x01, 0xff]);
 
@@ -642,7 +672,7 @@ impl Drop for BrotliEncoderStateWrapper {
 }
 
 impl BrotliResponseStream {
-  pub fn new(underlying: ResponseStream) -> Self {
+  pub fn new(underlying: ResponseStream, quality: u32) -> Self {
     // SAFETY: creating an FFI instance should be OK with these args.
     let stm = unsafe {
       let stm = brotli::ffi::compressor::BrotliEncoderCreateInstance(
@@ -650,13 +680,11 @@ impl BrotliResponseStream {
         None,
         std::ptr::null_mut(),
       );
-      // Quality level 6 is based on google's nginx default value for on-the-fly compression
-      // https://github.com/google/ngx_brotli#brotli_comp_level
       // lgwin 22 is equivalent to brotli window size of (2**22)-16 bytes (~4MB)
       brotli::ffi::compressor::BrotliEncoderSetParameter(
         stm,
         BrotliEncoderParameter::BROTLI_PARAM_QUALITY,
-        6,
+        quality,
       );
       brotli::ffi::compressor::BrotliEncoderSetParameter(
         stm,
@@ -794,6 +822,119 @@ impl PollFrame for BrotliResponseStream {
   }
 }
 
+#[derive(Copy, Clone, Debug)]
+enum ZstdState {
+  Streaming,
+  Flushing,
+  EndOfStream,
+}
+
+/// The size of the output buffer handed to zstd, which is its recommended streaming output size.
+const ZSTD_BUF_SIZE: usize = 128 * 1024;
+
+#[pin_project]
+pub struct ZstdResponseStream {
+  state: ZstdState,
+  stm: zstd::stream::raw::Encoder<'static>,
+  #[pin]
+  underlying: ResponseStream,
+}
+
+impl ZstdResponseStream {
+  pub fn new(underlying: ResponseStream, level: i32) -> Self {
+    Self {
+      state: ZstdState::Streaming,
+      stm: zstd::stream::raw::Encoder::new(level).unwrap(),
+      underlying,
+    }
+  }
+
+  pub fn abort(self) {
+    self.underlying.abort()
+  }
+
+  /// Compress a chunk of input, then flush (or finish the frame) so that everything we've been
+  /// given so far can be sent to the client.
+  fn compress(
+    &mut self,
+    input: &[u8],
+    finish: bool,
+  ) -> std::io::Result<Vec<u8>> {
+    let mut output = vec![];
+    let mut buf = vec![0u8; ZSTD_BUF_SIZE];
+    let mut input = InBuffer::around(input);
+    while input.pos() < input.src.len() {
+      let mut out = OutBuffer::around(&mut buf[..]);
+      self.stm.run(&mut input, &mut out)?;
+      output.extend_from_slice(out.as_slice());
+    }
+    loop {
+      let mut out = OutBuffer::around(&mut buf[..]);
+      let remaining = if finish {
+        self.stm.finish(&mut out, true)?
+      } else {
+        self.stm.flush(&mut out)?
+      };
+      output.extend_from_slice(out.as_slice());
+      if remaining == 0 {
+        break;
+      }
+    }
+    Ok(output)
+  }
+}
+
+impl PollFrame for ZstdResponseStream {
+  fn poll_frame(
+    self: Pin<&mut Self>,
+    cx: &mut std::task::Context<'_>,
+  ) -> std::task::Poll<ResponseStreamResult> {
+    let this = self.get_mut();
+    let frame = match this.state {
+      ZstdState::Streaming => {
+        ready!(Pin::new(&mut this.underlying).poll_frame(cx))
+      }
+      ZstdState::Flushing => {
+        this.state = ZstdState::EndOfStream;
+        return std::task::Poll::Ready(ResponseStreamResult::EndOfStream);
+      }
+      ZstdState::EndOfStream => {
+        return std::task::Poll::Ready(ResponseStreamResult::EndOfStream);
+      }
+    };
+
+    let res = match frame {
+      ResponseStreamResult::NonEmptyBuf(buf) => {
+        match this.compress(&buf, false) {
+          Ok(output) if output.is_empty() => ResponseStreamResult::NoData,
+          Ok(output) => {
+            ResponseStreamResult::NonEmptyBuf(BufView::from(output))
+          }
+          Err(err) => ResponseStreamResult::Error(err.into()),
+        }
+      }
+      ResponseStreamResult::EndOfStream => match this.compress(&[], true) {
+        Ok(output) if output.is_empty() => {
+          this.state = ZstdState::EndOfStream;
+          ResponseStreamResult::EndOfStream
+        }
+        Ok(output) => {
+          this.state = ZstdState::Flushing;
+          ResponseStreamResult::NonEmptyBuf(BufView::from(output))
+        }
+        Err(err) => ResponseStreamResult::Error(err.into()),
+      },
+      _ => frame,
+    };
+
+    std::task::Poll::Ready(res)
+  }
+
+  fn size_hint(&self) -> SizeHint {
+    SizeHint::default()
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -880,7 +1021,8 @@ mod tests {
     }
     let (tx, rx) = tokio::sync::mpsc::channel(1);
     let underlying = ResponseStream::TestChannel(rx);
-    let mut resp = GZipResponseStream::new(underlying);
+    let mut resp =
+      GZipResponseStream::new(underlying, Compression::DEFAULT_GZIP_LEVEL);
     let handle = tokio::task::spawn(async move {
       for chunk in v {
         tx.send(chunk.into()).await.ok().unwrap();
@@ -922,7 +1064,8 @@ mod tests {
     }
     let (tx, rx) = tokio::sync::mpsc::channel(1);
     let underlying = ResponseStream::TestChannel(rx);
-    let mut resp = BrotliResponseStream::new(underlying);
+    let mut resp =
+      BrotliResponseStream::new(underlying, Compression::DEFAULT_BROTLI_LEVEL);
     let handle = tokio::task::spawn(async move {
       for chunk in v {
         tx.send(chunk.into()).await.ok().unwrap();
@@ -958,22 +1101,74 @@ mod tests {
     handle.await.unwrap();
   }
 
+  async fn test_zstd(i: impl Iterator<Item = Vec<u8>> + Send + 'static) {
+    let v = i.collect::<Vec<_>>();
+    let mut expected: Vec<u8> = vec![];
+    for v in &v {
+      expected.extend(v);
+    }
+    let (tx, rx) = tokio::sync::mpsc::channel(1);
+    let underlying = ResponseStream::TestChannel(rx);
+    let mut resp =
+      ZstdResponseStream::new(underlying, Compression::DEFAULT_ZSTD_LEVEL);
+    let handle = tokio::task::spawn(async move {
+      for chunk in v {
+        tx.send(chunk.into()).await.ok().unwrap();
+      }
+    });
+    // Limit how many times we'll loop
+    const LIMIT: usize = 1000;
+    let mut v: Vec<u8> = vec![];
+    for i in 0..=LIMIT {
+      assert_ne!(i, LIMIT);
+      let frame = poll_fn(|cx| Pin::new(&mut resp).poll_frame(cx)).await;
+      if matches!(frame, ResponseStreamResult::EndOfStream) {
+        break;
+      }
+      if matches!(frame, ResponseStreamResult::NoData) {
+        continue;
+      }
+      let ResponseStreamResult::NonEmptyBuf(buf) = frame else {
+        panic!("Unexpected stream type");
+      };
+      assert_ne!(buf.len(), 0);
+      v.extend(&*buf);
+    }
+
+    let v = zstd::stream::decode_all(&*v).unwrap();
+    assert_eq!(v, expected);
+
+    handle.await.unwrap();
+  }
+
   #[tokio::test]
   async fn test_simple() {
     test_brotli(vec![b"hello world".to_vec()].into_iter()).await;
     test_gzip(vec![b"hello world".to_vec()].into_iter()).await;
+    test_zstd(vec![b"hello world".to_vec()].into_iter()).await;
   }
 
   #[tokio::test]
   async fn test_empty() {
     test_brotli(vec![].into_iter()).await;
     test_gzip(vec![].into_iter()).await;
+    test_zstd(vec![].into_iter()).await;
   }
 
   #[tokio::test]
   async fn test_simple_zeros() {
     test_brotli(vec![vec![0; 0x10000]].into_iter()).await;
     test_gzip(vec![vec![0; 0x10000]].into_iter()).await;
+    test_zstd(vec![vec![0; 0x10000]].into_iter()).await;
+  }
+
+  #[test]
+  fn test_compress_buf() {
+    let data = b"hello world, hello world, hello world".repeat(16);
+    let compressed = compress_buf(Compression::Zstd(19), &data).unwrap();
+    assert!(compressed.len() < data.len());
+    assert_eq!(zstd::stream::decode_all(&*compressed).unwrap(), data);
+    assert_eq!(compress_buf(Compression::None, &data), None);
   }
 
   macro_rules! test {
@@ -985,6 +1180,8 @@ mod tests {
           super::test_gzip(iter).await;
           let br_iter = super::chunk(super::$vec());
           super::test_brotli(br_iter).await;
+          let zstd_iter = super::chunk(super::$vec());
+          super::test_zstd(zstd_iter).await;
         }
 
         #[tokio::test]
@@ -993,6 +1190,8 @@ mod tests {
           super::test_gzip(iter).await;
           let br_iter = super::front_load(super::$vec());
           super::test_brotli(br_iter).await;
+          let zstd_iter = super::front_load(super::$vec());
+          super::test_zstd(zstd_iter).await;
         }
 
         #[tokio::test]
@@ -1001,6 +1200,8 @@ mod tests {
           super::test_gzip(iter).await;
           let br_iter = super::front_load_but_one(super::$vec());
           super::test_brotli(br_iter).await;
+          let zstd_iter = super::front_load_but_one(super::$vec());
+          super::test_zstd(zstd_iter).await;
         }
 
         #[tokio::test]
@@ -1009,6 +1210,8 @@ mod tests {
           super::test_gzip(iter).await;
           let br_iter = super::back_load(super::$vec());
           super::test_brotli(br_iter).await;
+          let zstd_iter = super::back_load(super::$vec());
+          super::test_zstd(zstd_iter).await;
         }
 
         #[tokio::test]
@@ -1017,6 +1220,8 @@ mod tests {
           super::test_gzip(iter).await;
           let br_iter = super::random(super::$vec());
           super::test_brotli(br_iter).await;
+          let zstd_iter = super::random(super::$vec());
+          super::test_zstd(zstd_iter).await;
         }
       }
     };
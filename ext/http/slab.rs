@@ -1,13 +1,21 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+use crate::request_body::BodyStream;
+use crate::request_body::HttpRequestBody;
 use crate::request_properties::HttpConnectionProperties;
 use crate::response_body::CompletionHandle;
 use crate::response_body::ResponseBytes;
+use crate::stats::HttpServerStats;
 use deno_core::error::AnyError;
 use deno_core::OpState;
 use deno_core::ResourceId;
+use deno_telemetry::Span;
+use deno_telemetry::SpanContext;
+use deno_telemetry::SpanKind;
+use deno_telemetry::TRACEPARENT;
 use http::request::Parts;
 use http::HeaderMap;
 use hyper1::body::Incoming;
+use hyper1::body::SizeHint;
 use hyper1::upgrade::OnUpgrade;
 
 use scopeguard::defer;
@@ -21,12 +29,15 @@ pub type Request = hyper1::Request<Incoming>;
 pub type Response = hyper1::Response<ResponseBytes>;
 pub type SlabId = u32;
 
+/// Keeps a server alive while connections and requests are outstanding, and carries the server's
+/// live statistics.
 #[repr(transparent)]
 #[derive(Clone, Default)]
-pub struct RefCount(pub Rc<()>);
+pub struct RefCount(pub Rc<HttpServerStats>);
 
 enum RequestBodyState {
   Incoming(Incoming),
+  Stream(BodyStream, SizeHint),
   Resource(HttpRequestBodyAutocloser),
 }
 
@@ -59,10 +70,67 @@ pub async fn new_slab_future(
   refcount: RefCount,
   tx: tokio::sync::mpsc::Sender<SlabId>,
 ) -> Result<Response, hyper::Error> {
-  let index = slab_insert(request, request_info, refcount);
+  let (request_parts, request_body) = request.into_parts();
+  Ok(
+    slab_future(
+      request_parts,
+      request_body.into(),
+      request_info,
+      refcount,
+      tx,
+    )
+    .await,
+  )
+}
+
+/// Like [`new_slab_future`], but for requests whose body does not come from hyper (ie: HTTP/3).
+pub async fn new_slab_future_from_stream(
+  request_parts: Parts,
+  request_body: BodyStream,
+  size_hint: SizeHint,
+  request_info: HttpConnectionProperties,
+  refcount: RefCount,
+  tx: tokio::sync::mpsc::Sender<SlabId>,
+) -> Response {
+  slab_future(
+    request_parts,
+    RequestBodyState::Stream(request_body, size_hint),
+    request_info,
+    refcount,
+    tx,
+  )
+  .await
+}
+
+async fn slab_future(
+  request_parts: Parts,
+  request_body: RequestBodyState,
+  request_info: HttpConnectionProperties,
+  refcount: RefCount,
+  tx: tokio::sync::mpsc::Sender<SlabId>,
+) -> Response {
+  let parent_span = request_parts
+    .headers
+    .get(TRACEPARENT)
+    .and_then(|value| SpanContext::from_traceparent(value.as_bytes()));
+  let mut span =
+    Span::start(request_parts.method.as_str(), SpanKind::Server, parent_span);
+  if let Some(span) = &mut span {
+    span.set_attribute("http.request.method", request_parts.method.as_str());
+    span.set_attribute("url.path", request_parts.uri.path());
+    if let Some(query) = request_parts.uri.query() {
+      span.set_attribute("url.query", query);
+    }
+  }
+  let request_guard = refcount.0.request_started();
+  let index =
+    slab_insert_raw(request_parts, Some(request_body), request_info, refcount);
   defer! {
     slab_drop(index);
   }
+  if let Some(span) = &span {
+    slab_get(index).self_mut().span_context = Some(span.context());
+  }
   let rx = slab_get(index).promise();
   if tx.send(index).await.is_ok() {
     http_trace!(index, "SlabFuture await");
@@ -71,7 +139,15 @@ pub async fn new_slab_future(
     http_trace!(index, "SlabFuture complete");
   }
   let response = slab_get(index).take_response();
-  Ok(response)
+  request_guard.complete(response.status());
+  if let Some(mut span) = span {
+    let status = response.status();
+    span.set_attribute("http.response.status_code", status.as_u16());
+    if status.is_server_error() {
+      span.set_error(status.to_string());
+    }
+  }
+  response
 }
 
 pub struct HttpSlabRecord {
@@ -82,6 +158,8 @@ pub struct HttpSlabRecord {
   response: Option<Response>,
   promise: CompletionHandle,
   trailers: Rc<RefCell<Option<HeaderMap>>>,
+  /// The server span of this request, if it is being traced.
+  span_context: Option<SpanContext>,
   been_dropped: bool,
   /// Use a `Rc` to keep track of outstanding requests. We don't use this, but
   /// when it drops, it decrements the refcount of the server itself.
@@ -152,7 +230,7 @@ pub fn slab_get(index: SlabId) -> SlabEntry {
 #[allow(clippy::let_and_return)]
 fn slab_insert_raw(
   request_parts: Parts,
-  request_body: Option<Incoming>,
+  request_body: Option<RequestBodyState>,
   request_info: HttpConnectionProperties,
   refcount: RefCount,
 ) -> SlabId {
@@ -160,13 +238,13 @@ fn slab_insert_raw(
     let mut slab = slab.borrow_mut();
     let body = ResponseBytes::default();
     let trailers = body.trailers();
-    let request_body = request_body.map(|r| r.into());
     slab.insert(HttpSlabRecord {
       request_info,
       request_parts,
       request_body,
       response: Some(Response::new(body)),
       trailers,
+      span_context: None,
       been_dropped: false,
       promise: CompletionHandle::default(),
       refcount: Some(refcount),
@@ -178,15 +256,6 @@ fn slab_insert_raw(
   index
 }
 
-pub fn slab_insert(
-  request: Request,
-  request_info: HttpConnectionProperties,
-  refcount: RefCount,
-) -> SlabId {
-  let (request_parts, request_body) = request.into_parts();
-  slab_insert_raw(request_parts, Some(request_body), request_info, refcount)
-}
-
 pub fn slab_drop(index: SlabId) {
   http_trace!(index, "slab_drop");
   let mut entry = slab_get(index);
@@ -249,12 +318,17 @@ impl SlabEntry {
       .ok_or_else(|| AnyError::msg("upgrade unavailable"))
   }
 
-  /// Take the Hyper body from this entry.
-  pub fn take_body(&mut self) -> Option<Incoming> {
+  /// Take the request body from this entry, wrapped up so that it can be read as a resource.
+  pub fn take_body(&mut self) -> Option<HttpRequestBody> {
     let body_holder = &mut self.self_mut().request_body;
     let body = body_holder.take();
     match body {
-      Some(RequestBodyState::Incoming(body)) => Some(body),
+      Some(RequestBodyState::Incoming(body)) => {
+        Some(HttpRequestBody::new(body))
+      }
+      Some(RequestBodyState::Stream(body, size_hint)) => {
+        Some(HttpRequestBody::from_stream(body, size_hint))
+      }
       x => {
         *body_holder = x;
         None
@@ -329,6 +403,11 @@ impl SlabEntry {
     &self.self_ref().request_parts
   }
 
+  /// Get the context of the server span tracing this request.
+  pub fn span_context(&self) -> Option<SpanContext> {
+    self.self_ref().span_context
+  }
+
   /// Get a reference to the completion handle.
   pub fn promise(&self) -> CompletionHandle {
     self.self_ref().promise.clone()
@@ -364,6 +443,7 @@ mod tests {
         peer_port: None,
         local_port: None,
         stream_type: NetworkStreamType::Tcp,
+        peer_certificates: None,
       },
       RefCount::default(),
     );
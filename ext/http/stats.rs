@@ -0,0 +1,240 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+use http::StatusCode;
+use pin_project::pin_project;
+use serde::Serialize;
+use std::cell::Cell;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::task::ready;
+use std::task::Context;
+use std::task::Poll;
+use std::time::Instant;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncWrite;
+use tokio::io::ReadBuf;
+
+/// Upper bounds (in milliseconds) of the request latency histogram buckets. A final bucket
+/// catches everything slower than the last bound.
+const LATENCY_BUCKETS_MS: [f64; 12] = [
+  1.0, 2.5, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 10000.0,
+];
+
+/// Byte counters are updated from connection I/O, which must be `Send`.
+#[derive(Default)]
+pub struct ByteCounters {
+  read: AtomicU64,
+  written: AtomicU64,
+}
+
+impl ByteCounters {
+  pub fn add_read(&self, n: usize) {
+    self.read.fetch_add(n as u64, Ordering::Relaxed);
+  }
+
+  pub fn add_written(&self, n: usize) {
+    self.written.fetch_add(n as u64, Ordering::Relaxed);
+  }
+}
+
+/// Live counters for a single `Deno.serve` server, shared by every connection and request through
+/// the server's [`RefCount`](crate::slab::RefCount).
+#[derive(Default)]
+pub struct HttpServerStats {
+  open_connections: Cell<u64>,
+  total_connections: Cell<u64>,
+  in_flight_requests: Cell<u64>,
+  total_requests: Cell<u64>,
+  server_errors: Cell<u64>,
+  bytes: Arc<ByteCounters>,
+  latency_counts: [Cell<u64>; LATENCY_BUCKETS_MS.len() + 1],
+  latency_sum_ms: Cell<f64>,
+}
+
+impl HttpServerStats {
+  /// Track an open connection until the returned guard is dropped.
+  pub fn connection_opened(self: &Rc<Self>) -> ConnectionGuard {
+    self.open_connections.set(self.open_connections.get() + 1);
+    self.total_connections.set(self.total_connections.get() + 1);
+    ConnectionGuard(self.clone())
+  }
+
+  /// Track an in-flight request until the returned guard is dropped.
+  pub fn request_started(self: &Rc<Self>) -> RequestGuard {
+    self
+      .in_flight_requests
+      .set(self.in_flight_requests.get() + 1);
+    self.total_requests.set(self.total_requests.get() + 1);
+    RequestGuard(self.clone(), Instant::now())
+  }
+
+  pub fn bytes(&self) -> &Arc<ByteCounters> {
+    &self.bytes
+  }
+
+  /// Wrap a connection stream so that everything read from or written to it is counted.
+  pub fn count_bytes<S>(&self, io: S) -> CountingStream<S> {
+    CountingStream {
+      io,
+      bytes: self.bytes.clone(),
+    }
+  }
+
+  pub fn snapshot(&self) -> HttpServerStatsSnapshot {
+    let latency = LATENCY_BUCKETS_MS
+      .iter()
+      .copied()
+      .chain(std::iter::once(f64::INFINITY))
+      .zip(self.latency_counts.iter())
+      .map(|(le, count)| LatencyBucket {
+        le,
+        count: count.get(),
+      })
+      .collect();
+    HttpServerStatsSnapshot {
+      open_connections: self.open_connections.get(),
+      total_connections: self.total_connections.get(),
+      in_flight_requests: self.in_flight_requests.get(),
+      total_requests: self.total_requests.get(),
+      server_errors: self.server_errors.get(),
+      bytes_read: self.bytes.read.load(Ordering::Relaxed),
+      bytes_written: self.bytes.written.load(Ordering::Relaxed),
+      latency,
+      latency_sum_ms: self.latency_sum_ms.get(),
+    }
+  }
+}
+
+pub struct ConnectionGuard(Rc<HttpServerStats>);
+
+impl Drop for ConnectionGuard {
+  fn drop(&mut self) {
+    let stats = &self.0;
+    stats.open_connections.set(stats.open_connections.get() - 1);
+  }
+}
+
+pub struct RequestGuard(Rc<HttpServerStats>, Instant);
+
+impl RequestGuard {
+  /// Record the time it took to produce the response head, and whether it was a server error.
+  pub fn complete(self, status: StatusCode) {
+    let stats = &self.0;
+    let elapsed_ms = self.1.elapsed().as_secs_f64() * 1000.0;
+    let bucket = LATENCY_BUCKETS_MS
+      .iter()
+      .position(|le| elapsed_ms <= *le)
+      .unwrap_or(LATENCY_BUCKETS_MS.len());
+    let count = &stats.latency_counts[bucket];
+    count.set(count.get() + 1);
+    stats
+      .latency_sum_ms
+      .set(stats.latency_sum_ms.get() + elapsed_ms);
+    if status.is_server_error() {
+      stats.server_errors.set(stats.server_errors.get() + 1);
+    }
+  }
+}
+
+impl Drop for RequestGuard {
+  fn drop(&mut self) {
+    let stats = &self.0;
+    stats
+      .in_flight_requests
+      .set(stats.in_flight_requests.get() - 1);
+  }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencyBucket {
+  le: f64,
+  count: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpServerStatsSnapshot {
+  open_connections: u64,
+  total_connections: u64,
+  in_flight_requests: u64,
+  total_requests: u64,
+  server_errors: u64,
+  bytes_read: u64,
+  bytes_written: u64,
+  latency: Vec<LatencyBucket>,
+  latency_sum_ms: f64,
+}
+
+/// A connection stream that counts the bytes passing through it.
+#[pin_project]
+pub struct CountingStream<S> {
+  #[pin]
+  io: S,
+  bytes: Arc<ByteCounters>,
+}
+
+impl<S> CountingStream<S> {
+  /// Stop counting, eg: when the connection is upgraded.
+  pub fn into_inner(self) -> S {
+    self.io
+  }
+}
+
+impl<S: AsyncRead> AsyncRead for CountingStream<S> {
+  fn poll_read(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    buf: &mut ReadBuf<'_>,
+  ) -> Poll<std::io::Result<()>> {
+    let this = self.project();
+    let before = buf.filled().len();
+    ready!(this.io.poll_read(cx, buf))?;
+    this.bytes.add_read(buf.filled().len() - before);
+    Poll::Ready(Ok(()))
+  }
+}
+
+impl<S: AsyncWrite> AsyncWrite for CountingStream<S> {
+  fn poll_write(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    buf: &[u8],
+  ) -> Poll<std::io::Result<usize>> {
+    let this = self.project();
+    let n = ready!(this.io.poll_write(cx, buf))?;
+    this.bytes.add_written(n);
+    Poll::Ready(Ok(n))
+  }
+
+  fn poll_write_vectored(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    bufs: &[std::io::IoSlice<'_>],
+  ) -> Poll<std::io::Result<usize>> {
+    let this = self.project();
+    let n = ready!(this.io.poll_write_vectored(cx, bufs))?;
+    this.bytes.add_written(n);
+    Poll::Ready(Ok(n))
+  }
+
+  fn is_write_vectored(&self) -> bool {
+    self.io.is_write_vectored()
+  }
+
+  fn poll_flush(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+  ) -> Poll<std::io::Result<()>> {
+    self.project().io.poll_flush(cx)
+  }
+
+  fn poll_shutdown(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+  ) -> Poll<std::io::Result<()>> {
+    self.project().io.poll_shutdown(cx)
+  }
+}
@@ -28,6 +28,8 @@ use deno_core::OpState;
 use deno_core::Resource;
 use deno_core::ResourceId;
 use deno_core::ToJsBuffer;
+use deno_telemetry::Span;
+use deno_telemetry::SpanKind;
 use serde::Deserialize;
 use serde::Serialize;
 
@@ -218,6 +220,15 @@ type SnapshotReadRange = (
   Option<ByteString>,
 );
 
+/// Starts a client span for a KV operation, if tracing is enabled.
+fn kv_span(operation: &'static str) -> Option<Span> {
+  let mut span =
+    Span::start(format!("kv.{operation}"), SpanKind::Client, None)?;
+  span.set_attribute("db.system", "deno_kv");
+  span.set_attribute("db.operation", operation);
+  Some(span)
+}
+
 #[op2(async)]
 #[serde]
 async fn op_kv_snapshot_read<DBH>(
@@ -276,8 +287,15 @@ where
   let opts = SnapshotReadOptions {
     consistency: consistency.into(),
   };
-  let output_ranges =
-    db.snapshot_read(state.clone(), read_ranges, opts).await?;
+  let mut span = kv_span("snapshot_read");
+  if let Some(span) = &mut span {
+    span.set_attribute("deno.kv.range_count", read_ranges.len());
+  }
+  let result = db.snapshot_read(state.clone(), read_ranges, opts).await;
+  if let (Some(span), Err(err)) = (&mut span, &result) {
+    span.set_error(err.to_string());
+  }
+  let output_ranges = result?;
   let output_ranges = output_ranges
     .into_iter()
     .map(|x| {
@@ -681,13 +699,27 @@ where
     )));
   }
 
+  let mut span = kv_span("atomic_write");
+  if let Some(span) = &mut span {
+    span.set_attribute("deno.kv.check_count", checks.len());
+    span.set_attribute("deno.kv.mutation_count", mutations.len());
+    span.set_attribute("deno.kv.enqueue_count", enqueues.len());
+  }
+
   let atomic_write = AtomicWrite {
     checks,
     mutations,
     enqueues,
   };
 
-  let result = db.atomic_write(state.clone(), atomic_write).await?;
+  let result = db.atomic_write(state.clone(), atomic_write).await;
+  if let Some(span) = &mut span {
+    match &result {
+      Ok(commit) => span.set_attribute("deno.kv.committed", commit.is_some()),
+      Err(err) => span.set_error(err.to_string()),
+    }
+  }
+  let result = result?;
 
   Ok(result.map(|res| hex::encode(res.versionstamp)))
 }
@@ -49,6 +49,7 @@ async function resolveDns(query, recordType, options) {
   try {
     return await core.opAsync("op_dns_resolve", {
       cancelRid,
+      resolverRid: resolverRid(options?.resolver),
       query,
       recordType,
       options,
@@ -63,6 +64,40 @@ async function resolveDns(query, recordType, options) {
   }
 }
 
+class DnsResolver {
+  #rid = 0;
+
+  constructor(options = {}) {
+    this.#rid = ops.op_dns_resolver_create(options);
+  }
+
+  get rid() {
+    return this.#rid;
+  }
+
+  resolve(query, recordType, options) {
+    return resolveDns(query, recordType, {
+      signal: options?.signal,
+      resolver: this,
+    });
+  }
+
+  close() {
+    core.close(this.#rid);
+  }
+}
+const DnsResolverPrototype = DnsResolver.prototype;
+
+function resolverRid(resolver) {
+  if (resolver === undefined) {
+    return undefined;
+  }
+  if (!ObjectPrototypeIsPrototypeOf(DnsResolverPrototype, resolver)) {
+    throw new TypeError("resolver must be a Deno.DnsResolver");
+  }
+  return resolver.rid;
+}
+
 class Conn {
   #rid = 0;
   #remoteAddr = null;
@@ -419,13 +454,31 @@ class Datagram {
 
 const listenOptionApiName = Symbol("listenOptionApiName");
 
+/**
+ * Picks the socket options out of the options of `Deno.listen`,
+ * `Deno.connect` or `Deno.listenDatagram`.
+ */
+function socketOptions(args) {
+  return {
+    recvBufferSize: args.recvBufferSize,
+    sendBufferSize: args.sendBufferSize,
+    ttl: args.ttl,
+    dscp: args.dscp,
+    bindToDevice: args.bindToDevice,
+  };
+}
+
 function listen(args) {
   switch (args.transport ?? "tcp") {
     case "tcp": {
-      const { 0: rid, 1: addr } = ops.op_net_listen_tcp({
-        hostname: args.hostname ?? "0.0.0.0",
-        port: Number(args.port),
-      }, args.reusePort);
+      const { 0: rid, 1: addr } = ops.op_net_listen_tcp(
+        {
+          hostname: args.hostname ?? "0.0.0.0",
+          port: Number(args.port),
+        },
+        args.reusePort,
+        socketOptions(args),
+      );
       addr.transport = "tcp";
       return new Listener(rid, addr);
     }
@@ -445,6 +498,20 @@ function listen(args) {
   }
 }
 
+/**
+ * Listen on an inherited socket, either the file descriptor `fd` or the first
+ * one passed with the systemd `LISTEN_FDS` protocol.
+ * @param {number | "LISTEN_FDS"} fd
+ * @param {string} apiName
+ */
+function listenFd(fd, apiName = "Deno.listen") {
+  const { 0: rid, 1: addr } = ops.op_net_listen_fd(
+    fd === "LISTEN_FDS" ? null : fd,
+    apiName,
+  );
+  return new Listener(rid, addr);
+}
+
 function createListenDatagram(udpOpFn, unixOpFn) {
   return function listenDatagram(args) {
     switch (args.transport) {
@@ -455,7 +522,9 @@ function createListenDatagram(udpOpFn, unixOpFn) {
             port: args.port,
           },
           args.reuseAddress ?? false,
+          args.reusePort ?? false,
           args.loopback ?? false,
+          socketOptions(args),
         );
         addr.transport = "udp";
         return new Datagram(rid, addr);
@@ -483,6 +552,8 @@ async function connect(args) {
           hostname: args.hostname ?? "127.0.0.1",
           port: args.port,
         },
+        socketOptions(args),
+        resolverRid(args.resolver),
       );
       localAddr.transport = "tcp";
       remoteAddr.transport = "tcp";
@@ -509,8 +580,10 @@ export {
   connect,
   createListenDatagram,
   Datagram,
+  DnsResolver,
   listen,
   Listener,
+  listenFd,
   listenOptionApiName,
   resolveDns,
   shutdown,
@@ -4,7 +4,7 @@ const core = globalThis.Deno.core;
 const ops = core.ops;
 import { Conn, Listener } from "ext:deno_net/01_net.js";
 const primordials = globalThis.__bootstrap.primordials;
-const { Number, TypeError } = primordials;
+const { ArrayIsArray, Number, Symbol, TypeError } = primordials;
 
 function opStartTls(args) {
   return core.opAsync("op_tls_start", args);
@@ -43,7 +43,34 @@ async function connectTls({
   return new TlsConn(rid, remoteAddr, localAddr);
 }
 
+// Keeps the certificates of a listener that is handed over to an HTTP
+// server replaceable, until `releaseCertificates` is called once that server
+// has stopped.
+const detachCertificates = Symbol("detachCertificates");
+const releaseCertificates = Symbol("releaseCertificates");
+
 class TlsListener extends Listener {
+  /** @type {number | null} */
+  #certificatesRid = null;
+
+  setCertificates({ cert, key, sni } = {}) {
+    ops.op_tls_listener_set_certificates(this.#certificatesRid ?? this.rid, {
+      cert,
+      key,
+      sni,
+    });
+  }
+
+  [detachCertificates]() {
+    this.#certificatesRid = ops.op_tls_listener_certificates(this.rid);
+  }
+
+  [releaseCertificates]() {
+    if (this.#certificatesRid !== null) {
+      core.tryClose(this.#certificatesRid);
+    }
+  }
+
   async accept() {
     const { 0: rid, 1: localAddr, 2: remoteAddr } = await core.opAsync(
       "op_net_accept_tls",
@@ -65,13 +92,37 @@ function listenTls({
   transport = "tcp",
   alpnProtocols = undefined,
   reusePort = false,
+  sni = undefined,
+  clientAuth = undefined,
+  recvBufferSize = undefined,
+  sendBufferSize = undefined,
+  ttl = undefined,
+  dscp = undefined,
+  bindToDevice = undefined,
 }) {
   if (transport !== "tcp") {
     throw new TypeError(`Unsupported transport: '${transport}'`);
   }
+  if (clientAuth !== undefined) {
+    const { ca, required = true } = clientAuth;
+    clientAuth = {
+      ca: ArrayIsArray(ca) ? ca : [ca],
+      required: !!required,
+    };
+  }
   const { 0: rid, 1: localAddr } = ops.op_net_listen_tls(
     { hostname, port: Number(port) },
-    { cert, certFile, key, keyFile, alpnProtocols, reusePort },
+    {
+      cert,
+      certFile,
+      key,
+      keyFile,
+      alpnProtocols,
+      reusePort,
+      sni,
+      clientAuth,
+    },
+    { recvBufferSize, sendBufferSize, ttl, dscp, bindToDevice },
   );
   return new TlsListener(rid, localAddr);
 }
@@ -95,4 +146,12 @@ async function startTls(
   return new TlsConn(rid, remoteAddr, localAddr);
 }
 
-export { connectTls, listenTls, startTls, TlsConn, TlsListener };
+export {
+  connectTls,
+  detachCertificates,
+  listenTls,
+  releaseCertificates,
+  startTls,
+  TlsConn,
+  TlsListener,
+};
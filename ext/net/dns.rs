@@ -0,0 +1,343 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use crate::NetPermissions;
+use deno_core::error::range_error;
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::op2;
+use deno_core::OpState;
+use deno_core::Resource;
+use deno_core::ResourceId;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::net::IpAddr;
+use std::net::SocketAddr;
+use std::rc::Rc;
+use std::str::FromStr;
+use std::time::Duration;
+use trust_dns_proto::rr::Name;
+use trust_dns_proto::rr::RecordType;
+use trust_dns_resolver::config::NameServerConfigGroup;
+use trust_dns_resolver::config::Protocol;
+use trust_dns_resolver::config::ResolverConfig;
+use trust_dns_resolver::config::ResolverOpts;
+use trust_dns_resolver::error::ResolveError;
+use trust_dns_resolver::error::ResolveErrorKind;
+use trust_dns_resolver::lookup::Lookup;
+use trust_dns_resolver::system_conf;
+use trust_dns_resolver::TokioAsyncResolver;
+
+pub type Resolver = TokioAsyncResolver;
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum NameServerProtocol {
+  #[default]
+  Udp,
+  Tcp,
+  Tls,
+  Https,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NameServer {
+  ip_addr: String,
+  port: Option<u16>,
+  #[serde(default)]
+  protocol: NameServerProtocol,
+  /// The name the certificate of a `tls` or `https` name server is verified
+  /// against.
+  tls_name: Option<String>,
+}
+
+/// The options of `Deno.resolveDns` and `Deno.DnsResolver` that configure
+/// how names are resolved.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolverOptions {
+  name_server: Option<NameServer>,
+  /// The timeout of a single query in milliseconds.
+  timeout: Option<u64>,
+  attempts: Option<usize>,
+  search_domains: Option<Vec<String>>,
+  ndots: Option<usize>,
+  /// The number of answers kept in the cache. `0` disables caching.
+  cache_size: Option<usize>,
+}
+
+impl ResolverOptions {
+  /// Whether the options differ from the system configuration.
+  pub fn is_custom(&self) -> bool {
+    self.name_server.is_some()
+      || self.timeout.is_some()
+      || self.attempts.is_some()
+      || self.search_domains.is_some()
+      || self.ndots.is_some()
+      || self.cache_size.is_some()
+  }
+
+  fn to_config(&self) -> Result<(ResolverConfig, ResolverOpts), AnyError> {
+    let (mut config, mut opts) = match &self.name_server {
+      Some(name_server) => (
+        ResolverConfig::from_parts(None, vec![], name_server.to_group()?),
+        ResolverOpts::default(),
+      ),
+      None => system_conf::read_system_conf()?,
+    };
+    if let Some(timeout) = self.timeout {
+      if timeout == 0 {
+        return Err(range_error("timeout must be greater than 0"));
+      }
+      opts.timeout = Duration::from_millis(timeout);
+    }
+    if let Some(attempts) = self.attempts {
+      opts.attempts = attempts;
+    }
+    if let Some(ndots) = self.ndots {
+      opts.ndots = ndots;
+    }
+    if let Some(cache_size) = self.cache_size {
+      opts.cache_size = cache_size;
+    }
+    for domain in self.search_domains.iter().flatten() {
+      let name = Name::from_str(domain).map_err(|err| {
+        type_error(format!("Invalid search domain \"{domain}\": {err}"))
+      })?;
+      config.add_search(name);
+    }
+    Ok((config, opts))
+  }
+}
+
+impl NameServer {
+  fn to_group(&self) -> Result<NameServerConfigGroup, AnyError> {
+    let ip: IpAddr = self.ip_addr.parse()?;
+    let tls_name = || {
+      self.tls_name.clone().ok_or_else(|| {
+        type_error("tlsName is required for \"tls\" and \"https\" name servers")
+      })
+    };
+    Ok(match self.protocol {
+      NameServerProtocol::Udp => NameServerConfigGroup::from_ips_clear(
+        &[ip],
+        self.port.unwrap_or(53),
+        true,
+      ),
+      NameServerProtocol::Tcp => NameServerConfigGroup::from_ips_clear(
+        &[ip],
+        self.port.unwrap_or(53),
+        true,
+      )
+      .into_inner()
+      .into_iter()
+      .filter(|name_server| name_server.protocol == Protocol::Tcp)
+      .collect::<Vec<_>>()
+      .into(),
+      NameServerProtocol::Tls => NameServerConfigGroup::from_ips_tls(
+        &[ip],
+        self.port.unwrap_or(853),
+        tls_name()?,
+        true,
+      ),
+      NameServerProtocol::Https => NameServerConfigGroup::from_ips_https(
+        &[ip],
+        self.port.unwrap_or(443),
+        tls_name()?,
+        true,
+      ),
+    })
+  }
+}
+
+/// A resolver together with the addresses of the name servers it queries,
+/// which are checked against the net permissions whenever it is used.
+pub struct DnsResolverResource {
+  resolver: Resolver,
+  name_servers: Vec<SocketAddr>,
+  search: Vec<Name>,
+  ndots: usize,
+}
+
+impl DnsResolverResource {
+  pub fn new(options: &ResolverOptions) -> Result<Self, AnyError> {
+    let (config, opts) = options.to_config()?;
+    let name_servers = config
+      .name_servers()
+      .iter()
+      .map(|name_server| name_server.socket_addr)
+      .collect();
+    let search = config.search().to_vec();
+    let ndots = opts.ndots;
+    let resolver = Resolver::tokio(config, opts)?;
+    Ok(Self {
+      resolver,
+      name_servers,
+      search,
+      ndots,
+    })
+  }
+
+  pub fn resolver(&self) -> &Resolver {
+    &self.resolver
+  }
+
+  /// Looks up the records of `name`, trying it with each of the search
+  /// domains appended like `lookup_ip` does. Names with at least `ndots`
+  /// dots are tried as they are first.
+  pub async fn lookup(
+    &self,
+    name: Name,
+    record_type: RecordType,
+  ) -> Result<Lookup, ResolveError> {
+    let mut names = vec![];
+    if !name.is_fqdn() {
+      for domain in &self.search {
+        if let Ok(name) = name.clone().append_domain(domain) {
+          names.push(name);
+        }
+      }
+      if has_ndots(&name, self.ndots) {
+        names.insert(0, name);
+      } else {
+        names.push(name);
+      }
+    } else {
+      names.push(name);
+    }
+
+    let mut result = None;
+    for name in names {
+      match self.resolver.lookup(name, record_type).await {
+        Err(err)
+          if matches!(err.kind(), ResolveErrorKind::NoRecordsFound { .. }) =>
+        {
+          result = Some(Err(err));
+        }
+        result => return result,
+      }
+    }
+    result.unwrap()
+  }
+
+  pub fn check_permissions<NP: NetPermissions>(
+    &self,
+    permissions: &mut NP,
+    api_name: &str,
+  ) -> Result<(), AnyError> {
+    for addr in &self.name_servers {
+      permissions
+        .check_net(&(addr.ip().to_string(), Some(addr.port())), api_name)?;
+    }
+    Ok(())
+  }
+}
+
+/// Whether `name` has at least `ndots` dots, in which case it is tried as it
+/// is before the search domains are appended (see `resolv.conf(5)`).
+fn has_ndots(name: &Name, ndots: usize) -> bool {
+  (name.num_labels() as usize).saturating_sub(1) >= ndots
+}
+
+impl Resource for DnsResolverResource {
+  fn name(&self) -> Cow<str> {
+    "dnsResolver".into()
+  }
+}
+
+/// The resolver `Deno.resolveDns` uses when it is called without a resolver
+/// or resolver options. Keeping it in the state lets its cache outlive a
+/// single call.
+struct DefaultDnsResolver(Rc<DnsResolverResource>);
+
+/// Gets the resolver to use for a lookup: the resolver resource `rid`, a new
+/// resolver for custom `options`, or the shared default resolver.
+pub fn get_resolver(
+  state: &mut OpState,
+  rid: Option<ResourceId>,
+  options: Option<&ResolverOptions>,
+) -> Result<Rc<DnsResolverResource>, AnyError> {
+  if let Some(rid) = rid {
+    return state.resource_table.get::<DnsResolverResource>(rid);
+  }
+  if let Some(options) = options.filter(|options| options.is_custom()) {
+    return Ok(Rc::new(DnsResolverResource::new(options)?));
+  }
+  if let Some(resolver) = state.try_borrow::<DefaultDnsResolver>() {
+    return Ok(resolver.0.clone());
+  }
+  let resolver = Rc::new(DnsResolverResource::new(&Default::default())?);
+  state.put(DefaultDnsResolver(resolver.clone()));
+  Ok(resolver)
+}
+
+#[op2]
+#[smi]
+pub fn op_dns_resolver_create<NP>(
+  state: &mut OpState,
+  #[serde] options: ResolverOptions,
+) -> Result<ResourceId, AnyError>
+where
+  NP: NetPermissions + 'static,
+{
+  super::check_unstable(state, "Deno.DnsResolver");
+  let resource = DnsResolverResource::new(&options)?;
+  resource.check_permissions(state.borrow_mut::<NP>(), "Deno.DnsResolver()")?;
+  Ok(state.resource_table.add(resource))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn resolver_options_config() {
+    let options = ResolverOptions {
+      name_server: Some(NameServer {
+        ip_addr: "1.1.1.1".to_string(),
+        port: None,
+        protocol: NameServerProtocol::Tcp,
+        tls_name: None,
+      }),
+      timeout: Some(1500),
+      search_domains: Some(vec!["svc.cluster.local".to_string()]),
+      cache_size: Some(0),
+      ..Default::default()
+    };
+    let (config, opts) = options.to_config().unwrap();
+    assert_eq!(config.name_servers().len(), 1);
+    assert_eq!(config.name_servers()[0].protocol, Protocol::Tcp);
+    assert_eq!(
+      config.name_servers()[0].socket_addr,
+      "1.1.1.1:53".parse().unwrap()
+    );
+    assert_eq!(config.search().len(), 1);
+    assert_eq!(opts.timeout, Duration::from_millis(1500));
+    assert_eq!(opts.cache_size, 0);
+  }
+
+  #[test]
+  fn resolver_options_tls() {
+    let mut name_server = NameServer {
+      ip_addr: "9.9.9.9".to_string(),
+      port: None,
+      protocol: NameServerProtocol::Tls,
+      tls_name: None,
+    };
+    assert!(name_server.to_group().is_err());
+    name_server.tls_name = Some("dns.quad9.net".to_string());
+    let group = name_server.to_group().unwrap();
+    assert_eq!(group[0].protocol, Protocol::Tls);
+    assert_eq!(group[0].socket_addr.port(), 853);
+  }
+
+  #[test]
+  fn ndots() {
+    let name = Name::from_str("db.internal").unwrap();
+    assert!(has_ndots(&name, 1));
+    assert!(!has_ndots(&name, 2));
+    let name = Name::from_str("localhost").unwrap();
+    assert!(!has_ndots(&name, 1));
+    assert!(has_ndots(&name, 0));
+  }
+}
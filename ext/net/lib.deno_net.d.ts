@@ -54,7 +54,8 @@ declare namespace Deno {
    *
    * @category Network
    */
-  export type TlsListener = Listener<TlsConn>;
+  // deno-lint-ignore no-empty-interface
+  export interface TlsListener extends Listener<TlsConn> {}
 
   /** @category Network */
   export interface Conn extends Reader, Writer, Closer {
@@ -113,9 +114,41 @@ declare namespace Deno {
     hostname?: string;
   }
 
+  /** Options that tune the socket of a TCP or UDP connection or listener.
+   * They are applied before the socket is bound or connected.
+   *
+   * ```ts
+   * const listener = Deno.listen({
+   *   port: 8080,
+   *   recvBufferSize: 4 * 1024 * 1024,
+   *   dscp: 46,
+   * });
+   * ```
+   *
+   * @category Network
+   */
+  export interface SocketOptions {
+    /** The size of the receive buffer in bytes (`SO_RCVBUF`). The operating
+     * system may round or double the value. */
+    recvBufferSize?: number;
+    /** The size of the send buffer in bytes (`SO_SNDBUF`). The operating
+     * system may round or double the value. */
+    sendBufferSize?: number;
+    /** The time-to-live of outgoing IPv4 packets, or the hop limit of
+     * outgoing IPv6 packets. Must be between 1 and 255. */
+    ttl?: number;
+    /** The Differentiated Services Code Point of outgoing packets, which is
+     * written to the upper six bits of the IPv4 TOS field. Must be between 0
+     * and 63. Only supported on IPv4 sockets. */
+    dscp?: number;
+    /** Bind the socket to a network interface, like `"eth0"`
+     * (`SO_BINDTODEVICE`). Only supported on Linux, where it may require the
+     * `CAP_NET_RAW` capability. */
+    bindToDevice?: string;
+  }
+
   /** @category Network */
-  // deno-lint-ignore no-empty-interface
-  export interface TcpListenOptions extends ListenOptions {
+  export interface TcpListenOptions extends ListenOptions, SocketOptions {
   }
 
   /** Listen announces on the local transport address.
@@ -180,7 +213,7 @@ declare namespace Deno {
   export function listenTls(options: ListenTlsOptions): TlsListener;
 
   /** @category Network */
-  export interface ConnectOptions {
+  export interface ConnectOptions extends SocketOptions {
     /** The port to connect to. */
     port: number;
     /** A literal IP address or host name that can be resolved to an IP address.
@@ -1,5 +1,6 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+pub mod dns;
 pub mod io;
 pub mod ops;
 pub mod ops_tls;
@@ -76,6 +77,7 @@ deno_core::extension!(deno_net,
     ops::op_net_set_multi_loopback_udp,
     ops::op_net_set_multi_ttl_udp,
     ops::op_dns_resolve<P>,
+    dns::op_dns_resolver_create<P>,
     ops::op_set_nodelay,
     ops::op_set_keepalive,
 
@@ -84,10 +86,14 @@ deno_core::extension!(deno_net,
     ops_tls::op_net_listen_tls<P>,
     ops_tls::op_net_accept_tls,
     ops_tls::op_tls_handshake,
+    ops_tls::op_tls_listener_certificates,
+    ops_tls::op_tls_listener_set_certificates,
 
     #[cfg(unix)] ops_unix::op_net_accept_unix,
     #[cfg(unix)] ops_unix::op_net_connect_unix<P>,
     #[cfg(unix)] ops_unix::op_net_listen_unix<P>,
+    #[cfg(unix)] ops_unix::op_net_listen_fd<P>,
+    #[cfg(not(unix))] ops::op_net_listen_fd,
     #[cfg(unix)] ops_unix::op_net_listen_unixpacket<P>,
     #[cfg(unix)] ops_unix::op_node_unstable_net_listen_unixpacket<P>,
     #[cfg(unix)] ops_unix::op_net_recv_unixpacket,
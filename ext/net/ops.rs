@@ -1,5 +1,8 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use crate::dns::get_resolver;
+use crate::dns::DnsResolverResource;
+use crate::dns::ResolverOptions;
 use crate::io::TcpStreamResource;
 use crate::resolve_addr::resolve_addr;
 use crate::resolve_addr::resolve_addr_sync;
@@ -7,6 +10,7 @@ use crate::NetPermissions;
 use deno_core::error::bad_resource;
 use deno_core::error::custom_error;
 use deno_core::error::generic_error;
+use deno_core::error::range_error;
 use deno_core::error::AnyError;
 use deno_core::op2;
 use deno_core::CancelFuture;
@@ -34,22 +38,21 @@ use std::net::SocketAddr;
 use std::rc::Rc;
 use std::str::FromStr;
 use tokio::net::TcpListener;
-use tokio::net::TcpStream;
+use tokio::net::TcpSocket;
 use tokio::net::UdpSocket;
 use trust_dns_proto::rr::rdata::caa::Value;
 use trust_dns_proto::rr::record_data::RData;
 use trust_dns_proto::rr::record_type::RecordType;
-use trust_dns_resolver::config::NameServerConfigGroup;
-use trust_dns_resolver::config::ResolverConfig;
-use trust_dns_resolver::config::ResolverOpts;
+use trust_dns_proto::rr::IntoName;
+use trust_dns_proto::rr::Name;
 use trust_dns_resolver::error::ResolveErrorKind;
-use trust_dns_resolver::system_conf;
-use trust_dns_resolver::AsyncResolver;
 
 #[derive(Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct TlsHandshakeInfo {
   pub alpn_protocol: Option<ByteString>,
+  /// The PEM encoded certificate chain the peer presented, if any.
+  pub peer_certificates: Option<Vec<String>>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -67,6 +70,71 @@ impl From<SocketAddr> for IpAddr {
   }
 }
 
+/// Socket options that can be set when a TCP or UDP socket is opened.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SocketOptions {
+  recv_buffer_size: Option<usize>,
+  send_buffer_size: Option<usize>,
+  ttl: Option<u32>,
+  dscp: Option<u32>,
+  bind_to_device: Option<String>,
+}
+
+impl SocketOptions {
+  /// Applies the options to a socket that is not bound or connected yet.
+  pub fn apply(&self, socket: &Socket, domain: Domain) -> Result<(), AnyError> {
+    if let Some(size) = self.recv_buffer_size {
+      if size == 0 {
+        return Err(range_error("recvBufferSize must be greater than 0"));
+      }
+      socket.set_recv_buffer_size(size)?;
+    }
+    if let Some(size) = self.send_buffer_size {
+      if size == 0 {
+        return Err(range_error("sendBufferSize must be greater than 0"));
+      }
+      socket.set_send_buffer_size(size)?;
+    }
+    if let Some(ttl) = self.ttl {
+      if !(1..=255).contains(&ttl) {
+        return Err(range_error("ttl must be between 1 and 255"));
+      }
+      if domain == Domain::IPV4 {
+        socket.set_ttl(ttl)?;
+      } else {
+        socket.set_unicast_hops_v6(ttl)?;
+      }
+    }
+    if let Some(dscp) = self.dscp {
+      if dscp > 63 {
+        return Err(range_error("dscp must be between 0 and 63"));
+      }
+      if domain != Domain::IPV4 {
+        return Err(custom_error(
+          "NotSupported",
+          "dscp is only supported on IPv4 sockets",
+        ));
+      }
+      // the DSCP is the upper six bits of the TOS byte
+      socket.set_tos(dscp << 2)?;
+    }
+    if let Some(device) = &self.bind_to_device {
+      #[cfg(any(target_os = "android", target_os = "linux"))]
+      socket.bind_device(Some(device.as_bytes()))?;
+      #[cfg(not(any(target_os = "android", target_os = "linux")))]
+      {
+        let _ = device;
+        return Err(custom_error(
+          "NotSupported",
+          "bindToDevice is only supported on Linux",
+        ));
+      }
+    }
+    Ok(())
+  }
+}
+
 pub(crate) fn accept_err(e: std::io::Error) -> AnyError {
   // FIXME(bartlomieju): compatibility with current JS implementation
   if let std::io::ErrorKind::Interrupted = e.kind() {
@@ -293,22 +361,43 @@ pub async fn op_net_set_multi_ttl_udp(
 pub async fn op_net_connect_tcp<NP>(
   state: Rc<RefCell<OpState>>,
   #[serde] addr: IpAddr,
+  #[serde] options: SocketOptions,
+  #[serde] resolver_rid: Option<ResourceId>,
 ) -> Result<(ResourceId, IpAddr, IpAddr), AnyError>
 where
   NP: NetPermissions + 'static,
 {
-  {
+  let resolver = {
     let mut state_ = state.borrow_mut();
     state_
       .borrow_mut::<NP>()
       .check_net(&(&addr.hostname, Some(addr.port)), "Deno.connect()")?;
-  }
+    resolver_rid
+      .map(|rid| state_.resource_table.get::<DnsResolverResource>(rid))
+      .transpose()?
+  };
 
-  let addr = resolve_addr(&addr.hostname, addr.port)
-    .await?
-    .next()
-    .ok_or_else(|| generic_error("No resolved address found"))?;
-  let tcp_stream = TcpStream::connect(&addr).await?;
+  let addr = if let Some(resolver) = resolver {
+    let hostname = addr.hostname.trim_start_matches('[').trim_end_matches(']');
+    let lookup = resolver.resolver().lookup_ip(hostname).await?;
+    lookup
+      .iter()
+      .next()
+      .map(|ip| SocketAddr::new(ip, addr.port))
+      .ok_or_else(|| generic_error("No resolved address found"))?
+  } else {
+    resolve_addr(&addr.hostname, addr.port)
+      .await?
+      .next()
+      .ok_or_else(|| generic_error("No resolved address found"))?
+  };
+  let (socket, domain) = if addr.is_ipv4() {
+    (TcpSocket::new_v4()?, Domain::IPV4)
+  } else {
+    (TcpSocket::new_v6()?, Domain::IPV6)
+  };
+  options.apply(&socket2::SockRef::from(&socket), domain)?;
+  let tcp_stream = socket.connect(addr).await?;
   let local_addr = tcp_stream.local_addr()?;
   let remote_addr = tcp_stream.peer_addr()?;
 
@@ -356,6 +445,7 @@ pub fn op_net_listen_tcp<NP>(
   state: &mut OpState,
   #[serde] addr: IpAddr,
   reuse_port: bool,
+  #[serde] options: SocketOptions,
 ) -> Result<(ResourceId, IpAddr), AnyError>
 where
   NP: NetPermissions + 'static,
@@ -381,6 +471,7 @@ where
     #[cfg(target_os = "linux")]
     socket.set_reuse_port(true)?;
   }
+  options.apply(&socket, domain)?;
   let socket_addr = socket2::SockAddr::from(addr);
   socket.bind(&socket_addr)?;
   socket.listen(128)?;
@@ -397,11 +488,29 @@ where
   Ok((rid, IpAddr::from(local_addr)))
 }
 
+/// Windows has no equivalent of inheriting a listening socket by its file
+/// descriptor.
+#[cfg(not(unix))]
+#[op2]
+pub fn op_net_listen_fd(
+  state: &mut OpState,
+  #[serde] _fd: Option<i32>,
+  #[string] api_name: String,
+) -> Result<(), AnyError> {
+  super::check_unstable(state, &api_name);
+  Err(custom_error(
+    "NotSupported",
+    format!("{api_name}() can not listen on a file descriptor on Windows"),
+  ))
+}
+
 fn net_listen_udp<NP>(
   state: &mut OpState,
   addr: IpAddr,
   reuse_address: bool,
+  reuse_port: bool,
   loopback: bool,
+  options: SocketOptions,
 ) -> Result<(ResourceId, IpAddr), AnyError>
 where
   NP: NetPermissions + 'static,
@@ -434,6 +543,22 @@ where
     #[cfg(all(unix, not(target_os = "linux")))]
     socket_tmp.set_reuse_port(true)?;
   }
+  if reuse_port {
+    // Unlike `reuse_address`, this enables the Linux semantics that let
+    // several sockets share the port, with datagrams distributed among them.
+    // Elsewhere SO_REUSEPORT does not distribute datagrams, so rather than
+    // silently behaving differently, the option is rejected.
+    #[cfg(target_os = "linux")]
+    socket_tmp.set_reuse_port(true)?;
+    #[cfg(not(target_os = "linux"))]
+    {
+      return Err(custom_error(
+        "NotSupported",
+        "reusePort is only supported on Linux for UDP sockets",
+      ));
+    }
+  }
+  options.apply(&socket_tmp, domain)?;
   let socket_addr = socket2::SockAddr::from(addr);
   socket_tmp.bind(&socket_addr)?;
   socket_tmp.set_nonblocking(true)?;
@@ -466,13 +591,22 @@ pub fn op_net_listen_udp<NP>(
   state: &mut OpState,
   #[serde] addr: IpAddr,
   reuse_address: bool,
+  reuse_port: bool,
   loopback: bool,
+  #[serde] options: SocketOptions,
 ) -> Result<(ResourceId, IpAddr), AnyError>
 where
   NP: NetPermissions + 'static,
 {
   super::check_unstable(state, "Deno.listenDatagram");
-  net_listen_udp::<NP>(state, addr, reuse_address, loopback)
+  net_listen_udp::<NP>(
+    state,
+    addr,
+    reuse_address,
+    reuse_port,
+    loopback,
+    options,
+  )
 }
 
 #[op2]
@@ -481,12 +615,21 @@ pub fn op_node_unstable_net_listen_udp<NP>(
   state: &mut OpState,
   #[serde] addr: IpAddr,
   reuse_address: bool,
+  reuse_port: bool,
   loopback: bool,
+  #[serde] options: SocketOptions,
 ) -> Result<(ResourceId, IpAddr), AnyError>
 where
   NP: NetPermissions + 'static,
 {
-  net_listen_udp::<NP>(state, addr, reuse_address, loopback)
+  net_listen_udp::<NP>(
+    state,
+    addr,
+    reuse_address,
+    reuse_port,
+    loopback,
+    options,
+  )
 }
 
 #[derive(Serialize, Eq, PartialEq, Debug)]
@@ -537,27 +680,10 @@ pub enum DnsReturnRecord {
 #[serde(rename_all = "camelCase")]
 pub struct ResolveAddrArgs {
   cancel_rid: Option<ResourceId>,
+  resolver_rid: Option<ResourceId>,
   query: String,
   record_type: RecordType,
-  options: Option<ResolveDnsOption>,
-}
-
-#[derive(Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct ResolveDnsOption {
-  name_server: Option<NameServer>,
-}
-
-fn default_port() -> u16 {
-  53
-}
-
-#[derive(Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct NameServer {
-  ip_addr: String,
-  #[serde(default = "default_port")]
-  port: u16,
+  options: Option<ResolverOptions>,
 }
 
 #[op2(async)]
@@ -574,40 +700,24 @@ where
     record_type,
     options,
     cancel_rid,
+    resolver_rid,
   } = args;
 
-  let (config, opts) = if let Some(name_server) =
-    options.as_ref().and_then(|o| o.name_server.as_ref())
-  {
-    let group = NameServerConfigGroup::from_ips_clear(
-      &[name_server.ip_addr.parse()?],
-      name_server.port,
-      true,
-    );
-    (
-      ResolverConfig::from_parts(None, vec![], group),
-      ResolverOpts::default(),
-    )
-  } else {
-    system_conf::read_system_conf()?
-  };
-
-  {
+  let resource = {
     let mut s = state.borrow_mut();
-    let perm = s.borrow_mut::<NP>();
+    let resource = get_resolver(&mut s, resolver_rid, options.as_ref())?;
 
     // Checks permission against the name servers which will be actually queried.
-    for ns in config.name_servers() {
-      let socker_addr = &ns.socket_addr;
-      let ip = socker_addr.ip().to_string();
-      let port = socker_addr.port();
-      perm.check_net(&(ip, Some(port)), "Deno.resolveDns()")?;
-    }
-  }
-
-  let resolver = AsyncResolver::tokio(config, opts)?;
+    resource.check_permissions(s.borrow_mut::<NP>(), "Deno.resolveDns()")?;
+    resource
+  };
 
-  let lookup_fut = resolver.lookup(query, record_type);
+  // PTR lookups of an IP address query its `in-addr.arpa` or `ip6.arpa` name
+  let name = match (record_type, query.parse::<std::net::IpAddr>()) {
+    (RecordType::PTR, Ok(ip)) => Name::from(ip),
+    _ => query.into_name()?,
+  };
+  let lookup_fut = resource.lookup(name, record_type);
 
   let cancel_handle = cancel_rid.and_then(|rid| {
     state
@@ -994,6 +1104,32 @@ mod tests {
     }
   }
 
+  #[test]
+  fn socket_options_apply() {
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, None).unwrap();
+    let options = SocketOptions {
+      ttl: Some(42),
+      dscp: Some(46),
+      ..Default::default()
+    };
+    options.apply(&socket, Domain::IPV4).unwrap();
+    assert_eq!(socket.ttl().unwrap(), 42);
+    assert_eq!(socket.tos().unwrap(), 46 << 2);
+
+    let options = SocketOptions {
+      ttl: Some(256),
+      ..Default::default()
+    };
+    assert!(options.apply(&socket, Domain::IPV4).is_err());
+
+    let socket = Socket::new(Domain::IPV6, Type::DGRAM, None).unwrap();
+    let options = SocketOptions {
+      dscp: Some(10),
+      ..Default::default()
+    };
+    assert!(options.apply(&socket, Domain::IPV6).is_err());
+  }
+
   #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
   async fn tcp_set_no_delay() {
     let set_nodelay = Box::new(|state: &mut OpState, rid| {
@@ -1058,9 +1194,13 @@ mod tests {
       port: server_addr[1].parse().unwrap(),
     };
 
-    let mut connect_fut =
-      op_net_connect_tcp::<TestPermission>::call(conn_state, ip_addr)
-        .boxed_local();
+    let mut connect_fut = op_net_connect_tcp::<TestPermission>::call(
+      conn_state,
+      ip_addr,
+      SocketOptions::default(),
+      None,
+    )
+    .boxed_local();
     let mut rid = None;
 
     tokio::select! {
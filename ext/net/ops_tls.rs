@@ -2,6 +2,7 @@
 
 use crate::io::TcpStreamResource;
 use crate::ops::IpAddr;
+use crate::ops::SocketOptions;
 use crate::ops::TlsHandshakeInfo;
 use crate::resolve_addr::resolve_addr;
 use crate::resolve_addr::resolve_addr_sync;
@@ -36,7 +37,10 @@ use deno_core::OpState;
 use deno_core::RcRef;
 use deno_core::Resource;
 use deno_core::ResourceId;
+use deno_tls::certificate_to_pem;
+use deno_tls::create_certified_key;
 use deno_tls::create_client_config;
+use deno_tls::create_server_config;
 use deno_tls::load_certs;
 use deno_tls::load_private_keys;
 use deno_tls::rustls::Certificate;
@@ -47,6 +51,9 @@ use deno_tls::rustls::PrivateKey;
 use deno_tls::rustls::ServerConfig;
 use deno_tls::rustls::ServerConnection;
 use deno_tls::rustls::ServerName;
+use deno_tls::ClientAuth;
+use deno_tls::ServerCertResolver;
+use deno_tls::ServerCertificates;
 use io::Error;
 use io::Read;
 use io::Write;
@@ -56,6 +63,7 @@ use socket2::Socket;
 use socket2::Type;
 use std::borrow::Cow;
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::convert::From;
 use std::convert::TryFrom;
 use std::fs::File;
@@ -179,6 +187,11 @@ impl TlsStream {
   fn get_alpn_protocol(&mut self) -> Option<ByteString> {
     self.inner_mut().tls.alpn_protocol().map(|s| s.into())
   }
+
+  fn get_peer_certificates(&mut self) -> Option<Vec<String>> {
+    let certs = self.inner_mut().tls.peer_certificates()?;
+    Some(certs.iter().map(certificate_to_pem).collect())
+  }
 }
 
 impl AsyncRead for TlsStream {
@@ -528,6 +541,10 @@ impl WriteHalf {
   fn get_alpn_protocol(&mut self) -> Option<ByteString> {
     self.shared.get_alpn_protocol()
   }
+
+  fn get_peer_certificates(&mut self) -> Option<Vec<String>> {
+    self.shared.get_peer_certificates()
+  }
 }
 
 impl AsyncWrite for WriteHalf {
@@ -651,6 +668,11 @@ impl Shared {
     let mut tls_stream = self.tls_stream.lock();
     tls_stream.get_alpn_protocol()
   }
+
+  fn get_peer_certificates(self: &Arc<Self>) -> Option<Vec<String>> {
+    let mut tls_stream = self.tls_stream.lock();
+    tls_stream.get_peer_certificates()
+  }
 }
 
 struct ImplementReadTrait<'a, T>(&'a mut T);
@@ -737,7 +759,11 @@ impl TlsStreamResource {
     wr.handshake().try_or_cancel(cancel_handle).await?;
 
     let alpn_protocol = wr.get_alpn_protocol();
-    let tls_info = TlsHandshakeInfo { alpn_protocol };
+    let peer_certificates = wr.get_peer_certificates();
+    let tls_info = TlsHandshakeInfo {
+      alpn_protocol,
+      peer_certificates,
+    };
     self.handshake_info.replace(Some(tls_info.clone()));
     Ok(tls_info)
   }
@@ -976,6 +1002,7 @@ fn load_private_keys_from_file(
 pub struct TlsListenerResource {
   pub(crate) tcp_listener: AsyncRefCell<TcpListener>,
   pub(crate) tls_config: Arc<ServerConfig>,
+  certificates: Rc<TlsCertificatesResource>,
   cancel_handle: CancelHandle,
 }
 
@@ -989,6 +1016,30 @@ impl Resource for TlsListenerResource {
   }
 }
 
+/// The certificates of a TLS listener. Owned by the listener resource, and
+/// only added to the resource table on its own when the listener is handed
+/// over to an HTTP server that still needs to replace them.
+pub struct TlsCertificatesResource(Arc<ServerCertResolver>);
+
+impl Resource for TlsCertificatesResource {
+  fn name(&self) -> Cow<str> {
+    "tlsCertificates".into()
+  }
+}
+
+#[derive(Deserialize)]
+pub struct TlsKeyPairArgs {
+  cert: String,
+  key: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientAuthArgs {
+  ca: Vec<String>,
+  required: bool,
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ListenTlsArgs {
@@ -1000,6 +1051,41 @@ pub struct ListenTlsArgs {
   key_file: Option<String>,
   alpn_protocols: Option<Vec<String>>,
   reuse_port: bool,
+  sni: Option<HashMap<String, TlsKeyPairArgs>>,
+  client_auth: Option<ClientAuthArgs>,
+}
+
+#[derive(Deserialize)]
+pub struct SetCertificatesArgs {
+  cert: Option<String>,
+  key: Option<String>,
+  sni: Option<HashMap<String, TlsKeyPairArgs>>,
+}
+
+fn load_server_certificates(
+  default: Option<(Vec<Certificate>, PrivateKey)>,
+  sni: Option<HashMap<String, TlsKeyPairArgs>>,
+) -> Result<ServerCertificates, AnyError> {
+  let default = match default {
+    Some((cert_chain, key_der)) => {
+      Some(Arc::new(create_certified_key(cert_chain, &key_der)?))
+    }
+    None => None,
+  };
+  let mut by_name = HashMap::new();
+  for (server_name, key_pair) in sni.unwrap_or_default() {
+    if server_name.is_empty() {
+      return Err(type_error("SNI server names must not be empty."));
+    }
+    let cert_chain = load_certs(&mut BufReader::new(key_pair.cert.as_bytes()))?;
+    let key_der = load_private_keys(key_pair.key.as_bytes())?.remove(0);
+    let certified_key = create_certified_key(cert_chain, &key_der)?;
+    by_name.insert(server_name.to_ascii_lowercase(), Arc::new(certified_key));
+  }
+  if default.is_none() && by_name.is_empty() {
+    return Err(generic_error("`cert` is not specified."));
+  }
+  Ok(ServerCertificates { default, by_name })
 }
 
 #[op2]
@@ -1008,6 +1094,7 @@ pub fn op_net_listen_tls<NP>(
   state: &mut OpState,
   #[serde] addr: IpAddr,
   #[serde] args: ListenTlsArgs,
+  #[serde] socket_options: SocketOptions,
 ) -> Result<(ResourceId, IpAddr), AnyError>
 where
   NP: NetPermissions + 'static,
@@ -1015,6 +1102,12 @@ where
   if args.reuse_port {
     super::check_unstable(state, "Deno.listenTls({ reusePort: true })");
   }
+  if args.sni.is_some() {
+    super::check_unstable(state, "Deno.listenTls({ sni })");
+  }
+  if args.client_auth.is_some() {
+    super::check_unstable(state, "Deno.listenTls({ clientAuth })");
+  }
 
   let cert_file = args.cert_file.as_deref();
   let key_file = args.key_file.as_deref();
@@ -1036,34 +1129,47 @@ where
   let cert_chain = if cert_file.is_some() && cert.is_some() {
     return Err(generic_error("Both cert and certFile is specified. You can specify either one of them."));
   } else if let Some(path) = cert_file {
-    load_certs_from_file(path)?
+    Some(load_certs_from_file(path)?)
   } else if let Some(cert) = cert {
-    load_certs(&mut BufReader::new(cert.as_bytes()))?
+    Some(load_certs(&mut BufReader::new(cert.as_bytes()))?)
   } else {
-    return Err(generic_error("`cert` is not specified."));
+    None
   };
   let key_der = if key_file.is_some() && key.is_some() {
     return Err(generic_error(
       "Both key and keyFile is specified. You can specify either one of them.",
     ));
   } else if let Some(path) = key_file {
-    load_private_keys_from_file(path)?.remove(0)
+    Some(load_private_keys_from_file(path)?.remove(0))
   } else if let Some(key) = key {
-    load_private_keys(key.as_bytes())?.remove(0)
+    Some(load_private_keys(key.as_bytes())?.remove(0))
   } else {
-    return Err(generic_error("`key` is not specified."));
+    None
+  };
+  let default = match (cert_chain, key_der) {
+    (Some(cert_chain), Some(key_der)) => Some((cert_chain, key_der)),
+    // With `sni`, the default certificate may be left out: handshakes for
+    // any other server name then fail.
+    (None, None) if args.sni.is_some() => None,
+    (None, _) => return Err(generic_error("`cert` is not specified.")),
+    (_, None) => return Err(generic_error("`key` is not specified.")),
   };
 
-  let mut tls_config = ServerConfig::builder()
-    .with_safe_defaults()
-    .with_no_client_auth()
-    .with_single_cert(cert_chain, key_der)
-    .map_err(|e| {
-      custom_error(
-        "InvalidData",
-        format!("Error creating TLS certificate: {:?}", e),
-      )
-    })?;
+  let cert_resolver = Arc::new(ServerCertResolver::new(
+    load_server_certificates(default, args.sni)?,
+  ));
+  let client_auth = match args.client_auth {
+    Some(client_auth) if client_auth.ca.is_empty() => {
+      return Err(type_error("clientAuth.ca must not be empty."));
+    }
+    Some(client_auth) => Some(ClientAuth {
+      ca_certs: client_auth.ca,
+      required: client_auth.required,
+    }),
+    None => None,
+  };
+  let mut tls_config =
+    create_server_config(cert_resolver.clone(), client_auth)?;
 
   if let Some(alpn_protocols) = args.alpn_protocols {
     tls_config.alpn_protocols =
@@ -1085,6 +1191,7 @@ where
     #[cfg(target_os = "linux")]
     socket.set_reuse_port(true)?;
   }
+  socket_options.apply(&socket, domain)?;
   let socket_addr = socket2::SockAddr::from(bind_addr);
   socket.bind(&socket_addr)?;
   socket.listen(128)?;
@@ -1096,6 +1203,7 @@ where
   let tls_listener_resource = TlsListenerResource {
     tcp_listener: AsyncRefCell::new(tcp_listener),
     tls_config: Arc::new(tls_config),
+    certificates: Rc::new(TlsCertificatesResource(cert_resolver)),
     cancel_handle: Default::default(),
   };
 
@@ -1104,6 +1212,52 @@ where
   Ok((rid, IpAddr::from(local_addr)))
 }
 
+/// Add the certificates of a TLS listener to the resource table, so that
+/// they can still be replaced once the listener has been taken out of it.
+#[op2(fast)]
+#[smi]
+pub fn op_tls_listener_certificates(
+  state: &mut OpState,
+  #[smi] rid: ResourceId,
+) -> Result<ResourceId, AnyError> {
+  let resource = state
+    .resource_table
+    .get::<TlsListenerResource>(rid)
+    .map_err(|_| bad_resource("Listener has been closed"))?;
+  Ok(state.resource_table.add_rc(resource.certificates.clone()))
+}
+
+/// Replace the certificates of a running TLS listener, given the resource id
+/// of the listener or of its certificates. Connections that have already
+/// completed their handshake are not affected.
+#[op2]
+pub fn op_tls_listener_set_certificates(
+  state: &mut OpState,
+  #[smi] rid: ResourceId,
+  #[serde] args: SetCertificatesArgs,
+) -> Result<(), AnyError> {
+  super::check_unstable(state, "Deno.TlsListener.setCertificates");
+  let resource = match state.resource_table.get::<TlsListenerResource>(rid) {
+    Ok(listener) => listener.certificates.clone(),
+    Err(_) => state
+      .resource_table
+      .get::<TlsCertificatesResource>(rid)
+      .map_err(|_| bad_resource("Listener has been closed"))?,
+  };
+  let default = match (args.cert, args.key) {
+    (Some(cert), Some(key)) => Some((
+      load_certs(&mut BufReader::new(cert.as_bytes()))?,
+      load_private_keys(key.as_bytes())?.remove(0),
+    )),
+    (None, None) => None,
+    _ => {
+      return Err(type_error("Both cert and key must be provided."));
+    }
+  };
+  resource.0.set(load_server_certificates(default, args.sni)?);
+  Ok(())
+}
+
 #[op2(async)]
 #[serde]
 pub async fn op_net_accept_tls(
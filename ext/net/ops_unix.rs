@@ -1,9 +1,12 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
 use crate::io::UnixStreamResource;
+use crate::ops::IpAddr;
+use crate::ops::TcpListenerResource;
 use crate::NetPermissions;
 use deno_core::error::bad_resource;
 use deno_core::error::custom_error;
+use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::op2;
 use deno_core::AsyncRefCell;
@@ -16,10 +19,16 @@ use deno_core::Resource;
 use deno_core::ResourceId;
 use serde::Deserialize;
 use serde::Serialize;
+use socket2::SockRef;
+use socket2::Type;
 use std::borrow::Cow;
 use std::cell::RefCell;
+use std::os::fd::AsFd;
+use std::os::fd::BorrowedFd;
+use std::os::fd::RawFd;
 use std::path::Path;
 use std::rc::Rc;
+use tokio::net::TcpListener;
 use tokio::net::UnixDatagram;
 use tokio::net::UnixListener;
 pub use tokio::net::UnixStream;
@@ -263,6 +272,137 @@ where
   net_listen_unixpacket::<NP>(state, path)
 }
 
+/// The first file descriptor passed by systemd socket activation.
+const SD_LISTEN_FDS_START: RawFd = 3;
+
+/// The listening socket passed to this process with the `LISTEN_FDS` protocol
+/// (https://www.freedesktop.org/software/systemd/man/sd_listen_fds.html).
+fn listen_fds_socket() -> Result<RawFd, AnyError> {
+  let pid = std::env::var("LISTEN_PID")
+    .ok()
+    .and_then(|pid| pid.parse::<u32>().ok());
+  let fds = std::env::var("LISTEN_FDS")
+    .ok()
+    .and_then(|fds| fds.parse::<u32>().ok())
+    .unwrap_or(0);
+  if pid != Some(std::process::id()) || fds == 0 {
+    return Err(custom_error(
+      "NotFound",
+      "No socket was passed to this process with LISTEN_FDS",
+    ));
+  }
+  Ok(SD_LISTEN_FDS_START)
+}
+
+#[derive(Serialize)]
+#[serde(tag = "transport", rename_all = "lowercase")]
+pub enum ListenerAddr {
+  Tcp {
+    #[serde(flatten)]
+    addr: IpAddr,
+  },
+  Unix {
+    path: Option<String>,
+  },
+}
+
+/// Listen on an inherited TCP or Unix socket, given its file descriptor or,
+/// with `None`, the first one passed with `LISTEN_FDS`. The listener owns a
+/// duplicate of the descriptor, so the original is never closed by it.
+#[op2]
+#[serde]
+pub fn op_net_listen_fd<NP>(
+  state: &mut OpState,
+  #[serde] fd: Option<i32>,
+  #[string] api_name: String,
+) -> Result<(ResourceId, ListenerAddr), AnyError>
+where
+  NP: NetPermissions + 'static,
+{
+  super::check_unstable(state, &api_name);
+  let api_call_expr = format!("{}()", api_name);
+  let fd = match fd {
+    Some(fd) if fd < 0 => {
+      return Err(type_error("fd must be a non-negative integer"))
+    }
+    Some(fd) => fd,
+    None => listen_fds_socket()?,
+  };
+
+  // SAFETY: the descriptor is only borrowed until it has been duplicated,
+  // and `fcntl` / `getsockname` fail cleanly if it isn't open.
+  let borrowed = unsafe { BorrowedFd::borrow_raw(fd) };
+  let socket = SockRef::from(&borrowed);
+  let local_addr = socket
+    .local_addr()
+    .map_err(|_| type_error(format!("File descriptor {fd} is not a socket")))?;
+  if socket.r#type()? != Type::STREAM {
+    return Err(type_error(format!(
+      "File descriptor {fd} is not a stream socket"
+    )));
+  }
+  let fd = borrowed.try_clone_to_owned()?;
+
+  if let Some(local_addr) = local_addr.as_socket() {
+    let addr = IpAddr::from(local_addr);
+    state
+      .borrow_mut::<NP>()
+      .check_net(&(&addr.hostname, Some(addr.port)), &api_call_expr)?;
+    listen_nonblocking(&fd)?;
+    let listener = TcpListener::from_std(std::net::TcpListener::from(fd))?;
+    let rid = state.resource_table.add(TcpListenerResource {
+      listener: AsyncRefCell::new(listener),
+      cancel: Default::default(),
+    });
+    return Ok((rid, ListenerAddr::Tcp { addr }));
+  }
+
+  let listener = std::os::unix::net::UnixListener::from(fd);
+  let path = listener.local_addr()?.as_pathname().map(Path::to_path_buf);
+  let permissions = state.borrow_mut::<NP>();
+  // Sockets without a path (unnamed or abstract) can't be scoped to one, so
+  // they require access to the whole file system.
+  let check_path = path.as_deref().unwrap_or(Path::new("/"));
+  permissions.check_read(check_path, &api_call_expr)?;
+  permissions.check_write(check_path, &api_call_expr)?;
+  listen_nonblocking(&listener)?;
+  let listener = UnixListener::from_std(listener)?;
+  let rid = state.resource_table.add(UnixListenerResource {
+    listener: AsyncRefCell::new(listener),
+    cancel: Default::default(),
+  });
+  let path = path.as_deref().map(pathstring).transpose()?;
+  Ok((rid, ListenerAddr::Unix { path }))
+}
+
+fn listen_nonblocking(fd: &impl AsFd) -> Result<(), AnyError> {
+  let socket = SockRef::from(fd);
+  // Listening again would replace the backlog of a socket that is already
+  // listening, eg: one passed in by systemd.
+  if !is_listener(&socket)? {
+    socket.listen(128)?;
+  }
+  // `fd` is a duplicate of the inherited descriptor and shares its open file
+  // description, so this makes the inherited descriptor non-blocking too.
+  socket.set_nonblocking(true)?;
+  Ok(())
+}
+
+#[cfg(any(target_os = "android", target_os = "freebsd", target_os = "linux"))]
+fn is_listener(socket: &SockRef) -> std::io::Result<bool> {
+  socket.is_listener()
+}
+
+/// socket2 only exposes `SO_ACCEPTCONN` on the platforms above.
+#[cfg(not(any(
+  target_os = "android",
+  target_os = "freebsd",
+  target_os = "linux"
+)))]
+fn is_listener(_socket: &SockRef) -> std::io::Result<bool> {
+  Ok(false)
+}
+
 pub fn pathstring(pathname: &Path) -> Result<String, AnyError> {
   into_string(pathname.into())
 }
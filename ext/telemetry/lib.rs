@@ -0,0 +1,502 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! OpenTelemetry compatible tracing.
+//!
+//! Ops that want to be traced start a [`Span`] with [`Span::start`], which
+//! returns `None` unless an exporter was set up with [`init`], so tracing
+//! costs nothing when it is disabled. Finished spans are batched on a
+//! background thread and exported as OTLP JSON.
+
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use deno_core::error::generic_error;
+use deno_core::error::AnyError;
+use deno_core::url::Url;
+use once_cell::sync::OnceCell;
+use serde_json::json;
+use serde_json::Value;
+
+/// Name of the W3C trace context header.
+pub const TRACEPARENT: &str = "traceparent";
+
+const EXPORT_INTERVAL: Duration = Duration::from_secs(1);
+const MAX_BATCH_SIZE: usize = 512;
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(5);
+
+static EXPORTER: OnceCell<Exporter> = OnceCell::new();
+
+/// Destination of the exported spans, as given to `--otel-export`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OtelExport {
+  /// Append OTLP JSON export requests to a file, one per line.
+  File(PathBuf),
+  /// Send OTLP JSON export requests to an OTLP/HTTP endpoint.
+  Endpoint(Url),
+}
+
+impl OtelExport {
+  /// `http:` and `https:` URLs are endpoints, anything else is a file path.
+  pub fn parse(value: &str) -> Self {
+    match Url::parse(value) {
+      Ok(url) if matches!(url.scheme(), "http" | "https") => {
+        OtelExport::Endpoint(url)
+      }
+      _ => OtelExport::File(PathBuf::from(value)),
+    }
+  }
+}
+
+/// Starts exporting spans to `export`. Can only be called once per process.
+pub fn init(export: OtelExport) -> Result<(), AnyError> {
+  let exporter = Exporter::spawn(export)?;
+  EXPORTER
+    .set(exporter)
+    .map_err(|_| generic_error("Telemetry is already initialized"))
+}
+
+/// Returns `true` if spans are being exported.
+pub fn is_enabled() -> bool {
+  EXPORTER.get().is_some()
+}
+
+/// Blocks until all the spans ended so far have been exported. Must be called
+/// before exiting the process, or the last batch of spans is lost.
+pub fn flush() {
+  if let Some(exporter) = EXPORTER.get() {
+    exporter.flush();
+  }
+}
+
+/// Identifies a span across process boundaries.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SpanContext {
+  pub trace_id: [u8; 16],
+  pub span_id: [u8; 8],
+  pub sampled: bool,
+}
+
+impl SpanContext {
+  /// Parses a `traceparent` header value, e.g.
+  /// `00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01`.
+  pub fn from_traceparent(value: &[u8]) -> Option<Self> {
+    let value = std::str::from_utf8(value).ok()?.trim();
+    let mut parts = value.split('-');
+    let version = parts.next()?;
+    let trace_id = parts.next()?;
+    let span_id = parts.next()?;
+    let flags = parts.next()?;
+    // Future versions may append fields, version 00 must not.
+    if version.len() != 2 || version == "ff" {
+      return None;
+    }
+    if version == "00" && parts.next().is_some() {
+      return None;
+    }
+    let mut context = SpanContext {
+      trace_id: [0; 16],
+      span_id: [0; 8],
+      sampled: false,
+    };
+    hex::decode_to_slice(trace_id, &mut context.trace_id).ok()?;
+    hex::decode_to_slice(span_id, &mut context.span_id).ok()?;
+    let mut flags_byte = [0; 1];
+    hex::decode_to_slice(flags, &mut flags_byte).ok()?;
+    if context.trace_id == [0; 16] || context.span_id == [0; 8] {
+      return None;
+    }
+    context.sampled = flags_byte[0] & 1 == 1;
+    Some(context)
+  }
+
+  pub fn to_traceparent(&self) -> String {
+    format!(
+      "00-{}-{}-{:02x}",
+      hex::encode(self.trace_id),
+      hex::encode(self.span_id),
+      self.sampled as u8
+    )
+  }
+}
+
+/// Mirrors `SpanKind` of the OTLP protocol.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SpanKind {
+  Internal = 1,
+  Server = 2,
+  Client = 3,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AttributeValue {
+  String(String),
+  Int(i64),
+  Bool(bool),
+}
+
+impl From<&str> for AttributeValue {
+  fn from(value: &str) -> Self {
+    AttributeValue::String(value.to_string())
+  }
+}
+
+impl From<String> for AttributeValue {
+  fn from(value: String) -> Self {
+    AttributeValue::String(value)
+  }
+}
+
+impl From<i64> for AttributeValue {
+  fn from(value: i64) -> Self {
+    AttributeValue::Int(value)
+  }
+}
+
+impl From<u16> for AttributeValue {
+  fn from(value: u16) -> Self {
+    AttributeValue::Int(value.into())
+  }
+}
+
+impl From<usize> for AttributeValue {
+  fn from(value: usize) -> Self {
+    AttributeValue::Int(value as i64)
+  }
+}
+
+impl From<bool> for AttributeValue {
+  fn from(value: bool) -> Self {
+    AttributeValue::Bool(value)
+  }
+}
+
+struct SpanData {
+  context: SpanContext,
+  parent_span_id: Option<[u8; 8]>,
+  name: String,
+  kind: SpanKind,
+  start_time: u128,
+  end_time: u128,
+  attributes: Vec<(&'static str, AttributeValue)>,
+  error: Option<String>,
+}
+
+impl SpanData {
+  fn to_otlp(&self) -> Value {
+    let mut span = json!({
+      "traceId": hex::encode(self.context.trace_id),
+      "spanId": hex::encode(self.context.span_id),
+      "name": self.name,
+      "kind": self.kind as u8,
+      "startTimeUnixNano": self.start_time.to_string(),
+      "endTimeUnixNano": self.end_time.to_string(),
+      "attributes": self
+        .attributes
+        .iter()
+        .map(|(key, value)| otlp_attribute(key, value))
+        .collect::<Vec<_>>(),
+    });
+    if let Some(parent_span_id) = self.parent_span_id {
+      span["parentSpanId"] = hex::encode(parent_span_id).into();
+    }
+    if let Some(message) = &self.error {
+      // STATUS_CODE_ERROR
+      span["status"] = json!({ "code": 2, "message": message });
+    }
+    span
+  }
+}
+
+fn otlp_attribute(key: &str, value: &AttributeValue) -> Value {
+  let value = match value {
+    AttributeValue::String(value) => json!({ "stringValue": value }),
+    // 64-bit integers are encoded as strings in OTLP JSON.
+    AttributeValue::Int(value) => json!({ "intValue": value.to_string() }),
+    AttributeValue::Bool(value) => json!({ "boolValue": value }),
+  };
+  json!({ "key": key, "value": value })
+}
+
+fn now() -> u128 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_nanos())
+    .unwrap_or_default()
+}
+
+/// A timed operation. The span ends, and is queued for export, when it is
+/// dropped. Spans of traces that are not sampled are never exported, but their
+/// context is still propagated.
+pub struct Span(Option<SpanData>);
+
+impl Span {
+  /// Starts a span, continuing the trace of `parent` if given. Returns `None`
+  /// if telemetry is disabled.
+  pub fn start(
+    name: impl Into<String>,
+    kind: SpanKind,
+    parent: Option<SpanContext>,
+  ) -> Option<Self> {
+    if !is_enabled() {
+      return None;
+    }
+    let (trace_id, parent_span_id, sampled) = match parent {
+      Some(parent) => (parent.trace_id, Some(parent.span_id), parent.sampled),
+      None => (rand::random(), None, true),
+    };
+    Some(Span(Some(SpanData {
+      context: SpanContext {
+        trace_id,
+        span_id: rand::random(),
+        sampled,
+      },
+      parent_span_id,
+      name: name.into(),
+      kind,
+      start_time: now(),
+      end_time: 0,
+      attributes: Vec::new(),
+      error: None,
+    })))
+  }
+
+  pub fn context(&self) -> SpanContext {
+    self.0.as_ref().unwrap().context
+  }
+
+  pub fn set_attribute(
+    &mut self,
+    key: &'static str,
+    value: impl Into<AttributeValue>,
+  ) {
+    self
+      .0
+      .as_mut()
+      .unwrap()
+      .attributes
+      .push((key, value.into()));
+  }
+
+  /// Marks the span as failed.
+  pub fn set_error(&mut self, message: impl Into<String>) {
+    self.0.as_mut().unwrap().error = Some(message.into());
+  }
+
+  pub fn end(self) {
+    drop(self)
+  }
+}
+
+impl Drop for Span {
+  fn drop(&mut self) {
+    let Some(mut data) = self.0.take() else {
+      return;
+    };
+    if !data.context.sampled {
+      return;
+    }
+    data.end_time = now();
+    if let Some(exporter) = EXPORTER.get() {
+      exporter.send(Message::Span(data));
+    }
+  }
+}
+
+enum Message {
+  Span(SpanData),
+  Flush(mpsc::Sender<()>),
+}
+
+struct Exporter {
+  sender: Mutex<mpsc::Sender<Message>>,
+}
+
+impl Exporter {
+  fn spawn(export: OtelExport) -> Result<Self, AnyError> {
+    let mut sink = Sink::new(export)?;
+    let (sender, receiver) = mpsc::channel::<Message>();
+    std::thread::Builder::new()
+      .name("otel-exporter".to_string())
+      .spawn(move || {
+        let mut batch = Vec::new();
+        loop {
+          match receiver.recv_timeout(EXPORT_INTERVAL) {
+            Ok(Message::Span(span)) => {
+              batch.push(span);
+              if batch.len() >= MAX_BATCH_SIZE {
+                sink.export(std::mem::take(&mut batch));
+              }
+            }
+            Ok(Message::Flush(done)) => {
+              sink.export(std::mem::take(&mut batch));
+              let _ = done.send(());
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+              sink.export(std::mem::take(&mut batch));
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+              sink.export(std::mem::take(&mut batch));
+              break;
+            }
+          }
+        }
+      })?;
+    Ok(Self {
+      sender: Mutex::new(sender),
+    })
+  }
+
+  fn send(&self, message: Message) {
+    // The exporter thread only goes away with the process.
+    let _ = self.sender.lock().unwrap().send(message);
+  }
+
+  fn flush(&self) {
+    let (done_tx, done_rx) = mpsc::channel();
+    self.send(Message::Flush(done_tx));
+    let _ = done_rx.recv_timeout(FLUSH_TIMEOUT);
+  }
+}
+
+enum Sink {
+  File(File),
+  Endpoint {
+    runtime: tokio::runtime::Runtime,
+    client: reqwest::Client,
+    url: Url,
+  },
+}
+
+impl Sink {
+  fn new(export: OtelExport) -> Result<Self, AnyError> {
+    match export {
+      OtelExport::File(path) => {
+        let file = OpenOptions::new()
+          .create(true)
+          .append(true)
+          .open(&path)
+          .map_err(|err| {
+            generic_error(format!(
+              "Failed to open OpenTelemetry export file {}: {err}",
+              path.display()
+            ))
+          })?;
+        Ok(Sink::File(file))
+      }
+      OtelExport::Endpoint(mut url) => {
+        if url.path() == "/" {
+          url.set_path("/v1/traces");
+        }
+        let runtime = tokio::runtime::Builder::new_current_thread()
+          .enable_all()
+          .build()?;
+        Ok(Sink::Endpoint {
+          runtime,
+          client: reqwest::Client::new(),
+          url,
+        })
+      }
+    }
+  }
+
+  fn export(&mut self, spans: Vec<SpanData>) {
+    if spans.is_empty() {
+      return;
+    }
+    let request = json!({
+      "resourceSpans": [{
+        "resource": {
+          "attributes": [
+            otlp_attribute("service.name", &service_name().into()),
+            otlp_attribute("process.pid", &(std::process::id() as i64).into()),
+          ],
+        },
+        "scopeSpans": [{
+          "scope": { "name": "deno" },
+          "spans": spans.iter().map(SpanData::to_otlp).collect::<Vec<_>>(),
+        }],
+      }],
+    });
+    match self {
+      Sink::File(file) => {
+        let mut line = request.to_string();
+        line.push('\n');
+        if let Err(err) = file.write_all(line.as_bytes()) {
+          log::debug!("Failed to write OpenTelemetry spans: {err}");
+        }
+      }
+      Sink::Endpoint {
+        runtime,
+        client,
+        url,
+      } => {
+        let result = runtime.block_on(
+          client
+            .post(url.clone())
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(request.to_string())
+            .send(),
+        );
+        match result {
+          Ok(res) if !res.status().is_success() => {
+            log::debug!("OpenTelemetry endpoint responded {}", res.status());
+          }
+          Ok(_) => {}
+          Err(err) => log::debug!("Failed to send OpenTelemetry spans: {err}"),
+        }
+      }
+    }
+  }
+}
+
+fn service_name() -> String {
+  std::env::var("OTEL_SERVICE_NAME").unwrap_or_else(|_| "deno".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn traceparent_roundtrip() {
+    let value = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+    let context = SpanContext::from_traceparent(value.as_bytes()).unwrap();
+    assert!(context.sampled);
+    assert_eq!(
+      context.span_id,
+      [0, 0xf0, 0x67, 0xaa, 0x0b, 0xa9, 0x02, 0xb7]
+    );
+    assert_eq!(context.to_traceparent(), value);
+
+    for invalid in [
+      "",
+      "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+      "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+      "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+      "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+      "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+      "00-4bf92f3577b34da6a3ce929d0e0e473-00f067aa0ba902b7-01",
+    ] {
+      assert!(SpanContext::from_traceparent(invalid.as_bytes()).is_none());
+    }
+  }
+
+  #[test]
+  fn parse_export() {
+    assert_eq!(
+      OtelExport::parse("http://localhost:4318"),
+      OtelExport::Endpoint(Url::parse("http://localhost:4318").unwrap())
+    );
+    assert_eq!(
+      OtelExport::parse("spans.jsonl"),
+      OtelExport::File(PathBuf::from("spans.jsonl"))
+    );
+  }
+}
@@ -9,22 +9,31 @@ pub use webpki_roots;
 use deno_core::anyhow::anyhow;
 use deno_core::error::custom_error;
 use deno_core::error::AnyError;
+use deno_core::parking_lot::RwLock;
 
 use rustls::client::HandshakeSignatureValid;
 use rustls::client::ServerCertVerified;
 use rustls::client::ServerCertVerifier;
 use rustls::client::WebPkiVerifier;
+use rustls::server::AllowAnyAnonymousOrAuthenticatedClient;
+use rustls::server::AllowAnyAuthenticatedClient;
+use rustls::server::ClientHello;
+use rustls::server::ResolvesServerCert;
+use rustls::sign::any_supported_type;
+use rustls::sign::CertifiedKey;
 use rustls::Certificate;
 use rustls::ClientConfig;
 use rustls::DigitallySignedStruct;
 use rustls::Error;
 use rustls::PrivateKey;
 use rustls::RootCertStore;
+use rustls::ServerConfig;
 use rustls::ServerName;
 use rustls_pemfile::certs;
 use rustls_pemfile::pkcs8_private_keys;
 use rustls_pemfile::rsa_private_keys;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::io::BufRead;
 use std::io::BufReader;
 use std::io::Cursor;
@@ -131,8 +140,112 @@ impl ServerCertVerifier for NoCertificateVerification {
 #[serde(rename_all = "camelCase")]
 #[serde(default)]
 pub struct Proxy {
+  /// The proxy hosts no rule matches connect through. `http`, `https`,
+  /// `socks5` and `socks5h` (resolving host names on the proxy) are
+  /// supported. Those hosts are connected to directly if it is empty.
   pub url: String,
   pub basic_auth: Option<BasicAuth>,
+  /// Sent to HTTP(S) proxies as `Proxy-Authorization: Bearer <token>`.
+  pub bearer_token: Option<String>,
+  /// Per-host rules, the first one matching a host applies.
+  pub rules: Vec<ProxyRule>,
+}
+
+#[derive(Deserialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[serde(default)]
+pub struct ProxyRule {
+  /// `example.com` matches that host only, `*.example.com` (or
+  /// `.example.com`) its subdomains and `*` every host.
+  pub hosts: Vec<String>,
+  /// The proxy matching hosts connect through. They are connected to
+  /// directly if it is not set.
+  pub url: Option<String>,
+  pub basic_auth: Option<BasicAuth>,
+  pub bearer_token: Option<String>,
+}
+
+/// A proxy server picked by a [`Proxy`].
+#[derive(Debug, Clone, Copy)]
+pub struct ProxyServer<'a> {
+  pub url: &'a str,
+  pub basic_auth: Option<&'a BasicAuth>,
+  pub bearer_token: Option<&'a str>,
+}
+
+impl ProxyServer<'_> {
+  /// The value of the `Proxy-Authorization` header sent to HTTP(S) proxies.
+  pub fn authorization(&self) -> Option<String> {
+    if let Some(token) = self.bearer_token {
+      return Some(format!("Bearer {token}"));
+    }
+    self.basic_auth.map(|auth| {
+      let credentials = format!("{}:{}", auth.username, auth.password);
+      format!("Basic {}", base64::encode(credentials))
+    })
+  }
+}
+
+impl Proxy {
+  /// The index of the rule that applies to `host`, or the number of rules if
+  /// none does.
+  pub fn rule_index(&self, host: &str) -> usize {
+    self
+      .rules
+      .iter()
+      .position(|rule| rule.hosts.iter().any(|p| host_matches(p, host)))
+      .unwrap_or(self.rules.len())
+  }
+
+  fn server(&self, index: usize) -> Option<ProxyServer> {
+    match self.rules.get(index) {
+      Some(rule) => rule.url.as_deref().map(|url| ProxyServer {
+        url,
+        basic_auth: rule.basic_auth.as_ref(),
+        bearer_token: rule.bearer_token.as_deref(),
+      }),
+      None if self.url.is_empty() => None,
+      None => Some(ProxyServer {
+        url: &self.url,
+        basic_auth: self.basic_auth.as_ref(),
+        bearer_token: self.bearer_token.as_deref(),
+      }),
+    }
+  }
+
+  /// The proxy server to connect to `host` through, or `None` to connect
+  /// directly.
+  pub fn for_host(&self, host: &str) -> Option<ProxyServer> {
+    self.server(self.rule_index(host))
+  }
+
+  /// All proxy servers, together with the [`Proxy::rule_index`] of the hosts
+  /// that connect through them.
+  pub fn servers(&self) -> impl Iterator<Item = (usize, ProxyServer)> {
+    (0..=self.rules.len())
+      .filter_map(|index| self.server(index).map(|server| (index, server)))
+  }
+}
+
+fn host_matches(pattern: &str, host: &str) -> bool {
+  let host = host
+    .trim_start_matches('[')
+    .trim_end_matches(']')
+    .trim_end_matches('.')
+    .to_ascii_lowercase();
+  let pattern = pattern.trim_end_matches('.').to_ascii_lowercase();
+  if pattern == "*" {
+    return true;
+  }
+  match pattern
+    .strip_prefix("*.")
+    .or_else(|| pattern.strip_prefix('.'))
+  {
+    Some(domain) => host
+      .strip_suffix(domain)
+      .map_or(false, |sub| sub.len() > 1 && sub.ends_with('.')),
+    None => host == pattern,
+  }
 }
 
 #[derive(Deserialize, Default, Debug, Clone)]
@@ -232,6 +345,118 @@ pub fn create_client_config(
   Ok(client)
 }
 
+/// The certificates a TLS server presents, keyed by the server name (SNI)
+/// clients ask for.
+#[derive(Default, Clone)]
+pub struct ServerCertificates {
+  /// Presented when the client sent no server name, or one that matches none
+  /// of `by_name`.
+  pub default: Option<Arc<CertifiedKey>>,
+  /// Lowercase host names. A name starting with `*.` matches any single
+  /// label in its place.
+  pub by_name: HashMap<String, Arc<CertifiedKey>>,
+}
+
+impl ServerCertificates {
+  fn resolve(&self, server_name: Option<&str>) -> Option<Arc<CertifiedKey>> {
+    if let Some(server_name) = server_name {
+      let server_name = server_name.to_ascii_lowercase();
+      if let Some(key) = self.by_name.get(&server_name) {
+        return Some(key.clone());
+      }
+      if let Some((_, parent)) = server_name.split_once('.') {
+        if let Some(key) = self.by_name.get(&format!("*.{parent}")) {
+          return Some(key.clone());
+        }
+      }
+    }
+    self.default.clone()
+  }
+}
+
+/// Picks the certificate for each server handshake by SNI. The certificates
+/// can be replaced while a listener is running (eg. when they are renewed);
+/// only handshakes that start afterwards see the new ones.
+#[derive(Default)]
+pub struct ServerCertResolver(RwLock<ServerCertificates>);
+
+impl ServerCertResolver {
+  pub fn new(certificates: ServerCertificates) -> Self {
+    Self(RwLock::new(certificates))
+  }
+
+  pub fn set(&self, certificates: ServerCertificates) {
+    *self.0.write() = certificates;
+  }
+}
+
+impl ResolvesServerCert for ServerCertResolver {
+  fn resolve(&self, client_hello: ClientHello) -> Option<Arc<CertifiedKey>> {
+    self.0.read().resolve(client_hello.server_name())
+  }
+}
+
+/// Client certificate verification for a TLS server.
+pub struct ClientAuth {
+  /// PEM encoded certificates of the CAs client certificates must chain to.
+  pub ca_certs: Vec<String>,
+  /// Reject clients that do not present a certificate.
+  pub required: bool,
+}
+
+pub fn create_server_config(
+  cert_resolver: Arc<ServerCertResolver>,
+  client_auth: Option<ClientAuth>,
+) -> Result<ServerConfig, AnyError> {
+  let builder = ServerConfig::builder().with_safe_defaults();
+  let builder = match client_auth {
+    Some(client_auth) => {
+      let mut root_cert_store = RootCertStore::empty();
+      for ca_cert in &client_auth.ca_certs {
+        for cert in load_certs(&mut ca_cert.as_bytes())? {
+          root_cert_store.add(&cert).map_err(|e| {
+            custom_error(
+              "InvalidData",
+              format!("Unable to add CA certificate: {e}"),
+            )
+          })?;
+        }
+      }
+      let verifier = if client_auth.required {
+        AllowAnyAuthenticatedClient::new(root_cert_store).boxed()
+      } else {
+        AllowAnyAnonymousOrAuthenticatedClient::new(root_cert_store).boxed()
+      };
+      builder.with_client_cert_verifier(verifier)
+    }
+    None => builder.with_no_client_auth(),
+  };
+  Ok(builder.with_cert_resolver(cert_resolver))
+}
+
+/// Pairs a certificate chain with the private key it was issued for.
+pub fn create_certified_key(
+  cert_chain: Vec<Certificate>,
+  private_key: &PrivateKey,
+) -> Result<CertifiedKey, AnyError> {
+  let signing_key = any_supported_type(private_key)
+    .map_err(|_| custom_error("InvalidData", "Unsupported private key type"))?;
+  Ok(CertifiedKey::new(cert_chain, signing_key))
+}
+
+/// Encodes a DER certificate as a PEM `CERTIFICATE` block.
+pub fn certificate_to_pem(cert: &Certificate) -> String {
+  let encoded = base64::encode(&cert.0);
+  let mut pem = String::from("-----BEGIN CERTIFICATE-----\n");
+  for line in encoded.as_bytes().chunks(64) {
+    // base64 output is always ASCII.
+    pem.push_str(std::str::from_utf8(line).unwrap());
+    pem.push('\n');
+  }
+  pem.push_str("-----END CERTIFICATE-----\n");
+  pem
+}
+
 pub fn load_certs(
   reader: &mut dyn BufRead,
 ) -> Result<Vec<Certificate>, AnyError> {
@@ -21,6 +21,7 @@ const {
 } = core.ensureFastOps();
 import * as webidl from "ext:deno_webidl/00_webidl.js";
 import { structuredClone } from "ext:deno_web/02_structured_clone.js";
+import DOMException from "ext:deno_web/01_dom_exception.js";
 import {
   AbortSignalPrototype,
   add,
@@ -1138,6 +1139,226 @@ function getWritableStreamResourceBacking(stream) {
   return stream[_resourceBacking];
 }
 
+/**
+ * @param {MessagePort} port
+ * @param {string} type
+ * @param {any} value
+ */
+function packAndPostMessage(port, type, value) {
+  port.postMessage({ type, value });
+}
+
+/**
+ * @param {MessagePort} port
+ * @param {string} type
+ * @param {any} value
+ * @returns {{ ok: true } | { ok: false, error: any }}
+ */
+function packAndPostMessageHandlingError(port, type, value) {
+  try {
+    packAndPostMessage(port, type, value);
+    return { ok: true };
+  } catch (error) {
+    crossRealmTransformSendError(port, error);
+    return { ok: false, error };
+  }
+}
+
+/**
+ * @param {MessagePort} port
+ * @param {any} error
+ */
+function crossRealmTransformSendError(port, error) {
+  try {
+    packAndPostMessage(port, "error", error);
+  } catch {
+    // The error could not be serialized, there is nothing left to report it
+    // with.
+  }
+}
+
+/**
+ * Sets up `stream` as the receiving half of a transferred stream, pulling
+ * chunks from the other realm through `port`.
+ *
+ * @param {ReadableStream} stream
+ * @param {MessagePort} port
+ */
+function setUpCrossRealmTransformReadable(stream, port) {
+  initializeReadableStream(stream);
+  const controller = webidl.createBranded(ReadableStreamDefaultController);
+  port.addEventListener("message", (event) => {
+    const { type, value } = event.data;
+    if (type === "chunk") {
+      if (readableStreamDefaultControllerCanCloseOrEnqueue(controller)) {
+        readableStreamDefaultControllerEnqueue(controller, value);
+      }
+    } else if (type === "close") {
+      readableStreamDefaultControllerClose(controller);
+      port.close();
+    } else if (type === "error") {
+      readableStreamDefaultControllerError(controller, value);
+      port.close();
+    }
+  });
+  port.addEventListener("messageerror", () => {
+    const error = new DOMException(
+      "Failed to deserialize a transferred stream chunk.",
+      "DataCloneError",
+    );
+    crossRealmTransformSendError(port, error);
+    readableStreamDefaultControllerError(controller, error);
+    port.close();
+  });
+  port.start();
+  const startAlgorithm = () => undefined;
+  const pullAlgorithm = () => {
+    packAndPostMessage(port, "pull", undefined);
+    return resolvePromiseWith(undefined);
+  };
+  const cancelAlgorithm = (reason) => {
+    const result = packAndPostMessageHandlingError(port, "error", reason);
+    port.close();
+    if (!result.ok) {
+      return PromiseReject(result.error);
+    }
+    return resolvePromiseWith(undefined);
+  };
+  setUpReadableStreamDefaultController(
+    stream,
+    controller,
+    startAlgorithm,
+    pullAlgorithm,
+    cancelAlgorithm,
+    0,
+    () => 1,
+  );
+}
+
+/**
+ * Sets up `stream` as the sending half of a transferred stream, forwarding
+ * written chunks to the other realm through `port`.
+ *
+ * @param {WritableStream} stream
+ * @param {MessagePort} port
+ */
+function setUpCrossRealmTransformWritable(stream, port) {
+  initializeWritableStream(stream);
+  const controller = webidl.createBranded(WritableStreamDefaultController);
+  /** @type {Deferred<void> | undefined} */
+  let backpressurePromise = new Deferred();
+  port.addEventListener("message", (event) => {
+    const { type, value } = event.data;
+    if (type === "pull") {
+      if (backpressurePromise !== undefined) {
+        backpressurePromise.resolve(undefined);
+        backpressurePromise = undefined;
+      }
+    } else if (type === "error") {
+      writableStreamDefaultControllerErrorIfNeeded(controller, value);
+      if (backpressurePromise !== undefined) {
+        backpressurePromise.resolve(undefined);
+        backpressurePromise = undefined;
+      }
+    }
+  });
+  port.addEventListener("messageerror", () => {
+    const error = new DOMException(
+      "Failed to deserialize a transferred stream message.",
+      "DataCloneError",
+    );
+    crossRealmTransformSendError(port, error);
+    writableStreamDefaultControllerErrorIfNeeded(controller, error);
+    port.close();
+  });
+  port.start();
+  const startAlgorithm = () => undefined;
+  const writeAlgorithm = (chunk) => {
+    if (backpressurePromise === undefined) {
+      backpressurePromise = new Deferred();
+      backpressurePromise.resolve(undefined);
+    }
+    return transformPromiseWith(backpressurePromise.promise, () => {
+      backpressurePromise = new Deferred();
+      const result = packAndPostMessageHandlingError(port, "chunk", chunk);
+      if (!result.ok) {
+        port.close();
+        return PromiseReject(result.error);
+      }
+      return undefined;
+    });
+  };
+  const closeAlgorithm = () => {
+    packAndPostMessage(port, "close", undefined);
+    port.close();
+    return resolvePromiseWith(undefined);
+  };
+  const abortAlgorithm = (reason) => {
+    const result = packAndPostMessageHandlingError(port, "error", reason);
+    port.close();
+    if (!result.ok) {
+      return PromiseReject(result.error);
+    }
+    return resolvePromiseWith(undefined);
+  };
+  setUpWritableStreamDefaultController(
+    stream,
+    controller,
+    startAlgorithm,
+    writeAlgorithm,
+    closeAlgorithm,
+    abortAlgorithm,
+    1,
+    () => 1,
+  );
+}
+
+/**
+ * @param {MessagePort} port
+ * @returns {ReadableStream}
+ */
+function createCrossRealmReadableStream(port) {
+  const stream = webidl.createBranded(ReadableStream);
+  setUpCrossRealmTransformReadable(stream, port);
+  return stream;
+}
+
+/**
+ * @param {MessagePort} port
+ * @returns {WritableStream}
+ */
+function createCrossRealmWritableStream(port) {
+  const stream = webidl.createBranded(WritableStream);
+  setUpCrossRealmTransformWritable(stream, port);
+  return stream;
+}
+
+/**
+ * Pipes `stream` into `port`, whose entangled port backs the transferred
+ * stream in the other realm.
+ *
+ * @param {ReadableStream} stream
+ * @param {MessagePort} port
+ */
+function transferReadableStream(stream, port) {
+  const writable = createCrossRealmWritableStream(port);
+  const promise = readableStreamPipeTo(stream, writable, false, false, false);
+  setPromiseIsHandledToTrue(promise);
+}
+
+/**
+ * Pipes `port` into `stream`, whose entangled port backs the transferred
+ * stream in the other realm.
+ *
+ * @param {WritableStream} stream
+ * @param {MessagePort} port
+ */
+function transferWritableStream(stream, port) {
+  const readable = createCrossRealmReadableStream(port);
+  const promise = readableStreamPipeTo(readable, stream, false, false, false);
+  setPromiseIsHandledToTrue(promise);
+}
+
 /*
  * @param {ReadableStream} stream
  */
@@ -6600,12 +6821,16 @@ export {
   // Exposed in global runtime scope
   ByteLengthQueuingStrategy,
   CountQueuingStrategy,
+  createCrossRealmReadableStream,
+  createCrossRealmWritableStream,
   createProxy,
   Deferred,
   errorReadableStream,
   getReadableStreamResourceBacking,
   getWritableStreamResourceBacking,
   isReadableStreamDisturbed,
+  isReadableStreamLocked,
+  isWritableStreamLocked,
   ReadableByteStreamController,
   ReadableStream,
   ReadableStreamBYOBReader,
@@ -6624,6 +6849,8 @@ export {
   readableStreamThrowIfErrored,
   resourceForReadableStream,
   TransformStream,
+  transferReadableStream,
+  transferWritableStream,
   TransformStreamDefaultController,
   WritableStream,
   writableStreamClose,
@@ -17,15 +17,41 @@ import {
   setIsTrusted,
 } from "ext:deno_web/02_event.js";
 import DOMException from "ext:deno_web/01_dom_exception.js";
+import {
+  createCrossRealmReadableStream,
+  createCrossRealmWritableStream,
+  isReadableStreamLocked,
+  isWritableStreamLocked,
+  ReadableStreamPrototype,
+  transferReadableStream,
+  transferWritableStream,
+  WritableStreamPrototype,
+} from "ext:deno_web/06_streams.js";
 const primordials = globalThis.__bootstrap.primordials;
 const {
   ArrayBufferPrototype,
   ArrayBufferPrototypeGetByteLength,
+  ArrayIsArray,
   ArrayPrototypeFilter,
   ArrayPrototypeIncludes,
   ArrayPrototypePush,
+  MapPrototype,
+  MapPrototypeClear,
+  MapPrototypeForEach,
+  MapPrototypeSet,
+  ObjectDefineProperty,
+  ObjectGetPrototypeOf,
+  ObjectHasOwn,
+  ObjectKeys,
+  ObjectPrototype,
   ObjectPrototypeIsPrototypeOf,
   ObjectSetPrototypeOf,
+  SafeSet,
+  SetPrototype,
+  SetPrototypeAdd,
+  SetPrototypeClear,
+  SetPrototypeForEach,
+  SetPrototypeHas,
   Symbol,
   SymbolFor,
   SymbolIterator,
@@ -198,6 +224,107 @@ function opCreateEntangledMessagePort() {
   return ops.op_message_port_create_entangled();
 }
 
+/**
+ * Transferred streams are serialized as plain objects carrying their index in
+ * the transfer list under a random key, and swapped for the cross-realm
+ * streams created on the receiving side once the message is deserialized.
+ * The key is generated for every message and sent along with its transfer
+ * list, so the data of a message can not forge a stand-in.
+ *
+ * @returns {string}
+ */
+function createTransferredStreamKey() {
+  return `[[TransferredStream ${ops.op_message_port_stream_key()}]]`;
+}
+
+/**
+ * @param {object[]} streams
+ * @param {string} key
+ */
+function markTransferredStreams(streams, key) {
+  for (let i = 0; i < streams.length; ++i) {
+    const stream = streams[i];
+    if (ObjectHasOwn(stream, key)) {
+      throw new DOMException(
+        "Can not transfer the same stream twice",
+        "DataCloneError",
+      );
+    }
+    ObjectDefineProperty(stream, key, {
+      __proto__: null,
+      value: i,
+      enumerable: true,
+      configurable: true,
+    });
+  }
+}
+
+/**
+ * @param {any} value
+ * @param {object[]} streams
+ * @param {string} key
+ * @returns {object | undefined}
+ */
+function transferredStreamFor(value, streams, key) {
+  if (
+    typeof value !== "object" || value === null || ArrayIsArray(value) ||
+    !ObjectHasOwn(value, key)
+  ) {
+    return undefined;
+  }
+  return streams[value[key]];
+}
+
+/**
+ * Walks the deserialized message and replaces the objects standing in for
+ * transferred streams with the streams themselves.
+ *
+ * @param {any} value
+ * @param {object[]} streams
+ * @param {string} key
+ * @param {SafeSet<object>} seen
+ * @returns {any}
+ */
+function replaceStreamMarkers(value, streams, key, seen) {
+  if (typeof value !== "object" || value === null) return value;
+  const stream = transferredStreamFor(value, streams, key);
+  if (stream !== undefined) return stream;
+  if (SetPrototypeHas(seen, value)) return value;
+  SetPrototypeAdd(seen, value);
+
+  if (ObjectPrototypeIsPrototypeOf(MapPrototype, value)) {
+    const entries = [];
+    MapPrototypeForEach(value, (v, k) => {
+      ArrayPrototypePush(entries, [
+        replaceStreamMarkers(k, streams, key, seen),
+        replaceStreamMarkers(v, streams, key, seen),
+      ]);
+    });
+    MapPrototypeClear(value);
+    for (let i = 0; i < entries.length; ++i) {
+      MapPrototypeSet(value, entries[i][0], entries[i][1]);
+    }
+  } else if (ObjectPrototypeIsPrototypeOf(SetPrototype, value)) {
+    const items = [];
+    SetPrototypeForEach(value, (v) => {
+      ArrayPrototypePush(items, replaceStreamMarkers(v, streams, key, seen));
+    });
+    SetPrototypeClear(value);
+    for (let i = 0; i < items.length; ++i) {
+      SetPrototypeAdd(value, items[i]);
+    }
+  } else if (
+    ArrayIsArray(value) || ObjectGetPrototypeOf(value) === ObjectPrototype
+  ) {
+    const keys = ObjectKeys(value);
+    for (let i = 0; i < keys.length; ++i) {
+      const name = keys[i];
+      value[name] = replaceStreamMarkers(value[name], streams, key, seen);
+    }
+  }
+  return value;
+}
+
 /**
  * @param {messagePort.MessageData} messageData
  * @returns {[any, object[]]}
@@ -207,6 +334,7 @@ function deserializeJsMessageData(messageData) {
   const transferables = [];
   const arrayBufferIdsInTransferables = [];
   const transferredArrayBuffers = [];
+  const transferredStreams = [];
   let options;
 
   if (messageData.transferables.length > 0) {
@@ -220,6 +348,20 @@ function deserializeJsMessageData(messageData) {
           ArrayPrototypePush(hostObjects, port);
           break;
         }
+        case "readableStream": {
+          const port = createMessagePort(transferable.data);
+          const stream = createCrossRealmReadableStream(port);
+          ArrayPrototypePush(transferables, stream);
+          ArrayPrototypePush(transferredStreams, stream);
+          break;
+        }
+        case "writableStream": {
+          const port = createMessagePort(transferable.data);
+          const stream = createCrossRealmWritableStream(port);
+          ArrayPrototypePush(transferables, stream);
+          ArrayPrototypePush(transferredStreams, stream);
+          break;
+        }
         case "arrayBuffer": {
           ArrayPrototypePush(transferredArrayBuffers, transferable.data);
           const index = ArrayPrototypePush(transferables, null);
@@ -237,7 +379,15 @@ function deserializeJsMessageData(messageData) {
     };
   }
 
-  const data = core.deserialize(messageData.data, options);
+  let data = core.deserialize(messageData.data, options);
+  if (transferredStreams.length > 0 && messageData.streamKey) {
+    data = replaceStreamMarkers(
+      data,
+      transferredStreams,
+      messageData.streamKey,
+      new SafeSet(),
+    );
+  }
 
   for (let i = 0; i < arrayBufferIdsInTransferables.length; ++i) {
     const id = arrayBufferIdsInTransferables[i];
@@ -255,6 +405,7 @@ function deserializeJsMessageData(messageData) {
 function serializeJsMessageData(data, transferables) {
   let options;
   const transferredArrayBuffers = [];
+  const transferredStreams = [];
   if (transferables.length > 0) {
     const hostObjects = [];
     for (let i = 0, j = 0; i < transferables.length; i++) {
@@ -273,6 +424,22 @@ function serializeJsMessageData(data, transferables) {
         ArrayPrototypePush(transferredArrayBuffers, t);
       } else if (ObjectPrototypeIsPrototypeOf(MessagePortPrototype, t)) {
         ArrayPrototypePush(hostObjects, t);
+      } else if (ObjectPrototypeIsPrototypeOf(ReadableStreamPrototype, t)) {
+        if (isReadableStreamLocked(t)) {
+          throw new DOMException(
+            "Can not transfer locked ReadableStream",
+            "DataCloneError",
+          );
+        }
+        ArrayPrototypePush(transferredStreams, t);
+      } else if (ObjectPrototypeIsPrototypeOf(WritableStreamPrototype, t)) {
+        if (isWritableStreamLocked(t)) {
+          throw new DOMException(
+            "Can not transfer locked WritableStream",
+            "DataCloneError",
+          );
+        }
+        ArrayPrototypePush(transferredStreams, t);
       }
     }
 
@@ -282,9 +449,20 @@ function serializeJsMessageData(data, transferables) {
     };
   }
 
-  const serializedData = core.serialize(data, options, (err) => {
-    throw new DOMException(err, "DataCloneError");
-  });
+  let serializedData;
+  const streamKey = transferredStreams.length > 0
+    ? createTransferredStreamKey()
+    : undefined;
+  try {
+    markTransferredStreams(transferredStreams, streamKey);
+    serializedData = core.serialize(data, options, (err) => {
+      throw new DOMException(err, "DataCloneError");
+    });
+  } finally {
+    for (let i = 0; i < transferredStreams.length; ++i) {
+      delete transferredStreams[i][streamKey];
+    }
+  }
 
   /** @type {messagePort.Transferable[]} */
   const serializedTransferables = [];
@@ -306,6 +484,24 @@ function serializeJsMessageData(data, transferables) {
         kind: "messagePort",
         data: id,
       });
+    } else if (
+      ObjectPrototypeIsPrototypeOf(ReadableStreamPrototype, transferable)
+    ) {
+      const { 0: localId, 1: remoteId } = opCreateEntangledMessagePort();
+      transferReadableStream(transferable, createMessagePort(localId));
+      ArrayPrototypePush(serializedTransferables, {
+        kind: "readableStream",
+        data: remoteId,
+      });
+    } else if (
+      ObjectPrototypeIsPrototypeOf(WritableStreamPrototype, transferable)
+    ) {
+      const { 0: localId, 1: remoteId } = opCreateEntangledMessagePort();
+      transferWritableStream(transferable, createMessagePort(localId));
+      ArrayPrototypePush(serializedTransferables, {
+        kind: "writableStream",
+        data: remoteId,
+      });
     } else if (
       ObjectPrototypeIsPrototypeOf(ArrayBufferPrototype, transferable)
     ) {
@@ -322,6 +518,7 @@ function serializeJsMessageData(data, transferables) {
   return {
     data: serializedData,
     transferables: serializedTransferables,
+    streamKey,
   };
 }
 
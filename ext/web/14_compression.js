@@ -20,18 +20,42 @@ webidl.converters.CompressionFormat = webidl.createEnumConverter(
     "deflate",
     "deflate-raw",
     "gzip",
+    "br",
+    "zstd",
   ],
 );
 
+webidl.converters.CompressionStreamOptions = webidl
+  .createDictionaryConverter("CompressionStreamOptions", [
+    {
+      key: "quality",
+      converter: (V, prefix, context, opts) =>
+        webidl.converters.long(V, prefix, context, {
+          ...opts,
+          enforceRange: true,
+        }),
+    },
+  ]);
+
 class CompressionStream {
   #transform;
 
-  constructor(format) {
+  constructor(format, options = {}) {
     const prefix = "Failed to construct 'CompressionStream'";
     webidl.requiredArguments(arguments.length, 1, prefix);
     format = webidl.converters.CompressionFormat(format, prefix, "Argument 1");
-
-    const rid = ops.op_compression_new(format, false);
+    options = webidl.converters.CompressionStreamOptions(
+      options,
+      prefix,
+      "Argument 2",
+    );
+
+    const rid = ops.op_compression_new(
+      format,
+      false,
+      options.quality !== undefined,
+      options.quality ?? 0,
+    );
 
     this.#transform = new TransformStream({
       transform(chunk, controller) {
@@ -73,7 +97,7 @@ class DecompressionStream {
     webidl.requiredArguments(arguments.length, 1, prefix);
     format = webidl.converters.CompressionFormat(format, prefix, "Argument 1");
 
-    const rid = ops.op_compression_new(format, true);
+    const rid = ops.op_compression_new(format, true, false, 0);
 
     this.#transform = new TransformStream({
       transform(chunk, controller) {
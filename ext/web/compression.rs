@@ -1,5 +1,6 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use deno_core::error::range_error;
 use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::op2;
@@ -19,11 +20,17 @@ use std::cell::RefCell;
 use std::io::Write;
 use std::rc::Rc;
 
-#[derive(Debug)]
+/// Size of the internal buffers of the brotli encoder and decoder.
+const BROTLI_BUFFER_SIZE: usize = 4096;
+/// Brotli's default window size, 2^22 bytes.
+const BROTLI_LG_WINDOW_SIZE: u32 = 22;
+const BROTLI_DEFAULT_QUALITY: u32 = 6;
+
 struct CompressionResource(RefCell<Inner>);
 
 /// https://wicg.github.io/compression/#supported-formats
-#[derive(Debug)]
+///
+/// `br` and `zstd` are supported in addition to the formats of the spec.
 enum Inner {
   DeflateDecoder(ZlibDecoder<Vec<u8>>),
   DeflateEncoder(ZlibEncoder<Vec<u8>>),
@@ -31,6 +38,14 @@ enum Inner {
   DeflateRawEncoder(DeflateEncoder<Vec<u8>>),
   GzDecoder(GzDecoder<Vec<u8>>),
   GzEncoder(GzEncoder<Vec<u8>>),
+  BrotliDecoder(Box<brotli::DecompressorWriter<Vec<u8>>>),
+  BrotliEncoder(Box<brotli::CompressorWriter<Vec<u8>>>),
+  // Unlike `zstd::stream::write::Decoder`, the writer can tell whether the
+  // last frame was complete when it is finished.
+  ZstdDecoder(
+    zstd::stream::zio::Writer<Vec<u8>, zstd::stream::raw::Decoder<'static>>,
+  ),
+  ZstdEncoder(zstd::stream::write::Encoder<'static, Vec<u8>>),
 }
 
 impl Resource for CompressionResource {
@@ -39,31 +54,84 @@ impl Resource for CompressionResource {
   }
 }
 
+fn check_quality(
+  format: &str,
+  quality: Option<i32>,
+  range: std::ops::RangeInclusive<i32>,
+) -> Result<Option<i32>, AnyError> {
+  match quality {
+    Some(quality) if !range.contains(&quality) => Err(range_error(format!(
+      "Quality for \"{format}\" must be between {} and {}, got {quality}",
+      range.start(),
+      range.end(),
+    ))),
+    quality => Ok(quality),
+  }
+}
+
+fn flate2_compression(
+  format: &str,
+  quality: Option<i32>,
+) -> Result<Compression, AnyError> {
+  Ok(match check_quality(format, quality, 0..=9)? {
+    Some(quality) => Compression::new(quality as u32),
+    None => Compression::default(),
+  })
+}
+
 #[op2(fast)]
 #[smi]
 pub fn op_compression_new(
   state: &mut OpState,
   #[string] format: &str,
   is_decoder: bool,
-) -> ResourceId {
+  has_quality: bool,
+  quality: i32,
+) -> Result<ResourceId, AnyError> {
+  let quality = has_quality.then_some(quality);
   let w = Vec::new();
   let inner = match (format, is_decoder) {
     ("deflate", true) => Inner::DeflateDecoder(ZlibDecoder::new(w)),
-    ("deflate", false) => {
-      Inner::DeflateEncoder(ZlibEncoder::new(w, Compression::default()))
-    }
+    ("deflate", false) => Inner::DeflateEncoder(ZlibEncoder::new(
+      w,
+      flate2_compression(format, quality)?,
+    )),
     ("deflate-raw", true) => Inner::DeflateRawDecoder(DeflateDecoder::new(w)),
-    ("deflate-raw", false) => {
-      Inner::DeflateRawEncoder(DeflateEncoder::new(w, Compression::default()))
-    }
+    ("deflate-raw", false) => Inner::DeflateRawEncoder(DeflateEncoder::new(
+      w,
+      flate2_compression(format, quality)?,
+    )),
     ("gzip", true) => Inner::GzDecoder(GzDecoder::new(w)),
     ("gzip", false) => {
-      Inner::GzEncoder(GzEncoder::new(w, Compression::default()))
+      Inner::GzEncoder(GzEncoder::new(w, flate2_compression(format, quality)?))
+    }
+    ("br", true) => Inner::BrotliDecoder(Box::new(
+      brotli::DecompressorWriter::new(w, BROTLI_BUFFER_SIZE),
+    )),
+    ("br", false) => {
+      let quality = check_quality(format, quality, 0..=11)?
+        .map(|quality| quality as u32)
+        .unwrap_or(BROTLI_DEFAULT_QUALITY);
+      Inner::BrotliEncoder(Box::new(brotli::CompressorWriter::new(
+        w,
+        BROTLI_BUFFER_SIZE,
+        quality,
+        BROTLI_LG_WINDOW_SIZE,
+      )))
+    }
+    ("zstd", true) => Inner::ZstdDecoder(zstd::stream::zio::Writer::new(
+      w,
+      zstd::stream::raw::Decoder::new()?,
+    )),
+    ("zstd", false) => {
+      // Level 0 selects zstd's default level.
+      let level = check_quality(format, quality, 1..=22)?.unwrap_or(0);
+      Inner::ZstdEncoder(zstd::stream::write::Encoder::new(w, level)?)
     }
     _ => unreachable!(),
   };
   let resource = CompressionResource(RefCell::new(inner));
-  state.resource_table.add(resource)
+  Ok(state.resource_table.add(resource))
 }
 
 #[op2]
@@ -106,6 +174,26 @@ pub fn op_compression_write(
       d.flush()?;
       d.get_mut().drain(..)
     }
+    Inner::BrotliDecoder(d) => {
+      d.write_all(input).map_err(|e| type_error(e.to_string()))?;
+      d.flush()?;
+      d.get_mut().drain(..)
+    }
+    Inner::BrotliEncoder(d) => {
+      d.write_all(input).map_err(|e| type_error(e.to_string()))?;
+      d.flush()?;
+      d.get_mut().drain(..)
+    }
+    Inner::ZstdDecoder(d) => {
+      d.write_all(input).map_err(|e| type_error(e.to_string()))?;
+      d.flush()?;
+      d.writer_mut().drain(..)
+    }
+    Inner::ZstdEncoder(d) => {
+      d.write_all(input).map_err(|e| type_error(e.to_string()))?;
+      d.flush()?;
+      d.get_mut().drain(..)
+    }
   }
   .collect();
   Ok(out.into())
@@ -118,7 +206,7 @@ pub fn op_compression_finish(
   #[smi] rid: ResourceId,
 ) -> Result<ToJsBuffer, AnyError> {
   let resource = state.resource_table.take::<CompressionResource>(rid)?;
-  let resource = Rc::try_unwrap(resource).unwrap();
+  let resource = Rc::try_unwrap(resource).ok().unwrap();
   let inner = resource.0.into_inner();
   let out: Vec<u8> = match inner {
     Inner::DeflateDecoder(d) => {
@@ -135,6 +223,22 @@ pub fn op_compression_finish(
     }
     Inner::GzDecoder(d) => d.finish().map_err(|e| type_error(e.to_string()))?,
     Inner::GzEncoder(d) => d.finish().map_err(|e| type_error(e.to_string()))?,
+    Inner::BrotliDecoder(mut d) => {
+      d.close().map_err(|e| type_error(e.to_string()))?;
+      // The inner writer is only handed back for complete streams.
+      d.into_inner()
+        .map_err(|_| type_error("Unexpected end of brotli stream"))?
+    }
+    // `into_inner` writes the end of the stream.
+    Inner::BrotliEncoder(d) => d.into_inner(),
+    Inner::ZstdDecoder(mut d) => {
+      // Fails if the input ended in the middle of a frame.
+      d.finish().map_err(|e| type_error(e.to_string()))?;
+      d.into_inner().0
+    }
+    Inner::ZstdEncoder(d) => {
+      d.finish().map_err(|e| type_error(e.to_string()))?
+    }
   };
   Ok(out.into())
 }
@@ -102,6 +102,12 @@ declare module "ext:deno_web/13_message_port.js" {
   type Transferable = {
     kind: "messagePort";
     data: number;
+  } | {
+    kind: "readableStream";
+    data: number;
+  } | {
+    kind: "writableStream";
+    data: number;
   } | {
     kind: "arrayBuffer";
     data: number;
@@ -109,5 +115,6 @@ declare module "ext:deno_web/13_message_port.js" {
   interface MessageData {
     data: Uint8Array;
     transferables: Transferable[];
+    streamKey?: string | null;
   }
 }
@@ -984,7 +984,11 @@ declare var MessageEvent: {
 };
 
 /** @category DOM APIs */
-declare type Transferable = ArrayBuffer | MessagePort;
+declare type Transferable =
+  | ArrayBuffer
+  | MessagePort
+  | ReadableStream
+  | WritableStream;
 
 /**
  * This type has been renamed to StructuredSerializeOptions. Use that type for
@@ -1138,9 +1142,26 @@ declare interface CompressionStream {
   readonly writable: WritableStream<Uint8Array>;
 }
 
+/**
+ * Options for a `CompressionStream`.
+ *
+ * @category Compression Streams API
+ */
+declare interface CompressionStreamOptions {
+  /**
+   * The compression level of the format: 0 to 9 for `"deflate"`,
+   * `"deflate-raw"` and `"gzip"`, 0 to 11 for `"br"` and 1 to 22 for
+   * `"zstd"`. Each format picks its own default when it is omitted.
+   */
+  quality?: number;
+}
+
 /**
  * An API for compressing a stream of data.
  *
+ * Besides the `"deflate"`, `"deflate-raw"` and `"gzip"` formats of the
+ * specification, `"br"` (Brotli) and `"zstd"` (Zstandard) are supported.
+ *
  * @example
  * ```ts
  * await Deno.stdin.readable
@@ -1148,6 +1169,13 @@ declare interface CompressionStream {
  *   .pipeTo(Deno.stdout.writable);
  * ```
  *
+ * @example
+ * ```ts
+ * await Deno.stdin.readable
+ *   .pipeThrough(new CompressionStream("br", { quality: 11 }))
+ *   .pipeTo(Deno.stdout.writable);
+ * ```
+ *
  * @category Compression Streams API
  */
 declare var CompressionStream: {
@@ -1157,9 +1185,10 @@ declare var CompressionStream: {
    * data.
    *
    * Throws a `TypeError` if the format passed to the constructor is not
-   * supported.
+   * supported, and a `RangeError` if the quality is out of range for the
+   * format.
    */
-  new (format: string): CompressionStream;
+  new (format: string, options?: CompressionStreamOptions): CompressionStream;
 };
 
 /**
@@ -1201,7 +1230,8 @@ declare var DecompressionStream: {
   readonly prototype: DecompressionStream;
   /**
    * Creates a new `DecompressionStream` object which decompresses a stream of
-   * data.
+   * data. `"br"` and `"zstd"` are supported in addition to the formats of the
+   * specification.
    *
    * Throws a `TypeError` if the format passed to the constructor is not
    * supported.
@@ -23,10 +23,12 @@ use tokio::sync::mpsc::UnboundedSender;
 
 enum Transferable {
   MessagePort(MessagePort),
+  ReadableStream(MessagePort),
+  WritableStream(MessagePort),
   ArrayBuffer(u32),
 }
 
-type MessagePortMessage = (DetachedBuffer, Vec<Transferable>);
+type MessagePortMessage = (DetachedBuffer, Vec<Transferable>, Option<String>);
 
 pub struct MessagePort {
   rx: RefCell<UnboundedReceiver<MessagePortMessage>>,
@@ -45,7 +47,7 @@ impl MessagePort {
     // Swallow the failed to send error. It means the channel was disentangled,
     // but not cleaned up.
     if let Some(tx) = &*self.tx.borrow() {
-      tx.send((data.data, transferables)).ok();
+      tx.send((data.data, transferables, data.stream_key)).ok();
     }
 
     Ok(())
@@ -60,12 +62,13 @@ impl MessagePort {
       .rx
       .try_borrow_mut()
       .map_err(|_| type_error("Port receiver is already borrowed"))?;
-    if let Some((data, transferables)) = rx.recv().await {
+    if let Some((data, transferables, stream_key)) = rx.recv().await {
       let js_transferables =
         serialize_transferables(&mut state.borrow_mut(), transferables);
       return Ok(Some(JsMessageData {
         data,
         transferables: js_transferables,
+        stream_key,
       }));
     }
     Ok(None)
@@ -136,9 +139,32 @@ pub fn op_message_port_create_entangled(
 pub enum JsTransferable {
   #[serde(rename_all = "camelCase")]
   MessagePort(ResourceId),
+  ReadableStream(ResourceId),
+  WritableStream(ResourceId),
   ArrayBuffer(u32),
 }
 
+fn take_message_port(
+  state: &mut OpState,
+  id: ResourceId,
+) -> Result<MessagePort, AnyError> {
+  let resource = state
+    .resource_table
+    .take::<MessagePortResource>(id)
+    .map_err(|_| type_error("Invalid message port transfer"))?;
+  resource.cancel.cancel();
+  let resource = Rc::try_unwrap(resource)
+    .map_err(|_| type_error("Message port is not ready for transfer"))?;
+  Ok(resource.port)
+}
+
+fn add_message_port(state: &mut OpState, port: MessagePort) -> ResourceId {
+  state.resource_table.add(MessagePortResource {
+    port,
+    cancel: CancelHandle::new(),
+  })
+}
+
 fn deserialize_js_transferables(
   state: &mut OpState,
   js_transferables: Vec<JsTransferable>,
@@ -147,14 +173,16 @@ fn deserialize_js_transferables(
   for js_transferable in js_transferables {
     match js_transferable {
       JsTransferable::MessagePort(id) => {
-        let resource = state
-          .resource_table
-          .take::<MessagePortResource>(id)
-          .map_err(|_| type_error("Invalid message port transfer"))?;
-        resource.cancel.cancel();
-        let resource = Rc::try_unwrap(resource)
-          .map_err(|_| type_error("Message port is not ready for transfer"))?;
-        transferables.push(Transferable::MessagePort(resource.port));
+        let port = take_message_port(state, id)?;
+        transferables.push(Transferable::MessagePort(port));
+      }
+      JsTransferable::ReadableStream(id) => {
+        let port = take_message_port(state, id)?;
+        transferables.push(Transferable::ReadableStream(port));
+      }
+      JsTransferable::WritableStream(id) => {
+        let port = take_message_port(state, id)?;
+        transferables.push(Transferable::WritableStream(port));
       }
       JsTransferable::ArrayBuffer(id) => {
         transferables.push(Transferable::ArrayBuffer(id));
@@ -172,12 +200,17 @@ fn serialize_transferables(
   for transferable in transferables {
     match transferable {
       Transferable::MessagePort(port) => {
-        let rid = state.resource_table.add(MessagePortResource {
-          port,
-          cancel: CancelHandle::new(),
-        });
+        let rid = add_message_port(state, port);
         js_transferables.push(JsTransferable::MessagePort(rid));
       }
+      Transferable::ReadableStream(port) => {
+        let rid = add_message_port(state, port);
+        js_transferables.push(JsTransferable::ReadableStream(rid));
+      }
+      Transferable::WritableStream(port) => {
+        let rid = add_message_port(state, port);
+        js_transferables.push(JsTransferable::WritableStream(rid));
+      }
       Transferable::ArrayBuffer(id) => {
         js_transferables.push(JsTransferable::ArrayBuffer(id));
       }
@@ -187,9 +220,21 @@ fn serialize_transferables(
 }
 
 #[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct JsMessageData {
   data: DetachedBuffer,
   transferables: Vec<JsTransferable>,
+  /// The key that marks the stand-ins for transferred streams in `data`.
+  #[serde(default)]
+  stream_key: Option<String>,
+}
+
+/// Returns a key for marking the streams transferred with a message, which
+/// the data of the message can not guess.
+#[op2]
+#[string]
+pub fn op_message_port_stream_key() -> String {
+  uuid::Uuid::new_v4().to_string()
 }
 
 #[op2]
@@ -42,6 +42,7 @@ const {
   SymbolIterator,
   PromisePrototypeCatch,
   SymbolFor,
+  TypeError,
   TypedArrayPrototypeGetByteLength,
 } = primordials;
 const { op_ws_check_permission_and_cancel_handle } = core.ops;
@@ -94,6 +95,65 @@ webidl.converters["WebSocketSend"] = (V, prefix, context, opts) => {
   return webidl.converters["USVString"](V, prefix, context, opts);
 };
 
+webidl.converters.WebSocketCompressionOptions = webidl.createDictionaryConverter(
+  "WebSocketCompressionOptions",
+  [
+    {
+      key: "serverNoContextTakeover",
+      converter: webidl.converters.boolean,
+      defaultValue: false,
+    },
+    {
+      key: "clientNoContextTakeover",
+      converter: webidl.converters.boolean,
+      defaultValue: false,
+    },
+    {
+      key: "serverMaxWindowBits",
+      converter: webidl.converters.octet,
+    },
+    {
+      key: "clientMaxWindowBits",
+      converter: webidl.converters.octet,
+    },
+  ],
+);
+
+function checkWindowBits(bits, prefix, context) {
+  if (bits !== undefined && (bits < 9 || bits > 15)) {
+    throw new TypeError(
+      `${prefix}: ${context} must be an integer between 9 and 15.`,
+    );
+  }
+}
+
+/**
+ * Convert the `compression` option of a WebSocket client or of
+ * `Deno.upgradeWebSocket` to the parameters of the permessage-deflate
+ * extension, or `null` if compression is disabled.
+ */
+function toCompressionOptions(value, prefix, context) {
+  if (value === undefined || value === false) {
+    return null;
+  }
+  const options = webidl.converters.WebSocketCompressionOptions(
+    value === true ? {} : value,
+    prefix,
+    context,
+  );
+  checkWindowBits(
+    options.serverMaxWindowBits,
+    prefix,
+    `${context}.serverMaxWindowBits`,
+  );
+  checkWindowBits(
+    options.clientMaxWindowBits,
+    prefix,
+    `${context}.clientMaxWindowBits`,
+  );
+  return options;
+}
+
 /** role */
 const SERVER = 0;
 const CLIENT = 1;
@@ -133,6 +193,19 @@ class WebSocket extends EventTarget {
     const prefix = "Failed to construct 'WebSocket'";
     webidl.requiredArguments(arguments.length, 1, prefix);
     url = webidl.converters.USVString(url, prefix, "Argument 1");
+    // Besides protocols, the second argument may be an object of options.
+    let compression = null;
+    if (
+      webidl.type(protocols) === "Object" && protocols !== null &&
+      protocols[SymbolIterator] === undefined
+    ) {
+      compression = toCompressionOptions(
+        protocols.compression,
+        prefix,
+        "Argument 2.compression",
+      );
+      protocols = protocols.protocols ?? [];
+    }
     protocols = webidl.converters["sequence<DOMString> or DOMString"](
       protocols,
       prefix,
@@ -212,6 +285,9 @@ class WebSocket extends EventTarget {
         "new WebSocket()",
         wsURL.href,
         ArrayPrototypeJoin(protocols, ", "),
+        undefined,
+        undefined,
+        compression,
       ),
       (create) => {
         this[_rid] = create.rid;
@@ -575,6 +651,7 @@ const WebSocketPrototype = WebSocket.prototype;
 
 export {
   _eventLoop,
+  _extensions,
   _idleTimeoutDuration,
   _idleTimeoutTimeout,
   _protocol,
@@ -584,5 +661,6 @@ export {
   _server,
   _serverHandleIdleTimeout,
   SERVER,
+  toCompressionOptions,
   WebSocket,
 };
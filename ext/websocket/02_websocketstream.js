@@ -13,6 +13,7 @@ import {
   headerListFromHeaders,
   headersFromHeaderList,
 } from "ext:deno_fetch/20_headers.js";
+import { toCompressionOptions } from "ext:deno_websocket/01_websocket.js";
 const primordials = globalThis.__bootstrap.primordials;
 const {
   ArrayPrototypeJoin,
@@ -61,6 +62,10 @@ webidl.converters.WebSocketStreamOptions = webidl.createDictionaryConverter(
       key: "headers",
       converter: webidl.converters.HeadersInit,
     },
+    {
+      key: "compression",
+      converter: webidl.converters.any,
+    },
   ],
 );
 webidl.converters.WebSocketCloseInfo = webidl.createDictionaryConverter(
@@ -105,6 +110,11 @@ class WebSocketStream {
       prefix,
       "Argument 2",
     );
+    const compression = toCompressionOptions(
+      options.compression,
+      prefix,
+      "Argument 2.compression",
+    );
 
     const wsURL = new URL(url);
 
@@ -169,6 +179,7 @@ class WebSocketStream {
           options.protocols ? ArrayPrototypeJoin(options.protocols, ", ") : "",
           cancelRid,
           headerListFromHeaders(headers),
+          compression,
         ),
         (create) => {
           options.signal?.[remove](abort);
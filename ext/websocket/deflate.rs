@@ -0,0 +1,620 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! The `permessage-deflate` extension (RFC 7692).
+//!
+//! fastwebsockets rejects frames with reserved bits set and has no way to set
+//! them, so compression is split in two: messages are deflated and inflated
+//! by [`PerMessageDeflate`] on top of fastwebsockets, while [`FrameScanner`]
+//! follows the frame boundaries of the raw byte stream underneath it to set
+//! and clear the RSV1 bit that marks a message as compressed.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use flate2::Compress;
+use flate2::Compression;
+use flate2::Decompress;
+use flate2::FlushCompress;
+use flate2::FlushDecompress;
+use flate2::Status;
+use serde::Deserialize;
+
+pub const EXTENSION_NAME: &str = "permessage-deflate";
+
+/// Marks the first frame of a compressed message.
+pub const RSV1: u8 = 0x40;
+
+/// The trailer of a sync flush, which is stripped from every compressed
+/// message (RFC 7692, section 7.2.1).
+const SYNC_FLUSH_TRAILER: [u8; 4] = [0x00, 0x00, 0xff, 0xff];
+
+/// The largest message that will be inflated, the same as the largest
+/// message fastwebsockets accepts.
+const MAX_MESSAGE_SIZE: usize = 64 << 20;
+
+/// Raw deflate streams in zlib can not use a window of 2^8 bytes.
+const MIN_WINDOW_BITS: u8 = 9;
+const MAX_WINDOW_BITS: u8 = 15;
+
+/// The parameters of a `permessage-deflate` offer or response, which double
+/// as the compression options accepted from JavaScript.
+#[derive(Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DeflateParams {
+  pub server_no_context_takeover: bool,
+  pub client_no_context_takeover: bool,
+  pub server_max_window_bits: Option<u8>,
+  pub client_max_window_bits: Option<u8>,
+}
+
+impl DeflateParams {
+  fn validate(&self) -> Result<(), AnyError> {
+    for bits in [self.server_max_window_bits, self.client_max_window_bits]
+      .into_iter()
+      .flatten()
+    {
+      if !(MIN_WINDOW_BITS..=MAX_WINDOW_BITS).contains(&bits) {
+        return Err(type_error(
+          "Window bits must be an integer between 9 and 15",
+        ));
+      }
+    }
+    Ok(())
+  }
+
+  /// Parse the parameters of one extension. Offers may send
+  /// `client_max_window_bits` without a value, which is read as 15.
+  fn parse(params: &[(String, Option<String>)], offer: bool) -> Option<Self> {
+    let mut result = Self::default();
+    let mut seen = Vec::with_capacity(params.len());
+    for (name, value) in params {
+      if seen.contains(&name) {
+        return None;
+      }
+      seen.push(name);
+      match (name.as_str(), value) {
+        ("server_no_context_takeover", None) => {
+          result.server_no_context_takeover = true;
+        }
+        ("client_no_context_takeover", None) => {
+          result.client_no_context_takeover = true;
+        }
+        ("server_max_window_bits", Some(value)) => {
+          result.server_max_window_bits = Some(parse_window_bits(value)?);
+        }
+        ("client_max_window_bits", Some(value)) => {
+          result.client_max_window_bits = Some(parse_window_bits(value)?);
+        }
+        ("client_max_window_bits", None) if offer => {
+          result.client_max_window_bits = Some(MAX_WINDOW_BITS);
+        }
+        _ => return None,
+      }
+    }
+    Some(result)
+  }
+
+  /// The `Sec-WebSocket-Extensions` header a client sends. It always
+  /// announces `client_max_window_bits`, as any value the server picks can
+  /// be honoured.
+  pub fn offer_header(&self) -> Result<String, AnyError> {
+    self.validate()?;
+    let mut header = String::from(EXTENSION_NAME);
+    if self.server_no_context_takeover {
+      header.push_str("; server_no_context_takeover");
+    }
+    if self.client_no_context_takeover {
+      header.push_str("; client_no_context_takeover");
+    }
+    if let Some(bits) = self.server_max_window_bits {
+      header.push_str(&format!("; server_max_window_bits={bits}"));
+    }
+    match self.client_max_window_bits {
+      Some(bits) => {
+        header.push_str(&format!("; client_max_window_bits={bits}"))
+      }
+      None => header.push_str("; client_max_window_bits"),
+    }
+    Ok(header)
+  }
+
+  fn response_header(&self) -> String {
+    let mut header = String::from(EXTENSION_NAME);
+    if self.server_no_context_takeover {
+      header.push_str("; server_no_context_takeover");
+    }
+    if self.client_no_context_takeover {
+      header.push_str("; client_no_context_takeover");
+    }
+    if let Some(bits) = self.server_max_window_bits {
+      header.push_str(&format!("; server_max_window_bits={bits}"));
+    }
+    if let Some(bits) = self.client_max_window_bits {
+      header.push_str(&format!("; client_max_window_bits={bits}"));
+    }
+    header
+  }
+}
+
+fn parse_window_bits(value: &str) -> Option<u8> {
+  if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+    return None;
+  }
+  let bits = value.parse::<u8>().ok()?;
+  (8..=MAX_WINDOW_BITS).contains(&bits).then_some(bits)
+}
+
+type Extension = (String, Vec<(String, Option<String>)>);
+
+/// Split a `Sec-WebSocket-Extensions` header into extensions and their
+/// parameters. Names are lowercased, quotes around values are removed.
+fn parse_extensions(header: &str) -> Vec<Extension> {
+  header
+    .split(',')
+    .filter_map(|extension| {
+      let mut parts = extension.split(';').map(str::trim);
+      let name = parts.next().filter(|name| !name.is_empty())?;
+      let params = parts
+        .filter(|param| !param.is_empty())
+        .map(|param| match param.split_once('=') {
+          Some((name, value)) => (
+            name.trim().to_ascii_lowercase(),
+            Some(value.trim().trim_matches('"').to_owned()),
+          ),
+          None => (param.to_ascii_lowercase(), None),
+        })
+        .collect();
+      Some((name.to_ascii_lowercase(), params))
+    })
+    .collect()
+}
+
+/// Pick the first acceptable `permessage-deflate` offer of a client and
+/// return the `Sec-WebSocket-Extensions` header to respond with, or `None` if
+/// the client did not offer compression this server can provide.
+pub fn negotiate(
+  offers: &str,
+  config: &DeflateParams,
+) -> Result<Option<String>, AnyError> {
+  config.validate()?;
+  for (name, params) in parse_extensions(offers) {
+    if name != EXTENSION_NAME {
+      continue;
+    }
+    let Some(offer) = DeflateParams::parse(&params, true) else {
+      continue;
+    };
+    let server_max_window_bits =
+      match (offer.server_max_window_bits, config.server_max_window_bits) {
+        (Some(offered), Some(configured)) => Some(offered.min(configured)),
+        (offered, configured) => offered.or(configured),
+      };
+    if server_max_window_bits == Some(8) {
+      continue;
+    }
+    // The server can only limit the client's window if the client offered
+    // to accept a limit.
+    let client_max_window_bits =
+      match (offer.client_max_window_bits, config.client_max_window_bits) {
+        (Some(offered), Some(configured)) => Some(offered.min(configured)),
+        _ => None,
+      };
+    let response = DeflateParams {
+      server_no_context_takeover: offer.server_no_context_takeover
+        || config.server_no_context_takeover,
+      client_no_context_takeover: offer.client_no_context_takeover
+        || config.client_no_context_takeover,
+      server_max_window_bits,
+      client_max_window_bits,
+    };
+    return Ok(Some(response.response_header()));
+  }
+  Ok(None)
+}
+
+/// Which side of the connection this endpoint is.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+  Client,
+  Server,
+}
+
+/// Compression state of a connection that negotiated `permessage-deflate`.
+pub struct PerMessageDeflate {
+  compress: RefCell<Compress>,
+  decompress: RefCell<Decompress>,
+  compress_no_context_takeover: bool,
+  decompress_no_context_takeover: bool,
+  /// The data messages read from the socket so far, in order. Filled by the
+  /// [`FrameScanner`] that reads the socket.
+  inbound: Rc<RefCell<VecDeque<InboundMessage>>>,
+}
+
+/// How a data message was received.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InboundMessage {
+  Uncompressed,
+  /// Compressed messages are handed to fastwebsockets as binary frames, so
+  /// that it does not validate their compressed payload as UTF-8.
+  Compressed {
+    text: bool,
+  },
+}
+
+impl PerMessageDeflate {
+  /// Set up compression from the `Sec-WebSocket-Extensions` header of a
+  /// handshake response. Clients pass what they offered, so that responses
+  /// that do not match the offer are rejected.
+  pub fn from_response(
+    header: &str,
+    side: Side,
+    offer: Option<&DeflateParams>,
+  ) -> Result<Option<Self>, AnyError> {
+    let mut extensions = parse_extensions(header).into_iter();
+    let Some((name, params)) = extensions.next() else {
+      return Ok(None);
+    };
+    if name != EXTENSION_NAME || extensions.next().is_some() {
+      return Err(type_error(format!(
+        "Unexpected WebSocket extensions: {header}"
+      )));
+    }
+    let params = DeflateParams::parse(&params, false).ok_or_else(|| {
+      type_error(format!("Invalid permessage-deflate response: {header}"))
+    })?;
+    if let Some(offer) = offer {
+      if matches!(
+        (offer.server_max_window_bits, params.server_max_window_bits),
+        (Some(offered), Some(bits)) if bits > offered
+      ) || matches!(
+        (offer.server_max_window_bits, params.server_max_window_bits),
+        (Some(_), None)
+      ) {
+        return Err(type_error(format!(
+          "Invalid permessage-deflate response: {header}"
+        )));
+      }
+    }
+
+    let (compress_bits, compress_no_context_takeover, decompress_reset) =
+      match side {
+        Side::Server => (
+          params.server_max_window_bits,
+          params.server_no_context_takeover,
+          params.client_no_context_takeover,
+        ),
+        Side::Client => (
+          params.client_max_window_bits,
+          params.client_no_context_takeover,
+          params.server_no_context_takeover,
+        ),
+      };
+    let compress_bits = compress_bits.unwrap_or(MAX_WINDOW_BITS);
+    if compress_bits < MIN_WINDOW_BITS {
+      return Err(type_error(
+        "permessage-deflate with a window of 8 bits is not supported",
+      ));
+    }
+    Ok(Some(Self {
+      compress: RefCell::new(Compress::new_with_window_bits(
+        Compression::default(),
+        false,
+        compress_bits,
+      )),
+      // Inflating with the largest window works for any window the peer
+      // compresses with.
+      decompress: RefCell::new(Decompress::new(false)),
+      compress_no_context_takeover: compress_no_context_takeover
+        || offer.is_some_and(|offer| offer.client_no_context_takeover),
+      decompress_no_context_takeover: decompress_reset,
+      inbound: Default::default(),
+    }))
+  }
+
+  pub fn frame_scanner(&self) -> FrameScanner {
+    FrameScanner {
+      inbound: Some(self.inbound.clone()),
+      ..Default::default()
+    }
+  }
+
+  /// How the next data message that was read was received.
+  pub fn next_message(&self) -> InboundMessage {
+    self
+      .inbound
+      .borrow_mut()
+      .pop_front()
+      .unwrap_or(InboundMessage::Uncompressed)
+  }
+
+  pub fn compress(&self, input: &[u8]) -> Result<Vec<u8>, AnyError> {
+    let mut compress = self.compress.borrow_mut();
+    let mut output = Vec::with_capacity(input.len() / 2 + 64);
+    let start = compress.total_in();
+    loop {
+      if output.len() == output.capacity() {
+        output.reserve(output.capacity().max(1024));
+      }
+      let consumed = (compress.total_in() - start) as usize;
+      compress.compress_vec(
+        &input[consumed..],
+        &mut output,
+        FlushCompress::Sync,
+      )?;
+      let consumed = (compress.total_in() - start) as usize;
+      if consumed == input.len() && output.len() < output.capacity() {
+        break;
+      }
+    }
+    if output.ends_with(&SYNC_FLUSH_TRAILER) {
+      output.truncate(output.len() - SYNC_FLUSH_TRAILER.len());
+    }
+    if self.compress_no_context_takeover {
+      compress.reset();
+    }
+    Ok(output)
+  }
+
+  pub fn decompress(&self, input: &[u8]) -> Result<Vec<u8>, AnyError> {
+    let mut decompress = self.decompress.borrow_mut();
+    let mut output = Vec::with_capacity(input.len() * 2 + 64);
+    for chunk in [input, &SYNC_FLUSH_TRAILER] {
+      let start = decompress.total_in();
+      loop {
+        if output.len() == output.capacity() {
+          if output.len() >= MAX_MESSAGE_SIZE {
+            return Err(type_error("Decompressed message is too large"));
+          }
+          output.reserve(output.capacity().max(1024));
+        }
+        let consumed = (decompress.total_in() - start) as usize;
+        let status = decompress
+          .decompress_vec(
+            &chunk[consumed..],
+            &mut output,
+            FlushDecompress::Sync,
+          )
+          .map_err(|_| type_error("Invalid compressed message"))?;
+        let consumed = (decompress.total_in() - start) as usize;
+        if status == Status::StreamEnd
+          || (consumed == chunk.len() && output.len() < output.capacity())
+        {
+          break;
+        }
+      }
+    }
+    if self.decompress_no_context_takeover {
+      decompress.reset(false);
+    }
+    Ok(output)
+  }
+}
+
+/// Follows the frame boundaries of one direction of a WebSocket byte stream,
+/// so that the first byte of each frame header can be found.
+#[derive(Default, Clone)]
+pub struct FrameScanner {
+  header: [u8; 14],
+  header_len: usize,
+  payload_remaining: u64,
+  /// Set on the read side, where it records how each data message was
+  /// received.
+  inbound: Option<Rc<RefCell<VecDeque<InboundMessage>>>>,
+}
+
+impl FrameScanner {
+  fn at_frame_start(&self) -> bool {
+    self.header_len == 0 && self.payload_remaining == 0
+  }
+
+  /// Consume bytes until the next one starts a frame. Returns how many were
+  /// consumed.
+  fn skip(&mut self, bytes: &[u8]) -> usize {
+    let mut consumed = 0;
+    while consumed < bytes.len() && !self.at_frame_start() {
+      if self.payload_remaining > 0 {
+        let len = self.payload_remaining.min((bytes.len() - consumed) as u64);
+        self.payload_remaining -= len;
+        consumed += len as usize;
+      } else {
+        self.push_header_byte(bytes[consumed]);
+        consumed += 1;
+      }
+    }
+    consumed
+  }
+
+  fn push_header_byte(&mut self, byte: u8) {
+    self.header[self.header_len] = byte;
+    self.header_len += 1;
+    if self.header_len < 2 {
+      return;
+    }
+    let masked = self.header[1] & 0x80 != 0;
+    let length_len = match self.header[1] & 0x7f {
+      126 => 2,
+      127 => 8,
+      _ => 0,
+    };
+    let header_len = 2 + length_len + if masked { 4 } else { 0 };
+    if self.header_len < header_len {
+      return;
+    }
+    self.payload_remaining = match length_len {
+      0 => (self.header[1] & 0x7f) as u64,
+      2 => u16::from_be_bytes([self.header[2], self.header[3]]) as u64,
+      _ => u64::from_be_bytes(self.header[2..10].try_into().unwrap()),
+    };
+    self.header_len = 0;
+  }
+
+  /// Find the first byte of the next data frame in bytes that have not been
+  /// consumed yet.
+  pub fn next_data_frame(&self, bytes: &[u8]) -> Option<usize> {
+    let mut scanner = FrameScanner {
+      inbound: None,
+      ..self.clone()
+    };
+    let mut offset = 0;
+    loop {
+      offset += scanner.skip(&bytes[offset..]);
+      let byte = *bytes.get(offset)?;
+      if is_data_frame(byte) {
+        return Some(offset);
+      }
+      scanner.push_header_byte(byte);
+      offset += 1;
+    }
+  }
+
+  /// Consume bytes that were written.
+  pub fn advance(&mut self, mut bytes: &[u8]) {
+    while !bytes.is_empty() {
+      let consumed = self.skip(bytes);
+      bytes = &bytes[consumed..];
+      if let Some((&byte, rest)) = bytes.split_first() {
+        self.push_header_byte(byte);
+        bytes = rest;
+      }
+    }
+  }
+
+  /// Consume bytes that were read, recording and clearing the RSV1 bit of
+  /// data frames. Compressed text frames are turned into binary frames.
+  /// Continuation and control frames keep RSV1, so that fastwebsockets
+  /// rejects them as the RFC requires.
+  pub fn advance_read(&mut self, bytes: &mut [u8]) {
+    let mut offset = 0;
+    while offset < bytes.len() {
+      offset += self.skip(&bytes[offset..]);
+      let Some(byte) = bytes.get_mut(offset) else {
+        break;
+      };
+      if is_data_frame(*byte) {
+        let message = if *byte & RSV1 != 0 {
+          let text = *byte & 0x0f == 0x1;
+          *byte = (*byte & !(RSV1 | 0x0f)) | 0x2;
+          InboundMessage::Compressed { text }
+        } else {
+          InboundMessage::Uncompressed
+        };
+        if let Some(inbound) = &self.inbound {
+          inbound.borrow_mut().push_back(message);
+        }
+      }
+      self.push_header_byte(*byte);
+      offset += 1;
+    }
+  }
+}
+
+/// Text and binary frames start a new message.
+fn is_data_frame(first_byte: u8) -> bool {
+  matches!(first_byte & 0x0f, 0x1 | 0x2)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn negotiate_offers() {
+    let config = DeflateParams::default();
+    assert_eq!(
+      negotiate("permessage-deflate; client_max_window_bits", &config)
+        .unwrap()
+        .as_deref(),
+      Some("permessage-deflate")
+    );
+    assert_eq!(
+      negotiate(
+        "x-webkit-deflate-frame, permessage-deflate; server_max_window_bits=8, permessage-deflate; server_no_context_takeover",
+        &config
+      )
+      .unwrap()
+      .as_deref(),
+      Some("permessage-deflate; server_no_context_takeover")
+    );
+    let config = DeflateParams {
+      client_max_window_bits: Some(10),
+      server_max_window_bits: Some(12),
+      ..Default::default()
+    };
+    assert_eq!(
+      negotiate(
+        "permessage-deflate; client_max_window_bits; server_max_window_bits=14",
+        &config
+      )
+      .unwrap()
+      .as_deref(),
+      Some(
+        "permessage-deflate; server_max_window_bits=12; client_max_window_bits=10"
+      )
+    );
+    assert_eq!(
+      negotiate("permessage-deflate; unknown_param", &config).unwrap(),
+      None
+    );
+  }
+
+  #[test]
+  fn round_trip() {
+    let response =
+      "permessage-deflate; server_no_context_takeover; client_max_window_bits=9";
+    let server = PerMessageDeflate::from_response(response, Side::Server, None)
+      .unwrap()
+      .unwrap();
+    let client = PerMessageDeflate::from_response(
+      response,
+      Side::Client,
+      Some(&DeflateParams::default()),
+    )
+    .unwrap()
+    .unwrap();
+    let message = "hello hello hello hello".repeat(10);
+    for _ in 0..3 {
+      let compressed = server.compress(message.as_bytes()).unwrap();
+      assert!(compressed.len() < message.len());
+      assert_eq!(client.decompress(&compressed).unwrap(), message.as_bytes());
+      let compressed = client.compress(message.as_bytes()).unwrap();
+      assert_eq!(server.decompress(&compressed).unwrap(), message.as_bytes());
+    }
+    assert_eq!(
+      client.decompress(&server.compress(b"").unwrap()).unwrap(),
+      b""
+    );
+  }
+
+  #[test]
+  fn scanner_marks_data_frames() {
+    let deflate = PerMessageDeflate::from_response(
+      "permessage-deflate",
+      Side::Server,
+      None,
+    )
+    .unwrap()
+    .unwrap();
+    let mut scanner = deflate.frame_scanner();
+    // A compressed masked text frame, a ping and an uncompressed binary
+    // frame with a 16 bit length.
+    let mut bytes = vec![0xc1, 0x82, 1, 2, 3, 4, 0xaa, 0xbb, 0x89, 0x00];
+    bytes.extend([0x82, 0x7e, 0x01, 0x00]);
+    bytes.extend([0u8; 256]);
+    let (first, second) = bytes.split_at_mut(7);
+    scanner.advance_read(first);
+    scanner.advance_read(second);
+    assert_eq!(bytes[0], 0x82);
+    assert_eq!(
+      deflate.next_message(),
+      InboundMessage::Compressed { text: true }
+    );
+    assert_eq!(deflate.next_message(), InboundMessage::Uncompressed);
+    assert!(scanner.at_frame_start());
+
+    let writer = FrameScanner::default();
+    assert_eq!(writer.next_data_frame(&[0x89, 0x00, 0x81, 0x00]), Some(2));
+  }
+}
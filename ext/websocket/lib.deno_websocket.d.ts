@@ -118,10 +118,45 @@ declare interface WebSocket extends EventTarget {
   ): void;
 }
 
+/**
+ * Parameters of the `permessage-deflate` extension, which compresses the
+ * messages sent over a WebSocket connection.
+ *
+ * @category Web Sockets
+ */
+declare interface WebSocketCompressionOptions {
+  /** Ask the server to compress each message on its own, which uses less
+   * memory at the cost of a worse compression ratio. Defaults to `false`. */
+  serverNoContextTakeover?: boolean;
+  /** Compress each message the client sends on its own. Defaults to
+   * `false`. */
+  clientNoContextTakeover?: boolean;
+  /** The largest LZ77 window the server may compress with, as a base 2
+   * logarithm between 9 and 15. Defaults to 15. */
+  serverMaxWindowBits?: number;
+  /** The largest LZ77 window the client may compress with, as a base 2
+   * logarithm between 9 and 15. Defaults to 15. */
+  clientMaxWindowBits?: number;
+}
+
+/** @category Web Sockets */
+declare interface WebSocketOptions {
+  /** The subprotocols to request, as with the second argument of the
+   * constructor. */
+  protocols?: string | string[];
+  /** Offer the `permessage-deflate` extension to compress messages, if the
+   * server accepts it. `true` offers it with the default parameters.
+   * Defaults to `false`. */
+  compression?: boolean | WebSocketCompressionOptions;
+}
+
 /** @category Web Sockets */
 declare var WebSocket: {
   readonly prototype: WebSocket;
-  new (url: string | URL, protocols?: string | string[]): WebSocket;
+  new (
+    url: string | URL,
+    protocols?: string | string[] | WebSocketOptions,
+  ): WebSocket;
   readonly CLOSED: number;
   readonly CLOSING: number;
   readonly CONNECTING: number;
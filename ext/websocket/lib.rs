@@ -1,4 +1,8 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+use crate::deflate::DeflateParams;
+use crate::deflate::InboundMessage;
+use crate::deflate::PerMessageDeflate;
+use crate::deflate::Side;
 use crate::stream::WebSocketStream;
 use bytes::Bytes;
 use deno_core::error::invalid_hostname;
@@ -20,6 +24,7 @@ use deno_core::ResourceId;
 use deno_core::ToJsBuffer;
 use deno_net::raw::NetworkStream;
 use deno_tls::create_client_config;
+use deno_tls::Proxy;
 use deno_tls::RootCertStoreProvider;
 use http::header::CONNECTION;
 use http::header::UPGRADE;
@@ -43,6 +48,7 @@ use std::sync::Arc;
 use tokio::io::AsyncRead;
 use tokio::io::AsyncWrite;
 use tokio::net::TcpStream;
+use tokio_rustls::rustls::ClientConfig;
 use tokio_rustls::rustls::RootCertStore;
 use tokio_rustls::rustls::ServerName;
 use tokio_rustls::TlsConnector;
@@ -53,6 +59,8 @@ use fastwebsockets::Frame;
 use fastwebsockets::OpCode;
 use fastwebsockets::Role;
 use fastwebsockets::WebSocket;
+pub mod deflate;
+mod proxy;
 mod stream;
 
 static USE_WRITEV: Lazy<bool> = Lazy::new(|| {
@@ -94,6 +102,9 @@ pub trait WebSocketPermissions {
 /// would override previously used alias.
 pub struct UnsafelyIgnoreCertificateErrors(Option<Vec<String>>);
 
+/// The proxy WebSocket connections are opened through.
+pub struct WsProxy(Option<Proxy>);
+
 pub struct WsCancelResource(Rc<CancelHandle>);
 
 impl Resource for WsCancelResource {
@@ -142,11 +153,18 @@ pub struct CreateResponse {
   extensions: String,
 }
 
+type ClientHandshake = (
+  WebSocket<WebSocketStream>,
+  http::Response<Body>,
+  Option<PerMessageDeflate>,
+);
+
 async fn handshake<S: AsyncRead + AsyncWrite + Send + Unpin + 'static>(
   cancel_resource: Option<Rc<CancelHandle>>,
   request: Request<Body>,
   socket: S,
-) -> Result<(WebSocket<WebSocketStream>, http::Response<Body>), AnyError> {
+  compression: Option<&DeflateParams>,
+) -> Result<ClientHandshake, AnyError> {
   let client =
     fastwebsockets::handshake::client(&LocalExecutor, request, socket);
 
@@ -161,12 +179,33 @@ async fn handshake<S: AsyncRead + AsyncWrite + Send + Unpin + 'static>(
     ))
   })?;
 
+  let extensions = response
+    .headers()
+    .get_all("Sec-WebSocket-Extensions")
+    .iter()
+    .map(|header| header.to_str())
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|err| type_error(err.to_string()))?
+    .join(", ");
+  let deflate = match compression {
+    Some(offer) => {
+      PerMessageDeflate::from_response(&extensions, Side::Client, Some(offer))?
+    }
+    None if !extensions.is_empty() => {
+      return Err(type_error(format!(
+        "Unexpected WebSocket extensions: {extensions}"
+      )));
+    }
+    None => None,
+  };
+
   let upgraded = upgraded.into_inner();
   let stream =
-    WebSocketStream::new(stream::WsStreamKind::Upgraded(upgraded), None);
+    WebSocketStream::new(stream::WsStreamKind::Upgraded(upgraded), None)
+      .with_deflate(deflate.as_ref());
   let stream = WebSocket::after_handshake(stream, Role::Client);
 
-  Ok((stream, response))
+  Ok((stream, response, deflate))
 }
 
 #[op2(async)]
@@ -178,6 +217,7 @@ pub async fn op_ws_create<WP>(
   #[string] protocols: String,
   #[smi] cancel_handle: Option<ResourceId>,
   #[serde] headers: Option<Vec<(ByteString, ByteString)>>,
+  #[serde] compression: Option<DeflateParams>,
 ) -> Result<CreateResponse, AnyError>
 where
   WP: WebSocketPermissions + 'static,
@@ -209,6 +249,10 @@ where
     .borrow()
     .borrow::<WsRootStoreProvider>()
     .get_or_try_init()?;
+  let proxy = state
+    .borrow()
+    .try_borrow::<WsProxy>()
+    .and_then(|it| it.0.clone());
   let user_agent = state.borrow().borrow::<WsUserAgent>().0.clone();
   let uri: Uri = url.parse()?;
   let mut request = Request::builder().method(Method::GET).uri(
@@ -238,6 +282,11 @@ where
     request = request.header("Sec-WebSocket-Protocol", protocols);
   }
 
+  if let Some(compression) = &compression {
+    request =
+      request.header("Sec-WebSocket-Extensions", compression.offer_header()?);
+  }
+
   if let Some(headers) = headers {
     for (key, value) in headers {
       let name = HeaderName::from_bytes(&key)
@@ -270,22 +319,31 @@ where
     _ => unreachable!(),
   });
   let addr = format!("{domain}:{port}");
-  let tcp_socket = TcpStream::connect(addr).await?;
+  let tls_config = || -> Result<Arc<ClientConfig>, AnyError> {
+    Ok(Arc::new(create_client_config(
+      root_cert_store.clone(),
+      vec![],
+      unsafely_ignore_certificate_errors.clone(),
+      None,
+    )?))
+  };
+  let proxy_server = proxy.as_ref().and_then(|p| p.for_host(domain));
+  let socket: Box<dyn proxy::ProxyStream> = match proxy_server {
+    Some(server) => proxy::connect(server, domain, *port, &tls_config).await?,
+    None => Box::new(TcpStream::connect(addr).await?),
+  };
 
-  let (stream, response) = match uri.scheme_str() {
-    Some("ws") => handshake(cancel_resource, request, tcp_socket).await?,
+  let compression = compression.as_ref();
+  let (stream, response, deflate) = match uri.scheme_str() {
+    Some("ws") => {
+      handshake(cancel_resource, request, socket, compression).await?
+    }
     Some("wss") => {
-      let tls_config = create_client_config(
-        root_cert_store,
-        vec![],
-        unsafely_ignore_certificate_errors,
-        None,
-      )?;
-      let tls_connector = TlsConnector::from(Arc::new(tls_config));
+      let tls_connector = TlsConnector::from(tls_config()?);
       let dnsname = ServerName::try_from(domain.as_str())
         .map_err(|_| invalid_hostname(domain))?;
-      let tls_socket = tls_connector.connect(dnsname, tcp_socket).await?;
-      handshake(cancel_resource, request, tls_socket).await?
+      let tls_socket = tls_connector.connect(dnsname, socket).await?;
+      handshake(cancel_resource, request, tls_socket, compression).await?
     }
     _ => unreachable!(),
   };
@@ -297,7 +355,9 @@ where
   }
 
   let mut state = state.borrow_mut();
-  let rid = state.resource_table.add(ServerWebSocket::new(stream));
+  let rid = state
+    .resource_table
+    .add(ServerWebSocket::new(stream, deflate));
 
   let protocol = match response.headers().get("Sec-WebSocket-Protocol") {
     Some(header) => header.to_str().unwrap(),
@@ -335,10 +395,14 @@ pub struct ServerWebSocket {
   string: Cell<Option<String>>,
   ws: AsyncRefCell<FragmentCollector<WebSocketStream>>,
   tx_lock: AsyncRefCell<()>,
+  deflate: Option<PerMessageDeflate>,
 }
 
 impl ServerWebSocket {
-  fn new(ws: WebSocket<WebSocketStream>) -> Self {
+  fn new(
+    ws: WebSocket<WebSocketStream>,
+    deflate: Option<PerMessageDeflate>,
+  ) -> Self {
     Self {
       buffered: Cell::new(0),
       error: Cell::new(None),
@@ -348,6 +412,7 @@ impl ServerWebSocket {
       string: Cell::new(None),
       ws: AsyncRefCell::new(FragmentCollector::new(ws)),
       tx_lock: AsyncRefCell::new(()),
+      deflate,
     }
   }
 
@@ -370,10 +435,19 @@ impl ServerWebSocket {
   pub async fn write_frame(
     self: &Rc<Self>,
     lock: AsyncMutFuture<()>,
-    frame: Frame<'_>,
+    mut frame: Frame<'_>,
   ) -> Result<(), AnyError> {
     lock.await;
 
+    // Messages are compressed in the order they are sent, which the peer
+    // relies on unless context takeover is disabled.
+    if let Some(deflate) = &self.deflate {
+      if matches!(frame.opcode, OpCode::Text | OpCode::Binary) {
+        let payload = deflate.compress(&frame.payload)?;
+        frame = Frame::new(frame.fin, frame.opcode, None, payload.into());
+      }
+    }
+
     // SAFETY: fastwebsockets only needs a mutable reference to the WebSocket
     // to populate the write buffer. We encounter an await point when writing
     // to the socket after the frame has already been written to the buffer.
@@ -391,22 +465,34 @@ impl Resource for ServerWebSocket {
   }
 }
 
+/// Create the resource for an upgraded server connection. `extensions` is the
+/// `Sec-WebSocket-Extensions` header the server responded with.
 pub fn ws_create_server_stream(
   state: &mut OpState,
   transport: NetworkStream,
   read_buf: Bytes,
+  extensions: Option<&str>,
 ) -> Result<ResourceId, AnyError> {
+  let deflate = match extensions {
+    Some(extensions) => {
+      PerMessageDeflate::from_response(extensions, Side::Server, None)?
+    }
+    None => None,
+  };
   let mut ws = WebSocket::after_handshake(
     WebSocketStream::new(
       stream::WsStreamKind::Network(transport),
       Some(read_buf),
-    ),
+    )
+    .with_deflate(deflate.as_ref()),
     Role::Server,
   );
-  ws.set_writev(*USE_WRITEV);
+  // Compressed messages are marked while they are written, which needs each
+  // frame header to be written in one piece.
+  ws.set_writev(*USE_WRITEV && deflate.is_none());
   ws.set_auto_close(true);
   ws.set_auto_pong(true);
-  let rid = state.resource_table.add(ServerWebSocket::new(ws));
+  let rid = state.resource_table.add(ServerWebSocket::new(ws, deflate));
   Ok(rid)
 }
 
@@ -644,8 +730,27 @@ pub async fn op_ws_next_event(
       }
     };
 
-    break match val.opcode {
-      OpCode::Text => match String::from_utf8(val.payload.to_vec()) {
+    let mut opcode = val.opcode;
+    let mut payload = val.payload;
+    if let (OpCode::Text | OpCode::Binary, Some(deflate)) =
+      (opcode, &resource.deflate)
+    {
+      if let InboundMessage::Compressed { text } = deflate.next_message() {
+        match deflate.decompress(&payload) {
+          Ok(data) => payload = data.into(),
+          Err(err) => {
+            resource.set_error(Some(err.to_string()));
+            return MessageKind::Error as u16;
+          }
+        }
+        if text {
+          opcode = OpCode::Text;
+        }
+      }
+    }
+
+    break match opcode {
+      OpCode::Text => match String::from_utf8(payload.to_vec()) {
         Ok(s) => {
           resource.string.set(Some(s));
           MessageKind::Text as u16
@@ -656,20 +761,18 @@ pub async fn op_ws_next_event(
         }
       },
       OpCode::Binary => {
-        resource.buffer.set(Some(val.payload.to_vec()));
+        resource.buffer.set(Some(payload.to_vec()));
         MessageKind::Binary as u16
       }
       OpCode::Close => {
         // Close reason is returned through error
-        if val.payload.len() < 2 {
+        if payload.len() < 2 {
           resource.set_error(None);
           MessageKind::ClosedDefault as u16
         } else {
-          let close_code = CloseCode::from(u16::from_be_bytes([
-            val.payload[0],
-            val.payload[1],
-          ]));
-          let reason = String::from_utf8(val.payload[2..].to_vec()).ok();
+          let close_code =
+            CloseCode::from(u16::from_be_bytes([payload[0], payload[1]]));
+          let reason = String::from_utf8(payload[2..].to_vec()).ok();
           resource.set_error(reason);
           close_code.into()
         }
@@ -706,13 +809,15 @@ deno_core::extension!(deno_websocket,
   options = {
     user_agent: String,
     root_cert_store_provider: Option<Arc<dyn RootCertStoreProvider>>,
-    unsafely_ignore_certificate_errors: Option<Vec<String>>
+    unsafely_ignore_certificate_errors: Option<Vec<String>>,
+    proxy: Option<Proxy>
   },
   state = |state, options| {
     state.put::<WsUserAgent>(WsUserAgent(options.user_agent));
     state.put(UnsafelyIgnoreCertificateErrors(
       options.unsafely_ignore_certificate_errors,
     ));
+    state.put(WsProxy(options.proxy));
     state.put::<WsRootStoreProvider>(WsRootStoreProvider(options.root_cert_store_provider));
   },
 );
@@ -1,4 +1,7 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+use crate::deflate::FrameScanner;
+use crate::deflate::PerMessageDeflate;
+use crate::deflate::RSV1;
 use bytes::Buf;
 use bytes::Bytes;
 use deno_net::raw::NetworkStream;
@@ -18,21 +21,45 @@ pub(crate) enum WsStreamKind {
 pub(crate) struct WebSocketStream {
   stream: WsStreamKind,
   pre: Option<Bytes>,
+  deflate: Option<Box<DeflateFraming>>,
 }
 
+/// Tracks frame boundaries in both directions to mark and unmark compressed
+/// messages, see [`crate::deflate`].
+struct DeflateFraming {
+  read: FrameScanner,
+  write: FrameScanner,
+  scratch: Vec<u8>,
+}
+
+/// The most bytes of a frame that are copied to set its RSV1 bit.
+const MAX_SCRATCH_WRITE: usize = 64 * 1024;
+
 impl WebSocketStream {
   pub fn new(stream: WsStreamKind, buffer: Option<Bytes>) -> Self {
     Self {
       stream,
       pre: buffer,
+      deflate: None,
     }
   }
-}
 
-impl AsyncRead for WebSocketStream {
+  /// Mark outgoing data frames as compressed and record which incoming ones
+  /// are. Must be called before any frame is read or written.
+  pub fn with_deflate(mut self, deflate: Option<&PerMessageDeflate>) -> Self {
+    self.deflate = deflate.map(|deflate| {
+      Box::new(DeflateFraming {
+        read: deflate.frame_scanner(),
+        write: FrameScanner::default(),
+        scratch: Vec::new(),
+      })
+    });
+    self
+  }
+
   // From hyper's Rewind (https://github.com/hyperium/hyper), MIT License, Copyright (c) Sean McArthur
-  fn poll_read(
-    mut self: Pin<&mut Self>,
+  fn poll_read_inner(
+    &mut self,
     cx: &mut std::task::Context<'_>,
     buf: &mut ReadBuf<'_>,
   ) -> Poll<std::io::Result<()>> {
@@ -56,6 +83,33 @@ impl AsyncRead for WebSocketStream {
       WsStreamKind::Upgraded(stream) => Pin::new(stream).poll_read(cx, buf),
     }
   }
+
+  fn poll_write_inner(
+    &mut self,
+    cx: &mut std::task::Context<'_>,
+    buf: &[u8],
+  ) -> Poll<Result<usize, std::io::Error>> {
+    match &mut self.stream {
+      WsStreamKind::Network(stream) => Pin::new(stream).poll_write(cx, buf),
+      WsStreamKind::Upgraded(stream) => Pin::new(stream).poll_write(cx, buf),
+    }
+  }
+}
+
+impl AsyncRead for WebSocketStream {
+  fn poll_read(
+    mut self: Pin<&mut Self>,
+    cx: &mut std::task::Context<'_>,
+    buf: &mut ReadBuf<'_>,
+  ) -> Poll<std::io::Result<()>> {
+    let start = buf.filled().len();
+    let this = &mut *self;
+    let res = this.poll_read_inner(cx, buf);
+    if let (Poll::Ready(Ok(())), Some(deflate)) = (&res, &mut this.deflate) {
+      deflate.read.advance_read(&mut buf.filled_mut()[start..]);
+    }
+    res
+  }
 }
 
 impl AsyncWrite for WebSocketStream {
@@ -64,10 +118,41 @@ impl AsyncWrite for WebSocketStream {
     cx: &mut std::task::Context<'_>,
     buf: &[u8],
   ) -> std::task::Poll<Result<usize, std::io::Error>> {
-    match &mut self.stream {
-      WsStreamKind::Network(stream) => Pin::new(stream).poll_write(cx, buf),
-      WsStreamKind::Upgraded(stream) => Pin::new(stream).poll_write(cx, buf),
+    let this = &mut *self;
+    let Some(deflate) = &mut this.deflate else {
+      return this.poll_write_inner(cx, buf);
+    };
+    // Every data frame that is written carries a compressed message, so the
+    // first byte of each gets RSV1. The bytes up to the next data frame are
+    // written as they are, a data frame header is written from a copy.
+    let res = match deflate.write.next_data_frame(buf) {
+      Some(0) => {
+        let mut scanner = deflate.write.clone();
+        scanner.advance(&buf[..1]);
+        let end = scanner
+          .next_data_frame(&buf[1..])
+          .map_or(buf.len(), |end| end + 1)
+          .min(MAX_SCRATCH_WRITE);
+        let mut scratch = std::mem::take(&mut deflate.scratch);
+        scratch.clear();
+        scratch.extend_from_slice(&buf[..end]);
+        scratch[0] |= RSV1;
+        let res = this.poll_write_inner(cx, &scratch);
+        this.deflate.as_mut().unwrap().scratch = scratch;
+        res
+      }
+      Some(end) => this.poll_write_inner(cx, &buf[..end]),
+      None => this.poll_write_inner(cx, buf),
+    };
+    if let Poll::Ready(Ok(written)) = res {
+      this
+        .deflate
+        .as_mut()
+        .unwrap()
+        .write
+        .advance(&buf[..written]);
     }
+    res
   }
 
   fn poll_flush(
@@ -91,6 +176,9 @@ impl AsyncWrite for WebSocketStream {
   }
 
   fn is_write_vectored(&self) -> bool {
+    if self.deflate.is_some() {
+      return false;
+    }
     match &self.stream {
       WsStreamKind::Network(stream) => stream.is_write_vectored(),
       WsStreamKind::Upgraded(stream) => stream.is_write_vectored(),
@@ -102,6 +190,13 @@ impl AsyncWrite for WebSocketStream {
     cx: &mut std::task::Context<'_>,
     bufs: &[std::io::IoSlice<'_>],
   ) -> std::task::Poll<Result<usize, std::io::Error>> {
+    if self.deflate.is_some() {
+      let buf = bufs
+        .iter()
+        .find(|buf| !buf.is_empty())
+        .map_or(&[][..], |buf| &**buf);
+      return self.poll_write(cx, buf);
+    }
     match &mut self.stream {
       WsStreamKind::Network(stream) => {
         Pin::new(stream).poll_write_vectored(cx, bufs)
@@ -17,7 +17,11 @@ pub use rusqlite;
 #[derive(Clone)]
 struct OriginStorageDir(PathBuf);
 
-const MAX_STORAGE_BYTES: usize = 10 * 1024 * 1024;
+#[derive(Clone, Copy)]
+struct MaxStorageBytes(usize);
+
+/// The default quota for a single origin, used when no quota is configured.
+pub const DEFAULT_MAX_STORAGE_BYTES: usize = 10 * 1024 * 1024;
 
 deno_core::extension!(deno_webstorage,
   deps = [ deno_webidl ],
@@ -32,12 +36,16 @@ deno_core::extension!(deno_webstorage,
   ],
   esm = [ "01_webstorage.js" ],
   options = {
-    origin_storage_dir: Option<PathBuf>
+    origin_storage_dir: Option<PathBuf>,
+    max_storage_bytes: Option<usize>,
   },
   state = |state, options| {
     if let Some(origin_storage_dir) = options.origin_storage_dir {
       state.put(OriginStorageDir(origin_storage_dir));
     }
+    state.put(MaxStorageBytes(
+      options.max_storage_bytes.unwrap_or(DEFAULT_MAX_STORAGE_BYTES),
+    ));
   },
 );
 
@@ -135,8 +143,8 @@ pub fn op_webstorage_key(
 }
 
 #[inline]
-fn size_check(input: usize) -> Result<(), AnyError> {
-  if input >= MAX_STORAGE_BYTES {
+fn size_check(max_storage_bytes: usize, input: usize) -> Result<(), AnyError> {
+  if input >= max_storage_bytes {
     return Err(
       deno_web::DomExceptionQuotaExceededError::new(
         "Exceeded maximum storage size",
@@ -155,15 +163,19 @@ pub fn op_webstorage_set(
   #[string] value: &str,
   persistent: bool,
 ) -> Result<(), AnyError> {
+  let max_storage_bytes = state
+    .try_borrow::<MaxStorageBytes>()
+    .map(|max| max.0)
+    .unwrap_or(DEFAULT_MAX_STORAGE_BYTES);
   let conn = get_webstorage(state, persistent)?;
 
-  size_check(key.len() + value.len())?;
+  size_check(max_storage_bytes, key.len() + value.len())?;
 
   let mut stmt = conn
     .prepare_cached("SELECT SUM(pgsize) FROM dbstat WHERE name = 'data'")?;
   let size: u32 = stmt.query_row(params![], |row| row.get(0))?;
 
-  size_check(size as usize)?;
+  size_check(max_storage_bytes, size as usize)?;
 
   let mut stmt = conn
     .prepare_cached("INSERT OR REPLACE INTO data (key, value) VALUES (?, ?)")?;
@@ -39,6 +39,14 @@ mod startup_snapshot {
     ) -> Result<(), deno_core::error::AnyError> {
       unreachable!("snapshotting!")
     }
+
+    fn check_write(
+      &mut self,
+      _p: &Path,
+      _api_name: &str,
+    ) -> Result<(), deno_core::error::AnyError> {
+      unreachable!("snapshotting!")
+    }
   }
 
   impl deno_websocket::WebSocketPermissions for Permissions {
@@ -211,8 +219,9 @@ mod startup_snapshot {
         "".to_owned(),
         None,
         None,
+        None,
       ),
-      deno_webstorage::deno_webstorage::init_ops_and_esm(None),
+      deno_webstorage::deno_webstorage::init_ops_and_esm(None, None),
       deno_crypto::deno_crypto::init_ops_and_esm(None),
       deno_broadcast_channel::deno_broadcast_channel::init_ops_and_esm(
         deno_broadcast_channel::InMemoryBroadcastChannel::default(),
@@ -2,15 +2,28 @@
 
 const core = globalThis.Deno.core;
 const ops = core.ops;
+const internals = globalThis.__bootstrap.internals;
 const primordials = globalThis.__bootstrap.primordials;
 const {
   ArrayPrototypeFilter,
+  ArrayPrototypeFind,
+  ArrayPrototypeMap,
+  ArrayPrototypePush,
+  ArrayPrototypeShift,
   Error,
+  JSONStringify,
+  NumberIsInteger,
   ObjectPrototypeIsPrototypeOf,
+  Promise,
+  PromisePrototypeThen,
+  RangeError,
+  ReflectApply,
   String,
   StringPrototypeStartsWith,
+  Symbol,
   SymbolIterator,
   SymbolToStringTag,
+  TypeError,
 } = primordials;
 import * as webidl from "ext:deno_webidl/00_webidl.js";
 import { URL } from "ext:deno_url/00_url.js";
@@ -35,6 +48,7 @@ function createWorker(
   hasSourceCode,
   sourceCode,
   permissions,
+  limits,
   name,
   workerType,
 ) {
@@ -42,6 +56,7 @@ function createWorker(
     hasSourceCode,
     name,
     permissions: serializePermissions(permissions),
+    limits,
     sourceCode,
     specifier,
     workerType,
@@ -64,6 +79,10 @@ function hostRecvMessage(id) {
   return core.opAsync("op_host_recv_message", id);
 }
 
+// Lets `WorkerPool` boot a module worker from a bootstrap script instead of
+// executing the module directly.
+const _sourceCode = Symbol("[[sourceCode]]");
+
 class Worker extends EventTarget {
   #id = 0;
   #name = "";
@@ -106,12 +125,17 @@ class Worker extends EventTarget {
       hasSourceCode = false;
       sourceCode = "";
     }
+    if (options[_sourceCode] !== undefined) {
+      hasSourceCode = true;
+      sourceCode = options[_sourceCode];
+    }
 
     const id = createWorker(
       specifier,
       hasSourceCode,
       sourceCode,
       deno?.permissions,
+      deno?.limits,
       name,
       workerType,
     );
@@ -249,9 +273,239 @@ defineEventHandler(Worker.prototype, "error");
 defineEventHandler(Worker.prototype, "message");
 defineEventHandler(Worker.prototype, "messageerror");
 
+/**
+ * Installs the task dispatcher in a worker spawned by `WorkerPool`. Tasks are
+ * `[id, fn, args]` messages, answered with `[id, ok, valueOrError]`.
+ *
+ * @param {Promise<object>} modulePromise
+ */
+function bootstrapWorkerPoolWorker(modulePromise) {
+  // A failed import is reported to every task instead of crashing the worker,
+  // which would only make the pool restart it over and over.
+  PromisePrototypeThen(modulePromise, undefined, () => {});
+  globalThis.addEventListener("message", async (event) => {
+    const { 0: id, 1: fn, 2: args } = event.data;
+    let result;
+    try {
+      const module = await modulePromise;
+      const f = module[fn];
+      if (typeof f !== "function") {
+        throw new TypeError(`Module does not export a function named "${fn}"`);
+      }
+      result = [id, true, await ReflectApply(f, undefined, args)];
+    } catch (error) {
+      result = [id, false, error];
+    }
+    try {
+      globalThis.postMessage(result);
+    } catch (error) {
+      globalThis.postMessage([id, false, error]);
+    }
+  });
+}
+
+internals.bootstrapWorkerPoolWorker = bootstrapWorkerPoolWorker;
+
+class WorkerPool {
+  #specifier;
+  #name;
+  #deno;
+  #maxQueue;
+  #slots = [];
+  #queue = [];
+  #spaceWaiters = [];
+  #nextTaskId = 0;
+  #terminated = false;
+
+  constructor(specifier, options = {}) {
+    specifier = String(specifier);
+    const baseUrl = getLocationHref();
+    if (baseUrl != null) {
+      specifier = new URL(specifier, baseUrl).href;
+    }
+    const {
+      deno,
+      maxQueue = Infinity,
+      name = "WorkerPool",
+      size = globalThis.navigator?.hardwareConcurrency ?? 1,
+    } = options;
+    if (!NumberIsInteger(size) || size < 1) {
+      throw new RangeError(
+        `WorkerPool size must be a positive integer, received ${size}`,
+      );
+    }
+    if (maxQueue !== Infinity && (!NumberIsInteger(maxQueue) || maxQueue < 1)) {
+      throw new RangeError(
+        `WorkerPool maxQueue must be a positive integer, received ${maxQueue}`,
+      );
+    }
+    this.#specifier = specifier;
+    this.#name = String(name);
+    this.#deno = deno;
+    this.#maxQueue = maxQueue;
+    for (let i = 0; i < size; ++i) {
+      const slot = {
+        id: i,
+        worker: null,
+        task: null,
+        tasksCompleted: 0,
+        tasksFailed: 0,
+        restarts: 0,
+      };
+      ArrayPrototypePush(this.#slots, slot);
+      this.#spawn(slot);
+    }
+  }
+
+  get size() {
+    return this.#slots.length;
+  }
+
+  #spawn(slot) {
+    const worker = new Worker(this.#specifier, {
+      type: "module",
+      name: `${this.#name}-${slot.id}`,
+      deno: this.#deno,
+      [_sourceCode]: `Deno[Deno.internal].bootstrapWorkerPoolWorker(import(${
+        JSONStringify(this.#specifier)
+      }));`,
+    });
+    worker.onmessage = (event) => {
+      const { 0: id, 1: ok, 2: value } = event.data;
+      const task = slot.task;
+      if (task === null || task.id !== id) {
+        return;
+      }
+      slot.task = null;
+      if (ok) {
+        slot.tasksCompleted++;
+        task.resolve(value);
+      } else {
+        slot.tasksFailed++;
+        task.reject(value);
+      }
+      this.#dispatch();
+    };
+    worker.onmessageerror = (event) => {
+      const task = slot.task;
+      if (task === null) {
+        return;
+      }
+      slot.task = null;
+      slot.tasksFailed++;
+      task.reject(event.data);
+      this.#dispatch();
+    };
+    worker.onerror = (event) => {
+      event.preventDefault();
+      this.#restart(slot, event.message);
+    };
+    slot.worker = worker;
+  }
+
+  #restart(slot, message) {
+    slot.worker.terminate();
+    const task = slot.task;
+    slot.task = null;
+    if (task !== null) {
+      slot.tasksFailed++;
+      task.reject(new Error(`Worker ${slot.id} crashed: ${message}`));
+    }
+    if (this.#terminated) {
+      return;
+    }
+    slot.restarts++;
+    this.#spawn(slot);
+    this.#dispatch();
+  }
+
+  #dispatch() {
+    while (this.#queue.length > 0) {
+      const slot = ArrayPrototypeFind(this.#slots, (slot) => slot.task === null);
+      if (slot === undefined) {
+        return;
+      }
+      const task = ArrayPrototypeShift(this.#queue);
+      slot.task = task;
+      try {
+        slot.worker.postMessage([task.id, task.fn, task.args], task.transfer);
+      } catch (error) {
+        slot.task = null;
+        slot.tasksFailed++;
+        task.reject(error);
+      }
+      const waiter = ArrayPrototypeShift(this.#spaceWaiters);
+      if (waiter !== undefined) {
+        waiter();
+      }
+    }
+  }
+
+  async run(fn, args = [], options = {}) {
+    fn = String(fn);
+    const { transfer = [] } = options;
+    while (!this.#terminated && this.#queue.length >= this.#maxQueue) {
+      await new Promise((resolve) =>
+        ArrayPrototypePush(this.#spaceWaiters, resolve)
+      );
+    }
+    if (this.#terminated) {
+      throw new Error("WorkerPool has been terminated");
+    }
+    return new Promise((resolve, reject) => {
+      ArrayPrototypePush(this.#queue, {
+        id: this.#nextTaskId++,
+        fn,
+        args,
+        transfer,
+        resolve,
+        reject,
+      });
+      this.#dispatch();
+    });
+  }
+
+  stats() {
+    return {
+      queued: this.#queue.length,
+      workers: ArrayPrototypeMap(this.#slots, (slot) => ({
+        id: slot.id,
+        busy: slot.task !== null,
+        tasksCompleted: slot.tasksCompleted,
+        tasksFailed: slot.tasksFailed,
+        restarts: slot.restarts,
+      })),
+    };
+  }
+
+  terminate() {
+    if (this.#terminated) {
+      return;
+    }
+    this.#terminated = true;
+    const error = new Error("WorkerPool has been terminated");
+    for (let i = 0; i < this.#slots.length; ++i) {
+      const slot = this.#slots[i];
+      slot.worker.terminate();
+      if (slot.task !== null) {
+        slot.task.reject(error);
+        slot.task = null;
+      }
+    }
+    while (this.#queue.length > 0) {
+      ArrayPrototypeShift(this.#queue).reject(error);
+    }
+    while (this.#spaceWaiters.length > 0) {
+      ArrayPrototypeShift(this.#spaceWaiters)();
+    }
+  }
+
+  [SymbolToStringTag] = "WorkerPool";
+}
+
 webidl.converters["WorkerType"] = webidl.createEnumConverter("WorkerType", [
   "classic",
   "module",
 ]);
 
-export { Worker };
+export { Worker, WorkerPool };
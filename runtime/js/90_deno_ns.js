@@ -24,6 +24,7 @@ import * as tty from "ext:runtime/40_tty.js";
 // TODO(bartlomieju): this is funky we have two `http` imports
 import * as httpRuntime from "ext:runtime/40_http.js";
 import * as kv from "ext:deno_kv/01_db.ts";
+import * as worker from "ext:runtime/11_workers.js";
 
 const denoNs = {
   metrics: core.metrics,
@@ -160,6 +161,7 @@ const denoNsUnstable = {
     ops.op_net_listen_unixpacket,
   ),
   umask: fs.umask,
+  DnsResolver: net.DnsResolver,
   HttpClient: httpClient.HttpClient,
   createHttpClient: httpClient.createHttpClient,
   // TODO(bartlomieju): why is it needed?
@@ -179,6 +181,7 @@ const denoNsUnstable = {
   Kv: kv.Kv,
   KvU64: kv.KvU64,
   KvListIterator: kv.KvListIterator,
+  WorkerPool: worker.WorkerPool,
 };
 
 export { denoNs, denoNsUnstable };
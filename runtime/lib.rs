@@ -14,6 +14,7 @@ pub use deno_kv;
 pub use deno_napi;
 pub use deno_net;
 pub use deno_node;
+pub use deno_telemetry;
 pub use deno_tls;
 pub use deno_url;
 pub use deno_web;
@@ -156,6 +156,7 @@ fn op_set_exit_code(state: &mut OpState, #[smi] code: i32) {
 #[op2(fast)]
 fn op_exit(state: &mut OpState) {
   let code = state.borrow::<ExitCode>().get();
+  deno_telemetry::flush();
   std::process::exit(code)
 }
 
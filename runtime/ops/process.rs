@@ -18,6 +18,8 @@ use deno_io::fs::FileResource;
 use deno_io::ChildStderrResource;
 use deno_io::ChildStdinResource;
 use deno_io::ChildStdoutResource;
+use deno_telemetry::Span;
+use deno_telemetry::SpanKind;
 use serde::Deserialize;
 use serde::Serialize;
 use std::borrow::Cow;
@@ -117,7 +119,13 @@ deno_core::extension!(
 
 /// Second member stores the pid separately from the RefCell. It's needed for
 /// `op_spawn_kill`, where the RefCell is borrowed mutably by `op_spawn_wait`.
-struct ChildResource(RefCell<tokio::process::Child>, u32);
+/// Third member holds the tracing span of the process, ended by
+/// `op_spawn_wait`.
+struct ChildResource(
+  RefCell<tokio::process::Child>,
+  u32,
+  RefCell<Option<Span>>,
+);
 
 impl Resource for ChildResource {
   fn name(&self) -> Cow<str> {
@@ -276,10 +284,31 @@ struct Child {
   stderr_rid: Option<ResourceId>,
 }
 
+/// Starts a span covering the lifetime of a subprocess, if tracing is enabled.
+/// The trace context is passed on to the subprocess in `TRACEPARENT`.
+fn process_span(command: &mut std::process::Command) -> Option<Span> {
+  let name = command.get_program().to_string_lossy().into_owned();
+  let mut span =
+    Span::start(format!("spawn {name}"), SpanKind::Internal, None)?;
+  span.set_attribute("process.executable.name", name);
+  command.env("TRACEPARENT", span.context().to_traceparent());
+  Some(span)
+}
+
+fn record_exit_status(span: &mut Span, status: ExitStatus) {
+  if let Some(code) = status.code() {
+    span.set_attribute("process.exit.code", code as i64);
+  }
+  if !status.success() {
+    span.set_error(format!("Process exited with {status}"));
+  }
+}
+
 fn spawn_child(
   state: &mut OpState,
-  command: std::process::Command,
+  mut command: std::process::Command,
 ) -> Result<Child, AnyError> {
+  let mut span = process_span(&mut command);
   let mut command = tokio::process::Command::from(command);
   // TODO(@crowlkats): allow detaching processes.
   //  currently deno will orphan a process when exiting with an error or Deno.exit()
@@ -289,6 +318,9 @@ fn spawn_child(
   let mut child = match command.spawn() {
     Ok(child) => child,
     Err(err) => {
+      if let Some(span) = &mut span {
+        span.set_error(err.to_string());
+      }
       let command = command.as_std();
       let command_name = command.get_program().to_string_lossy();
 
@@ -334,6 +366,9 @@ fn spawn_child(
   };
 
   let pid = child.id().expect("Process ID should be set.");
+  if let Some(span) = &mut span {
+    span.set_attribute("process.pid", pid as i64);
+  }
 
   let stdin_rid = child
     .stdin
@@ -350,9 +385,11 @@ fn spawn_child(
     .take()
     .map(|stderr| state.resource_table.add(ChildStderrResource::from(stderr)));
 
-  let child_rid = state
-    .resource_table
-    .add(ChildResource(RefCell::new(child), pid));
+  let child_rid = state.resource_table.add(ChildResource(
+    RefCell::new(child),
+    pid,
+    RefCell::new(span),
+  ));
 
   Ok(Child {
     rid: child_rid,
@@ -385,7 +422,11 @@ async fn op_spawn_wait(
     .borrow_mut()
     .resource_table
     .get::<ChildResource>(rid)?;
-  let result = resource.0.try_borrow_mut()?.wait().await?.try_into();
+  let status = resource.0.try_borrow_mut()?.wait().await?;
+  if let Some(mut span) = resource.2.borrow_mut().take() {
+    record_exit_status(&mut span, status);
+  }
+  let result = status.try_into();
   if let Ok(resource) = state.borrow_mut().resource_table.take_any(rid) {
     resource.close();
   }
@@ -401,7 +442,15 @@ fn op_spawn_sync(
   let stdout = matches!(args.stdio.stdout, Stdio::Piped);
   let stderr = matches!(args.stdio.stderr, Stdio::Piped);
   let mut command = create_command(state, args, "Deno.Command().outputSync()")?;
-  let output = command.output().with_context(|| {
+  let mut span = process_span(&mut command);
+  let output = command.output();
+  if let Some(span) = &mut span {
+    match &output {
+      Ok(output) => record_exit_status(span, output.status),
+      Err(err) => span.set_error(err.to_string()),
+    }
+  }
+  let output = output.with_context(|| {
     format!(
       "Failed to spawn '{}'",
       command.get_program().to_string_lossy()
@@ -1426,6 +1426,15 @@ impl deno_fetch::FetchPermissions for PermissionsContainer {
   ) -> Result<(), AnyError> {
     self.0.lock().read.check(path, Some(api_name))
   }
+
+  #[inline(always)]
+  fn check_write(
+    &mut self,
+    path: &Path,
+    api_name: &str,
+  ) -> Result<(), AnyError> {
+    self.0.lock().write.check(path, Some(api_name))
+  }
 }
 
 impl deno_web::TimersPermission for PermissionsContainer {
@@ -11,6 +11,7 @@ use deno_broadcast_channel::InMemoryBroadcastChannel;
 use deno_cache::CreateCache;
 use deno_cache::SqliteBackedCache;
 use deno_core::ascii_str;
+use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::error::JsError;
 use deno_core::futures::channel::mpsc;
@@ -18,6 +19,8 @@ use deno_core::futures::future::poll_fn;
 use deno_core::futures::stream::StreamExt;
 use deno_core::futures::task::AtomicWaker;
 use deno_core::located_script_name;
+use deno_core::parking_lot::Condvar;
+use deno_core::parking_lot::Mutex;
 use deno_core::serde::Deserialize;
 use deno_core::serde::Serialize;
 use deno_core::serde_json::json;
@@ -31,6 +34,7 @@ use deno_core::ModuleCode;
 use deno_core::ModuleId;
 use deno_core::ModuleLoader;
 use deno_core::ModuleSpecifier;
+use deno_core::OpState;
 use deno_core::RuntimeOptions;
 use deno_core::SharedArrayBufferStore;
 use deno_core::Snapshot;
@@ -40,6 +44,7 @@ use deno_http::DefaultHttpPropertyExtractor;
 use deno_io::Stdio;
 use deno_kv::dynamic::MultiBackendDbHandler;
 use deno_node::SUPPORTED_BUILTIN_NODE_MODULES_WITH_PREFIX;
+use deno_tls::Proxy;
 use deno_tls::RootCertStoreProvider;
 use deno_web::create_entangled_message_port;
 use deno_web::BlobStore;
@@ -53,6 +58,8 @@ use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::task::Context;
 use std::task::Poll;
+use std::time::Duration;
+use std::time::Instant;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -61,6 +68,21 @@ pub enum WebWorkerType {
   Module,
 }
 
+/// Resource limits applied to a single web worker. A worker that exceeds any
+/// of them is terminated and its host receives a terminal error event.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkerLimits {
+  /// Maximum size of the worker's V8 heap, in megabytes.
+  #[serde(rename = "maxHeapMB")]
+  pub max_heap_mb: Option<u64>,
+  /// Maximum CPU time the worker thread may consume, in milliseconds. Time
+  /// spent waiting on I/O or timers doesn't count towards it.
+  pub max_cpu_ms: Option<u64>,
+  /// Maximum number of async ops that may be pending at the same time.
+  pub max_pending_ops: Option<u64>,
+}
+
 #[derive(
   Debug, Default, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize,
 )]
@@ -192,6 +214,197 @@ impl WebWorkerInternalHandle {
     // Wake parent by closing the channel
     self.sender.close_channel();
   }
+
+  fn limits_enforcer(&self) -> WorkerLimitsEnforcer {
+    WorkerLimitsEnforcer {
+      sender: self.sender.clone(),
+      has_terminated: self.has_terminated.clone(),
+      terminate_waker: self.terminate_waker.clone(),
+      isolate_handle: self.isolate_handle.clone(),
+    }
+  }
+}
+
+/// A thread safe handle that terminates a worker which exceeded one of its
+/// `WorkerLimits`. Unlike `WebWorkerInternalHandle` it can be moved to a
+/// watchdog thread or into V8 callbacks.
+#[derive(Clone)]
+struct WorkerLimitsEnforcer {
+  sender: mpsc::Sender<WorkerControlEvent>,
+  has_terminated: Arc<AtomicBool>,
+  terminate_waker: Arc<AtomicWaker>,
+  isolate_handle: v8::IsolateHandle,
+}
+
+impl WorkerLimitsEnforcer {
+  fn is_terminated(&self) -> bool {
+    self.has_terminated.load(Ordering::SeqCst)
+  }
+
+  /// Reports `message` to the host as a terminal error and stops the worker.
+  fn terminate(&self, message: String) {
+    if self.has_terminated.swap(true, Ordering::SeqCst) {
+      return;
+    }
+    debug!("{}", message);
+    let mut sender = self.sender.clone();
+    let _ = sender
+      .try_send(WorkerControlEvent::TerminalError(generic_error(message)));
+    self.isolate_handle.terminate_execution();
+    sender.close_channel();
+    self.terminate_waker.wake();
+  }
+}
+
+/// The CPU time consumed by the calling thread.
+fn thread_cpu_time() -> Duration {
+  #[cfg(unix)]
+  {
+    let mut time = libc::timespec {
+      tv_sec: 0,
+      tv_nsec: 0,
+    };
+    // SAFETY: `time` is a valid pointer to a `timespec`.
+    let result =
+      unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut time) };
+    assert_eq!(result, 0, "reading the thread CPU time");
+    Duration::new(time.tv_sec as u64, time.tv_nsec as u32)
+  }
+  #[cfg(windows)]
+  {
+    use winapi::shared::minwindef::FILETIME;
+    use winapi::um::processthreadsapi::GetCurrentThread;
+    use winapi::um::processthreadsapi::GetThreadTimes;
+
+    // SAFETY: `FILETIME` is a plain struct of integers.
+    let mut times: [FILETIME; 4] = unsafe { std::mem::zeroed() };
+    let [creation, exit, kernel, user] = &mut times;
+    // SAFETY: all pointers are valid and `GetCurrentThread` returns a pseudo
+    // handle that doesn't need to be closed.
+    let ok = unsafe {
+      GetThreadTimes(GetCurrentThread(), creation, exit, kernel, user)
+    };
+    assert_ne!(ok, 0, "reading the thread CPU time");
+    // `FILETIME`s count 100 ns intervals
+    let ticks = |time: &FILETIME| {
+      ((time.dwHighDateTime as u64) << 32) | time.dwLowDateTime as u64
+    };
+    Duration::from_nanos((ticks(kernel) + ticks(user)) * 100)
+  }
+}
+
+/// The CPU time a worker thread may still consume before it is terminated.
+struct CpuBudget {
+  /// The CPU time of the worker thread when the budget was created.
+  start: Duration,
+  limit: Duration,
+  enforcer: WorkerLimitsEnforcer,
+}
+
+impl CpuBudget {
+  /// Checks the budget against the CPU time of the calling thread, which must
+  /// be the worker thread, and returns how much of it is left.
+  fn remaining(&self) -> Option<Duration> {
+    let used = thread_cpu_time().saturating_sub(self.start);
+    self.limit.checked_sub(used).filter(|left| !left.is_zero())
+  }
+
+  fn check(self: Arc<Self>) {
+    if self.enforcer.is_terminated() {
+      return;
+    }
+    match self.remaining() {
+      Some(remaining) => CPU_WATCHDOG.schedule(self, remaining),
+      None => self.enforcer.terminate(format!(
+        "Worker exceeded its CPU time limit of {} ms",
+        self.limit.as_millis()
+      )),
+    }
+  }
+}
+
+/// Runs on the worker thread, in between JavaScript execution.
+extern "C" fn check_cpu_budget_interrupt(
+  _isolate: &mut v8::Isolate,
+  data: *mut std::ffi::c_void,
+) {
+  // SAFETY: `data` was created by `Arc::into_raw` in `CpuWatchdog::run`.
+  let budget = unsafe { Arc::from_raw(data as *const CpuBudget) };
+  budget.check();
+}
+
+static CPU_WATCHDOG: once_cell::sync::Lazy<CpuWatchdog> =
+  once_cell::sync::Lazy::new(|| {
+    std::thread::Builder::new()
+      .name("worker-cpu-watchdog".to_string())
+      .spawn(|| CPU_WATCHDOG.run())
+      .unwrap();
+    CpuWatchdog {
+      deadlines: Mutex::new(vec![]),
+      condvar: Condvar::new(),
+    }
+  });
+
+/// A single thread shared by all workers with a CPU time limit.
+///
+/// Another thread can't read the CPU time of a worker thread portably, but a
+/// thread never consumes more CPU time than wall time passes. So the watchdog
+/// waits until a worker could at the earliest have used up its budget, then
+/// interrupts its isolate to compare the budget with the actual CPU time of
+/// the worker thread. A worker that is waiting on its event loop handles the
+/// interrupt once it runs JavaScript again.
+struct CpuWatchdog {
+  deadlines: Mutex<Vec<(Instant, Arc<CpuBudget>)>>,
+  condvar: Condvar,
+}
+
+impl CpuWatchdog {
+  fn schedule(&self, budget: Arc<CpuBudget>, remaining: Duration) {
+    self
+      .deadlines
+      .lock()
+      .push((Instant::now() + remaining, budget));
+    self.condvar.notify_one();
+  }
+
+  fn run(&self) {
+    let mut deadlines = self.deadlines.lock();
+    loop {
+      deadlines.retain(|(_, budget)| !budget.enforcer.is_terminated());
+      let now = Instant::now();
+      let (due, pending) = deadlines
+        .drain(..)
+        .partition::<Vec<_>, _>(|(deadline, _)| *deadline <= now);
+      *deadlines = pending;
+      for (_, budget) in due {
+        let handle = budget.enforcer.isolate_handle.clone();
+        let data = Arc::into_raw(budget) as *mut std::ffi::c_void;
+        if !handle.request_interrupt(check_cpu_budget_interrupt, data) {
+          // the isolate is gone, so the interrupt will never run
+          // SAFETY: `data` was created by `Arc::into_raw` above.
+          drop(unsafe { Arc::from_raw(data as *const CpuBudget) });
+        }
+      }
+      match deadlines.iter().map(|(deadline, _)| *deadline).min() {
+        Some(next) => {
+          self.condvar.wait_until(&mut deadlines, next);
+        }
+        None => self.condvar.wait(&mut deadlines),
+      }
+    }
+  }
+}
+
+fn pending_async_ops(state: &OpState) -> u64 {
+  state
+    .tracker
+    .per_op()
+    .iter()
+    .map(|m| {
+      (m.ops_dispatched_async + m.ops_dispatched_async_unref)
+        .saturating_sub(m.ops_completed_async + m.ops_completed_async_unref)
+    })
+    .sum()
 }
 
 pub struct SendableWebWorkerHandle {
@@ -325,6 +538,7 @@ pub struct WebWorker {
   pub main_module: ModuleSpecifier,
   poll_for_messages_fn: Option<v8::Global<v8::Value>>,
   bootstrap_fn_global: Option<v8::Global<v8::Function>>,
+  limits: WorkerLimits,
 }
 
 pub struct WebWorkerOptions {
@@ -332,9 +546,14 @@ pub struct WebWorkerOptions {
   pub extensions: Vec<Extension>,
   pub startup_snapshot: Option<Snapshot>,
   pub unsafely_ignore_certificate_errors: Option<Vec<String>>,
+  /// The proxy `fetch` and WebSocket connections go through.
+  pub proxy: Option<Proxy>,
   pub root_cert_store_provider: Option<Arc<dyn RootCertStoreProvider>>,
   pub seed: Option<u64>,
   pub fs: Arc<dyn FileSystem>,
+  /// The file system `deno_node` resolves and reads CommonJS modules from.
+  /// Defaults to `fs`.
+  pub node_fs: Option<Arc<dyn FileSystem>>,
   pub module_loader: Rc<dyn ModuleLoader>,
   pub npm_resolver: Option<Arc<dyn deno_node::NpmResolver>>,
   pub create_web_worker_cb: Arc<ops::worker_host::CreateWebWorkerCb>,
@@ -349,6 +568,7 @@ pub struct WebWorkerOptions {
   pub compiled_wasm_module_store: Option<CompiledWasmModuleStore>,
   pub cache_storage_dir: Option<std::path::PathBuf>,
   pub stdio: Stdio,
+  pub limits: WorkerLimits,
 }
 
 impl WebWorker {
@@ -411,6 +631,7 @@ impl WebWorker {
           unsafely_ignore_certificate_errors: options
             .unsafely_ignore_certificate_errors
             .clone(),
+          proxy: options.proxy.clone(),
           file_fetch_handler: Rc::new(deno_fetch::FsFetchHandler),
           ..Default::default()
         },
@@ -422,8 +643,9 @@ impl WebWorker {
         options.bootstrap.user_agent.clone(),
         options.root_cert_store_provider.clone(),
         options.unsafely_ignore_certificate_errors.clone(),
+        options.proxy.clone(),
       ),
-      deno_webstorage::deno_webstorage::init_ops_and_esm(None).disable(),
+      deno_webstorage::deno_webstorage::init_ops_and_esm(None, None).disable(),
       deno_crypto::deno_crypto::init_ops_and_esm(options.seed),
       deno_broadcast_channel::deno_broadcast_channel::init_ops_and_esm(
         options.broadcast_channel.clone(),
@@ -445,7 +667,7 @@ impl WebWorker {
       ),
       deno_node::deno_node::init_ops_and_esm::<PermissionsContainer>(
         options.npm_resolver,
-        options.fs,
+        options.node_fs.unwrap_or(options.fs),
       ),
       // Runtime ops that are always initialized for WebWorkers
       ops::web_worker::deno_web_worker::init_ops_and_esm(),
@@ -497,8 +719,14 @@ impl WebWorker {
     let preserve_snapshotted_modules =
       Some(SUPPORTED_BUILTIN_NODE_MODULES_WITH_PREFIX);
 
+    let create_params = options.limits.max_heap_mb.map(|max_heap_mb| {
+      let max_heap_bytes = (max_heap_mb as usize) * 1024 * 1024;
+      v8::CreateParams::default().heap_limits(0, max_heap_bytes)
+    });
+
     let mut js_runtime = JsRuntime::new(RuntimeOptions {
       module_loader: Some(options.module_loader.clone()),
+      create_params,
       startup_snapshot: options
         .startup_snapshot
         .or_else(crate::js::deno_isolate_init),
@@ -544,6 +772,18 @@ impl WebWorker {
       (internal_handle, external_handle)
     };
 
+    if let Some(max_heap_mb) = options.limits.max_heap_mb {
+      let enforcer = internal_handle.limits_enforcer();
+      js_runtime.add_near_heap_limit_callback(move |current_limit, _| {
+        enforcer.terminate(format!(
+          "Worker exceeded its heap limit of {max_heap_mb} MB"
+        ));
+        // Give V8 enough room to unwind after the termination request,
+        // instead of crashing the whole process with an OOM error.
+        current_limit * 2
+      });
+    }
+
     let bootstrap_fn_global = {
       let context = js_runtime.main_context();
       let scope = &mut js_runtime.handle_scope();
@@ -576,6 +816,7 @@ impl WebWorker {
         main_module,
         poll_for_messages_fn: None,
         bootstrap_fn_global: Some(bootstrap_fn_global),
+        limits: options.limits,
       },
       external_handle,
     )
@@ -717,7 +958,8 @@ impl WebWorker {
 
     self.internal_handle.terminate_waker.register(cx.waker());
 
-    match self.js_runtime.poll_event_loop(cx, wait_for_inspector) {
+    let poll_result = self.js_runtime.poll_event_loop(cx, wait_for_inspector);
+    match poll_result {
       Poll::Ready(r) => {
         // If js ended because we are terminating, just return Ok
         if self.internal_handle.terminate_if_needed() {
@@ -732,7 +974,34 @@ impl WebWorker {
           "coding error: either js is polling or the worker is terminated"
         );
       }
-      Poll::Pending => Poll::Pending,
+      Poll::Pending => {
+        if let Some(max_pending_ops) = self.limits.max_pending_ops {
+          let op_state = self.js_runtime.op_state();
+          let pending_ops = pending_async_ops(&op_state.borrow());
+          if pending_ops > max_pending_ops {
+            self.internal_handle.limits_enforcer().terminate(format!(
+              "Worker exceeded its limit of {max_pending_ops} pending ops"
+            ));
+            return Poll::Ready(Ok(()));
+          }
+        }
+        Poll::Pending
+      }
+    }
+  }
+
+  /// Starts enforcing the CPU time limit of the worker, if it has one. This
+  /// must be called on the worker thread. CPU time spent before this call
+  /// (such as bootstrapping) is not accounted for.
+  fn start_cpu_watchdog(&self) {
+    if let Some(max_cpu_ms) = self.limits.max_cpu_ms {
+      let limit = Duration::from_millis(max_cpu_ms);
+      let budget = CpuBudget {
+        start: thread_cpu_time(),
+        limit,
+        enforcer: self.internal_handle.limits_enforcer(),
+      };
+      CPU_WATCHDOG.schedule(Arc::new(budget), limit);
     }
   }
 
@@ -791,6 +1060,7 @@ pub fn run_web_worker(
 
   let fut = async move {
     let internal_handle = worker.internal_handle.clone();
+    worker.start_cpu_watchdog();
 
     // Execute provided source code immediately
     let result = if let Some(source_code) = maybe_source_code.take() {
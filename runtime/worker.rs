@@ -35,6 +35,7 @@ use deno_http::DefaultHttpPropertyExtractor;
 use deno_io::Stdio;
 use deno_kv::dynamic::MultiBackendDbHandler;
 use deno_node::SUPPORTED_BUILTIN_NODE_MODULES_WITH_PREFIX;
+use deno_tls::Proxy;
 use deno_tls::RootCertStoreProvider;
 use deno_web::BlobStore;
 use log::debug;
@@ -92,10 +93,15 @@ pub struct WorkerOptions {
   pub create_params: Option<v8::CreateParams>,
 
   pub unsafely_ignore_certificate_errors: Option<Vec<String>>,
+  /// The proxy `fetch` and WebSocket connections go through.
+  pub proxy: Option<Proxy>,
   pub root_cert_store_provider: Option<Arc<dyn RootCertStoreProvider>>,
   pub seed: Option<u64>,
 
   pub fs: Arc<dyn FileSystem>,
+  /// The file system `deno_node` resolves and reads CommonJS modules from.
+  /// Defaults to `fs`.
+  pub node_fs: Option<Arc<dyn FileSystem>>,
   /// Implementation of `ModuleLoader` which will be
   /// called when V8 requests to load ES modules.
   ///
@@ -123,6 +129,12 @@ pub struct WorkerOptions {
   pub get_error_class_fn: Option<GetErrorClassFn>,
   pub cache_storage_dir: Option<std::path::PathBuf>,
   pub origin_storage_dir: Option<std::path::PathBuf>,
+  /// Overrides `origin_storage_dir` for `localStorage` only. Other origin
+  /// scoped storage, like the default Deno KV database, is unaffected.
+  pub local_storage_dir: Option<std::path::PathBuf>,
+  /// The maximum number of bytes `localStorage` and `sessionStorage` may
+  /// hold. Defaults to 10 MiB.
+  pub local_storage_quota: Option<usize>,
   pub blob_store: Arc<BlobStore>,
   pub broadcast_channel: InMemoryBroadcastChannel,
 
@@ -150,9 +162,11 @@ impl Default for WorkerOptions {
         unimplemented!("web workers are not supported")
       }),
       fs: Arc::new(deno_fs::RealFs),
+      node_fs: None,
       module_loader: Rc::new(FsModuleLoader),
       seed: None,
       unsafely_ignore_certificate_errors: Default::default(),
+      proxy: Default::default(),
       should_break_on_first_statement: Default::default(),
       should_wait_for_inspector_session: Default::default(),
       compiled_wasm_module_store: Default::default(),
@@ -161,6 +175,8 @@ impl Default for WorkerOptions {
       format_js_error_fn: Default::default(),
       get_error_class_fn: Default::default(),
       origin_storage_dir: Default::default(),
+      local_storage_dir: Default::default(),
+      local_storage_quota: Default::default(),
       cache_storage_dir: Default::default(),
       broadcast_channel: Default::default(),
       source_map_getter: Default::default(),
@@ -231,6 +247,7 @@ impl MainWorker {
           unsafely_ignore_certificate_errors: options
             .unsafely_ignore_certificate_errors
             .clone(),
+          proxy: options.proxy.clone(),
           file_fetch_handler: Rc::new(deno_fetch::FsFetchHandler),
           ..Default::default()
         },
@@ -242,9 +259,14 @@ impl MainWorker {
         options.bootstrap.user_agent.clone(),
         options.root_cert_store_provider.clone(),
         options.unsafely_ignore_certificate_errors.clone(),
+        options.proxy.clone(),
       ),
       deno_webstorage::deno_webstorage::init_ops_and_esm(
-        options.origin_storage_dir.clone(),
+        options
+          .local_storage_dir
+          .clone()
+          .or_else(|| options.origin_storage_dir.clone()),
+        options.local_storage_quota,
       ),
       deno_crypto::deno_crypto::init_ops_and_esm(options.seed),
       deno_broadcast_channel::deno_broadcast_channel::init_ops_and_esm(
@@ -269,7 +291,7 @@ impl MainWorker {
       ),
       deno_node::deno_node::init_ops_and_esm::<PermissionsContainer>(
         options.npm_resolver,
-        options.fs,
+        options.node_fs.unwrap_or(options.fs),
       ),
       // Ops from this crate
       ops::runtime::deno_runtime::init_ops_and_esm(main_module.clone()),
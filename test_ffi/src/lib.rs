@@ -505,6 +505,18 @@ pub extern "C" fn print_rect(rect: Rect) {
   println!("{rect:?}");
 }
 
+#[no_mangle]
+pub extern "C" fn call_fn_ptr_struct(
+  func: Option<extern "C" fn(Rect) -> Rect>,
+  rect: Rect,
+) {
+  if func.is_none() {
+    return;
+  }
+  let func = func.unwrap();
+  println!("{:?}", func(rect));
+}
+
 #[derive(Debug)]
 #[repr(C)]
 pub struct Mixed {
@@ -0,0 +1,62 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+// A subset of the test_ffi library for testing `deno ffi bindgen`.
+
+#ifndef TEST_FFI_H
+#define TEST_FFI_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#define TEST_FFI_VERSION "0.1.0"
+#define BUFFER_LENGTH 8
+#define MASK (1ULL << 63)
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef enum {
+  SHAPE_POINT,
+  SHAPE_RECT = 4,
+} Shape;
+
+typedef struct Rect {
+  double x;
+  double y;
+  double w;
+  double h;
+} Rect;
+
+typedef struct {
+  uint8_t u8;
+  float f32;
+  Rect rect;
+  size_t usize;
+  uint32_t array[2];
+} Mixed;
+
+typedef void (*callback_t)(void);
+
+void print_something(void);
+uint32_t add_u32(uint32_t a, uint32_t b);
+int64_t add_i64(int64_t a, int64_t b);
+double add_f64(double a, double b);
+bool is_null_ptr(const uint8_t *ptr);
+void fill_buffer(uint8_t value, uint8_t *buf, size_t len);
+void call_fn_ptr(callback_t func);
+const char *ffi_string(void);
+Rect make_rect(double x, double y, double w, double h);
+void print_rect(Rect rect);
+Mixed create_mixed(uint8_t u8, float f32, Rect rect, size_t usize,
+                   const uint32_t (*array)[2]);
+void print_mixed(Mixed mixed);
+
+extern const uint32_t static_u32;
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,63 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+// Run using cargo test, with the URL of the module generated from `bindgen.h`
+// as the first argument.
+
+import { assertEquals } from "../../test_util/std/testing/asserts.ts";
+
+const targetDir = Deno.execPath().replace(/[^\/\\]+$/, "");
+const [libPrefix, libSuffix] = {
+  darwin: ["lib", "dylib"],
+  linux: ["lib", "so"],
+  windows: ["", "dll"],
+}[Deno.build.os];
+const libPath = `${targetDir}/${libPrefix}test_ffi.${libSuffix}`;
+
+const bindings = await import(Deno.args[0]);
+
+assertEquals(bindings.TEST_FFI_VERSION, "0.1.0");
+assertEquals(bindings.BUFFER_LENGTH, 8);
+assertEquals(bindings.MASK, 1n << 63n);
+assertEquals(bindings.Shape, { SHAPE_POINT: 0, SHAPE_RECT: 4 });
+
+const lib = bindings.load(libPath);
+const { symbols } = lib;
+
+symbols.print_something();
+console.log(symbols.add_u32(123, 456));
+console.log(symbols.add_i64(-123n, 456n));
+console.log(symbols.add_f64(123.5, 456.25));
+console.log(symbols.is_null_ptr(null));
+
+const buffer = new Uint8Array(bindings.BUFFER_LENGTH);
+symbols.fill_buffer(3, buffer, buffer.length);
+console.log(buffer.join(", "));
+console.log(bindings.readCString(symbols.ffi_string()));
+
+const callback = new Deno.UnsafeCallback(
+  { parameters: [], result: "void" },
+  () => console.log("callback"),
+);
+symbols.call_fn_ptr(callback.pointer);
+callback.close();
+
+const rect = symbols.make_rect(10, 20, 100, 200);
+console.log(bindings.decodeRect(rect));
+symbols.print_rect(bindings.encodeRect({ x: 1, y: 2, w: 3, h: 4 }));
+
+const array = new Uint32Array([7, 8]);
+const mixed = bindings.decodeMixed(
+  symbols.create_mixed(
+    1,
+    1.5,
+    rect,
+    12,
+    Deno.UnsafePointer.of(array),
+  ),
+);
+console.log(mixed.u8, mixed.f32, mixed.rect.w, mixed.usize, mixed.array);
+symbols.print_mixed(bindings.encodeMixed(mixed));
+
+console.log(symbols.static_u32);
+
+lib.close();
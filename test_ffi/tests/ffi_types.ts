@@ -51,6 +51,11 @@ const remote = Deno.dlopen(
       result: "void",
       optional: true,
     },
+    method26: {
+      parameters: ["buffer"],
+      variadic: ["i32", "f64"],
+      result: "i32",
+    },
     static1: { type: "usize" },
     static2: { type: "pointer" },
     static3: { type: "usize" },
@@ -286,6 +291,12 @@ let r24_0: true = remote.symbols.method24(true);
 let r42_1: number = remote.symbols.method24(true);
 <boolean> remote.symbols.method24(Math.random() > 0.5);
 
+// @ts-expect-error: Variadic arguments are required.
+remote.symbols.method26(null);
+// @ts-expect-error: Variadic arguments are typed.
+remote.symbols.method26(null, 1, "2");
+remote.symbols.method26(null, 1, 2);
+
 // @ts-expect-error: Optional symbol; can be null.
 remote.symbols.method25();
 
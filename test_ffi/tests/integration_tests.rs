@@ -3,6 +3,7 @@
 use pretty_assertions::assert_eq;
 use std::process::Command;
 use test_util::deno_cmd;
+use test_util::TempDir;
 
 #[cfg(debug_assertions)]
 const BUILD_VARIANT: &str = "debug";
@@ -122,6 +123,9 @@ fn basic() {
     Rect { x: 10.0, y: 20.0, w: 100.0, h: 200.0 }\n\
     Rect { x: 20.0, y: 20.0, w: 100.0, h: 200.0 }\n\
     Mixed { u8: 3, f32: 12.515, rect: Rect { x: 10.0, y: 20.0, w: 100.0, h: 200.0 }, usize: 12456789, array: [8, 32] }\n\
+    Rect { x: 20.0, y: 30.0, w: 110.0, h: 210.0 }\n\
+    Rect { x: 10.0, y: 20.0, w: 0.0, h: 0.0 }\n\
+    Rect { x: 0.0, y: 0.0, w: 0.0, h: 0.0 }\n\
     2264956937\n\
     2264956937\n\
     Correct number of resources\n";
@@ -237,3 +241,74 @@ fn event_loop_integration() {
   assert_eq!(stdout, expected);
   assert_eq!(stderr, "");
 }
+
+#[test]
+fn bindgen() {
+  build();
+
+  let temp_dir = TempDir::new();
+  let bindings = temp_dir.path().join("bindgen.ts");
+  let output = deno_cmd()
+    .arg("ffi")
+    .arg("bindgen")
+    .arg("tests/bindgen.h")
+    .arg("--output")
+    .arg(&bindings)
+    .env("NO_COLOR", "1")
+    .output()
+    .unwrap();
+  let stderr = std::str::from_utf8(&output.stderr).unwrap();
+  println!("stderr {stderr}");
+  assert!(output.status.success());
+  assert!(!stderr.contains("Warning"));
+
+  let output = deno_cmd()
+    .arg("check")
+    .arg("--unstable")
+    .arg("--quiet")
+    .arg(&bindings)
+    .env("NO_COLOR", "1")
+    .output()
+    .unwrap();
+  let stderr = std::str::from_utf8(&output.stderr).unwrap();
+  if !output.status.success() {
+    println!("stderr {stderr}");
+  }
+  assert!(output.status.success());
+
+  let output = deno_cmd()
+    .arg("run")
+    .arg("--allow-ffi")
+    .arg("--allow-read")
+    .arg("--unstable")
+    .arg("--quiet")
+    .arg("tests/bindgen_test.ts")
+    .arg(bindings.uri_file().as_str())
+    .env("NO_COLOR", "1")
+    .output()
+    .unwrap();
+  let stdout = std::str::from_utf8(&output.stdout).unwrap();
+  let stderr = std::str::from_utf8(&output.stderr).unwrap();
+  if !output.status.success() {
+    println!("stdout {stdout}");
+    println!("stderr {stderr}");
+  }
+  println!("{:?}", output.status);
+  assert!(output.status.success());
+  let expected = "\
+    something\n\
+    579\n\
+    333\n\
+    579.75\n\
+    true\n\
+    3, 3, 3, 3, 3, 3, 3, 3\n\
+    Hello, world!\n\
+    callback\n\
+    { x: 10, y: 20, w: 100, h: 200 }\n\
+    Rect { x: 1.0, y: 2.0, w: 3.0, h: 4.0 }\n\
+    1 1.5 100 12n [ 7, 8 ]\n\
+    Mixed { u8: 1, f32: 1.5, rect: Rect { x: 10.0, y: 20.0, w: 100.0, h: 200.0 }, usize: 12, array: [7, 8] }\n\
+    42\n";
+  assert_eq!(stdout, expected);
+  assert_eq!(stderr, "");
+}
@@ -269,6 +269,10 @@ const dylib = Deno.dlopen(libPath, {
     parameters: [{ struct: RectNestedCached }],
     result: "void",
   },
+  call_fn_ptr_struct: {
+    parameters: ["function", { struct: Rect }],
+    result: "void",
+  },
   print_rect_async: {
     name: "print_rect",
     nonblocking: true,
@@ -697,9 +701,85 @@ const cb = new Deno.UnsafeCallback({
 const cbFfi = new Deno.UnsafeFnPointer(cb.pointer, cb.definition);
 const cbResult = new Float64Array(cbFfi.call(rect_async).buffer);
 assertEquals(Array.from(cbResult), [20, 30, 110, 210]);
+dylib.symbols.call_fn_ptr_struct(cb.pointer, rect_async);
 
 cb.close();
 
+// Struct bytes not covered by the returned buffer are zeroed
+const partialRectCallback = new Deno.UnsafeCallback({
+  parameters: [{ struct: Rect }],
+  result: { struct: Rect },
+}, (innerRect) => new Float64Array(innerRect.buffer, 0, 2));
+dylib.symbols.call_fn_ptr_struct(partialRectCallback.pointer, rect_async);
+partialRectCallback.close();
+
+const invalidRectCallback = new Deno.UnsafeCallback({
+  parameters: [{ struct: Rect }],
+  result: { struct: Rect },
+}, () => null);
+dylib.symbols.call_fn_ptr_struct(invalidRectCallback.pointer, rect_async);
+invalidRectCallback.close();
+
+{
+  // Variadic functions are declared with the types of the variadic arguments
+  // they are called with.
+  const libcPath = {
+    darwin: "libSystem.B.dylib",
+    linux: "libc.so.6",
+    windows: "msvcrt.dll",
+  }[Deno.build.os];
+  const snprintf = Deno.build.os === "windows" ? "_snprintf" : "snprintf";
+  const libc = Deno.dlopen(libcPath, {
+    snprintf_i32_f64: {
+      name: snprintf,
+      parameters: ["buffer", "usize", "buffer"],
+      variadic: ["i32", "f64"],
+      result: "i32",
+    },
+    snprintf_pointer: {
+      name: snprintf,
+      parameters: ["buffer", "usize", "buffer"],
+      variadic: ["buffer"],
+      result: "i32",
+    },
+  });
+  const encoder = new TextEncoder();
+  const decoder = new TextDecoder();
+  const out = new Uint8Array(32);
+
+  let length = libc.symbols.snprintf_i32_f64(
+    out,
+    out.length,
+    encoder.encode("%d %.2f\0"),
+    42,
+    1.5,
+  );
+  assertEquals(decoder.decode(out.subarray(0, length)), "42 1.50");
+
+  length = libc.symbols.snprintf_pointer(
+    out,
+    out.length,
+    encoder.encode("<%s>\0"),
+    encoder.encode("deno\0"),
+  );
+  assertEquals(decoder.decode(out.subarray(0, length)), "<deno>");
+
+  assertThrows(
+    () =>
+      Deno.dlopen(libcPath, {
+        snprintf: {
+          name: snprintf,
+          parameters: ["buffer", "usize", "buffer"],
+          variadic: ["f32"],
+          result: "i32",
+        },
+      }),
+    TypeError,
+    "Variadic arguments of type f32 are promoted by C, use f64 instead",
+  );
+  libc.close();
+}
+
 const arrayBuffer = view.getArrayBuffer(4);
 const uint32Array = new Uint32Array(arrayBuffer);
 assertEquals(arrayBuffer.byteLength, 4);